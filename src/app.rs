@@ -1,16 +1,46 @@
-use leptos::{logging::log, prelude::*};
+use leptos::{logging::log, prelude::*, task::spawn_local};
 use leptos_meta::*;
 use leptos_router::{
     components::{Router, Route, Routes},
-    StaticSegment, WildcardSegment,
+    ParamSegment, StaticSegment, WildcardSegment,
 };
 
 use auth::AuthForm;
+use i18n::{provide_locale, set_locale, t, use_locale, Locale};
 use model::User;
-use page::{HomePage, AccountPage};
+use page::{HomePage, AccountPage, DoorPage, OfflineDoorPage, GuestListPage, CartPage, EventPage, EventAdminPage, OrderPage, InvoicesAdminPage, EventRefundsPage, DiscountsAdminPage, ShopPage, ProductPage, ProductsAdminPage, PackingListPage, NewsletterConfirmPage, NewsletterUnsubscribePage, NewsletterAdminPage, CalendarPage, FeedAdminPage, NewsPage, PostPage, NewsAdminPage, MediaAdminPage, ArtistsPage, ArtistPage, ArtistsAdminPage, OccupancyPage, AreasAdminPage, WaitlistOfferPage, WaitlistAdminPage, JobsAdminPage};
+use page::newsletter::NewsletterForm;
+use page::cart::{provide_cart, use_cart};
+use payment::fake::FakePaymentPage;
+use push::{provide_push, use_push, PushMessage};
+use seo::PageMeta;
 
 pub mod page;
 pub mod auth;
+pub mod scanner;
+pub mod offline;
+pub mod signing;
+pub mod time;
+pub mod csv;
+pub mod payment;
+pub mod pdf;
+pub mod ical;
+pub mod feed;
+pub mod markdown;
+pub mod seo;
+pub mod images;
+pub mod media;
+pub mod occupancy;
+pub mod push;
+pub mod waitlist;
+pub mod jobs;
+pub mod event_mail;
+pub mod i18n;
+pub mod sitemap;
+pub mod invoice;
+pub mod mail;
+pub mod refund;
+pub mod stock;
 pub mod database;
 pub mod errors;
 pub mod model;
@@ -21,8 +51,14 @@ stylance::import_style!(style, "style/app.module.scss");
 pub fn App() -> impl IntoView {
     // Provides context that manages stylesheets, titles, meta tags, etc.
     provide_meta_context();
+    provide_cart();
+    provide_locale();
+    provide_push();
+    let locale = use_locale();
 
     view! {
+        <Html {..} lang=move || locale.get().code()/>
+
         // injects a stylesheet into the document <head>
         // id=leptos means cargo-leptos will hot-reload this stylesheet
         <Stylesheet id="leptos" href="/pkg/stampffabrik.css"/>
@@ -35,9 +71,42 @@ pub fn App() -> impl IntoView {
         <Router>
             <main>
                 <Header/>
+                <Notices/>
                     <Routes fallback=move || "not found.">
                         <Route path=StaticSegment("") view=HomePage/>
                         <Route path=StaticSegment("account") view=AccountPage/>
+                        <Route path=(StaticSegment("door"), ParamSegment("event")) view=DoorPage/>
+                        <Route path=(StaticSegment("door"), ParamSegment("event"), StaticSegment("offline")) view=OfflineDoorPage/>
+                        <Route path=(StaticSegment("guestlist"), ParamSegment("event")) view=GuestListPage/>
+                        <Route path=StaticSegment("occupancy") view=OccupancyPage/>
+                        <Route path=StaticSegment("calendar") view=CalendarPage/>
+                        <Route path=StaticSegment("news") view=NewsPage/>
+                        <Route path=(StaticSegment("news"), ParamSegment("slug")) view=PostPage/>
+                        <Route path=(StaticSegment("events"), ParamSegment("slug")) view=EventPage/>
+                        <Route path=StaticSegment("artists") view=ArtistsPage/>
+                        <Route path=(StaticSegment("artists"), ParamSegment("slug")) view=ArtistPage/>
+                        <Route path=StaticSegment("shop") view=ShopPage/>
+                        <Route path=(StaticSegment("shop"), ParamSegment("slug")) view=ProductPage/>
+                        <Route path=StaticSegment("cart") view=CartPage/>
+                        <Route path=(StaticSegment("orders"), ParamSegment("order")) view=OrderPage/>
+                        <Route path=(StaticSegment("payment"), StaticSegment("fake"), ParamSegment("order"), ParamSegment("payment")) view=FakePaymentPage/>
+                        <Route path=(StaticSegment("admin"), StaticSegment("invoices")) view=InvoicesAdminPage/>
+                        <Route path=(StaticSegment("admin"), StaticSegment("discounts")) view=DiscountsAdminPage/>
+                        <Route path=(StaticSegment("admin"), StaticSegment("products")) view=ProductsAdminPage/>
+                        <Route path=(StaticSegment("admin"), StaticSegment("packing")) view=PackingListPage/>
+                        <Route path=(StaticSegment("admin"), StaticSegment("newsletter")) view=NewsletterAdminPage/>
+                        <Route path=(StaticSegment("admin"), StaticSegment("feed")) view=FeedAdminPage/>
+                        <Route path=(StaticSegment("admin"), StaticSegment("news")) view=NewsAdminPage/>
+                        <Route path=(StaticSegment("admin"), StaticSegment("media")) view=MediaAdminPage/>
+                        <Route path=(StaticSegment("admin"), StaticSegment("artists")) view=ArtistsAdminPage/>
+                        <Route path=(StaticSegment("admin"), StaticSegment("areas")) view=AreasAdminPage/>
+                        <Route path=(StaticSegment("admin"), StaticSegment("waitlist")) view=WaitlistAdminPage/>
+                        <Route path=(StaticSegment("admin"), StaticSegment("jobs")) view=JobsAdminPage/>
+                        <Route path=(StaticSegment("newsletter"), StaticSegment("confirm"), ParamSegment("subscriber"), ParamSegment("token")) view=NewsletterConfirmPage/>
+                        <Route path=(StaticSegment("newsletter"), StaticSegment("unsubscribe"), ParamSegment("subscriber"), ParamSegment("token")) view=NewsletterUnsubscribePage/>
+                        <Route path=(StaticSegment("waitlist"), ParamSegment("entry"), ParamSegment("token")) view=WaitlistOfferPage/>
+                        <Route path=(StaticSegment("admin"), StaticSegment("events"), ParamSegment("event")) view=EventAdminPage/>
+                        <Route path=(StaticSegment("admin"), StaticSegment("refunds"), ParamSegment("event")) view=EventRefundsPage/>
                         <Route path=WildcardSegment("any") view=NotFound/>
                     </Routes>
                 <Footer/>
@@ -49,6 +118,7 @@ pub fn App() -> impl IntoView {
 #[component]
 pub fn Header() -> impl IntoView {
     let (show_modal, set_show_modal) = signal(false);
+    let cart = use_cart();
     
     let get_user= || -> Option<ReadSignal<Option<User>>> {
         match use_context::<(ReadSignal<Option<User>>, WriteSignal<Option<User>>)>() {
@@ -59,12 +129,12 @@ pub fn Header() -> impl IntoView {
     let render_account = move || {
         view!{
             <Show when=move || get_user().is_none() || get_user().unwrap()().is_none()> 
-                <a class=style::menu_entry on:click=move |_| set_show_modal(!show_modal())>
+                <a class=style::menu_entry title=t("nav-account") on:click=move |_| set_show_modal(!show_modal())>
                     <i class="bi bi-person-circle"></i>
                 </a>
             </Show>
             <Show when=move || get_user().is_some() && get_user().unwrap()().is_some()>
-                <a class=style::menu_entry href="/account" on:click=move |_| ()>
+                <a class=style::menu_entry title=t("nav-account") href="/account" on:click=move |_| ()>
                     <i class="bi bi-person-circle"></i>
                 </a>
             </Show>
@@ -77,16 +147,84 @@ pub fn Header() -> impl IntoView {
                 <img src="/assets/stampffabrik_64.png" class=style::nav_icon/>Stampffabrik
             </a>
             <div class=style::nav_menu>
-                <a class=style::menu_entry href="/">
+                <a class=style::menu_entry title=t("nav-home") href="/">
                     <i class="bi bi-house-door-fill"></i>
                 </a>
+                <a class=style::menu_entry title=t("nav-news") href="/news">
+                    <i class="bi bi-newspaper"></i>
+                </a>
+                <a class=style::menu_entry title=t("nav-calendar") href="/calendar">
+                    <i class="bi bi-calendar-event"></i>
+                </a>
+                <a class=style::menu_entry title=t("nav-shop") href="/shop">
+                    <i class="bi bi-bag"></i>
+                </a>
+                <a class=style::menu_entry title=t("nav-cart") href="/cart">
+                    <i class="bi bi-cart"></i>
+                    <Show when=move || cart.with(|c| !c.is_empty())>
+                        <span class=style::badge>{move || cart.with(|c| c.count())}</span>
+                    </Show>
+                </a>
                 {render_account()}
+                <LanguageSwitcher/>
             </div>
         </div>
         <AuthForm show_modal set_show_modal/>
     }
 }
 
+/// Switches the language right away and keeps it in a cookie for the
+/// following requests.
+#[component]
+fn LanguageSwitcher() -> impl IntoView {
+    let locale = use_locale();
+
+    view! {
+        <span class=style::languages title=t("language")>
+            {Locale::ALL.into_iter().map(|option| view! {
+                <a class=move || if locale.get() == option { style::language_active } else { style::language }
+                    on:click=move |_| {
+                        locale.set(option);
+                        spawn_local(async move {
+                            let _ = set_locale(option).await;
+                        });
+                    }
+                >
+                    {option.code().to_uppercase()}
+                </a>
+            }).collect_view()}
+        </span>
+    }
+}
+
+/// Pushed notifications, until dismissed.
+#[component]
+fn Notices() -> impl IntoView {
+    let notices = RwSignal::new(Vec::<(String, Option<String>)>::new());
+    let locale = use_locale();
+    use_push().on(move |message| {
+        if let PushMessage::Notification { key, args, link } = message {
+            let args: Vec<(&str, &str)> = args.iter().map(|(name, value)| (name.as_str(), value.as_str())).collect();
+            let text = locale.get_untracked().format(&key, &args);
+            notices.update(|n| n.push((text, link)));
+        }
+    });
+
+    view! {
+        <div class=style::notices>
+            {move || notices.get().into_iter().enumerate().map(|(i, (text, link))| view! {
+                <div class=style::notice>
+                    {match link {
+                        Some(link) => view! { <a href=link>{text}</a> }.into_any(),
+                        None => view! { <span>{text}</span> }.into_any(),
+                    }}
+                    <a class="bi bi-x-lg" on:click=move |_| notices.update(|n| { n.remove(i); })></a>
+                </div>
+            }).collect_view()}
+        </div>
+    }
+}
+
 #[component]
 pub fn Footer() -> impl IntoView {
     view! {
@@ -95,6 +233,10 @@ pub fn Footer() -> impl IntoView {
                 <a class="bi bi-instagram" href="https://www.instagram.com/stampffabrik"></a>
                 <a class="bi bi-facebook" href="https://www.facebook.com/stampffabrik"></a>
             </span>
+            <NewsletterForm/>
+            <a class=style::mail href="/shop">{t("nav-shop")}</a>
+            <a class=style::mail href="/artists">{t("nav-artists")}</a>
+            <a class=style::mail href="/feed.atom" rel="external"><i class="bi bi-rss"></i>" Feed"</a>
             <a class=style::mail href="mailto:mail@stampffabrik.de">mail@stampffabrik.de</a>
             <span inner_html="&copy; 2024 Stampffabrik"></span>
         </div>
@@ -119,6 +261,7 @@ fn NotFound() -> impl IntoView {
     }
 
     view! {
-        <h1>"Not Found"</h1>
+        <PageMeta title=use_locale().get_untracked().text("not-found") noindex=true/>
+        <h1>{t("not-found")}</h1>
     }
 }
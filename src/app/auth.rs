@@ -7,6 +7,8 @@ use serde::{Deserialize, Serialize};
 use wasm_bindgen::JsCast;
 use leptos::web_sys::HtmlElement;
 
+use crate::app::i18n::{t, use_locale};
+use crate::app::push::use_push;
 use crate::app::model::{
     user::LoginRequest,
     user::RegisterRequest,
@@ -51,6 +53,16 @@ pub fn AuthForm(
 
     spawn_local(initial_auth(set_user));
 
+    // the push stream only carries the topics of whoever opened it
+    let push = use_push();
+    Effect::new(move |previous: Option<Option<String>>| {
+        let uuid = user.0.with(|user| user.as_ref().map(|user| user.uuid.clone()));
+        if previous.is_some_and(|previous| previous != uuid) {
+            push.reconnect();
+        }
+        uuid
+    });
+
     view! {
         <Show when = move || { show_modal() }>
             <div class=style::back id="back" on:click=on_back_pressed>
@@ -88,6 +100,7 @@ pub fn SignInForm(
 
     let (error_message, set_error_message) = signal(String::new());
     let (if_error, set_if_error) = signal(false);
+    let locale = use_locale();
 
     let on_register_pressed = move |_| {
         set_current_modal(CurrentModal::Register);
@@ -113,14 +126,15 @@ pub fn SignInForm(
                         }
                         Err(e) => {
                             log!("Error {:?}", e);
-                            set_error_message(format! {"Error adding {:?}", e});
+                            set_if_error(true);
+                            set_error_message(locale.get_untracked().text("auth-login-failed").to_string());
                         }
                     }
                 });
             }
             Err(_) => {
                 set_if_error(true);
-                set_error_message(String::from("All fields are required!"));
+                set_error_message(locale.get_untracked().text("auth-fields-required").to_string());
             }
         }
     };
@@ -133,14 +147,14 @@ pub fn SignInForm(
     
     view! {
         <div class=style::container>
-            <input type="email" placeholder="E-Mail"
+            <input type="email" placeholder=t("email")
                 value=email
                 on:input=move |e| {
                     set_email(event_target_value(&e));
                 }
                 class=style::input
             />
-            <input type="password" placeholder="Passwort"
+            <input type="password" placeholder=t("auth-password")
                 value=password
                 on:input=move |e| {
                     set_password(event_target_value(&e));
@@ -153,8 +167,8 @@ pub fn SignInForm(
                     {error_message()}
                 </Show>
             </span>
-            <button on:click=on_login class=style::button>"Login"</button>
-            <a class=style::link on:click=on_register_pressed>"Neues Konto erstellen"</a>
+            <button on:click=on_login class=style::button>{t("auth-login")}</button>
+            <a class=style::link on:click=on_register_pressed>{t("auth-create-account")}</a>
         </div>
     }
 }
@@ -169,13 +183,13 @@ pub async fn sign_in(
     let user = get_user_by_mail(login_request.email).await;
     let user = match user {
         Some(u) => u,
-        None => return Err(ServerFnError::Args(String::from("User not found"))),
+        None => return Err(ServerFnError::Args(request_text("error-user-not-found"))),
     };
 
     let verification = verify_password(login_request.password.to_owned(), user.password_hash.to_owned()).await;
     let verification = match verification {
         Ok(result) => result,
-        Err(_) => return Err(ServerFnError::Args(String::from("Error logging in!"))),
+        Err(_) => return Err(ServerFnError::Args(request_text("error-login"))),
     };
 
     if verification {
@@ -195,10 +209,10 @@ pub async fn sign_in(
                 }
                 Ok(user)
             }
-            Err(_) => Err(ServerFnError::Args(String::from("Error logging in!"))),
+            Err(_) => Err(ServerFnError::Args(request_text("error-login"))),
         }
     } else {
-        Err(ServerFnError::Args(String::from("Error logging in!")))
+        Err(ServerFnError::Args(request_text("error-login")))
     }
 }
 
@@ -215,6 +229,7 @@ pub fn SignUpForm(
 
     let (error_message, set_error_message) = signal(String::new());
     let (if_error, set_if_error) = signal(false);
+    let locale = use_locale();
 
     let on_login_pressed = move |_| {
         set_current_modal(CurrentModal::Login);
@@ -238,14 +253,15 @@ pub fn SignUpForm(
                         }
                         Err(e) => {
                             log!("Error adding {:?}", e);
-                            set_error_message(format! {"Error adding {:?}", e});
+                            set_if_error(true);
+                            set_error_message(locale.get_untracked().text("auth-register-failed").to_string());
                         }
                     }
                 });
             }
             Err(_) => {
                 set_if_error(true);
-                set_error_message(String::from("All fields are required!"));
+                set_error_message(locale.get_untracked().text("auth-fields-required").to_string());
             }
         }
     };
@@ -260,14 +276,14 @@ pub fn SignUpForm(
 
     view! {
         <div class=style::container>
-            <input type="email" placeholder="E-Mail"
+            <input type="email" placeholder=t("email")
                 value=email
                 on:input=move |e| {
                     set_email(event_target_value(&e));
                 }
                 class=style::input
             />
-            <input type="password" placeholder="Passwort"
+            <input type="password" placeholder=t("auth-password")
                 value=password
                 on:input=move |e| {
                     set_password(event_target_value(&e));
@@ -280,8 +296,8 @@ pub fn SignUpForm(
                     {error_message()}
                 </Show>
             </span>
-            <button on:click=on_register class=style::button>"Registrieren"</button>
-            <a class=style::link on:click=on_login_pressed>"Ich habe bereits ein Konto"</a>
+            <button on:click=on_register class=style::button>{t("auth-register")}</button>
+            <a class=style::link on:click=on_login_pressed>{t("auth-have-account")}</a>
         </div>
     }
 }
@@ -308,7 +324,7 @@ pub async fn sign_up(add_user_request: RegisterRequest) -> Result<User, ServerFn
             }
             Ok(res.0)
         },
-        Err(_) => Err(ServerFnError::Args(String::from("Error signing up!"))),
+        Err(_) => Err(ServerFnError::Args(request_text("error-sign-up"))),
     }
 }
 
@@ -326,21 +342,21 @@ pub async fn authenticate(
             let cookie = match http_request {
                 Some(request) => match request.cookie("auth_token") {
                     Some(c) => c,
-                    None => return Err(ServerFnError::Args(String::from("No cookie provided")))
+                    None => return Err(ServerFnError::Args(request_text("error-no-cookie")))
                 },
-                None => return Err(ServerFnError::Args(String::from("No valid request"))),
+                None => return Err(ServerFnError::Args(request_text("error-no-request"))),
             };
             cookie.value().to_owned()
         }
     };
     let claims = match validate_jwt(&token).await {
         Ok(decoded) => decoded,
-        Err(_) => return Err(ServerFnError::Args(String::from("Invalid Token")))
+        Err(_) => return Err(ServerFnError::Args(request_text("error-invalid-token")))
     };
     
     match get_user_by_id(claims.sub).await {
         Some(user) => Ok(user),
-        None => Err(ServerFnError::Args(String::from("User not found"))),
+        None => Err(ServerFnError::Args(request_text("error-user-not-found"))),
     }
 }
 
@@ -348,6 +364,8 @@ cfg_if::cfg_if! {
     if #[cfg(feature = "ssr")] {
         use crate::app::database;
         use crate::app::errors::{ ResponseError };
+        use crate::app::i18n::{request_locale, request_text};
+        use crate::app::model::user::Role;
         use chrono::Local;
         use uuid::Uuid;
         use jsonwebtoken::{encode, decode, Header, Validation, EncodingKey, DecodingKey};
@@ -369,6 +387,29 @@ cfg_if::cfg_if! {
             database::get_user_by_id(uuid).await
         }
 
+        /// Resolves the user behind the `auth_token` cookie of the current request.
+        pub async fn current_user() -> Option<User> {
+            use actix_web::HttpRequest;
+            let request = use_context::<HttpRequest>()?;
+            user_from_request(&request).await
+        }
+
+        /// Same as `current_user`, for plain actix handlers outside Leptos.
+        pub async fn user_from_request(request: &actix_web::HttpRequest) -> Option<User> {
+            let cookie = request.cookie("auth_token")?;
+            let claims = validate_jwt(cookie.value()).await.ok()?;
+            get_user_by_id(claims.sub).await
+        }
+
+        /// Guard for server functions that are restricted to staff or admins.
+        pub async fn require_role(role: Role) -> Result<User, ServerFnError> {
+            match current_user().await {
+                Some(user) if user.role >= role => Ok(user),
+                Some(_) => Err(ServerFnError::Args(request_text("error-not-allowed"))),
+                None => Err(ServerFnError::Args(request_text("error-not-logged-in"))),
+            }
+        }
+
         async fn generate_password_hash(password: String) -> Result<String, argon2::password_hash::Error> {
             let salt = SaltString::generate(&mut OsRng);
 
@@ -423,12 +464,10 @@ cfg_if::cfg_if! {
             let current_now = Local::now();
             let current_formatted = current_now.to_string();
 
-            let new_user = User::new(
-                uuid.into(),
-                email.into(),
-                password_hash,
-                current_formatted
-            );
+            let new_user = User {
+                locale: request_locale(),
+                ..User::new(uuid.into(), email.into(), password_hash, current_formatted)
+            };
 
             let token = match database::add_user(new_user.clone()).await {
                 Some(_user) => generate_jwt(uuid).await,
@@ -0,0 +1,91 @@
+//! Minimal RFC 4180 reading and writing for list imports and exports.
+
+/// Splits `text` into records. Quoted fields may contain separators, quotes
+/// (doubled) and line breaks. Blank lines are dropped.
+pub fn parse(text: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', true) => quoted = false,
+            ('"', false) if field.is_empty() => quoted = true,
+            (',' | ';', false) => record.push(std::mem::take(&mut field)),
+            ('\r', false) => (),
+            ('\n', false) => {
+                record.push(std::mem::take(&mut field));
+                if record.iter().any(|f| !f.trim().is_empty()) {
+                    records.push(std::mem::take(&mut record));
+                }
+                record.clear();
+            }
+            (c, _) => field.push(c),
+        }
+    }
+    record.push(field);
+    if record.iter().any(|f| !f.trim().is_empty()) {
+        records.push(record);
+    }
+    records
+}
+
+pub fn write(records: &[Vec<String>]) -> String {
+    records
+        .iter()
+        .map(|record| record.iter().map(|field| escape(field)).collect::<Vec<_>>().join(","))
+        .map(|line| line + "\r\n")
+        .collect()
+}
+
+fn escape(field: &str) -> String {
+    if field.contains([',', ';', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(fields: &[&str]) -> Vec<String> {
+        fields.iter().map(|f| f.to_string()).collect()
+    }
+
+    #[test]
+    fn plain_fields_are_not_quoted() {
+        assert_eq!(write(&[record(&["Anna", "2", "DJ Kurt"])]), "Anna,2,DJ Kurt\r\n");
+    }
+
+    #[test]
+    fn separators_quotes_and_breaks_are_quoted() {
+        assert_eq!(escape("Müller, Anna"), "\"Müller, Anna\"");
+        assert_eq!(escape("a;b"), "\"a;b\"");
+        assert_eq!(escape("\"Kurti\""), "\"\"\"Kurti\"\"\"");
+        assert_eq!(escape("line\nbreak"), "\"line\nbreak\"");
+        assert_eq!(escape("line\rbreak"), "\"line\rbreak\"");
+    }
+
+    #[test]
+    fn written_records_read_back() {
+        let records = vec![
+            record(&["name", "plus", "invited_by", "note"]),
+            record(&["Müller, Anna", "1", "DJ \"Kurt\"", "first\nsecond"]),
+            record(&["", "0", "Bar", ""]),
+        ];
+        assert_eq!(parse(&write(&records)), records);
+    }
+
+    #[test]
+    fn semicolons_and_blank_lines() {
+        assert_eq!(parse("a;b\n\n \nc,\"d\"\"e\"\n"), vec![record(&["a", "b"]), record(&["c", "d\"e"])]);
+    }
+}
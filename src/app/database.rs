@@ -1,14 +1,82 @@
 cfg_if::cfg_if! {
     if #[cfg(feature = "ssr")] {
 
-        use crate::app::model::User;
+        use crate::app::model::{User, Event, Ticket, GuestListEntry};
+        use crate::app::model::guest::{GuestAudit, GuestQuota};
+        use crate::app::model::event::TicketType;
+        use crate::app::model::product::ProductVariant;
+        use crate::app::model::discount::{DiscountCode, GiftVoucher, Redemption, RedemptionReport};
+        use crate::app::model::newsletter::{Campaign, CampaignStats, Subscriber, SubscriberCounts};
+        use crate::app::model::feed::FeedItem;
+        use crate::app::model::media::MediaFile;
+        use crate::app::model::Artist;
+        use crate::app::model::artist::Performance;
+        use crate::app::model::venue::{Area, OccupancyClick};
+        use crate::app::model::waitlist::{WaitlistAudit, WaitlistEntry, WaitlistOffer};
+        use crate::app::model::job::{Job, JobStatus};
+        use crate::app::model::event_mail::EventMail;
+        use crate::app::model::user::NotificationSettings;
+        use crate::app::i18n::Locale;
+        use crate::app::mail::QueuedMail;
+        use crate::app::model::{Invoice, Order, Post, Product, Refund};
+        use crate::app::model::order::{OrderStatus, PaymentRef};
+        use crate::app::model::ticket::{TicketStatus, DoorCounts};
+        use crate::app::model::door::{LocalScan, ScanConflict};
         use crate::app::errors::{ ResponseError };
         use surrealdb::engine::remote::ws::{Client, Ws};
         use surrealdb::opt::auth::Root;
         use surrealdb::{ Surreal};
         use once_cell::sync::Lazy;
 
-        static DB: Lazy<Surreal<Client>> = Lazy::new(Surreal::init);
+        /// Requests sign in on the shared session and invalidate it after
+        /// their query. Background work polls all the time and would sign
+        /// requests out between their signin and their query, so it runs in
+        /// `background` and goes through a session that stays signed in.
+        static SHARED_DB: Lazy<Surreal<Client>> = Lazy::new(Surreal::init);
+        static BACKGROUND_DB: Lazy<Surreal<Client>> = Lazy::new(Surreal::init);
+
+        tokio::task_local! {
+            static IN_BACKGROUND: ();
+        }
+
+        fn in_background() -> bool {
+            IN_BACKGROUND.try_with(|_| ()).is_ok()
+        }
+
+        /// The session of the running task.
+        struct Session;
+
+        static DB: Session = Session;
+
+        impl std::ops::Deref for Session {
+            type Target = Surreal<Client>;
+
+            fn deref(&self) -> &Surreal<Client> {
+                if in_background() {
+                    &BACKGROUND_DB
+                } else {
+                    &SHARED_DB
+                }
+            }
+        }
+
+        impl Session {
+            /// Signs the request out again. The background session is left
+            /// signed in.
+            async fn invalidate(&self) -> surrealdb::Result<()> {
+                if in_background() {
+                    return Ok(());
+                }
+                SHARED_DB.invalidate().await
+            }
+        }
+
+        /// Runs `work` on the background session: the job workers, the jobs
+        /// themselves and the mail queue. Tasks it spawns need their own
+        /// `background`.
+        pub async fn background<F: std::future::Future>(work: F) -> F::Output {
+            IN_BACKGROUND.scope((), work).await
+        }
 
         pub async fn open_db_connection() {
             let _ = DB.connect::<Ws>("127.0.0.1:8000").await;
@@ -47,7 +115,7 @@ cfg_if::cfg_if! {
                 Ok(mut res) => {
                     let found:Result<Vec<User>,_> = res.take(0);
                     match found {
-                        Ok(found_user) => Some(found_user[0].clone()),
+                        Ok(found_user) => found_user.into_iter().next(),
                         Err(_) => None,
                     }
                 },
@@ -64,7 +132,7 @@ cfg_if::cfg_if! {
                 Ok(mut res) => {
                     let found:Result<Vec<User>,_> = res.take(0);
                     match found {
-                        Ok(found_user) => Some(found_user[0].clone()),
+                        Ok(found_user) => found_user.into_iter().next(),
                         Err(_) => None,
                     }
                 },
@@ -100,6 +168,2277 @@ cfg_if::cfg_if! {
                 Err(_) => Err(ResponseError::UserDeleteFailure)
             }
         }
+        pub async fn get_event_by_slug(slug: String) -> Option<Event> {
+            open_db_connection().await;
+            let event = DB.query("SELECT * FROM event WHERE slug = $slug").bind(("slug", slug)).await;
+            let _ = DB.invalidate().await;
+
+            match event {
+                Ok(mut res) => {
+                    let found: Result<Vec<Event>, _> = res.take(0);
+                    match found {
+                        Ok(found_event) => found_event.into_iter().next(),
+                        Err(_) => None,
+                    }
+                },
+                Err(_) => None,
+            }
+        }
+
+        pub async fn add_ticket(new_ticket: Ticket) -> Option<Ticket> {
+            open_db_connection().await;
+            let results = DB.create(("ticket", new_ticket.uuid.to_string()))
+                .content(new_ticket)
+                .await;
+            let _ = DB.invalidate().await;
+
+            match results {
+                Ok(created_ticket) => created_ticket,
+                Err(e) => {
+                    println!("error in adding ticket: {:?}",e);
+                    None
+                }
+            }
+        }
+
+        pub async fn get_ticket_by_code(event: String, code: String) -> Option<Ticket> {
+            open_db_connection().await;
+            let ticket = DB.query("SELECT * FROM ticket WHERE event = $event AND code = $code")
+                .bind(("event", event))
+                .bind(("code", code))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match ticket {
+                Ok(mut res) => {
+                    let found: Result<Vec<Ticket>, _> = res.take(0);
+                    match found {
+                        Ok(found_ticket) => found_ticket.into_iter().next(),
+                        Err(_) => None,
+                    }
+                },
+                Err(_) => None,
+            }
+        }
+
+        pub async fn search_tickets_by_name(event: String, query: String) -> Option<Vec<Ticket>> {
+            open_db_connection().await;
+            let tickets = DB.query("SELECT * FROM ticket WHERE event = $event \
+                    AND string::contains(string::lowercase(holder_name), string::lowercase($query)) \
+                    ORDER BY holder_name LIMIT 20")
+                .bind(("event", event))
+                .bind(("query", query))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match tickets {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        /// Admits a ticket in a single UPDATE so that two devices scanning the
+        /// same code cannot both succeed. Returns the ticket as it was before
+        /// the scan, or `None` if it was not admissible.
+        pub async fn check_in_ticket(event: String, code: String, now: String, device: String)
+            -> Result<Option<Ticket>, ResponseError> {
+
+            open_db_connection().await;
+            let admitted = DB.query("UPDATE ticket SET \
+                    reentries = IF status = 'Out' THEN reentries + 1 ELSE reentries END, \
+                    checked_in_at = IF status = 'Valid' THEN $now ELSE checked_in_at END, \
+                    checked_in_by = IF status = 'Valid' THEN $device ELSE checked_in_by END, \
+                    status = 'CheckedIn' \
+                    WHERE event = $event AND code = $code AND status IN ['Valid', 'Out'] \
+                    RETURN BEFORE")
+                .bind(("event", event))
+                .bind(("code", code))
+                .bind(("now", now))
+                .bind(("device", device))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match admitted {
+                Ok(mut res) => {
+                    let found: Result<Vec<Ticket>, _> = res.take(0);
+                    match found {
+                        Ok(before) => Ok(before.into_iter().next()),
+                        Err(_) => Err(ResponseError::CheckInFailure),
+                    }
+                },
+                Err(_) => Err(ResponseError::CheckInFailure),
+            }
+        }
+
+        /// Marks a checked-in ticket as having left with a re-entry stamp.
+        pub async fn stamp_out_ticket(event: String, code: String)
+            -> Result<Ticket, ResponseError> {
+
+            open_db_connection().await;
+            let stamped = DB.query("UPDATE ticket SET status = 'Out' \
+                    WHERE event = $event AND code = $code AND status = 'CheckedIn' \
+                    RETURN AFTER")
+                .bind(("event", event))
+                .bind(("code", code))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match stamped {
+                Ok(mut res) => {
+                    let found: Result<Vec<Ticket>, _> = res.take(0);
+                    match found {
+                        Ok(after) => after.into_iter().next().ok_or(ResponseError::TicketNotFound),
+                        Err(_) => Err(ResponseError::CheckInFailure),
+                    }
+                },
+                Err(_) => Err(ResponseError::CheckInFailure),
+            }
+        }
+
+        #[derive(serde::Deserialize)]
+        struct StatusCount {
+            status: TicketStatus,
+            total: u32,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct GuestCount {
+            inside: Option<u32>,
+            expected: Option<u32>,
+        }
+
+        /// Tickets and guest list parties together, companions included.
+        pub async fn get_door_counts(event: String) -> Option<DoorCounts> {
+            open_db_connection().await;
+            let counts = DB.query("SELECT status, count() AS total FROM ticket \
+                    WHERE event = $event GROUP BY status; \
+                    SELECT math::sum(checked_in) AS inside, math::sum(plus + 1) AS expected FROM guest \
+                    WHERE event = $event GROUP ALL")
+                .bind(("event", event))
+                .await;
+            let _ = DB.invalidate().await;
+
+            let (rows, guests): (Vec<StatusCount>, Option<GuestCount>) = match counts {
+                Ok(mut res) => (res.take(0).ok()?, res.take(1).ok().flatten()),
+                Err(_) => return None,
+            };
+
+            let mut door_counts = DoorCounts::default();
+            for row in rows {
+                match row.status {
+                    TicketStatus::CheckedIn => door_counts.inside += row.total,
+                    TicketStatus::Out => door_counts.out += row.total,
+                    TicketStatus::Refunded | TicketStatus::Cancelled => continue,
+                    TicketStatus::Valid => (),
+                }
+                door_counts.expected += row.total;
+            }
+            if let Some(guests) = guests {
+                door_counts.inside += guests.inside.unwrap_or_default();
+                door_counts.expected += guests.expected.unwrap_or_default();
+            }
+            Some(door_counts)
+        }
+
+        pub async fn get_tickets_for_event(event: String) -> Option<Vec<Ticket>> {
+            open_db_connection().await;
+            let tickets = DB.query("SELECT * FROM ticket WHERE event = $event")
+                .bind(("event", event))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match tickets {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        #[derive(serde::Serialize, serde::Deserialize, Clone)]
+        struct DoorCode {
+            code: String,
+            event: String,
+        }
+
+        /// Tops the event's reserve of unsold ticket codes up to `count` and
+        /// returns it. Door devices only get their hashes.
+        pub async fn reserve_door_codes(event: String, count: usize) -> Option<Vec<String>> {
+            open_db_connection().await;
+            let reserved = DB.query("SELECT VALUE code FROM door_code WHERE event = $event")
+                .bind(("event", event.clone()))
+                .await;
+            let mut codes: Vec<String> = match reserved {
+                Ok(mut res) => res.take(0).unwrap_or_default(),
+                Err(e) => {
+                    println!("error in loading door codes: {:?}",e);
+                    let _ = DB.invalidate().await;
+                    return None;
+                }
+            };
+            let missing = (codes.len()..count)
+                .map(|_| DoorCode { code: Ticket::generate_code(), event: event.clone() })
+                .collect::<Vec<_>>();
+            if !missing.is_empty() {
+                let added = DB.query("INSERT INTO door_code $codes")
+                    .bind(("codes", missing.clone()))
+                    .await;
+                match added.map(|res| res.check()) {
+                    Ok(Ok(_)) => codes.extend(missing.into_iter().map(|c| c.code)),
+                    Ok(Err(e)) | Err(e) => println!("error in adding door codes: {:?}",e),
+                }
+            }
+            let _ = DB.invalidate().await;
+            Some(codes)
+        }
+
+        /// Takes a code from the event's reserve. None once it is used up, or
+        /// when another sale took the same code first.
+        pub async fn take_door_code(event: String) -> Option<String> {
+            open_db_connection().await;
+            let taken = DB
+                .query("LET $code = (SELECT * FROM door_code WHERE event = $event LIMIT 1)[0]")
+                .query("IF $code { DELETE $code.id RETURN BEFORE } ELSE { [] }")
+                .bind(("event", event))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match taken {
+                Ok(mut res) => res.take::<Vec<DoorCode>>(1).ok()?.into_iter().next().map(|c| c.code),
+                Err(_) => None,
+            }
+        }
+
+        /// Whether an offline scan was synced before.
+        pub async fn has_door_scan(uuid: String) -> bool {
+            open_db_connection().await;
+            let existing = DB.select::<Option<LocalScan>>(("door_scan", uuid)).await;
+            let _ = DB.invalidate().await;
+
+            matches!(existing, Ok(Some(_)))
+        }
+
+        /// Stores an offline scan once, after it was applied. Returns false if
+        /// the scan was synced before.
+        pub async fn add_door_scan(scan: LocalScan) -> bool {
+            open_db_connection().await;
+            let results = DB.create::<Option<LocalScan>>(("door_scan", scan.uuid.to_string()))
+                .content(scan)
+                .await;
+            let _ = DB.invalidate().await;
+
+            matches!(results, Ok(Some(_)))
+        }
+
+        /// Moves the first admission of a ticket to an earlier scan from another device.
+        pub async fn set_check_in_origin(event: String, code: String, at: String, device: String)
+            -> Result<(), ResponseError> {
+
+            open_db_connection().await;
+            let updated = DB.query("UPDATE ticket SET checked_in_at = $at, checked_in_by = $device \
+                    WHERE event = $event AND code = $code")
+                .bind(("event", event))
+                .bind(("code", code))
+                .bind(("at", at))
+                .bind(("device", device))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match updated {
+                Ok(_) => Ok(()),
+                Err(_) => Err(ResponseError::CheckInFailure),
+            }
+        }
+
+        pub async fn add_scan_conflict(conflict: ScanConflict) -> Option<ScanConflict> {
+            open_db_connection().await;
+            let results = DB.create(("door_conflict", conflict.scan.to_string()))
+                .content(conflict)
+                .await;
+            let _ = DB.invalidate().await;
+
+            match results {
+                Ok(created) => created,
+                Err(e) => {
+                    println!("error in adding scan conflict: {:?}",e);
+                    None
+                }
+            }
+        }
+
+        pub async fn get_guests(event: String) -> Option<Vec<GuestListEntry>> {
+            open_db_connection().await;
+            let guests = DB.query("SELECT * FROM guest WHERE event = $event ORDER BY name")
+                .bind(("event", event))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match guests {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        pub async fn search_guests(event: String, query: String) -> Option<Vec<GuestListEntry>> {
+            open_db_connection().await;
+            let guests = DB.query("SELECT * FROM guest WHERE event = $event \
+                    AND (string::contains(string::lowercase(name), string::lowercase($query)) \
+                    OR string::contains(string::lowercase(invited_by), string::lowercase($query))) \
+                    ORDER BY name LIMIT 20")
+                .bind(("event", event))
+                .bind(("query", query))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match guests {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        /// Adds the guest unless that would take the inviter past their quota.
+        /// Counting and adding happen in one transaction, so parallel adds
+        /// cannot both take the last spot.
+        pub async fn add_guest(new_guest: GuestListEntry) -> Result<GuestListEntry, ResponseError> {
+            open_db_connection().await;
+            let uuid = new_guest.uuid.clone();
+            let added = DB
+                .query("BEGIN TRANSACTION")
+                .query("LET $spots = (SELECT VALUE spots FROM guest_quota \
+                    WHERE event = $guest.event AND invited_by = $guest.invited_by)[0]")
+                .query("LET $used = math::sum(SELECT VALUE plus + 1 FROM guest \
+                    WHERE event = $guest.event AND invited_by = $guest.invited_by)")
+                .query("IF $spots != NONE AND $used + $guest.plus + 1 > $spots { THROW 'guest quota used up' }")
+                .query("CREATE type::thing('guest', $uuid) CONTENT $guest")
+                .query("COMMIT TRANSACTION")
+                .bind(("uuid", uuid.clone()))
+                .bind(("guest", new_guest))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match added.map(|res| res.check()) {
+                Ok(Ok(_)) => get_guest_by_id(uuid).await.ok_or(ResponseError::GuestCreationFailure),
+                Ok(Err(e)) | Err(e) if e.to_string().contains("guest quota used up") => Err(ResponseError::GuestQuotaExceeded),
+                Ok(Err(e)) | Err(e) => {
+                    println!("error in adding guest: {:?}",e);
+                    Err(ResponseError::GuestCreationFailure)
+                }
+            }
+        }
+
+        pub async fn delete_guest(guest_uuid: String)
+            -> Result<Option<GuestListEntry>, ResponseError> {
+
+            open_db_connection().await;
+            let delete_results = DB.delete(("guest", guest_uuid)).await;
+            let _ = DB.invalidate().await;
+
+            match delete_results {
+                Ok(deleted_guest) => Ok(deleted_guest),
+                Err(_) => Err(ResponseError::GuestNotFound)
+            }
+        }
+
+        /// Checks in a guest and their companions at once. Returns the entry as
+        /// it was before, or `None` if the party was already checked in.
+        pub async fn check_in_guest(guest_uuid: String, now: String)
+            -> Result<Option<GuestListEntry>, ResponseError> {
+
+            open_db_connection().await;
+            let admitted = DB.query("UPDATE type::thing('guest', $uuid) \
+                    SET checked_in = plus + 1, checked_in_at = $now \
+                    WHERE checked_in = 0 RETURN BEFORE")
+                .bind(("uuid", guest_uuid))
+                .bind(("now", now))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match admitted {
+                Ok(mut res) => {
+                    let found: Result<Vec<GuestListEntry>, _> = res.take(0);
+                    match found {
+                        Ok(before) => Ok(before.into_iter().next()),
+                        Err(_) => Err(ResponseError::CheckInFailure),
+                    }
+                },
+                Err(_) => Err(ResponseError::CheckInFailure),
+            }
+        }
+
+        pub async fn get_guest_by_id(guest_uuid: String) -> Option<GuestListEntry> {
+            open_db_connection().await;
+            let guest = DB.select(("guest", guest_uuid)).await;
+            let _ = DB.invalidate().await;
+
+            guest.ok().flatten()
+        }
+
+        pub async fn get_guest_quotas(event: String) -> Option<Vec<GuestQuota>> {
+            open_db_connection().await;
+            let quotas = DB.query("SELECT * FROM guest_quota WHERE event = $event ORDER BY invited_by")
+                .bind(("event", event))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match quotas {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        pub async fn set_guest_quota(quota: GuestQuota) -> Option<GuestQuota> {
+            open_db_connection().await;
+            let results = DB.query("UPSERT type::thing('guest_quota', [$event, $invited_by]) CONTENT $quota")
+                .bind(("event", quota.event.clone()))
+                .bind(("invited_by", quota.invited_by.clone()))
+                .bind(("quota", quota))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match results {
+                Ok(mut res) => {
+                    let saved: Result<Vec<GuestQuota>, _> = res.take(0);
+                    saved.ok().and_then(|s| s.into_iter().next())
+                },
+                Err(e) => {
+                    println!("error in setting guest quota: {:?}",e);
+                    None
+                }
+            }
+        }
+
+        pub async fn add_guest_audit(audit: GuestAudit) -> Option<GuestAudit> {
+            open_db_connection().await;
+            let results = DB.create(("guest_audit", audit.uuid.to_string()))
+                .content(audit)
+                .await;
+            let _ = DB.invalidate().await;
+
+            match results {
+                Ok(created) => created,
+                Err(e) => {
+                    println!("error in adding guest audit: {:?}",e);
+                    None
+                }
+            }
+        }
+
+        pub async fn get_guest_audit(event: String) -> Option<Vec<GuestAudit>> {
+            open_db_connection().await;
+            let audit = DB.query("SELECT * FROM guest_audit WHERE event = $event ORDER BY at DESC")
+                .bind(("event", event))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match audit {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        pub async fn get_ticket_type(ticket_type: String) -> Option<TicketType> {
+            open_db_connection().await;
+            let found = DB.select(("ticket_type", ticket_type)).await;
+            let _ = DB.invalidate().await;
+
+            found.ok().flatten()
+        }
+
+        pub async fn get_ticket_types(event: String) -> Option<Vec<TicketType>> {
+            open_db_connection().await;
+            let ticket_types = DB.query("SELECT * FROM ticket_type WHERE event = $event ORDER BY price_cents")
+                .bind(("event", event))
+                .await;
+            let _ = DB.invalidate().await;
 
+            match ticket_types {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        /// Takes `quantity` tickets out of the contingent, failing instead of
+        /// overselling or selling for a cancelled event.
+        pub async fn reserve_tickets(ticket_type: String, quantity: u32) -> Result<TicketType, ResponseError> {
+            open_db_connection().await;
+            let reserved = DB.query("UPDATE type::thing('ticket_type', $uuid) SET sold += $quantity \
+                    WHERE sold + $quantity <= capacity \
+                    AND (SELECT VALUE cancelled FROM event WHERE uuid = $parent.event)[0] != true")
+                .bind(("uuid", ticket_type))
+                .bind(("quantity", quantity))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match reserved {
+                Ok(mut res) => {
+                    let updated: Result<Vec<TicketType>, _> = res.take(0);
+                    updated.ok().and_then(|u| u.into_iter().next()).ok_or(ResponseError::SoldOut)
+                },
+                Err(_) => Err(ResponseError::SoldOut),
+            }
+        }
+
+        pub async fn release_tickets(ticket_type: String, quantity: u32) {
+            open_db_connection().await;
+            let _ = DB.query("UPDATE type::thing('ticket_type', $uuid) SET sold = math::max([0, sold - $quantity])")
+                .bind(("uuid", ticket_type))
+                .bind(("quantity", quantity))
+                .await;
+            let _ = DB.invalidate().await;
+        }
+
+        pub async fn get_products() -> Option<Vec<Product>> {
+            open_db_connection().await;
+            let products = DB.query("SELECT * FROM product ORDER BY name").await;
+            let _ = DB.invalidate().await;
+
+            match products {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        pub async fn get_product(product: String) -> Option<Product> {
+            open_db_connection().await;
+            let found = DB.select(("product", product)).await;
+            let _ = DB.invalidate().await;
+
+            found.ok().flatten()
+        }
+
+        pub async fn get_product_by_slug(slug: String) -> Option<Product> {
+            open_db_connection().await;
+            let product = DB.query("SELECT * FROM product WHERE slug = $slug LIMIT 1")
+                .bind(("slug", slug))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match product {
+                Ok(mut res) => res.take::<Vec<Product>>(0).ok().and_then(|p| p.into_iter().next()),
+                Err(_) => None,
+            }
+        }
+
+        pub async fn add_product(new_product: Product) -> Option<Product> {
+            open_db_connection().await;
+            let results = DB.create(("product", new_product.uuid.to_string()))
+                .content(new_product)
+                .await;
+            let _ = DB.invalidate().await;
+
+            match results {
+                Ok(created_product) => created_product,
+                Err(e) => {
+                    println!("error in adding product: {:?}",e);
+                    None
+                }
+            }
+        }
+
+        pub async fn update_product(product: Product) -> Option<Product> {
+            open_db_connection().await;
+            let results = DB.update(("product", product.uuid.to_string()))
+                .content(product)
+                .await;
+            let _ = DB.invalidate().await;
+
+            match results {
+                Ok(updated_product) => updated_product,
+                Err(e) => {
+                    println!("error in updating product: {:?}",e);
+                    None
+                }
+            }
+        }
+
+        pub async fn get_variant(sku: String) -> Option<ProductVariant> {
+            open_db_connection().await;
+            let found = DB.select(("product_variant", sku)).await;
+            let _ = DB.invalidate().await;
+
+            found.ok().flatten()
+        }
+
+        /// Variants of the given products, or of all products if `products` is empty.
+        pub async fn get_variants(products: Vec<String>) -> Option<Vec<ProductVariant>> {
+            open_db_connection().await;
+            let variants = DB.query("SELECT * FROM product_variant \
+                    WHERE array::len($products) = 0 OR product IN $products ORDER BY sku")
+                .bind(("products", products))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match variants {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        pub async fn add_variant(new_variant: ProductVariant) -> Option<ProductVariant> {
+            open_db_connection().await;
+            let results = DB.create(("product_variant", new_variant.sku.clone()))
+                .content(new_variant)
+                .await;
+            let _ = DB.invalidate().await;
+
+            match results {
+                Ok(created_variant) => created_variant,
+                Err(e) => {
+                    println!("error in adding variant: {:?}",e);
+                    None
+                }
+            }
+        }
+
+        pub async fn set_variant_stock(sku: String, stock: u32) -> Result<(), ResponseError> {
+            open_db_connection().await;
+            let updated = DB.query("UPDATE type::thing('product_variant', $sku) SET stock = $stock")
+                .bind(("sku", sku))
+                .bind(("stock", stock))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match updated {
+                Ok(_) => Ok(()),
+                Err(e) => {
+                    println!("error in updating stock: {:?}",e);
+                    Err(ResponseError::ProductNotFound)
+                }
+            }
+        }
+
+        /// Takes `quantity` off the stock unless there is not enough left.
+        /// Returns the variant with the new stock.
+        pub async fn take_stock(sku: String, quantity: u32) -> Result<ProductVariant, ResponseError> {
+            open_db_connection().await;
+            let taken = DB.query("UPDATE type::thing('product_variant', $sku) SET stock -= $quantity \
+                    WHERE stock >= $quantity")
+                .bind(("sku", sku))
+                .bind(("quantity", quantity))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match taken {
+                Ok(mut res) => {
+                    let updated: Result<Vec<ProductVariant>, _> = res.take(0);
+                    match updated {
+                        Ok(updated) => updated.into_iter().next().ok_or(ResponseError::SoldOut),
+                        _ => Err(ResponseError::SoldOut),
+                    }
+                },
+                Err(_) => Err(ResponseError::SoldOut),
+            }
+        }
+
+        pub async fn return_stock(sku: String, quantity: u32) {
+            open_db_connection().await;
+            let _ = DB.query("UPDATE type::thing('product_variant', $sku) SET stock += $quantity")
+                .bind(("sku", sku))
+                .bind(("quantity", quantity))
+                .await;
+            let _ = DB.invalidate().await;
+        }
+
+        pub async fn add_order(new_order: Order) -> Option<Order> {
+            open_db_connection().await;
+            let results = DB.create(("shop_order", new_order.uuid.to_string()))
+                .content(new_order)
+                .await;
+            let _ = DB.invalidate().await;
+
+            match results {
+                Ok(created_order) => created_order,
+                Err(e) => {
+                    println!("error in adding order: {:?}",e);
+                    None
+                }
+            }
+        }
+
+        pub async fn get_order(order: String) -> Option<Order> {
+            open_db_connection().await;
+            let found = DB.select(("shop_order", order)).await;
+            let _ = DB.invalidate().await;
+
+            found.ok().flatten()
+        }
+
+        pub async fn set_order_payment(order: String, payment: PaymentRef) -> Result<(), ResponseError> {
+            open_db_connection().await;
+            let updated = DB.query("UPDATE type::thing('shop_order', $uuid) SET payment = $payment")
+                .bind(("uuid", order))
+                .bind(("payment", payment))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match updated {
+                Ok(_) => Ok(()),
+                Err(_) => Err(ResponseError::OrderNotFound),
+            }
+        }
+
+        /// Moves an order to `next` if its current state allows it. Returns the
+        /// order as it was before, or `None` if another request moved it first.
+        pub async fn transition_order(order: String, next: OrderStatus, now: String)
+            -> Result<Option<Order>, ResponseError> {
+
+            open_db_connection().await;
+            let moved = DB.query("UPDATE type::thing('shop_order', $uuid) \
+                    SET status = $next, updated_at = $now \
+                    WHERE status IN $sources RETURN BEFORE")
+                .bind(("uuid", order))
+                .bind(("next", next))
+                .bind(("sources", OrderStatus::sources(next)))
+                .bind(("now", now))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match moved {
+                Ok(mut res) => {
+                    let before: Result<Vec<Order>, _> = res.take(0);
+                    match before {
+                        Ok(before) => Ok(before.into_iter().next()),
+                        Err(_) => Err(ResponseError::OrderTransitionFailure),
+                    }
+                },
+                Err(_) => Err(ResponseError::OrderTransitionFailure),
+            }
+        }
+
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct PaymentEventRecord {
+            provider: String,
+            payload: String,
+            received_at: String,
+        }
+
+        pub async fn has_payment_event(provider: String, event_id: String) -> bool {
+            open_db_connection().await;
+            let found = DB.select::<Option<PaymentEventRecord>>(("payment_event", format!("{provider}:{event_id}"))).await;
+            let _ = DB.invalidate().await;
+
+            matches!(found, Ok(Some(_)))
+        }
+
+        /// Claims a provider callback by its event id, which is the record id:
+        /// of two concurrent claims only one is created. Returns false if the
+        /// callback was claimed before.
+        pub async fn add_payment_event(provider: String, event_id: String, payload: String, received_at: String) -> bool {
+            open_db_connection().await;
+            let results = DB.create::<Option<PaymentEventRecord>>(("payment_event", format!("{provider}:{event_id}")))
+                .content(PaymentEventRecord { provider, payload, received_at })
+                .await;
+            let _ = DB.invalidate().await;
+
+            matches!(results, Ok(Some(_)))
+        }
+
+        /// Gives up the claim of a callback that could not be handled.
+        pub async fn remove_payment_event(provider: String, event_id: String) {
+            open_db_connection().await;
+            let removed = DB.delete::<Option<PaymentEventRecord>>(("payment_event", format!("{provider}:{event_id}"))).await;
+            let _ = DB.invalidate().await;
+
+            if let Err(e) = removed {
+                println!("error in removing payment event {provider}:{event_id}: {:?}", e);
+            }
+        }
+
+        /// Assigns the next invoice number and stores the invoice in one
+        /// transaction, so numbers have no gaps. Issued invoices can neither be
+        /// updated nor deleted, and each invoice can be cancelled only once.
+        pub async fn add_invoice(new_invoice: Invoice) -> Result<Invoice, ResponseError> {
+            open_db_connection().await;
+            let uuid = new_invoice.uuid.clone();
+            let created = DB.query("DEFINE EVENT IF NOT EXISTS immutable ON TABLE invoice \
+                    WHEN $event = 'DELETE' OR ($event = 'UPDATE' AND $before.number > 0) \
+                    THEN { THROW 'invoices are immutable' }")
+                .query("BEGIN TRANSACTION")
+                .query("IF $invoice.cancels AND count(SELECT uuid FROM invoice WHERE cancels = $invoice.cancels) > 0 \
+                    { THROW 'invoice is already cancelled' }")
+                .query("UPSERT invoice_counter:invoice SET value += 1")
+                .query("LET $number = (SELECT VALUE value FROM ONLY invoice_counter:invoice)")
+                .query("CREATE type::thing('invoice', $uuid) CONTENT $invoice")
+                .query("UPDATE type::thing('invoice', $uuid) SET number = $number")
+                .query("COMMIT TRANSACTION")
+                .bind(("uuid", uuid.clone()))
+                .bind(("invoice", new_invoice))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match created.map(|res| res.check()) {
+                Ok(Ok(_)) => get_invoice(uuid).await.ok_or(ResponseError::InvoiceCreationFailure),
+                Ok(Err(e)) | Err(e) => {
+                    println!("error in adding invoice: {:?}",e);
+                    Err(ResponseError::InvoiceCreationFailure)
+                }
+            }
+        }
+
+        pub async fn get_invoice(invoice: String) -> Option<Invoice> {
+            open_db_connection().await;
+            let found = DB.select(("invoice", invoice)).await;
+            let _ = DB.invalidate().await;
+
+            found.ok().flatten()
+        }
+
+        /// The invoice currently in force for an order, i.e. not cancelled.
+        pub async fn get_invoice_for_order(order: String) -> Option<Invoice> {
+            open_db_connection().await;
+            let invoices = DB.query("SELECT * FROM invoice WHERE order = $order AND kind = 'Invoice' \
+                    AND number NOT IN (SELECT VALUE cancels FROM invoice WHERE order = $order AND kind = 'Cancellation') \
+                    ORDER BY number DESC LIMIT 1")
+                .bind(("order", order))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match invoices {
+                Ok(mut res) => res.take::<Vec<Invoice>>(0).ok().and_then(|i| i.into_iter().next()),
+                Err(_) => None,
+            }
+        }
+
+        pub async fn get_invoices_for_user(user: String) -> Option<Vec<Invoice>> {
+            open_db_connection().await;
+            let invoices = DB.query("SELECT * FROM invoice WHERE user = $user ORDER BY number DESC")
+                .bind(("user", user))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match invoices {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        pub async fn get_all_invoices() -> Option<Vec<Invoice>> {
+            open_db_connection().await;
+            let invoices = DB.query("SELECT * FROM invoice ORDER BY number DESC").await;
+            let _ = DB.invalidate().await;
+
+            match invoices {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        pub async fn get_orders_for_user(user: String) -> Option<Vec<Order>> {
+            open_db_connection().await;
+            let orders = DB.query("SELECT * FROM shop_order WHERE user = $user ORDER BY created_at DESC")
+                .bind(("user", user))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match orders {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        pub async fn get_ticket(ticket: String) -> Option<Ticket> {
+            open_db_connection().await;
+            let found = DB.select(("ticket", ticket)).await;
+            let _ = DB.invalidate().await;
+
+            found.ok().flatten()
+        }
+
+        /// Tickets owned by the user. Email addresses are not verified, so
+        /// tickets without an owner are not matched by address.
+        pub async fn get_wallet_tickets(user: String) -> Option<Vec<Ticket>> {
+            open_db_connection().await;
+            let tickets = DB.query("SELECT * FROM ticket WHERE owner = $user AND transferred_to = NONE")
+                .bind(("user", user))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match tickets {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        pub async fn get_events(uuids: Vec<String>) -> Option<Vec<Event>> {
+            open_db_connection().await;
+            let events = DB.query("SELECT * FROM event WHERE uuid IN $uuids ORDER BY starts_at")
+                .bind(("uuids", uuids))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match events {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        /// Revokes a ticket that was not used yet and stores its replacement,
+        /// both or neither.
+        pub async fn transfer_ticket(ticket: String, replacement: Ticket) -> Result<Ticket, ResponseError> {
+            open_db_connection().await;
+            let uuid = replacement.uuid.clone();
+            let transferred = DB.query("BEGIN TRANSACTION")
+                .query("LET $revoked = UPDATE type::thing('ticket', $ticket) \
+                    SET status = 'Cancelled', transferred_to = $uuid \
+                    WHERE status = 'Valid' AND transferred_to = NONE RETURN BEFORE")
+                .query("IF array::len($revoked) = 0 { THROW 'ticket cannot be transferred' }")
+                .query("CREATE type::thing('ticket', $uuid) CONTENT $replacement")
+                .query("COMMIT TRANSACTION")
+                .bind(("ticket", ticket))
+                .bind(("uuid", uuid.clone()))
+                .bind(("replacement", replacement))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match transferred.map(|res| res.check()) {
+                Ok(Ok(_)) => get_ticket(uuid).await.ok_or(ResponseError::TicketNotFound),
+                Ok(Err(e)) | Err(e) => {
+                    println!("error in transferring ticket: {:?}",e);
+                    Err(ResponseError::TicketTransferFailure)
+                }
+            }
+        }
+
+        pub async fn set_event_cancelled(event: String) -> Result<(), ResponseError> {
+            open_db_connection().await;
+            let updated = DB.query("UPDATE event SET cancelled = true WHERE uuid = $event")
+                .bind(("event", event))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match updated {
+                Ok(_) => Ok(()),
+                Err(_) => Err(ResponseError::EventNotFound),
+            }
+        }
+
+        /// Saves the fields admins edit; dates, slug and cancellation stay.
+        pub async fn update_event(event: Event) -> Option<Event> {
+            open_db_connection().await;
+            let updated = DB.query("UPDATE event SET title = $title, poster = $poster, gallery_url = $gallery_url, \
+                    survey_url = $survey_url WHERE uuid = $uuid")
+                .bind(("uuid", event.uuid))
+                .bind(("title", event.title))
+                .bind(("poster", event.poster))
+                .bind(("gallery_url", event.gallery_url))
+                .bind(("survey_url", event.survey_url))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match updated {
+                Ok(mut res) => {
+                    let saved: Result<Vec<Event>, _> = res.take(0);
+                    saved.ok().and_then(|s| s.into_iter().next())
+                },
+                Err(e) => {
+                    println!("error in updating event: {:?}",e);
+                    None
+                }
+            }
+        }
+
+        /// Marks an unused ticket as refunded. Returns false if it was used or
+        /// invalidated already.
+        pub async fn refund_ticket(ticket: String) -> bool {
+            open_db_connection().await;
+            let refunded = DB.query("UPDATE type::thing('ticket', $uuid) SET status = 'Refunded' \
+                    WHERE status = 'Valid' RETURN BEFORE")
+                .bind(("uuid", ticket))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match refunded {
+                Ok(mut res) => res.take::<Vec<Ticket>>(0).map(|t| !t.is_empty()).unwrap_or(false),
+                Err(_) => false,
+            }
+        }
+
+        /// Invalidates every ticket of a cancelled event: sold ones become
+        /// refunded, the rest cancelled.
+        pub async fn invalidate_event_tickets(event: String) -> Result<(), ResponseError> {
+            open_db_connection().await;
+            let updated = DB.query("UPDATE ticket \
+                    SET status = IF order != NONE THEN 'Refunded' ELSE 'Cancelled' END \
+                    WHERE event = $event AND status NOT IN ['Refunded', 'Cancelled']")
+                .bind(("event", event))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match updated {
+                Ok(_) => Ok(()),
+                Err(_) => Err(ResponseError::RefundFailure),
+            }
+        }
+
+        pub async fn get_tickets_for_order(order: String) -> Option<Vec<Ticket>> {
+            open_db_connection().await;
+            let tickets = DB.query("SELECT * FROM ticket WHERE order = $order")
+                .bind(("order", order))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match tickets {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        /// Paid orders with a line for any of the given references.
+        pub async fn get_paid_orders_containing(references: Vec<String>) -> Option<Vec<Order>> {
+            open_db_connection().await;
+            let orders = DB.query("SELECT * FROM shop_order WHERE status IN ['Paid', 'Fulfilled'] \
+                    AND items.reference CONTAINSANY $references")
+                .bind(("references", references))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match orders {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        /// Stores refunded quantities, unless the order was refunded from
+        /// elsewhere since it was read. Returns false in that case.
+        pub async fn update_order_refunds(order: Order, previous_refunded_cents: i64) -> bool {
+            open_db_connection().await;
+            let updated = DB.query("UPDATE type::thing('shop_order', $uuid) \
+                    SET items = $items, refunded_cents = $refunded_cents, updated_at = $now \
+                    WHERE refunded_cents = $previous OR (refunded_cents = NONE AND $previous = 0)")
+                .bind(("uuid", order.uuid))
+                .bind(("items", order.items))
+                .bind(("refunded_cents", order.refunded_cents))
+                .bind(("now", order.updated_at))
+                .bind(("previous", previous_refunded_cents))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match updated {
+                Ok(mut res) => res.take::<Vec<Order>>(0).map(|o| !o.is_empty()).unwrap_or(false),
+                Err(_) => false,
+            }
+        }
+
+        pub async fn add_refund(new_refund: Refund) -> Option<Refund> {
+            open_db_connection().await;
+            let results = DB.create(("refund", new_refund.uuid.to_string()))
+                .content(new_refund)
+                .await;
+            let _ = DB.invalidate().await;
+
+            match results {
+                Ok(created_refund) => created_refund,
+                Err(e) => {
+                    println!("error in adding refund: {:?}",e);
+                    None
+                }
+            }
+        }
+
+        pub async fn get_refunds_for_event(event: String) -> Option<Vec<Refund>> {
+            open_db_connection().await;
+            let refunds = DB.query("SELECT * FROM refund WHERE event = $event ORDER BY created_at DESC")
+                .bind(("event", event))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match refunds {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        pub async fn get_discount(code: String) -> Option<DiscountCode> {
+            open_db_connection().await;
+            let found = DB.select(("discount", code)).await;
+            let _ = DB.invalidate().await;
+
+            found.ok().flatten()
+        }
+
+        pub async fn get_discounts() -> Option<Vec<DiscountCode>> {
+            open_db_connection().await;
+            let discounts = DB.query("SELECT * FROM discount ORDER BY code").await;
+            let _ = DB.invalidate().await;
+
+            match discounts {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        pub async fn add_discount(new_discount: DiscountCode) -> Option<DiscountCode> {
+            open_db_connection().await;
+            let results = DB.create(("discount", new_discount.code.clone()))
+                .content(new_discount)
+                .await;
+            let _ = DB.invalidate().await;
+
+            match results {
+                Ok(created_discount) => created_discount,
+                Err(e) => {
+                    println!("error in adding discount: {:?}",e);
+                    None
+                }
+            }
+        }
+
+        /// Counts a use of the code unless its limit is reached in the meantime.
+        pub async fn use_discount(code: String) -> bool {
+            open_db_connection().await;
+            let used = DB.query("UPDATE type::thing('discount', $code) SET uses += 1 \
+                    WHERE active = true AND (max_uses = NONE OR uses < max_uses)")
+                .bind(("code", code))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match used {
+                Ok(mut res) => res.take::<Vec<DiscountCode>>(0).map(|d| !d.is_empty()).unwrap_or(false),
+                Err(_) => false,
+            }
+        }
+
+        pub async fn release_discount(code: String) {
+            open_db_connection().await;
+            let _ = DB.query("UPDATE type::thing('discount', $code) SET uses = math::max([0, uses - 1])")
+                .bind(("code", code))
+                .await;
+            let _ = DB.invalidate().await;
+        }
+
+        pub async fn get_voucher(code: String) -> Option<GiftVoucher> {
+            open_db_connection().await;
+            let found = DB.select(("voucher", code)).await;
+            let _ = DB.invalidate().await;
+
+            found.ok().flatten()
+        }
+
+        pub async fn get_vouchers() -> Option<Vec<GiftVoucher>> {
+            open_db_connection().await;
+            let vouchers = DB.query("SELECT * FROM voucher ORDER BY created_at DESC").await;
+            let _ = DB.invalidate().await;
+
+            match vouchers {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        pub async fn get_vouchers_for_order(order: String) -> Option<Vec<GiftVoucher>> {
+            open_db_connection().await;
+            let vouchers = DB.query("SELECT * FROM voucher WHERE order = $order ORDER BY created_at")
+                .bind(("order", order))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match vouchers {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        pub async fn add_voucher(new_voucher: GiftVoucher) -> Option<GiftVoucher> {
+            open_db_connection().await;
+            let results = DB.create(("voucher", new_voucher.code.clone()))
+                .content(new_voucher)
+                .await;
+            let _ = DB.invalidate().await;
+
+            match results {
+                Ok(created_voucher) => created_voucher,
+                Err(e) => {
+                    println!("error in adding voucher: {:?}",e);
+                    None
+                }
+            }
+        }
+
+        /// Takes `amount` off the balance, failing if it does not cover it.
+        pub async fn debit_voucher(code: String, amount: i64) -> bool {
+            open_db_connection().await;
+            let debited = DB.query("UPDATE type::thing('voucher', $code) SET balance_cents -= $amount \
+                    WHERE balance_cents >= $amount")
+                .bind(("code", code))
+                .bind(("amount", amount))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match debited {
+                Ok(mut res) => res.take::<Vec<GiftVoucher>>(0).map(|v| !v.is_empty()).unwrap_or(false),
+                Err(_) => false,
+            }
+        }
+
+        pub async fn credit_voucher(code: String, amount: i64) {
+            open_db_connection().await;
+            let _ = DB.query("UPDATE type::thing('voucher', $code) SET balance_cents += $amount")
+                .bind(("code", code))
+                .bind(("amount", amount))
+                .await;
+            let _ = DB.invalidate().await;
+        }
+
+        pub async fn add_redemption(new_redemption: Redemption) -> Option<Redemption> {
+            open_db_connection().await;
+            let results = DB.create(("redemption", new_redemption.uuid.to_string()))
+                .content(new_redemption)
+                .await;
+            let _ = DB.invalidate().await;
+
+            match results {
+                Ok(created_redemption) => created_redemption,
+                Err(e) => {
+                    println!("error in adding redemption: {:?}",e);
+                    None
+                }
+            }
+        }
+
+        pub async fn get_redemption_report() -> Option<Vec<RedemptionReport>> {
+            open_db_connection().await;
+            let report = DB.query("SELECT code, kind, count() AS redemptions, math::sum(amount_cents) AS total_cents \
+                    FROM redemption GROUP BY code, kind ORDER BY code")
+                .await;
+            let _ = DB.invalidate().await;
+
+            match report {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        pub async fn set_discount_active(code: String, active: bool) -> Result<(), ResponseError> {
+            open_db_connection().await;
+            let updated = DB.query("UPDATE type::thing('discount', $code) SET active = $active")
+                .bind(("code", code))
+                .bind(("active", active))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match updated {
+                Ok(_) => Ok(()),
+                Err(e) => {
+                    println!("error in updating discount: {:?}",e);
+                    Err(ResponseError::DiscountInvalid)
+                }
+            }
+        }
+
+        /// Paid orders with merch that has not been handed over yet, oldest first.
+        pub async fn get_orders_to_pack() -> Option<Vec<Order>> {
+            open_db_connection().await;
+            let orders = DB.query("SELECT * FROM shop_order WHERE status = 'Paid' \
+                    AND items.kind CONTAINS 'Merch' ORDER BY created_at")
+                .await;
+            let _ = DB.invalidate().await;
+
+            match orders {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        pub async fn add_queued_mails(mails: Vec<QueuedMail>) -> Result<(), ResponseError> {
+            open_db_connection().await;
+            let inserted = DB.query("INSERT INTO mail_queue $mails")
+                .bind(("mails", mails))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match inserted.and_then(|res| res.check()) {
+                Ok(_) => Ok(()),
+                Err(e) => {
+                    println!("error in queueing mails: {:?}",e);
+                    Err(ResponseError::MailFailure)
+                }
+            }
+        }
+
+        /// Queued mails that may be sent at `now`, transactional ones first.
+        pub async fn get_due_mails(now: String, limit: u32) -> Option<Vec<QueuedMail>> {
+            open_db_connection().await;
+            let mails = DB.query("SELECT * FROM mail_queue WHERE status = 'Queued' AND <datetime> send_after <= <datetime> $now \
+                    ORDER BY bulk, created_at LIMIT $limit")
+                .bind(("now", now))
+                .bind(("limit", limit))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match mails {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        pub async fn set_mail_sent(mail: String, now: String) {
+            open_db_connection().await;
+            let _ = DB.query("UPDATE mail_queue SET status = 'Sent', sent_at = $now WHERE uuid = $uuid")
+                .bind(("uuid", mail))
+                .bind(("now", now))
+                .await;
+            let _ = DB.invalidate().await;
+        }
+
+        pub async fn set_mail_failed(mail: String, attempts: u32, give_up: bool, retry_at: String) {
+            open_db_connection().await;
+            let _ = DB.query("UPDATE mail_queue SET attempts = $attempts, send_after = $retry_at, \
+                    status = IF $give_up THEN 'Failed' ELSE 'Queued' END WHERE uuid = $uuid")
+                .bind(("uuid", mail))
+                .bind(("attempts", attempts))
+                .bind(("give_up", give_up))
+                .bind(("retry_at", retry_at))
+                .await;
+            let _ = DB.invalidate().await;
+        }
+
+        pub async fn add_subscriber(new_subscriber: Subscriber) -> Option<Subscriber> {
+            open_db_connection().await;
+            let results = DB.create(("subscriber", new_subscriber.uuid.to_string()))
+                .content(new_subscriber)
+                .await;
+            let _ = DB.invalidate().await;
+
+            match results {
+                Ok(created_subscriber) => created_subscriber,
+                Err(e) => {
+                    println!("error in adding subscriber: {:?}",e);
+                    None
+                }
+            }
+        }
+
+        pub async fn update_subscriber(subscriber: Subscriber) -> Option<Subscriber> {
+            open_db_connection().await;
+            let results = DB.update(("subscriber", subscriber.uuid.to_string()))
+                .content(subscriber)
+                .await;
+            let _ = DB.invalidate().await;
+
+            match results {
+                Ok(updated_subscriber) => updated_subscriber,
+                Err(e) => {
+                    println!("error in updating subscriber: {:?}",e);
+                    None
+                }
+            }
+        }
+
+        pub async fn get_subscriber(subscriber: String) -> Option<Subscriber> {
+            open_db_connection().await;
+            let found = DB.select(("subscriber", subscriber)).await;
+            let _ = DB.invalidate().await;
+
+            found.ok().flatten()
+        }
+
+        pub async fn get_subscriber_by_email(email: String) -> Option<Subscriber> {
+            open_db_connection().await;
+            let subscriber = DB.query("SELECT * FROM subscriber WHERE email = $email LIMIT 1")
+                .bind(("email", email))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match subscriber {
+                Ok(mut res) => res.take::<Vec<Subscriber>>(0).ok().and_then(|s| s.into_iter().next()),
+                Err(_) => None,
+            }
+        }
+
+        pub async fn get_confirmed_subscribers() -> Option<Vec<Subscriber>> {
+            open_db_connection().await;
+            let subscribers = DB.query("SELECT * FROM subscriber WHERE status = 'Confirmed'").await;
+            let _ = DB.invalidate().await;
+
+            match subscribers {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        pub async fn get_subscriber_counts() -> Option<SubscriberCounts> {
+            open_db_connection().await;
+            let counts = DB.query("RETURN { \
+                    pending: count(SELECT * FROM subscriber WHERE status = 'Pending'), \
+                    confirmed: count(SELECT * FROM subscriber WHERE status = 'Confirmed'), \
+                    unsubscribed: count(SELECT * FROM subscriber WHERE status = 'Unsubscribed') }")
+                .await;
+            let _ = DB.invalidate().await;
+
+            match counts {
+                Ok(mut res) => res.take::<Option<SubscriberCounts>>(0).ok().flatten(),
+                Err(_) => None,
+            }
+        }
+
+        pub async fn add_campaign(new_campaign: Campaign) -> Option<Campaign> {
+            open_db_connection().await;
+            let results = DB.create(("campaign", new_campaign.uuid.to_string()))
+                .content(new_campaign)
+                .await;
+            let _ = DB.invalidate().await;
+
+            match results {
+                Ok(created_campaign) => created_campaign,
+                Err(e) => {
+                    println!("error in adding campaign: {:?}",e);
+                    None
+                }
+            }
+        }
+
+        /// Campaigns, newest first, with how many of their mails went out.
+        pub async fn get_campaign_stats() -> Option<Vec<CampaignStats>> {
+            open_db_connection().await;
+            let stats = DB.query("SELECT $this.* AS campaign, \
+                    count(SELECT * FROM mail_queue WHERE campaign = $parent.uuid AND status = 'Sent') AS sent, \
+                    count(SELECT * FROM mail_queue WHERE campaign = $parent.uuid AND status = 'Failed') AS failed \
+                    FROM campaign ORDER BY campaign.created_at DESC")
+                .await;
+            let _ = DB.invalidate().await;
+
+            match stats {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        /// Events starting at or after `from`, soonest first.
+        pub async fn get_events_since(from: String) -> Option<Vec<Event>> {
+            open_db_connection().await;
+            let events = DB.query("SELECT * FROM event WHERE <datetime> starts_at >= <datetime> $from ORDER BY starts_at")
+                .bind(("from", from))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match events {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+        /// Creates the entry or replaces the one with the same uuid.
+        pub async fn save_feed_item(item: FeedItem) -> Option<FeedItem> {
+            open_db_connection().await;
+            let results = DB.query("UPSERT type::thing('feed_item', $uuid) CONTENT $item")
+                .bind(("uuid", item.uuid.clone()))
+                .bind(("item", item))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match results {
+                Ok(mut res) => {
+                    let saved: Result<Vec<FeedItem>, _> = res.take(0);
+                    saved.ok().and_then(|s| s.into_iter().next())
+                },
+                Err(e) => {
+                    println!("error in saving feed item: {:?}",e);
+                    None
+                }
+            }
+        }
+
+        pub async fn get_feed_item(uuid: String) -> Option<FeedItem> {
+            open_db_connection().await;
+            let found = DB.select(("feed_item", uuid)).await;
+            let _ = DB.invalidate().await;
+
+            found.ok().flatten()
+        }
+
+        /// Removes the entry if there is one.
+        pub async fn delete_feed_item(uuid: String) -> Option<FeedItem> {
+            open_db_connection().await;
+            let deleted = DB.delete(("feed_item", uuid)).await;
+            let _ = DB.invalidate().await;
+
+            match deleted {
+                Ok(deleted_item) => deleted_item,
+                Err(e) => {
+                    println!("error in deleting feed item: {:?}",e);
+                    None
+                }
+            }
+        }
+
+        /// The latest entries published by `now`, newest first.
+        pub async fn get_feed_items(now: String, limit: u32) -> Option<Vec<FeedItem>> {
+            open_db_connection().await;
+            let items = DB.query("SELECT * FROM feed_item WHERE <datetime> published_at <= <datetime> $now \
+                    ORDER BY published_at DESC LIMIT $limit")
+                .bind(("now", now))
+                .bind(("limit", limit))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match items {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+        pub async fn add_post(new_post: Post) -> Option<Post> {
+            open_db_connection().await;
+            let results = DB.create(("post", new_post.uuid.to_string()))
+                .content(new_post)
+                .await;
+            let _ = DB.invalidate().await;
+
+            match results {
+                Ok(created_post) => created_post,
+                Err(e) => {
+                    println!("error in adding post: {:?}",e);
+                    None
+                }
+            }
+        }
+
+        pub async fn update_post(post: Post) -> Option<Post> {
+            open_db_connection().await;
+            let results = DB.update(("post", post.uuid.to_string()))
+                .content(post)
+                .await;
+            let _ = DB.invalidate().await;
+
+            match results {
+                Ok(updated_post) => updated_post,
+                Err(e) => {
+                    println!("error in updating post: {:?}",e);
+                    None
+                }
+            }
+        }
+
+        pub async fn get_post(post: String) -> Option<Post> {
+            open_db_connection().await;
+            let found = DB.select(("post", post)).await;
+            let _ = DB.invalidate().await;
+
+            found.ok().flatten()
+        }
+
+        pub async fn get_post_by_slug(slug: String) -> Option<Post> {
+            open_db_connection().await;
+            let post = DB.query("SELECT * FROM post WHERE slug = $slug LIMIT 1")
+                .bind(("slug", slug))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match post {
+                Ok(mut res) => {
+                    let found: Result<Vec<Post>, _> = res.take(0);
+                    found.ok().and_then(|f| f.into_iter().next())
+                },
+                Err(_) => None,
+            }
+        }
+
+        /// All posts including drafts, newest first.
+        pub async fn get_posts() -> Option<Vec<Post>> {
+            open_db_connection().await;
+            let posts = DB.query("SELECT * FROM post ORDER BY created_at DESC").await;
+            let _ = DB.invalidate().await;
+
+            match posts {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        /// Posts visible at `now`, latest first.
+        pub async fn get_published_posts(now: String) -> Option<Vec<Post>> {
+            open_db_connection().await;
+            let posts = DB.query("SELECT * FROM post WHERE published_at != NONE AND <datetime> published_at <= <datetime> $now \
+                    ORDER BY published_at DESC")
+                .bind(("now", now))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match posts {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+        /// Keyed by content hash, so a second record for the same content
+        /// fails instead of duplicating the file.
+        pub async fn add_media(file: MediaFile) -> Option<MediaFile> {
+            open_db_connection().await;
+            let results = DB.create(("media", file.hash.clone()))
+                .content(file)
+                .await;
+            let _ = DB.invalidate().await;
+
+            match results {
+                Ok(created) => created,
+                Err(e) => {
+                    println!("error in adding media: {:?}",e);
+                    None
+                }
+            }
+        }
+
+        pub async fn get_media_by_hash(hash: String) -> Option<MediaFile> {
+            open_db_connection().await;
+            let found = DB.select(("media", hash)).await;
+            let _ = DB.invalidate().await;
+
+            found.ok().flatten()
+        }
+
+        /// All uploads, newest first.
+        pub async fn get_media() -> Option<Vec<MediaFile>> {
+            open_db_connection().await;
+            let files = DB.query("SELECT * FROM media ORDER BY created_at DESC").await;
+            let _ = DB.invalidate().await;
+
+            match files {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        pub async fn delete_media(hash: String) -> Option<MediaFile> {
+            open_db_connection().await;
+            let deleted = DB.delete(("media", hash)).await;
+            let _ = DB.invalidate().await;
+
+            match deleted {
+                Ok(deleted_file) => deleted_file,
+                Err(e) => {
+                    println!("error in deleting media: {:?}",e);
+                    None
+                }
+            }
+        }
+        pub async fn add_artist(new_artist: Artist) -> Option<Artist> {
+            open_db_connection().await;
+            let results = DB.create(("artist", new_artist.uuid.to_string()))
+                .content(new_artist)
+                .await;
+            let _ = DB.invalidate().await;
+
+            match results {
+                Ok(created_artist) => created_artist,
+                Err(e) => {
+                    println!("error in adding artist: {:?}",e);
+                    None
+                }
+            }
+        }
+
+        pub async fn update_artist(artist: Artist) -> Option<Artist> {
+            open_db_connection().await;
+            let results = DB.update(("artist", artist.uuid.to_string()))
+                .content(artist)
+                .await;
+            let _ = DB.invalidate().await;
+
+            match results {
+                Ok(updated_artist) => updated_artist,
+                Err(e) => {
+                    println!("error in updating artist: {:?}",e);
+                    None
+                }
+            }
+        }
+
+        pub async fn get_artist(artist: String) -> Option<Artist> {
+            open_db_connection().await;
+            let found = DB.select(("artist", artist)).await;
+            let _ = DB.invalidate().await;
+
+            found.ok().flatten()
+        }
+
+        pub async fn get_artist_by_slug(slug: String) -> Option<Artist> {
+            open_db_connection().await;
+            let artist = DB.query("SELECT * FROM artist WHERE slug = $slug LIMIT 1")
+                .bind(("slug", slug))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match artist {
+                Ok(mut res) => {
+                    let found: Result<Vec<Artist>, _> = res.take(0);
+                    found.ok().and_then(|f| f.into_iter().next())
+                },
+                Err(_) => None,
+            }
+        }
+
+        /// All artists by name.
+        pub async fn get_artists() -> Option<Vec<Artist>> {
+            open_db_connection().await;
+            let artists = DB.query("SELECT * FROM artist ORDER BY name").await;
+            let _ = DB.invalidate().await;
+
+            match artists {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        /// Creates the slot or replaces the one with the same uuid.
+        pub async fn save_performance(performance: Performance) -> Option<Performance> {
+            open_db_connection().await;
+            let results = DB.query("UPSERT type::thing('performance', $uuid) CONTENT $performance")
+                .bind(("uuid", performance.uuid.clone()))
+                .bind(("performance", performance))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match results {
+                Ok(mut res) => {
+                    let saved: Result<Vec<Performance>, _> = res.take(0);
+                    saved.ok().and_then(|s| s.into_iter().next())
+                },
+                Err(e) => {
+                    println!("error in saving performance: {:?}",e);
+                    None
+                }
+            }
+        }
+
+        pub async fn delete_performance(uuid: String) -> Option<Performance> {
+            open_db_connection().await;
+            let deleted = DB.delete(("performance", uuid)).await;
+            let _ = DB.invalidate().await;
+
+            match deleted {
+                Ok(deleted_performance) => deleted_performance,
+                Err(e) => {
+                    println!("error in deleting performance: {:?}",e);
+                    None
+                }
+            }
+        }
+
+        pub async fn get_event_performances(event: String) -> Option<Vec<Performance>> {
+            open_db_connection().await;
+            let performances = DB.query("SELECT * FROM performance WHERE event = $event")
+                .bind(("event", event))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match performances {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        pub async fn get_artist_performances(artist: String) -> Option<Vec<Performance>> {
+            open_db_connection().await;
+            let performances = DB.query("SELECT * FROM performance WHERE artist = $artist")
+                .bind(("artist", artist))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match performances {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+        pub async fn save_area(area: Area) -> Option<Area> {
+            open_db_connection().await;
+            // the count stays as it is, staff may be counting right now
+            let results = DB.query("UPSERT type::thing('area', $uuid) SET uuid = $uuid, name = $name, \
+                    capacity = $capacity, position = $position, inside = inside ?? 0")
+                .bind(("uuid", area.uuid))
+                .bind(("name", area.name))
+                .bind(("capacity", area.capacity))
+                .bind(("position", area.position))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match results {
+                Ok(mut res) => {
+                    let saved: Result<Vec<Area>, _> = res.take(0);
+                    saved.ok().and_then(|s| s.into_iter().next())
+                },
+                Err(e) => {
+                    println!("error in saving area: {:?}",e);
+                    None
+                }
+            }
+        }
+
+        pub async fn delete_area(uuid: String) -> Option<Area> {
+            open_db_connection().await;
+            let deleted = DB.delete(("area", uuid)).await;
+            let _ = DB.invalidate().await;
+
+            match deleted {
+                Ok(deleted_area) => deleted_area,
+                Err(e) => {
+                    println!("error in deleting area: {:?}",e);
+                    None
+                }
+            }
+        }
+
+        pub async fn get_areas() -> Option<Vec<Area>> {
+            open_db_connection().await;
+            let areas = DB.query("SELECT * FROM area ORDER BY position, name").await;
+            let _ = DB.invalidate().await;
+
+            match areas {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        /// Counts one person into the area, failing instead of going over
+        /// the capacity.
+        pub async fn enter_area(uuid: String) -> Result<Area, ResponseError> {
+            open_db_connection().await;
+            // the second statement tells a full area from a missing one
+            let entered = DB.query("UPDATE type::thing('area', $uuid) SET inside += 1 WHERE inside < capacity;
+                    SELECT * FROM type::thing('area', $uuid);")
+                .bind(("uuid", uuid))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match entered {
+                Ok(mut res) => {
+                    let updated: Result<Vec<Area>, _> = res.take(0);
+                    let existing: Result<Vec<Area>, _> = res.take(1);
+                    match updated.ok().and_then(|u| u.into_iter().next()) {
+                        Some(area) => Ok(area),
+                        None if existing.is_ok_and(|e| !e.is_empty()) => Err(ResponseError::AreaFull),
+                        None => Err(ResponseError::AreaNotFound),
+                    }
+                },
+                Err(_) => Err(ResponseError::AreaNotFound),
+            }
+        }
+
+        pub async fn leave_area(uuid: String) -> Result<Area, ResponseError> {
+            open_db_connection().await;
+            let left = DB.query("UPDATE type::thing('area', $uuid) SET inside = math::max([0, inside - 1])")
+                .bind(("uuid", uuid))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match left {
+                Ok(mut res) => {
+                    let updated: Result<Vec<Area>, _> = res.take(0);
+                    updated.ok().and_then(|u| u.into_iter().next()).ok_or(ResponseError::AreaNotFound)
+                },
+                Err(_) => Err(ResponseError::AreaNotFound),
+            }
+        }
+
+        pub async fn reset_area(uuid: String) -> Result<Area, ResponseError> {
+            open_db_connection().await;
+            let reset = DB.query("UPDATE type::thing('area', $uuid) SET inside = 0")
+                .bind(("uuid", uuid))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match reset {
+                Ok(mut res) => {
+                    let updated: Result<Vec<Area>, _> = res.take(0);
+                    updated.ok().and_then(|u| u.into_iter().next()).ok_or(ResponseError::AreaNotFound)
+                },
+                Err(_) => Err(ResponseError::AreaNotFound),
+            }
+        }
+
+        pub async fn add_occupancy_click(click: OccupancyClick) {
+            open_db_connection().await;
+            let created: Result<Option<OccupancyClick>, _> = DB.create(("occupancy_click", click.uuid.clone()))
+                .content(click)
+                .await;
+            let _ = DB.invalidate().await;
+
+            if let Err(e) = created {
+                println!("error in adding occupancy click: {:?}",e);
+            }
+        }
+        /// Puts the user in line, numbered after everyone before. Fails if
+        /// they are in line for the event already.
+        pub async fn add_waitlist_entry(entry: WaitlistEntry) -> Result<WaitlistEntry, ResponseError> {
+            open_db_connection().await;
+            let uuid = entry.uuid.clone();
+            let created = DB
+                .query("BEGIN TRANSACTION")
+                .query("IF count(SELECT uuid FROM waitlist_entry WHERE event = $entry.event AND user = $entry.user \
+                    AND status IN ['Waiting', 'Offered']) > 0 { THROW 'already on the waitlist' }")
+                .query("UPSERT waitlist_counter:entry SET value += 1")
+                .query("LET $number = (SELECT VALUE value FROM ONLY waitlist_counter:entry)")
+                .query("CREATE type::thing('waitlist_entry', $uuid) CONTENT $entry")
+                .query("UPDATE type::thing('waitlist_entry', $uuid) SET number = $number")
+                .query("COMMIT TRANSACTION")
+                .bind(("uuid", uuid.clone()))
+                .bind(("entry", entry))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match created.map(|res| res.check()) {
+                Ok(Ok(_)) => get_waitlist_entry(uuid).await.ok_or(ResponseError::WaitlistFailure),
+                Ok(Err(e)) | Err(e) => {
+                    println!("error in adding waitlist entry: {:?}",e);
+                    Err(ResponseError::WaitlistFailure)
+                }
+            }
+        }
+
+        pub async fn get_waitlist_entry(uuid: String) -> Option<WaitlistEntry> {
+            open_db_connection().await;
+            let entry = DB.select(("waitlist_entry", uuid)).await;
+            let _ = DB.invalidate().await;
+
+            entry.ok().flatten()
+        }
+
+        /// All entries of an event in line order.
+        pub async fn get_waitlist(event: String) -> Option<Vec<WaitlistEntry>> {
+            open_db_connection().await;
+            let entries = DB.query("SELECT * FROM waitlist_entry WHERE event = $event ORDER BY number")
+                .bind(("event", event))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match entries {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        pub async fn get_active_waitlist_entry(event: String, user: String) -> Option<WaitlistEntry> {
+            open_db_connection().await;
+            let entry = DB.query("SELECT * FROM waitlist_entry WHERE event = $event AND user = $user \
+                    AND status IN ['Waiting', 'Offered'] LIMIT 1")
+                .bind(("event", event))
+                .bind(("user", user))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match entry {
+                Ok(mut res) => res.take::<Vec<WaitlistEntry>>(0).ok().and_then(|e| e.into_iter().next()),
+                Err(_) => None,
+            }
+        }
+
+        /// The first in line who hasn't been offered anything yet.
+        pub async fn get_next_waiting(event: String) -> Option<WaitlistEntry> {
+            open_db_connection().await;
+            let entry = DB.query("SELECT * FROM waitlist_entry WHERE event = $event AND status = 'Waiting' \
+                    ORDER BY number LIMIT 1")
+                .bind(("event", event))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match entry {
+                Ok(mut res) => res.take::<Vec<WaitlistEntry>>(0).ok().and_then(|e| e.into_iter().next()),
+                Err(_) => None,
+            }
+        }
+
+        /// Waiting -> Offered. `None` if the entry left or got an offer in
+        /// the meantime.
+        pub async fn offer_waitlist_entry(uuid: String, offer: WaitlistOffer) -> Option<WaitlistEntry> {
+            open_db_connection().await;
+            let offered = DB.query("UPDATE type::thing('waitlist_entry', $uuid) SET status = 'Offered', offer = $offer \
+                    WHERE status = 'Waiting'")
+                .bind(("uuid", uuid))
+                .bind(("offer", offer))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match offered {
+                Ok(mut res) => res.take::<Vec<WaitlistEntry>>(0).ok().and_then(|e| e.into_iter().next()),
+                Err(_) => None,
+            }
+        }
+
+        /// Offered -> Claimed, unless the offer ran out.
+        pub async fn claim_waitlist_offer(uuid: String, order: String, now: String) -> Option<WaitlistEntry> {
+            open_db_connection().await;
+            let claimed = DB.query("UPDATE type::thing('waitlist_entry', $uuid) SET status = 'Claimed', offer.order = $order \
+                    WHERE status = 'Offered' AND <datetime> offer.expires_at > <datetime> $now")
+                .bind(("uuid", uuid))
+                .bind(("order", order))
+                .bind(("now", now))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match claimed {
+                Ok(mut res) => res.take::<Vec<WaitlistEntry>>(0).ok().and_then(|e| e.into_iter().next()),
+                Err(_) => None,
+            }
+        }
+
+        /// Takes an active entry out of line, returning it as it was.
+        pub async fn leave_waitlist(uuid: String) -> Option<WaitlistEntry> {
+            open_db_connection().await;
+            let left = DB.query("UPDATE type::thing('waitlist_entry', $uuid) SET status = 'Left' \
+                    WHERE status IN ['Waiting', 'Offered'] RETURN BEFORE")
+                .bind(("uuid", uuid))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match left {
+                Ok(mut res) => res.take::<Vec<WaitlistEntry>>(0).ok().and_then(|e| e.into_iter().next()),
+                Err(_) => None,
+            }
+        }
+
+        /// Offered -> Expired for all offers that ran out by `now`.
+        pub async fn expire_waitlist_offers(now: String) -> Option<Vec<WaitlistEntry>> {
+            open_db_connection().await;
+            let expired = DB.query("UPDATE waitlist_entry SET status = 'Expired' \
+                    WHERE status = 'Offered' AND <datetime> offer.expires_at <= <datetime> $now")
+                .bind(("now", now))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match expired {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        pub async fn add_waitlist_audit(audit: WaitlistAudit) {
+            open_db_connection().await;
+            let created: Result<Option<WaitlistAudit>, _> = DB.create(("waitlist_audit", audit.uuid.clone()))
+                .content(audit)
+                .await;
+            let _ = DB.invalidate().await;
+
+            if let Err(e) = created {
+                println!("error in adding waitlist audit: {:?}",e);
+            }
+        }
+
+        pub async fn get_waitlist_audit(event: String) -> Option<Vec<WaitlistAudit>> {
+            open_db_connection().await;
+            let audit = DB.query("SELECT * FROM waitlist_audit WHERE event = $event ORDER BY created_at")
+                .bind(("event", event))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match audit {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        /// Adds the job unless its key is taken by a queued or running job.
+        /// Returns whether it was added.
+        pub async fn add_job(job: Job) -> Result<bool, ResponseError> {
+            open_db_connection().await;
+            let uuid = job.uuid.clone();
+            let added = DB
+                .query("BEGIN TRANSACTION")
+                .query("LET $taken = $job.key != NONE AND count(SELECT uuid FROM job WHERE key = $job.key \
+                    AND status IN ['Queued', 'Running']) > 0")
+                .query("IF !$taken { CREATE type::thing('job', $uuid) CONTENT $job }")
+                .query("COMMIT TRANSACTION")
+                .bind(("uuid", uuid.clone()))
+                .bind(("job", job))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match added.map(|res| res.check()) {
+                Ok(Ok(_)) => Ok(get_job(uuid).await.is_some()),
+                Ok(Err(e)) | Err(e) => {
+                    println!("error in adding job: {:?}",e);
+                    Err(ResponseError::JobFailure)
+                }
+            }
+        }
+
+        pub async fn get_job(uuid: String) -> Option<Job> {
+            open_db_connection().await;
+            let job = DB.select(("job", uuid)).await;
+            let _ = DB.invalidate().await;
+
+            job.ok().flatten()
+        }
+
+        /// Latest first, optionally only those with `status`.
+        pub async fn get_jobs(status: Option<JobStatus>, limit: u32) -> Option<Vec<Job>> {
+            open_db_connection().await;
+            let jobs = match status {
+                Some(status) => DB.query("SELECT * FROM job WHERE status = $status ORDER BY run_at DESC LIMIT $limit")
+                    .bind(("status", status))
+                    .bind(("limit", limit))
+                    .await,
+                None => DB.query("SELECT * FROM job ORDER BY run_at DESC LIMIT $limit")
+                    .bind(("limit", limit))
+                    .await,
+            };
+            let _ = DB.invalidate().await;
+
+            match jobs {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        /// Queued jobs that may run at `now`, longest due first.
+        pub async fn get_due_jobs(now: String, limit: u32) -> Option<Vec<Job>> {
+            open_db_connection().await;
+            let jobs = DB.query("SELECT * FROM job WHERE status = 'Queued' AND <datetime> run_at <= <datetime> $now ORDER BY run_at LIMIT $limit")
+                .bind(("now", now))
+                .bind(("limit", limit))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match jobs {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        /// Queued -> Running, counting the attempt. `None` if another worker
+        /// took the job first.
+        pub async fn start_job(uuid: String, now: String) -> Option<Job> {
+            open_db_connection().await;
+            let started = DB.query("UPDATE type::thing('job', $uuid) SET status = 'Running', started_at = $now, \
+                    attempts += 1 WHERE status = 'Queued'")
+                .bind(("uuid", uuid))
+                .bind(("now", now))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match started {
+                Ok(mut res) => res.take::<Vec<Job>>(0).ok().and_then(|j| j.into_iter().next()),
+                Err(_) => None,
+            }
+        }
+
+        pub async fn finish_job(uuid: String, now: String) {
+            open_db_connection().await;
+            let _ = DB.query("UPDATE type::thing('job', $uuid) SET status = 'Done', finished_at = $now, last_error = NONE")
+                .bind(("uuid", uuid))
+                .bind(("now", now))
+                .await;
+            let _ = DB.invalidate().await;
+        }
+
+        /// Queues the job again for `retry_at`, or marks it dead without one.
+        pub async fn fail_job(uuid: String, error: String, retry_at: Option<String>, now: String) {
+            open_db_connection().await;
+            let failed = match retry_at {
+                Some(retry_at) => DB.query("UPDATE type::thing('job', $uuid) SET status = 'Queued', run_at = $retry_at, \
+                        last_error = $error")
+                    .bind(("retry_at", retry_at)),
+                None => DB.query("UPDATE type::thing('job', $uuid) SET status = 'Dead', finished_at = $now, \
+                        last_error = $error")
+                    .bind(("now", now)),
+            };
+            let _ = failed.bind(("uuid", uuid)).bind(("error", error)).await;
+            let _ = DB.invalidate().await;
+        }
+
+        /// Jobs still running since before `before` were cut off, e.g. by a
+        /// restart; they are retried like failed ones. Returns those marked dead.
+        pub async fn requeue_stale_jobs(before: String, now: String) -> Option<Vec<Job>> {
+            open_db_connection().await;
+            let stale = DB.query("UPDATE job SET status = 'Dead', finished_at = $now, last_error = 'cut off' \
+                    WHERE status = 'Running' AND <datetime> started_at < <datetime> $before AND attempts >= max_attempts")
+                .query("UPDATE job SET status = 'Queued', run_at = $now, last_error = 'cut off' \
+                    WHERE status = 'Running' AND <datetime> started_at < <datetime> $before")
+                .bind(("before", before))
+                .bind(("now", now))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match stale {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        /// Dead -> Queued with fresh attempts.
+        pub async fn retry_job(uuid: String, now: String) {
+            open_db_connection().await;
+            let _ = DB.query("UPDATE type::thing('job', $uuid) SET status = 'Queued', attempts = 0, run_at = $now, \
+                    finished_at = NONE WHERE status = 'Dead'")
+                .bind(("uuid", uuid))
+                .bind(("now", now))
+                .await;
+            let _ = DB.invalidate().await;
+        }
+
+        /// Moves a queued job's time to `now`.
+        pub async fn run_job_now(uuid: String, now: String) {
+            open_db_connection().await;
+            let _ = DB.query("UPDATE type::thing('job', $uuid) SET run_at = $now WHERE status = 'Queued'")
+                .bind(("uuid", uuid))
+                .bind(("now", now))
+                .await;
+            let _ = DB.invalidate().await;
+        }
+
+        /// Deletes a job that isn't running.
+        pub async fn delete_job(uuid: String) {
+            open_db_connection().await;
+            let _ = DB.query("DELETE type::thing('job', $uuid) WHERE status != 'Running'")
+                .bind(("uuid", uuid))
+                .await;
+            let _ = DB.invalidate().await;
+        }
+
+        /// Deletes jobs done before `before`; dead ones stay for a look.
+        pub async fn purge_jobs(before: String) {
+            open_db_connection().await;
+            let _ = DB.query("DELETE job WHERE status = 'Done' AND <datetime> finished_at < <datetime> $before")
+                .bind(("before", before))
+                .await;
+            let _ = DB.invalidate().await;
+        }
+
+        /// Orders still waiting for their payment that were placed before `before`.
+        pub async fn get_pending_orders_before(before: String) -> Option<Vec<Order>> {
+            open_db_connection().await;
+            let orders = DB.query("SELECT * FROM shop_order WHERE status = 'Pending' AND <datetime> created_at < <datetime> $before")
+                .bind(("before", before))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match orders {
+                Ok(mut res) => res.take(0).ok(),
+                Err(_) => None,
+            }
+        }
+
+        pub async fn set_notification_settings(user: String, settings: NotificationSettings) -> Option<User> {
+            open_db_connection().await;
+            let updated = DB.query("UPDATE user SET notifications = $settings WHERE uuid = $uuid")
+                .bind(("uuid", user))
+                .bind(("settings", settings))
+                .await;
+            let _ = DB.invalidate().await;
+
+            match updated {
+                Ok(mut res) => res.take::<Vec<User>>(0).ok().and_then(|u| u.into_iter().next()),
+                Err(_) => None,
+            }
+        }
+
+        pub async fn set_user_locale(user: String, locale: Locale) {
+            open_db_connection().await;
+            let _ = DB.query("UPDATE user SET locale = $locale WHERE uuid = $uuid")
+                .bind(("uuid", user))
+                .bind(("locale", locale))
+                .await;
+            let _ = DB.invalidate().await;
+        }
+
+        /// Records the mail as sent. False if it was sent before.
+        pub async fn add_event_mail(mail: EventMail) -> bool {
+            open_db_connection().await;
+            let created: Result<Option<EventMail>, _> = DB.create(("event_mail", mail.uuid.clone()))
+                .content(mail)
+                .await;
+            let _ = DB.invalidate().await;
+
+            matches!(created, Ok(Some(_)))
+        }
+
+        pub async fn delete_event_mail(uuid: String) {
+            open_db_connection().await;
+            let _: Result<Option<EventMail>, _> = DB.delete(("event_mail", uuid)).await;
+            let _ = DB.invalidate().await;
+        }
     }
 }
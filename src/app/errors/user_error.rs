@@ -1,3 +1,5 @@
+use crate::app::i18n::request_text;
+
 #[derive(Debug)]
 
 pub enum ResponseError {
@@ -5,6 +7,38 @@ pub enum ResponseError {
     UserUpdateFailure,
     UserCreationFailure,
     UserDeleteFailure,
+    EventNotFound,
+    EventInvalid,
+    TicketNotFound,
+    CheckInFailure,
+    TicketTransferFailure,
+    GuestNotFound,
+    GuestCreationFailure,
+    GuestQuotaExceeded,
+    OrderNotFound,
+    OrderCreationFailure,
+    OrderTransitionFailure,
+    SoldOut,
+    PaymentFailure,
+    InvoiceNotFound,
+    InvoiceCreationFailure,
+    RefundFailure,
+    MailFailure,
+    DiscountInvalid,
+    VoucherInvalid,
+    ProductNotFound,
+    DeliveryInvalid,
+    PostNotFound,
+    MediaNotFound,
+    MediaInvalid,
+    MediaInUse,
+    MediaStorageFailure,
+    ArtistNotFound,
+    AreaNotFound,
+    AreaFull,
+    WaitlistFailure,
+    OfferInvalid,
+    JobFailure,
 }
 
 pub type ErrorMessage = String;
@@ -14,12 +48,46 @@ pub trait ResponseErrorTrait {
 }
 
 impl ResponseErrorTrait for ErrorMessage {
+    /// The message in the language of the request.
     fn create(user_error: ResponseError) -> ErrorMessage {
-        match user_error {
-            ResponseError::UserNotFound => ErrorMessage::from("User not found"),
-            ResponseError::UserUpdateFailure => ErrorMessage::from("failed to update user"),
-            ResponseError::UserCreationFailure => ErrorMessage::from("failed to create user"),
-            ResponseError::UserDeleteFailure => ErrorMessage::from("failed to delete user"),
-        }
+        let key = match user_error {
+            ResponseError::UserNotFound => "error-user-not-found",
+            ResponseError::UserUpdateFailure => "error-user-update",
+            ResponseError::UserCreationFailure => "error-user-create",
+            ResponseError::UserDeleteFailure => "error-user-delete",
+            ResponseError::EventNotFound => "error-event-not-found",
+            ResponseError::EventInvalid => "error-event-invalid",
+            ResponseError::TicketNotFound => "error-ticket-not-found",
+            ResponseError::CheckInFailure => "error-check-in",
+            ResponseError::TicketTransferFailure => "error-ticket-transfer",
+            ResponseError::GuestNotFound => "error-guest-not-found",
+            ResponseError::GuestCreationFailure => "error-guest-create",
+            ResponseError::GuestQuotaExceeded => "error-guest-quota",
+            ResponseError::OrderNotFound => "error-order-not-found",
+            ResponseError::OrderCreationFailure => "error-order-create",
+            ResponseError::OrderTransitionFailure => "error-order-status",
+            ResponseError::SoldOut => "sold-out",
+            ResponseError::PaymentFailure => "error-payment",
+            ResponseError::InvoiceNotFound => "error-invoice-not-found",
+            ResponseError::InvoiceCreationFailure => "error-invoice-create",
+            ResponseError::RefundFailure => "error-refund",
+            ResponseError::MailFailure => "error-mail",
+            ResponseError::DiscountInvalid => "error-discount-invalid",
+            ResponseError::VoucherInvalid => "error-voucher-invalid",
+            ResponseError::ProductNotFound => "error-product-not-found",
+            ResponseError::DeliveryInvalid => "error-delivery-invalid",
+            ResponseError::PostNotFound => "error-post-not-found",
+            ResponseError::MediaNotFound => "error-media-not-found",
+            ResponseError::MediaInvalid => "error-media-invalid",
+            ResponseError::MediaInUse => "error-media-in-use",
+            ResponseError::MediaStorageFailure => "error-media-storage",
+            ResponseError::ArtistNotFound => "error-artist-not-found",
+            ResponseError::AreaNotFound => "error-area-not-found",
+            ResponseError::AreaFull => "error-area-full",
+            ResponseError::WaitlistFailure => "error-waitlist",
+            ResponseError::OfferInvalid => "error-offer-invalid",
+            ResponseError::JobFailure => "error-job",
+        };
+        request_text(key)
     }
 }
\ No newline at end of file
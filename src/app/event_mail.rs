@@ -0,0 +1,208 @@
+//! Mails to the attendees of an event: a reminder the day before doors, and
+//! thanks with the photos and a survey after the night. A recurring job
+//! looks for events that are due; each mail goes out once per event to every
+//! address holding a ticket, unless the account behind it turned it off in
+//! its settings. Addresses without an account only get the reminder.
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use chrono::{DateTime, Duration, Utc};
+        use std::collections::BTreeMap;
+
+        use crate::app::database;
+        use crate::app::errors::ResponseError;
+        use crate::app::i18n::Locale;
+        use crate::app::ical::{end_of, parse_berlin};
+        use crate::app::invoice::seller_from_env;
+        use crate::app::mail::{self, site_url, Mail};
+        use crate::app::model::{Event, User};
+        use crate::app::model::event_mail::{EventMail, EventMailKind};
+        use crate::app::model::ticket::TicketStatus;
+        use crate::app::time::{now, stamp};
+
+        /// Reminders go out this long before doors.
+        const REMINDER_HOURS: i64 = 24;
+        /// Thanks go out the next morning rather than at closing time.
+        const FOLLOW_UP_HOURS: i64 = 10;
+        /// Events further back are left alone, e.g. after a long downtime.
+        const LOOKBACK_DAYS: i64 = 3;
+
+        /// The post with the house rules, configured via `HOUSE_RULES_POST`.
+        fn house_rules_url() -> String {
+            let slug = std::env::var("HOUSE_RULES_POST").unwrap_or_else(|_| String::from("hausordnung"));
+            format!("{}/news/{}", site_url(), slug)
+        }
+
+        fn survey_url(event: &Event) -> Option<String> {
+            event.survey_url.clone().or_else(|| std::env::var("FEEDBACK_SURVEY_URL").ok())
+        }
+
+        fn venue_address() -> String {
+            let venue = seller_from_env();
+            let city = format!("{} {}", venue.address.zipcode, venue.address.city);
+            [venue.name, venue.address.line1, city.trim().to_string()]
+                .into_iter()
+                .filter(|part| !part.is_empty())
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+
+        struct Recipient {
+            email: String,
+            name: String,
+            tickets: u32,
+            /// The account's language, else the one the ticket was bought in.
+            locale: Locale,
+        }
+
+        /// Who gets the mail, one per address with the number of tickets.
+        async fn recipients(event: &Event, kind: EventMailKind, users: &[User]) -> Vec<Recipient> {
+            let statuses: &[TicketStatus] = match kind {
+                EventMailKind::Reminder => &[TicketStatus::Valid],
+                EventMailKind::FollowUp => &[TicketStatus::CheckedIn, TicketStatus::Out],
+            };
+            let mut recipients: BTreeMap<String, Recipient> = BTreeMap::new();
+            let mut order_locales: BTreeMap<String, Locale> = BTreeMap::new();
+            for ticket in database::get_tickets_for_event(event.uuid.clone()).await.unwrap_or_default() {
+                if !statuses.contains(&ticket.status) {
+                    continue;
+                }
+                let owner = ticket.owner.as_ref().and_then(|owner| users.iter().find(|u| u.uuid == *owner));
+                let Some(email) = owner.map(|u| u.email.clone()).or(ticket.holder_email.clone()) else {
+                    continue;
+                };
+                let email = email.trim().to_lowercase();
+                let user = owner.or_else(|| users.iter().find(|u| u.email.to_lowercase() == email));
+                let wanted = match (user, kind) {
+                    (Some(user), EventMailKind::Reminder) => user.notifications.event_reminders,
+                    (Some(user), EventMailKind::FollowUp) => user.notifications.event_follow_ups,
+                    (None, kind) => kind == EventMailKind::Reminder,
+                };
+                if !wanted {
+                    continue;
+                }
+                if let Some(recipient) = recipients.get_mut(&email) {
+                    recipient.tickets += 1;
+                    continue;
+                }
+                let locale = match (user, &ticket.order) {
+                    (Some(user), _) => user.locale,
+                    (None, Some(order)) => match order_locales.get(order) {
+                        Some(locale) => *locale,
+                        None => {
+                            let locale = database::get_order(order.clone()).await.map(|o| o.locale).unwrap_or_default();
+                            order_locales.insert(order.clone(), locale);
+                            locale
+                        }
+                    },
+                    (None, None) => Locale::default(),
+                };
+                recipients.insert(email.clone(), Recipient {
+                    email,
+                    name: user.map(|u| u.name.clone()).filter(|n| !n.is_empty()).unwrap_or(ticket.holder_name.clone()),
+                    tickets: 1,
+                    locale,
+                });
+            }
+            recipients.into_values().collect()
+        }
+
+        fn footer(locale: Locale) -> String {
+            locale.format("mail-settings-footer", &[("link", &format!("{}/account", site_url()))])
+        }
+
+        fn reminder(event: &Event, recipient: &Recipient) -> Mail {
+            let locale = recipient.locale;
+            let time = |time: &str| parse_berlin(time).map(|t| locale.time(t)).unwrap_or_default();
+            let date = parse_berlin(&event.starts_at).map(|t| locale.date(t.date())).unwrap_or_default();
+            Mail::new(
+                recipient.email.clone(),
+                locale.format("mail-reminder-subject", &[("event", &event.title)]),
+                locale.format("mail-reminder-body", &[
+                    ("name", &recipient.name),
+                    ("date", &date),
+                    ("event", &event.title),
+                    ("doors", &time(&event.doors_at)),
+                    ("starts", &time(&event.starts_at)),
+                    ("venue", &venue_address()),
+                    ("tickets", &recipient.tickets.to_string()),
+                    ("account", &format!("{}/account", site_url())),
+                    ("rules", &house_rules_url()),
+                    ("footer", &footer(locale)),
+                ]),
+            )
+        }
+
+        fn follow_up(event: &Event, recipient: &Recipient) -> Mail {
+            let locale = recipient.locale;
+            let mut parts = vec![locale.format("mail-follow-up-thanks", &[("name", &recipient.name), ("event", &event.title)])];
+            if let Some(gallery) = &event.gallery_url {
+                parts.push(locale.format("mail-follow-up-photos", &[("link", gallery)]));
+            }
+            if let Some(survey) = survey_url(event) {
+                parts.push(locale.format("mail-follow-up-survey", &[("link", &survey)]));
+            }
+            parts.push(locale.text("mail-follow-up-closing").to_string());
+            parts.push(footer(locale));
+            Mail::new(
+                recipient.email.clone(),
+                locale.format("mail-follow-up-subject", &[("event", &event.title)]),
+                parts.join("\n\n"),
+            )
+        }
+
+        /// Queues the mail for the event unless it went out before.
+        async fn send(event: &Event, kind: EventMailKind, users: &[User]) -> Result<(), ResponseError> {
+            let recipients = recipients(event, kind, users).await;
+            let record = EventMail {
+                recipients: recipients.len() as u32,
+                ..EventMail::new(event.uuid.clone(), kind, now())
+            };
+            let uuid = record.uuid.clone();
+            if !database::add_event_mail(record).await {
+                return Ok(());
+            }
+            let mails = recipients
+                .iter()
+                .map(|recipient| match kind {
+                    EventMailKind::Reminder => reminder(event, recipient),
+                    EventMailKind::FollowUp => follow_up(event, recipient),
+                })
+                .collect();
+            if let Err(e) = mail::queue::enqueue_bulk(mails, None).await {
+                // so the next run tries again
+                database::delete_event_mail(uuid).await;
+                return Err(e);
+            }
+            Ok(())
+        }
+
+        /// Sends the mails of all events that are due. Run by `jobs`.
+        pub async fn send_due() -> Result<(), ResponseError> {
+            let now = Utc::now();
+            let from = stamp(now - Duration::days(LOOKBACK_DAYS));
+            let events = database::get_events_since(from).await.unwrap_or_default();
+            let users = database::get_all_users().await.unwrap_or_default();
+
+            let mut result = Ok(());
+            for event in events.iter().filter(|event| !event.cancelled) {
+                let doors = DateTime::parse_from_rfc3339(&event.doors_at).ok().map(|d| d.with_timezone(&Utc));
+                let reminder_due = doors.is_some_and(|doors| doors - Duration::hours(REMINDER_HOURS) <= now && now < doors);
+                let follow_up_due = end_of(event).is_some_and(|ends| {
+                    ends + Duration::hours(FOLLOW_UP_HOURS) <= now && now < ends + Duration::days(LOOKBACK_DAYS)
+                });
+
+                for (due, kind) in [(reminder_due, EventMailKind::Reminder), (follow_up_due, EventMailKind::FollowUp)] {
+                    if !due {
+                        continue;
+                    }
+                    if let Err(e) = send(event, kind, &users).await {
+                        println!("error in sending {:?} for event {}: {:?}", kind, event.uuid, e);
+                        result = Err(e);
+                    }
+                }
+            }
+            result
+        }
+    }
+}
@@ -0,0 +1,258 @@
+//! Atom (RFC 4287) and RSS 2.0 renderings of the feed items, served by actix
+//! next to the iCalendar feed.
+
+use chrono::{DateTime, Utc};
+
+use crate::app::model::feed::{FeedItem, FeedKind};
+
+/// Entries per feed.
+pub const FEED_LENGTH: u32 = 50;
+
+const TITLE: &str = "Stampffabrik";
+const SUBTITLE: &str = "Konzerte und Neuigkeiten aus der Stampffabrik";
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Links are stored as paths on the site, feed readers need absolute URLs.
+fn absolute(site_url: &str, link: &str) -> String {
+    if link.starts_with("http://") || link.starts_with("https://") {
+        link.to_string()
+    } else {
+        format!("{site_url}/{}", link.trim_start_matches('/'))
+    }
+}
+
+fn category(kind: FeedKind) -> &'static str {
+    match kind {
+        FeedKind::Announcement => "news",
+        FeedKind::Event => "event",
+    }
+}
+
+fn parse(time: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(time).ok().map(|t| t.with_timezone(&Utc))
+}
+
+/// Latest change of any entry, for `<updated>` and `Last-Modified`.
+pub fn last_updated(items: &[FeedItem]) -> Option<DateTime<Utc>> {
+    items.iter().filter_map(|item| parse(&item.updated_at)).max()
+}
+
+/// `Last-Modified` format, always in GMT.
+pub fn http_date(time: DateTime<Utc>) -> String {
+    time.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+pub fn atom(items: &[FeedItem], site_url: &str, updated: DateTime<Utc>) -> String {
+    let mut xml = format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+         <feed xmlns=\"http://www.w3.org/2005/Atom\" xml:lang=\"de\">\n\
+         <id>{site_url}/feed.atom</id>\n\
+         <title>{}</title>\n\
+         <subtitle>{}</subtitle>\n\
+         <link rel=\"self\" type=\"application/atom+xml\" href=\"{site_url}/feed.atom\"/>\n\
+         <link rel=\"alternate\" type=\"text/html\" href=\"{site_url}/\"/>\n\
+         <updated>{}</updated>\n\
+         <author><name>{}</name></author>\n",
+        escape(TITLE),
+        escape(SUBTITLE),
+        updated.to_rfc3339(),
+        escape(TITLE),
+    );
+    for item in items {
+        let (Some(published), Some(updated)) = (parse(&item.published_at), parse(&item.updated_at)) else {
+            continue;
+        };
+        xml.push_str(&format!(
+            "<entry>\n\
+             <id>urn:uuid:{}</id>\n\
+             <title>{}</title>\n\
+             <link rel=\"alternate\" type=\"text/html\" href=\"{}\"/>\n\
+             <published>{}</published>\n\
+             <updated>{}</updated>\n\
+             <category term=\"{}\"/>\n\
+             <summary>{}</summary>\n",
+            item.uuid,
+            escape(&item.title),
+            escape(&absolute(site_url, &item.link)),
+            published.to_rfc3339(),
+            updated.to_rfc3339(),
+            category(item.kind),
+            escape(&item.summary),
+        ));
+        if let Some(enclosure) = &item.enclosure {
+            xml.push_str(&format!(
+                "<link rel=\"enclosure\" type=\"{}\" length=\"{}\" href=\"{}\"/>\n",
+                escape(&enclosure.mime_type),
+                enclosure.length,
+                escape(&absolute(site_url, &enclosure.url)),
+            ));
+        }
+        xml.push_str("</entry>\n");
+    }
+    xml + "</feed>\n"
+}
+
+pub fn rss(items: &[FeedItem], site_url: &str, updated: DateTime<Utc>) -> String {
+    let mut xml = format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+         <rss version=\"2.0\" xmlns:atom=\"http://www.w3.org/2005/Atom\">\n\
+         <channel>\n\
+         <title>{}</title>\n\
+         <link>{site_url}/</link>\n\
+         <description>{}</description>\n\
+         <language>de</language>\n\
+         <atom:link href=\"{site_url}/feed.rss\" rel=\"self\" type=\"application/rss+xml\"/>\n\
+         <lastBuildDate>{}</lastBuildDate>\n",
+        escape(TITLE),
+        escape(SUBTITLE),
+        updated.to_rfc2822(),
+    );
+    for item in items {
+        let Some(published) = parse(&item.published_at) else {
+            continue;
+        };
+        xml.push_str(&format!(
+            "<item>\n\
+             <title>{}</title>\n\
+             <link>{}</link>\n\
+             <guid isPermaLink=\"false\">urn:uuid:{}</guid>\n\
+             <pubDate>{}</pubDate>\n\
+             <category>{}</category>\n\
+             <description>{}</description>\n",
+            escape(&item.title),
+            escape(&absolute(site_url, &item.link)),
+            item.uuid,
+            published.to_rfc2822(),
+            category(item.kind),
+            escape(&item.summary),
+        ));
+        if let Some(enclosure) = &item.enclosure {
+            xml.push_str(&format!(
+                "<enclosure url=\"{}\" length=\"{}\" type=\"{}\"/>\n",
+                escape(&absolute(site_url, &enclosure.url)),
+                enclosure.length,
+                escape(&enclosure.mime_type),
+            ));
+        }
+        xml.push_str("</item>\n");
+    }
+    xml + "</channel>\n</rss>\n"
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use crate::app::database;
+        use crate::app::ical::berlin_time;
+        use crate::app::markdown;
+        use crate::app::model::{Event, Post};
+        use crate::app::model::feed::Enclosure;
+        use crate::app::time;
+
+        /// Characters of a post shown in the feed.
+        const SUMMARY_LENGTH: usize = 280;
+
+        fn mime_type(path: &str) -> &'static str {
+            let extension = path.rsplit('.').next().unwrap_or_default().to_lowercase();
+            match extension.as_str() {
+                "png" => "image/png",
+                "jpg" | "jpeg" => "image/jpeg",
+                "webp" => "image/webp",
+                "gif" => "image/gif",
+                _ => "application/octet-stream",
+            }
+        }
+
+        /// Size of a file served from the site root. Remote files are not
+        /// fetched, their size stays unknown.
+        fn file_length(path: &str) -> u64 {
+            if path.contains("://") {
+                return 0;
+            }
+            let site_root = std::env::var("LEPTOS_SITE_ROOT").unwrap_or_else(|_| String::from("target/site"));
+            std::fs::metadata(format!("{site_root}/{}", path.trim_start_matches('/')))
+                .map(|metadata| metadata.len())
+                .unwrap_or(0)
+        }
+
+        fn local_time(time: &str) -> String {
+            parse(time)
+                .map(|t| berlin_time(t).format("%d.%m.%Y %H:%M").to_string())
+                .unwrap_or_else(|| time.to_string())
+        }
+
+        fn enclosure(path: &str) -> Enclosure {
+            Enclosure {
+                url: path.to_string(),
+                mime_type: mime_type(path).to_string(),
+                length: file_length(path),
+            }
+        }
+
+        /// Stores the entry. A new version keeps the publication date of the
+        /// old one unless it brings its own, and `updated_at` only moves when
+        /// something readers see changed.
+        async fn save(mut item: FeedItem) -> Option<FeedItem> {
+            let now = time::now();
+            match database::get_feed_item(item.uuid.clone()).await {
+                Some(existing) => {
+                    if item.published_at.is_empty() {
+                        item.published_at = existing.published_at.clone();
+                    }
+                    item.updated_at = existing.updated_at.clone();
+                    if item == existing {
+                        return Some(existing);
+                    }
+                }
+                None if item.published_at.is_empty() => item.published_at = now.clone(),
+                None => (),
+            }
+            // scheduled entries count as changed when they show up
+            item.updated_at = now.max(item.published_at.clone());
+            database::save_feed_item(item).await
+        }
+
+        /// Publishes the event, or brings its entry up to date.
+        pub async fn publish_event(event: &Event) -> Option<FeedItem> {
+            let title = if event.cancelled {
+                format!("Abgesagt: {}", event.title)
+            } else {
+                event.title.clone()
+            };
+            let summary = format!("Beginn {}, Einlass {}", local_time(&event.starts_at), local_time(&event.doors_at));
+            let mut item = FeedItem::new(event.uuid.clone(), FeedKind::Event, title, summary, format!("/events/{}", event.slug));
+            item.reference = Some(event.uuid.clone());
+            item.enclosure = event.poster.as_deref().map(enclosure);
+            save(item).await
+        }
+
+        /// Puts a post into the feed as of its publication date, which may
+        /// lie in the future. Drafts are not published.
+        pub async fn publish_post(post: &Post) -> Option<FeedItem> {
+            let published_at = post.published_at.clone()?;
+            let mut item = FeedItem::new(
+                post.uuid.clone(),
+                FeedKind::Announcement,
+                post.title.clone(),
+                markdown::excerpt(&post.body, SUMMARY_LENGTH),
+                format!("/news/{}", post.slug),
+            );
+            item.reference = Some(post.uuid.clone());
+            item.enclosure = post.cover.as_deref().map(enclosure);
+            item.published_at = published_at;
+            save(item).await
+        }
+
+        /// Takes an entry out again, e.g. when a post goes back to draft.
+        pub async fn withdraw(uuid: String) {
+            database::delete_feed_item(uuid).await;
+        }
+        }
+    }
+}
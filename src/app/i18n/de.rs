@@ -0,0 +1,529 @@
+pub const MONTHS: [&str; 12] = [
+    "Januar", "Februar", "März", "April", "Mai", "Juni",
+    "Juli", "August", "September", "Oktober", "November", "Dezember",
+];
+
+pub const WEEKDAYS: [&str; 7] = ["Mo", "Di", "Mi", "Do", "Fr", "Sa", "So"];
+
+pub const TEXTS: &[(&str, &str)] = &[
+    // shared
+    ("loading", "Lädt …"),
+    ("site-description", "Konzerte, Partys und Merch aus der Stampffabrik."),
+    ("language", "Sprache"),
+    ("email", "E-Mail"),
+    ("name", "Name"),
+    ("zipcode", "PLZ"),
+    ("city", "Ort"),
+    ("sold-out", "Ausverkauft"),
+    ("cancelled", "Abgesagt"),
+    ("add-to-cart", "In den Warenkorb"),
+    ("go-to-cart", "Zum Warenkorb"),
+    ("pickup", "Abholung an der Bar"),
+    ("column-item", "Artikel"),
+    ("column-quantity", "Anzahl"),
+    ("column-price", "Preis"),
+    ("column-sum", "Summe"),
+    ("column-number", "Nummer"),
+    ("column-date", "Datum"),
+    ("column-amount", "Betrag"),
+    ("total", "Gesamt"),
+    // errors
+    ("error-not-logged-in", "Nicht angemeldet"),
+    ("error-not-allowed", "Keine Berechtigung"),
+    ("error-invalid-email", "Ungültige E-Mail-Adresse"),
+    ("error-slug-taken", "Slug ist schon vergeben"),
+    ("error-artist-invalid", "Name und ein Slug aus Buchstaben, Ziffern und Bindestrichen sind nötig"),
+    ("error-links-https", "Links müssen mit https:// beginnen"),
+    ("error-set-time", "Ungültige Spielzeit"),
+    ("error-set-order", "Das Set endet vor seinem Beginn"),
+    ("error-title-required", "Titel fehlt"),
+    ("error-announcement-failed", "Ankündigung konnte nicht gespeichert werden"),
+    ("error-publish-failed", "Event konnte nicht veröffentlicht werden"),
+    ("error-product-invalid", "Name und Slug sind nötig"),
+    ("error-variant-invalid", "SKU und Preis sind nötig"),
+    ("error-sku-taken", "SKU ist schon vergeben"),
+    ("error-confirm-failed", "Bestätigung fehlgeschlagen"),
+    ("error-unsubscribe-failed", "Abmeldung fehlgeschlagen"),
+    ("error-campaign-invalid", "Betreff und Text sind nötig"),
+    ("error-invalid-link", "Ungültiger Link"),
+    ("error-fields-required", "Bitte alle Felder ausfüllen!"),
+    ("error-quota-failed", "Kontingent konnte nicht gespeichert werden"),
+    ("error-name-required", "Name fehlt!"),
+    ("error-door-name-failed", "Name konnte nicht eingetragen werden"),
+    ("error-door-codes-failed", "Einlassliste konnte nicht geladen werden"),
+    ("error-publish-date", "Ungültiges Veröffentlichungsdatum"),
+    ("error-post-invalid", "Titel und ein Slug aus Buchstaben, Ziffern und Bindestrichen sind nötig"),
+    ("error-code-length", "Der Code muss 3 bis 32 Zeichen lang sein"),
+    ("error-code-taken", "Code ist schon vergeben"),
+    ("error-area-invalid", "Name und eine Kapazität von mindestens 1 sind nötig"),
+    ("error-too-heavy", "Zu schwer für den Versand in dieses Land"),
+    ("error-cart-empty", "Der Warenkorb ist leer"),
+    ("error-user-not-found", "Konto nicht gefunden"),
+    ("error-login", "Anmeldung fehlgeschlagen!"),
+    ("error-sign-up", "Registrierung fehlgeschlagen!"),
+    ("error-no-cookie", "Kein Cookie mitgeschickt"),
+    ("error-no-request", "Keine gültige Anfrage"),
+    ("error-invalid-token", "Ungültiges Token"),
+    ("error-user-update", "Konto konnte nicht gespeichert werden"),
+    ("error-user-create", "Konto konnte nicht angelegt werden"),
+    ("error-user-delete", "Konto konnte nicht gelöscht werden"),
+    ("error-event-not-found", "Event nicht gefunden"),
+    ("error-event-invalid", "Titel fehlt oder ein Link ist keine Webadresse"),
+    ("error-ticket-not-found", "Ticket nicht gefunden"),
+    ("error-check-in", "Einlass konnte nicht gespeichert werden"),
+    ("error-ticket-transfer", "Ticket konnte nicht übertragen werden"),
+    ("error-guest-not-found", "Gast nicht gefunden"),
+    ("error-guest-create", "Gast konnte nicht eingetragen werden"),
+    ("error-guest-quota", "Kontingent erschöpft"),
+    ("error-order-not-found", "Bestellung nicht gefunden"),
+    ("error-order-create", "Bestellung konnte nicht angelegt werden"),
+    ("error-order-status", "Bestellstatus konnte nicht geändert werden"),
+    ("error-payment", "Zahlung fehlgeschlagen"),
+    ("error-invoice-not-found", "Rechnung nicht gefunden"),
+    ("error-invoice-create", "Rechnung konnte nicht erstellt werden"),
+    ("error-refund", "Erstattung fehlgeschlagen"),
+    ("error-mail", "Mail konnte nicht verschickt werden"),
+    ("error-discount-invalid", "Ungültiger Rabattcode"),
+    ("error-voucher-invalid", "Gutschein ungültig oder aufgebraucht"),
+    ("error-product-not-found", "Produkt nicht gefunden"),
+    ("error-delivery-invalid", "Ungültige Versandart oder Adresse"),
+    ("error-post-not-found", "Beitrag nicht gefunden"),
+    ("error-media-not-found", "Datei nicht gefunden"),
+    ("error-media-invalid", "Nur JPEG-, PNG- und WebP-Bilder bis 20 MB können hochgeladen werden"),
+    ("error-media-in-use", "Datei wird noch verwendet"),
+    ("error-media-storage", "Datei konnte nicht gespeichert werden"),
+    ("error-artist-not-found", "Künstler:in nicht gefunden"),
+    ("error-area-not-found", "Bereich nicht gefunden"),
+    ("error-area-full", "Bereich ist voll"),
+    ("error-waitlist", "Eintrag auf der Warteliste fehlgeschlagen"),
+    ("error-offer-invalid", "Das Angebot gilt nicht mehr"),
+    ("error-job", "Aufgabe konnte nicht eingeplant werden"),
+    ("error-waitlist-places", "Zwischen 1 und {max} Plätzen"),
+    ("error-no-quota", "{name}: {inviter} hat kein Kontingent"),
+    ("error-quota-used-up", "{name}: Kontingent von {inviter} erschöpft"),
+    ("error-address-missing", "{field} fehlt"),
+    ("error-address-country", "In dieses Land versenden wir nicht"),
+    ("error-address-zipcode", "Postleitzahl passt nicht zum Land"),
+    ("error-guest-name", "Name fehlt"),
+    ("error-guest-plus", "{name}: höchstens 20 Begleitungen"),
+    // header and footer
+    ("nav-home", "Start"),
+    ("nav-news", "News"),
+    ("nav-calendar", "Kalender"),
+    ("nav-artists", "Künstler:innen"),
+    ("nav-shop", "Shop"),
+    ("nav-cart", "Warenkorb"),
+    ("nav-account", "Konto"),
+    ("not-found", "Seite nicht gefunden"),
+    // sign in and sign up
+    ("auth-password", "Passwort"),
+    ("auth-login", "Login"),
+    ("auth-register", "Registrieren"),
+    ("auth-create-account", "Neues Konto erstellen"),
+    ("auth-have-account", "Ich habe bereits ein Konto"),
+    ("auth-fields-required", "Bitte alle Felder ausfüllen!"),
+    ("auth-login-failed", "Anmeldung fehlgeschlagen"),
+    ("auth-register-failed", "Registrierung fehlgeschlagen"),
+    // home
+    ("home-upcoming", "Kommende Events"),
+    ("home-all-dates", "Alle Termine"),
+    ("crowd-quiet", "Gerade ist noch viel Platz – komm vorbei!"),
+    ("crowd-busy", "Gerade ist gut was los."),
+    ("crowd-full", "Gerade sehr voll – am Einlass kann es dauern."),
+    // events and calendar
+    ("event-times", "Einlass {doors} · Beginn {starts}"),
+    ("event-description", "{title} in der Stampffabrik, {date}"),
+    ("event-edit-title", "Event bearbeiten"),
+    ("event-edit-name", "Titel"),
+    ("event-edit-poster", "Plakat"),
+    ("event-edit-gallery", "Fotogalerie"),
+    ("event-edit-survey", "Umfrage"),
+    ("event-edit-links-hint", "Galerie und Umfrage werden in der Mail nach dem Abend verlinkt. Ohne Umfrage gilt die allgemeine."),
+    ("event-edit-save", "Speichern"),
+    ("event-edit-saved", "Gespeichert."),
+    ("calendar-title", "Kalender"),
+    ("calendar-description", "Alle Konzerte und Partys der Stampffabrik, auch zum Abonnieren im eigenen Kalender."),
+    ("calendar-month", "Monat"),
+    ("calendar-list", "Liste"),
+    ("calendar-subscribe", "Kalender abonnieren"),
+    ("calendar-empty", "Keine Termine."),
+    ("calendar-add", "In den Kalender"),
+    // waitlist
+    ("waitlist-title", "Warteliste"),
+    ("waitlist-intro", "Werden Tickets wieder frei, bieten wir sie der Reihe nach an. Du bekommst dann eine Mail mit einem Link, über den du sie eine Zeit lang kaufen kannst."),
+    ("waitlist-login", "Melde dich an, um dich auf die Warteliste zu setzen."),
+    ("waitlist-places", "{count} Ticket(s)"),
+    ("waitlist-join", "Auf die Warteliste"),
+    ("waitlist-position", "Du stehst auf der Warteliste, vor dir: {ahead}."),
+    ("waitlist-offered", "Für dich sind Tickets frei!"),
+    ("waitlist-leave", "Warteliste verlassen"),
+    ("offer-title", "Tickets von der Warteliste"),
+    ("offer-details", "{quantity} × {ticket} für {event}, zusammen {price}."),
+    ("offer-expires", "Wir halten sie für dich bis {time}, danach gehen sie an die Nächsten in der Reihe."),
+    ("offer-claim", "Jetzt kaufen"),
+    ("offer-login", "Melde dich mit dem Konto an, mit dem du auf der Warteliste stehst."),
+    ("offer-gone", "Dieses Angebot gilt nicht mehr."),
+    // shop
+    ("shop-title", "Shop"),
+    ("shop-description", "Shirts, Hoodies und mehr aus der Stampffabrik."),
+    ("shop-from", "ab {price}"),
+    // cart
+    ("cart-title", "Warenkorb"),
+    ("cart-empty", "Dein Warenkorb ist leer."),
+    ("cart-street", "Straße (optional)"),
+    ("cart-discount-code", "Rabattcode"),
+    ("cart-voucher-code", "Gutscheincode"),
+    ("cart-checkout", "Zur Kasse"),
+    ("cart-shipping", "Versand"),
+    ("cart-shipping-cost", "Versand: {price}"),
+    ("cart-free", "Kostenlos"),
+    ("cart-state", "Bundesstaat"),
+    ("cart-address-line1", "Straße und Hausnummer"),
+    ("cart-address-line2", "Adresszusatz"),
+    ("cart-vouchers", "Gutscheine"),
+    ("cart-voucher", "Gutschein {price}"),
+    ("cart-voucher-item", "Gutschein {value}"),
+    ("cart-shipping-item", "Versand nach {country}"),
+    // orders
+    ("order-title", "Bestellung"),
+    ("order-history", "Bestellungen"),
+    ("order-vat", "enthaltene MwSt. {rate} %"),
+    ("order-shipping-to", "Versand an {address}"),
+    ("order-pending", "Offen"),
+    ("order-paid", "Bezahlt"),
+    ("order-fulfilled", "Abgeschlossen"),
+    ("order-refunded", "Erstattet"),
+    ("order-cancelled", "Storniert"),
+    // account and tickets
+    ("account-title", "Konto"),
+    ("account-address", "Adresse"),
+    ("wallet-title", "Meine Tickets"),
+    ("wallet-past", "Vergangene Events"),
+    ("wallet-expired", "Abgelaufen"),
+    ("wallet-used", "Eingelöst"),
+    ("wallet-refunded", "Erstattet"),
+    ("wallet-cancelled", "Storniert"),
+    ("wallet-transfer", "Weitergeben"),
+    ("wallet-send", "Ticket senden"),
+    ("settings-title", "Benachrichtigungen"),
+    ("settings-event-reminders", "Erinnerung am Tag vor dem Event"),
+    ("settings-event-follow-ups", "Danke-Mail mit Fotos und Umfrage nach dem Event"),
+    ("settings-save", "Speichern"),
+    ("settings-saved", "Gespeichert."),
+    // newsletter
+    ("newsletter-placeholder", "Newsletter: deine E-Mail"),
+    ("newsletter-subscribe", "Anmelden"),
+    ("newsletter-pending", "Fast geschafft! Bitte bestätige die Anmeldung in der Mail, die wir dir geschickt haben."),
+    ("newsletter-confirm-title", "Newsletter bestätigen"),
+    ("newsletter-confirmed", "Danke! Du bekommst ab jetzt unseren Newsletter."),
+    ("newsletter-unsubscribe-title", "Newsletter abbestellen"),
+    ("newsletter-unsubscribe-question", "Möchtest du unseren Newsletter abbestellen?"),
+    ("newsletter-unsubscribe", "Abmelden"),
+    ("newsletter-unsubscribed", "Du bist abgemeldet und bekommst keinen Newsletter mehr."),
+    // invoices
+    ("invoices-title", "Rechnungen"),
+    ("invoice-cancels", "Storno zu {number}"),
+    ("invoice-cancel", "Stornieren"),
+    ("invoice-save", "Speichern"),
+    // news
+    ("news-title", "News"),
+    ("news-description", "Neuigkeiten aus der Stampffabrik: Lineups, Hausregeln, Schließtage."),
+    ("news-empty", "Noch keine Beiträge."),
+    ("news-all", "Alle Beiträge"),
+    // artists
+    ("artists-title", "Künstler:innen"),
+    ("artists-description", "Bands, DJs und Acts, die in der Stampffabrik spielen und gespielt haben."),
+    ("artists-empty", "Noch keine Künstler:innen."),
+    ("artists-all-genres", "Alle"),
+    ("artist-description", "{name} in der Stampffabrik: kommende und vergangene Auftritte."),
+    ("artist-upcoming", "Demnächst"),
+    ("artist-past", "Schon hier gespielt"),
+    ("artist-no-dates", "Gerade keine Termine."),
+    ("artists-all", "Alle Künstler:innen"),
+    ("lineup-title", "Lineup"),
+    // admin pages
+    ("admin-save", "Speichern"),
+    ("admin-create", "Anlegen"),
+    ("admin-delete", "Löschen"),
+    ("push-live", "Live"),
+    ("push-reconnecting", "Keine Live-Verbindung, verbinde neu …"),
+    // occupancy
+    ("occupancy-title", "Auslastung"),
+    ("occupancy-empty", "Noch keine Bereiche angelegt."),
+    ("occupancy-reset", "Zurücksetzen"),
+    ("occupancy-reset-confirm", "Zähler auf 0 setzen?"),
+    ("occupancy-full", "VOLL – niemanden reinlassen"),
+    ("occupancy-warning", "Fast voll"),
+    ("occupancy-of", "von {capacity} · {percent} %"),
+    ("areas-title", "Bereiche"),
+    ("areas-hint", "Warnung ab {percent} % der Kapazität, bei voller Kapazität ist kein Einlass mehr möglich."),
+    ("areas-capacity", "Kapazität"),
+    ("areas-position", "Reihenfolge"),
+    ("areas-new", "Neuer Bereich"),
+    // door
+    ("door-title", "Einlass"),
+    ("door-mode-in", "Einlass"),
+    ("door-mode-out", "Stempel / Raus"),
+    ("door-inside", "Drin"),
+    ("door-out", "Draußen (Stempel)"),
+    ("door-expected", "Gesamt"),
+    ("door-search", "Name suchen"),
+    ("door-check-in", "Check-in"),
+    ("door-add-name", "Name hinzufügen"),
+    ("scan-admitted", "EINLASS"),
+    ("scan-re-entry", "WIEDEREINLASS"),
+    ("scan-stamped-out", "GESTEMPELT"),
+    ("scan-provisional", "EINLASS (OFFLINE)"),
+    ("scan-duplicate", "BEREITS EINGECHECKT"),
+    ("scan-refunded", "ERSTATTET"),
+    ("scan-cancelled", "STORNIERT"),
+    ("scan-unknown", "UNBEKANNT"),
+    ("scan-since", "seit {time}"),
+    ("ticket-valid", "Gültig"),
+    ("ticket-checked-in", "Eingecheckt"),
+    ("ticket-out", "Draußen"),
+    ("ticket-refunded", "Erstattet"),
+    ("ticket-cancelled", "Storniert"),
+    // offline door
+    ("offline-title", "Einlass offline"),
+    ("offline-online", "Online"),
+    ("offline-offline", "Offline"),
+    ("offline-pending", "{count} nicht synchronisiert"),
+    ("offline-loaded", "Liste geladen: {count} Einträge"),
+    ("offline-load-failed", "Liste konnte nicht geladen werden"),
+    ("offline-synced", "{applied} Scans synchronisiert, {conflicts} Konflikte"),
+    ("offline-sync-failed", "Synchronisierung fehlgeschlagen"),
+    ("offline-no-list", "Keine Liste geladen"),
+    ("conflict-duplicate", "doppelt, zuerst {device} um {time}"),
+    ("conflict-refunded", "erstattet"),
+    ("conflict-cancelled", "storniert"),
+    ("conflict-unknown", "unbekannt"),
+    ("offline-not-inside", "Ticket ist nicht drin"),
+    // scanner
+    ("scanner-code", "Code"),
+    ("scanner-ok", "OK"),
+    ("scanner-unsupported", "Dieser Browser kann nicht mit der Kamera scannen"),
+    ("scanner-no-camera", "Kamera nicht verfügbar"),
+    // guest list
+    ("guests-title", "Gästeliste"),
+    ("guests-invited-by", "Eingeladen von"),
+    ("guests-invited-by-hint", "Eingeladen von (leer: du)"),
+    ("guests-note", "Notiz"),
+    ("guests-added-by", "Eingetragen von"),
+    ("guests-inside", "Drin"),
+    ("guests-audit", "Protokoll"),
+    ("guests-action-added", "Eingetragen"),
+    ("guests-action-imported", "Importiert"),
+    ("guests-action-removed", "Entfernt"),
+    ("guests-action-checked-in", "Eingecheckt"),
+    ("guests-add", "Hinzufügen"),
+    ("guests-used", "Vergeben"),
+    ("guests-quota", "Kontingent"),
+    ("guests-set-quota", "Kontingent setzen"),
+    ("guests-import", "Importieren"),
+    ("guests-export", "Exportieren"),
+    ("guests-csv", "gaesteliste.csv"),
+    ("guests-imported", "{count} importiert"),
+    ("guests-search", "Gästeliste durchsuchen"),
+    ("guests-already-inside", "{name} ist bereits drin (seit {time})"),
+    ("guests-checked-in", "{name} +{plus} eingecheckt"),
+    ("guests-is-inside", "drin"),
+    ("error-import-plus", "Zeile {line}: ungültige Anzahl '{plus}'"),
+    // jobs
+    ("jobs-title", "Jobs"),
+    ("jobs-refresh", "Aktualisieren"),
+    ("jobs-recurring", "Wiederkehrend"),
+    ("jobs-job", "Job"),
+    ("jobs-schedule", "Zeitplan"),
+    ("jobs-next-run", "Nächster Lauf"),
+    ("jobs-history", "Verlauf"),
+    ("jobs-all", "Alle"),
+    ("jobs-filter-queued", "Wartend"),
+    ("jobs-filter-running", "Laufend"),
+    ("jobs-status", "Status"),
+    ("jobs-attempts", "Versuche"),
+    ("jobs-due", "Fällig"),
+    ("jobs-error", "Fehler"),
+    ("jobs-run-now", "Jetzt"),
+    ("jobs-delete", "Abbrechen"),
+    ("jobs-retry", "Erneut"),
+    ("job-retrying", "Wiederholung"),
+    ("job-queued", "Wartet"),
+    ("job-running", "Läuft"),
+    ("job-done", "Erledigt"),
+    ("job-dead", "Fehlgeschlagen"),
+    ("job-expire-offers", "Wartelisten-Angebote"),
+    ("job-expire-orders", "Unbezahlte Bestellungen"),
+    ("job-purge", "Alte Jobs löschen"),
+    ("job-event-mails", "Mails zu Events"),
+    // refunds
+    ("refunds-title", "Erstattungen"),
+    ("refunds-cancel-event", "Event absagen"),
+    ("refunds-confirm-cancel", "Wirklich absagen?"),
+    ("refunds-report", "{orders} Bestellungen erstattet, {amount} gesamt, {mailed} Mails verschickt"),
+    ("refunds-failed", "Erstattung fehlgeschlagen: {order}"),
+    ("refunds-code", "Code"),
+    ("refunds-holder", "Inhaber"),
+    ("refunds-status", "Status"),
+    ("refunds-refund", "Erstatten"),
+    ("refunds-order", "Bestellung"),
+    ("refunds-reason", "Grund"),
+    ("refunds-reason-cancelled", "Absage"),
+    ("refunds-reason-requested", "Einzeln"),
+    ("refunds-succeeded", "Erstattet"),
+    ("refunds-status-failed", "Fehlgeschlagen"),
+    // discounts
+    ("discounts-title", "Rabatte"),
+    ("discounts-codes", "Rabattcodes"),
+    ("discounts-vouchers", "Gutscheine"),
+    ("discounts-redemptions", "Einlösungen"),
+    ("discounts-invalid-value", "Ungültiger Wert"),
+    ("discounts-code", "Code"),
+    ("discounts-percent", "Prozent"),
+    ("discounts-euro", "Euro"),
+    ("discounts-value", "Wert"),
+    ("discounts-value-euro", "Wert in Euro"),
+    ("discounts-scope", "Gilt für"),
+    ("discounts-scope-all", "Alles"),
+    ("discounts-scope-event", "Event"),
+    ("discounts-scope-ticket-type", "Ticketart"),
+    ("discounts-scope-id", "Event- oder Ticketart-ID"),
+    ("discounts-max-uses", "Max."),
+    ("discounts-discount", "Rabatt"),
+    ("discounts-voucher", "Gutschein"),
+    ("discounts-used", "Genutzt"),
+    ("discounts-period", "Zeitraum"),
+    ("discounts-activate", "Aktivieren"),
+    ("discounts-deactivate", "Deaktivieren"),
+    ("discounts-create-voucher", "Gutschein erstellen"),
+    ("discounts-created", "Erstellt"),
+    ("discounts-balance", "Guthaben"),
+    ("discounts-kind", "Art"),
+    ("discounts-orders", "Bestellungen"),
+    // feed
+    ("feed-title", "Feed"),
+    ("feed-item-title", "Titel"),
+    ("feed-link", "Link, z.B. /shop"),
+    ("feed-text", "Text"),
+    ("feed-announce", "Ankündigen"),
+    ("feed-event", "Event"),
+    ("feed-announcement", "Ankündigung"),
+    ("feed-update", "Aktualisieren"),
+    ("feed-publish", "Veröffentlichen"),
+    ("feed-published", "Veröffentlicht"),
+    ("feed-kind", "Art"),
+    ("feed-updated", "Geändert"),
+    // media
+    ("media-title", "Medien"),
+    ("media-only-unused", "Nur unbenutzte"),
+    ("media-uploading", "Lädt hoch …"),
+    ("media-upload", "Hochladen"),
+    ("media-hint", "JPEG, PNG oder WebP bis 20 MB. Ortsdaten und andere Metadaten werden entfernt."),
+    ("media-stray", "{count} Dateien im Speicher ohne Eintrag"),
+    ("media-clean-up", "Aufräumen"),
+    ("media-unused", "Nicht verwendet"),
+    ("media-unreadable", "{name} konnte nicht gelesen werden"),
+    // packing
+    ("packing-title", "Packliste"),
+    ("packing-export", "Exportieren"),
+    ("packing-empty", "Nichts zu packen."),
+    ("packing-shipped", "Versendet"),
+    ("packing-picked-up", "Abgeholt"),
+    ("packing-shipping", "Versand"),
+    ("packing-pickup", "Abholung"),
+    // products
+    ("products-title", "Produkte"),
+    ("products-description", "Beschreibung"),
+    ("products-images", "Bilder, kommagetrennt"),
+    ("products-hide", "Ausblenden"),
+    ("products-show", "Einblenden"),
+    ("products-variant", "Variante"),
+    ("products-stock", "Bestand"),
+    ("products-low", "niedrig"),
+    ("products-price-invalid", "Ungültiger Preis"),
+    ("products-size", "Größe"),
+    ("products-colour", "Farbe"),
+    ("products-grams", "Gramm"),
+    // fake payment
+    ("fake-payment-title", "Testzahlung"),
+    ("fake-payment-id", "Zahlung {payment}"),
+    ("fake-payment-pay", "Bezahlen"),
+    ("fake-payment-cancel", "Abbrechen"),
+    ("fake-payment-failed", "Zahlung konnte nicht abgeschlossen werden"),
+    // waitlists
+    ("admin-choose-event", "Event wählen"),
+    ("waitlists-title", "Wartelisten"),
+    ("waitlists-places", "Plätze"),
+    ("waitlists-status", "Status"),
+    ("waitlists-offer-until", "Angebot bis"),
+    ("waitlists-log", "Protokoll"),
+    ("waitlists-waiting", "Wartet"),
+    ("waitlists-offered", "Angeboten"),
+    ("waitlists-claimed", "Eingelöst"),
+    ("waitlists-expired", "Abgelaufen"),
+    ("waitlists-left", "Ausgetragen"),
+    ("waitlists-joined", "Eingetragen"),
+    ("waitlists-released", "Freigegeben"),
+    // artists admin
+    ("admin-edit", "Bearbeiten"),
+    ("artists-new", "Neu"),
+    ("artists-lineups", "Lineups"),
+    ("artists-photo", "Foto"),
+    ("artists-genres", "Genres, mit Komma getrennt"),
+    ("artists-links", "Links, einer pro Zeile"),
+    ("artists-bio", "Bio in Markdown"),
+    ("artists-remove", "Entfernen"),
+    ("artists-choose", "Künstler:in wählen"),
+    ("artists-floor", "Floor"),
+    ("artists-set-start", "Beginn des Sets"),
+    ("artists-set-end", "Ende des Sets"),
+    ("artists-add", "Hinzufügen"),
+    // news admin
+    ("news-posts", "Beiträge"),
+    ("news-new", "Neuer Beitrag"),
+    ("news-draft", "Entwurf"),
+    ("news-post-title", "Titel"),
+    ("news-cover", "Titelbild"),
+    ("news-published-hint", "Leer lassen für einen Entwurf"),
+    ("news-body", "Text in Markdown"),
+    // newsletter admin
+    ("newsletter-title", "Newsletter"),
+    ("newsletter-counts", "{confirmed} bestätigt · {pending} unbestätigt · {unsubscribed} abgemeldet"),
+    ("newsletter-subject", "Betreff"),
+    ("newsletter-body", "Text; der Abmeldelink wird automatisch angehängt."),
+    ("newsletter-send-confirm", "Wirklich an alle senden?"),
+    ("newsletter-send", "Senden"),
+    ("newsletter-sent", "Verschickt"),
+    ("newsletter-failed", "Fehler"),
+    // notices
+    ("notice-sold-out", "Ausverkauft: {name}"),
+    ("notice-low-stock", "Bestand niedrig: {product} ({count} Stück)"),
+    ("notice-waitlist-offer", "Tickets für {event} sind für dich frei"),
+    // mails
+    ("mail-payout-provider", "{amount} werden über deine ursprüngliche Zahlungsart erstattet."),
+    ("mail-payout-voucher", "{amount} werden deinem Gutschein gutgeschrieben."),
+    ("mail-payout-both", "{amount} werden über deine ursprüngliche Zahlungsart erstattet, {voucher} deinem Gutschein gutgeschrieben."),
+    ("mail-refund-subject", "Erstattung deiner Bestellung"),
+    ("mail-refund-body", "Hallo,\n\nwir haben ein Ticket deiner Bestellung {order} storniert. {payout}\n\nDeine Stampffabrik"),
+    ("mail-cancelled-subject", "Absage: {event}"),
+    ("mail-cancelled-body", "Hallo,\n\nleider müssen wir {event} am {date} absagen. Deine Tickets sind damit ungültig. {payout}\n\nDeine Stampffabrik"),
+    ("mail-settings-footer", "Welche Mails du von uns bekommst, stellst du in deinem Konto ein:\n{link}"),
+    ("mail-reminder-subject", "Bald ist es so weit: {event}"),
+    ("mail-reminder-body", "Hallo {name},\n\nam {date} ist {event} – wir freuen uns auf dich!\n\nEinlass: {doors} Uhr\nBeginn: {starts} Uhr\nOrt: {venue}\n\nDeine Tickets ({tickets}) findest du in deinem Konto, zeig einfach den QR-Code am Einlass:\n{account}\n\nBitte lies vorher unsere Hausordnung:\n{rules}\n\nDeine Stampffabrik\n\n{footer}"),
+    ("mail-follow-up-subject", "Danke für {event}"),
+    ("mail-follow-up-thanks", "Hallo {name},\n\ndanke, dass du bei {event} dabei warst!"),
+    ("mail-follow-up-photos", "Die Fotos des Abends findest du hier:\n{link}"),
+    ("mail-follow-up-survey", "Wie war's? Sag uns in ein paar Minuten deine Meinung:\n{link}"),
+    ("mail-follow-up-closing", "Bis zum nächsten Mal!\nDeine Stampffabrik"),
+    ("mail-newsletter-confirm-subject", "Bitte bestätige deine Newsletter-Anmeldung"),
+    ("mail-newsletter-confirm-body", "Hallo,\n\njemand, hoffentlich du, hat diese Adresse für den Newsletter der Stampffabrik angemeldet. Bitte bestätige das mit einem Klick:\n\n{link}\n\nFalls du das nicht warst, ignoriere diese Mail einfach.\n\nDeine Stampffabrik"),
+    ("mail-newsletter-footer", "Du bekommst diese Mail, weil du den Newsletter der Stampffabrik abonniert hast. Abmelden: {link}"),
+    ("mail-shipped-subject", "Deine Bestellung ist unterwegs"),
+    ("mail-shipped-body", "Hallo {name},\n\ndeine Bestellung {order} hat uns gerade verlassen.\n\nDeine Stampffabrik"),
+    ("mail-vouchers-subject", "Deine Gutscheine"),
+    ("mail-vouchers-body", "Hallo,\n\nvielen Dank für deinen Einkauf. Hier sind deine Gutscheincodes:\n\n{codes}\n\nSie können beim Bezahlen im Warenkorb eingelöst werden, auch in Teilen.\n\nDeine Stampffabrik"),
+    ("mail-waitlist-subject", "Tickets für {event} frei"),
+    ("mail-waitlist-body", "Hallo,\n\nauf der Warteliste für {event} bist du jetzt dran: wir halten {quantity} × {ticket_type} für dich bereit.\n\nBis {expires} kannst du sie hier kaufen, danach gehen sie an die Nächsten in der Reihe:\n{link}\n\nDeine Stampffabrik"),
+    ("mail-low-stock-subject", "Bestand niedrig: {product} {sku}"),
+    ("mail-low-stock-body", "Von {product} ({variant}, SKU {sku}) sind nur noch {count} Stück auf Lager.\n\nDer Bestand kann unter /admin/products angepasst werden."),
+];
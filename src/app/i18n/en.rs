@@ -0,0 +1,529 @@
+pub const MONTHS: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June",
+    "July", "August", "September", "October", "November", "December",
+];
+
+pub const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+pub const TEXTS: &[(&str, &str)] = &[
+    // shared
+    ("loading", "Loading…"),
+    ("site-description", "Concerts, parties and merch from the Stampffabrik."),
+    ("language", "Language"),
+    ("email", "Email"),
+    ("name", "Name"),
+    ("zipcode", "Postcode"),
+    ("city", "City"),
+    ("sold-out", "Sold out"),
+    ("cancelled", "Cancelled"),
+    ("add-to-cart", "Add to cart"),
+    ("go-to-cart", "Go to cart"),
+    ("pickup", "Pick up at the bar"),
+    ("column-item", "Item"),
+    ("column-quantity", "Quantity"),
+    ("column-price", "Price"),
+    ("column-sum", "Subtotal"),
+    ("column-number", "Number"),
+    ("column-date", "Date"),
+    ("column-amount", "Amount"),
+    ("total", "Total"),
+    // errors
+    ("error-not-logged-in", "Not logged in"),
+    ("error-not-allowed", "Not allowed"),
+    ("error-invalid-email", "Invalid email"),
+    ("error-slug-taken", "Slug already exists"),
+    ("error-artist-invalid", "Name and a slug of letters, digits and dashes are required"),
+    ("error-links-https", "Links must start with https://"),
+    ("error-set-time", "Invalid set time"),
+    ("error-set-order", "Set ends before it starts"),
+    ("error-title-required", "Title is required"),
+    ("error-announcement-failed", "Announcement could not be saved"),
+    ("error-publish-failed", "Event could not be published"),
+    ("error-product-invalid", "Name and slug are required"),
+    ("error-variant-invalid", "SKU and price are required"),
+    ("error-sku-taken", "SKU already exists"),
+    ("error-confirm-failed", "Could not confirm"),
+    ("error-unsubscribe-failed", "Could not unsubscribe"),
+    ("error-campaign-invalid", "Subject and text are required"),
+    ("error-invalid-link", "Invalid link"),
+    ("error-fields-required", "All fields are required!"),
+    ("error-quota-failed", "Error setting quota!"),
+    ("error-name-required", "Name is required!"),
+    ("error-door-name-failed", "Error adding name!"),
+    ("error-door-codes-failed", "Error loading door codes!"),
+    ("error-publish-date", "Invalid publish date"),
+    ("error-post-invalid", "Title and a slug of letters, digits and dashes are required"),
+    ("error-code-length", "Code must be 3 to 32 characters"),
+    ("error-code-taken", "Code already exists"),
+    ("error-area-invalid", "Name and a capacity of at least 1 are required"),
+    ("error-too-heavy", "Too heavy to ship to this country"),
+    ("error-cart-empty", "Cart is empty"),
+    ("error-user-not-found", "User not found"),
+    ("error-login", "Error logging in!"),
+    ("error-sign-up", "Error signing up!"),
+    ("error-no-cookie", "No cookie provided"),
+    ("error-no-request", "No valid request"),
+    ("error-invalid-token", "Invalid token"),
+    ("error-user-update", "failed to update user"),
+    ("error-user-create", "failed to create user"),
+    ("error-user-delete", "failed to delete user"),
+    ("error-event-not-found", "Event not found"),
+    ("error-event-invalid", "a title is required and links must be web addresses"),
+    ("error-ticket-not-found", "Ticket not found"),
+    ("error-check-in", "failed to check in ticket"),
+    ("error-ticket-transfer", "failed to transfer ticket"),
+    ("error-guest-not-found", "Guest not found"),
+    ("error-guest-create", "failed to add guest"),
+    ("error-guest-quota", "guest quota is used up"),
+    ("error-order-not-found", "Order not found"),
+    ("error-order-create", "failed to create order"),
+    ("error-order-status", "failed to update order status"),
+    ("error-payment", "payment failed"),
+    ("error-invoice-not-found", "Invoice not found"),
+    ("error-invoice-create", "failed to issue invoice"),
+    ("error-refund", "failed to refund"),
+    ("error-mail", "failed to send mail"),
+    ("error-discount-invalid", "invalid discount code"),
+    ("error-voucher-invalid", "invalid or empty voucher"),
+    ("error-product-not-found", "product not found"),
+    ("error-delivery-invalid", "invalid delivery option or address"),
+    ("error-post-not-found", "post not found"),
+    ("error-media-not-found", "file not found"),
+    ("error-media-invalid", "only JPEG, PNG and WebP images up to 20 MB can be uploaded"),
+    ("error-media-in-use", "file is still in use"),
+    ("error-media-storage", "file could not be stored"),
+    ("error-artist-not-found", "artist not found"),
+    ("error-area-not-found", "area not found"),
+    ("error-area-full", "area is at capacity"),
+    ("error-waitlist", "could not join the waitlist"),
+    ("error-offer-invalid", "offer is no longer valid"),
+    ("error-job", "could not schedule the job"),
+    ("error-waitlist-places", "Between 1 and {max} places"),
+    ("error-no-quota", "{name}: {inviter} has no quota"),
+    ("error-quota-used-up", "{name}: {inviter}'s quota is used up"),
+    ("error-address-missing", "{field} is missing"),
+    ("error-address-country", "We do not ship to this country"),
+    ("error-address-zipcode", "Postcode does not match the country"),
+    ("error-guest-name", "Name is missing"),
+    ("error-guest-plus", "{name}: at most 20 companions"),
+    // header and footer
+    ("nav-home", "Home"),
+    ("nav-news", "News"),
+    ("nav-calendar", "Calendar"),
+    ("nav-artists", "Artists"),
+    ("nav-shop", "Shop"),
+    ("nav-cart", "Cart"),
+    ("nav-account", "Account"),
+    ("not-found", "Page not found"),
+    // sign in and sign up
+    ("auth-password", "Password"),
+    ("auth-login", "Log in"),
+    ("auth-register", "Sign up"),
+    ("auth-create-account", "Create a new account"),
+    ("auth-have-account", "I already have an account"),
+    ("auth-fields-required", "All fields are required!"),
+    ("auth-login-failed", "Login failed"),
+    ("auth-register-failed", "Sign up failed"),
+    // home
+    ("home-upcoming", "Upcoming events"),
+    ("home-all-dates", "All dates"),
+    ("crowd-quiet", "Plenty of room right now – come on over!"),
+    ("crowd-busy", "It's busy right now."),
+    ("crowd-full", "Packed right now – expect a wait at the door."),
+    // events and calendar
+    ("event-times", "Doors {doors} · Start {starts}"),
+    ("event-description", "{title} at the Stampffabrik, {date}"),
+    ("event-edit-title", "Edit event"),
+    ("event-edit-name", "Title"),
+    ("event-edit-poster", "Poster"),
+    ("event-edit-gallery", "Photo gallery"),
+    ("event-edit-survey", "Survey"),
+    ("event-edit-links-hint", "Gallery and survey are linked in the mail after the night. Without a survey the general one is used."),
+    ("event-edit-save", "Save"),
+    ("event-edit-saved", "Saved."),
+    ("calendar-title", "Calendar"),
+    ("calendar-description", "All concerts and parties at the Stampffabrik, also as a subscription for your own calendar."),
+    ("calendar-month", "Month"),
+    ("calendar-list", "List"),
+    ("calendar-subscribe", "Subscribe to calendar"),
+    ("calendar-empty", "No upcoming dates."),
+    ("calendar-add", "Add to calendar"),
+    // waitlist
+    ("waitlist-title", "Waitlist"),
+    ("waitlist-intro", "When tickets become available again, we offer them in order. You'll get an email with a link to buy them for a limited time."),
+    ("waitlist-login", "Log in to join the waitlist."),
+    ("waitlist-places", "{count} ticket(s)"),
+    ("waitlist-join", "Join the waitlist"),
+    ("waitlist-position", "You're on the waitlist, ahead of you: {ahead}."),
+    ("waitlist-offered", "Tickets are available for you!"),
+    ("waitlist-leave", "Leave the waitlist"),
+    ("offer-title", "Tickets from the waitlist"),
+    ("offer-details", "{quantity} × {ticket} for {event}, {price} in total."),
+    ("offer-expires", "We're holding them for you until {time}, then they go to the next in line."),
+    ("offer-claim", "Buy now"),
+    ("offer-login", "Log in with the account you joined the waitlist with."),
+    ("offer-gone", "This offer is no longer valid."),
+    // shop
+    ("shop-title", "Shop"),
+    ("shop-description", "Shirts, hoodies and more from the Stampffabrik."),
+    ("shop-from", "from {price}"),
+    // cart
+    ("cart-title", "Cart"),
+    ("cart-empty", "Your cart is empty."),
+    ("cart-street", "Street (optional)"),
+    ("cart-discount-code", "Discount code"),
+    ("cart-voucher-code", "Voucher code"),
+    ("cart-checkout", "Checkout"),
+    ("cart-shipping", "Shipping"),
+    ("cart-shipping-cost", "Shipping: {price}"),
+    ("cart-free", "Free"),
+    ("cart-state", "State"),
+    ("cart-address-line1", "Street and number"),
+    ("cart-address-line2", "Address line 2"),
+    ("cart-vouchers", "Vouchers"),
+    ("cart-voucher", "Voucher {price}"),
+    ("cart-voucher-item", "Voucher {value}"),
+    ("cart-shipping-item", "Shipping to {country}"),
+    // orders
+    ("order-title", "Order"),
+    ("order-history", "Orders"),
+    ("order-vat", "incl. {rate} % VAT"),
+    ("order-shipping-to", "Shipping to {address}"),
+    ("order-pending", "Pending"),
+    ("order-paid", "Paid"),
+    ("order-fulfilled", "Completed"),
+    ("order-refunded", "Refunded"),
+    ("order-cancelled", "Cancelled"),
+    // account and tickets
+    ("account-title", "Account"),
+    ("account-address", "Address"),
+    ("wallet-title", "My tickets"),
+    ("wallet-past", "Past events"),
+    ("wallet-expired", "Expired"),
+    ("wallet-used", "Used"),
+    ("wallet-refunded", "Refunded"),
+    ("wallet-cancelled", "Cancelled"),
+    ("wallet-transfer", "Pass on"),
+    ("wallet-send", "Send ticket"),
+    ("settings-title", "Notifications"),
+    ("settings-event-reminders", "Reminder the day before the event"),
+    ("settings-event-follow-ups", "Thank-you mail with photos and a survey after the event"),
+    ("settings-save", "Save"),
+    ("settings-saved", "Saved."),
+    // newsletter
+    ("newsletter-placeholder", "Newsletter: your email"),
+    ("newsletter-subscribe", "Subscribe"),
+    ("newsletter-pending", "Almost done! Please confirm your subscription in the mail we just sent you."),
+    ("newsletter-confirm-title", "Confirm newsletter"),
+    ("newsletter-confirmed", "Thanks! You'll get our newsletter from now on."),
+    ("newsletter-unsubscribe-title", "Unsubscribe from newsletter"),
+    ("newsletter-unsubscribe-question", "Do you want to unsubscribe from our newsletter?"),
+    ("newsletter-unsubscribe", "Unsubscribe"),
+    ("newsletter-unsubscribed", "You're unsubscribed and won't get any more newsletters."),
+    // invoices
+    ("invoices-title", "Invoices"),
+    ("invoice-cancels", "cancels {number}"),
+    ("invoice-cancel", "Cancel"),
+    ("invoice-save", "Save"),
+    // news
+    ("news-title", "News"),
+    ("news-description", "News from the Stampffabrik: lineups, house rules, closing days."),
+    ("news-empty", "No posts yet."),
+    ("news-all", "All posts"),
+    // artists
+    ("artists-title", "Artists"),
+    ("artists-description", "Bands, DJs and acts who play and played at the Stampffabrik."),
+    ("artists-empty", "No artists yet."),
+    ("artists-all-genres", "All"),
+    ("artist-description", "{name} at the Stampffabrik: upcoming and past shows."),
+    ("artist-upcoming", "Upcoming"),
+    ("artist-past", "Played here"),
+    ("artist-no-dates", "No dates at the moment."),
+    ("artists-all", "All artists"),
+    ("lineup-title", "Lineup"),
+    // admin pages
+    ("admin-save", "Save"),
+    ("admin-create", "Create"),
+    ("admin-delete", "Delete"),
+    ("push-live", "Live"),
+    ("push-reconnecting", "No live connection, reconnecting …"),
+    // occupancy
+    ("occupancy-title", "Occupancy"),
+    ("occupancy-empty", "No areas set up yet."),
+    ("occupancy-reset", "Reset"),
+    ("occupancy-reset-confirm", "Set the counter to 0?"),
+    ("occupancy-full", "FULL – let nobody in"),
+    ("occupancy-warning", "Almost full"),
+    ("occupancy-of", "of {capacity} · {percent} %"),
+    ("areas-title", "Areas"),
+    ("areas-hint", "Warning from {percent} % of capacity, at full capacity nobody can enter."),
+    ("areas-capacity", "Capacity"),
+    ("areas-position", "Order"),
+    ("areas-new", "New area"),
+    // door
+    ("door-title", "Door"),
+    ("door-mode-in", "Entry"),
+    ("door-mode-out", "Stamp / out"),
+    ("door-inside", "Inside"),
+    ("door-out", "Outside (stamped)"),
+    ("door-expected", "Total"),
+    ("door-search", "Search name"),
+    ("door-check-in", "Check in"),
+    ("door-add-name", "Add name"),
+    ("scan-admitted", "ADMITTED"),
+    ("scan-re-entry", "RE-ENTRY"),
+    ("scan-stamped-out", "STAMPED"),
+    ("scan-provisional", "ADMITTED (OFFLINE)"),
+    ("scan-duplicate", "ALREADY CHECKED IN"),
+    ("scan-refunded", "REFUNDED"),
+    ("scan-cancelled", "CANCELLED"),
+    ("scan-unknown", "UNKNOWN"),
+    ("scan-since", "since {time}"),
+    ("ticket-valid", "Valid"),
+    ("ticket-checked-in", "Checked in"),
+    ("ticket-out", "Outside"),
+    ("ticket-refunded", "Refunded"),
+    ("ticket-cancelled", "Cancelled"),
+    // offline door
+    ("offline-title", "Door offline"),
+    ("offline-online", "Online"),
+    ("offline-offline", "Offline"),
+    ("offline-pending", "{count} not synced"),
+    ("offline-loaded", "List loaded: {count} entries"),
+    ("offline-load-failed", "The list could not be loaded"),
+    ("offline-synced", "{applied} scans synced, {conflicts} conflicts"),
+    ("offline-sync-failed", "Sync failed"),
+    ("offline-no-list", "No list loaded"),
+    ("conflict-duplicate", "duplicate, first {device} at {time}"),
+    ("conflict-refunded", "refunded"),
+    ("conflict-cancelled", "cancelled"),
+    ("conflict-unknown", "unknown"),
+    ("offline-not-inside", "Ticket is not inside"),
+    // scanner
+    ("scanner-code", "Code"),
+    ("scanner-ok", "OK"),
+    ("scanner-unsupported", "Camera scanning is not supported by this browser"),
+    ("scanner-no-camera", "Camera not available"),
+    // guest list
+    ("guests-title", "Guest list"),
+    ("guests-invited-by", "Invited by"),
+    ("guests-invited-by-hint", "Invited by (empty: you)"),
+    ("guests-note", "Note"),
+    ("guests-added-by", "Added by"),
+    ("guests-inside", "Inside"),
+    ("guests-audit", "Log"),
+    ("guests-action-added", "Added"),
+    ("guests-action-imported", "Imported"),
+    ("guests-action-removed", "Removed"),
+    ("guests-action-checked-in", "Checked in"),
+    ("guests-add", "Add"),
+    ("guests-used", "Used"),
+    ("guests-quota", "Quota"),
+    ("guests-set-quota", "Set quota"),
+    ("guests-import", "Import"),
+    ("guests-export", "Export"),
+    ("guests-csv", "guest-list.csv"),
+    ("guests-imported", "{count} imported"),
+    ("guests-search", "Search guest list"),
+    ("guests-already-inside", "{name} is already inside (since {time})"),
+    ("guests-checked-in", "{name} +{plus} checked in"),
+    ("guests-is-inside", "inside"),
+    ("error-import-plus", "Line {line}: invalid number '{plus}'"),
+    // jobs
+    ("jobs-title", "Jobs"),
+    ("jobs-refresh", "Refresh"),
+    ("jobs-recurring", "Recurring"),
+    ("jobs-job", "Job"),
+    ("jobs-schedule", "Schedule"),
+    ("jobs-next-run", "Next run"),
+    ("jobs-history", "History"),
+    ("jobs-all", "All"),
+    ("jobs-filter-queued", "Queued"),
+    ("jobs-filter-running", "Running"),
+    ("jobs-status", "Status"),
+    ("jobs-attempts", "Attempts"),
+    ("jobs-due", "Due"),
+    ("jobs-error", "Error"),
+    ("jobs-run-now", "Now"),
+    ("jobs-delete", "Cancel"),
+    ("jobs-retry", "Retry"),
+    ("job-retrying", "Retrying"),
+    ("job-queued", "Queued"),
+    ("job-running", "Running"),
+    ("job-done", "Done"),
+    ("job-dead", "Failed"),
+    ("job-expire-offers", "Waitlist offers"),
+    ("job-expire-orders", "Unpaid orders"),
+    ("job-purge", "Delete old jobs"),
+    ("job-event-mails", "Event mails"),
+    // refunds
+    ("refunds-title", "Refunds"),
+    ("refunds-cancel-event", "Cancel event"),
+    ("refunds-confirm-cancel", "Really cancel?"),
+    ("refunds-report", "{orders} orders refunded, {amount} in total, {mailed} mails sent"),
+    ("refunds-failed", "Refund failed: {order}"),
+    ("refunds-code", "Code"),
+    ("refunds-holder", "Holder"),
+    ("refunds-status", "Status"),
+    ("refunds-refund", "Refund"),
+    ("refunds-order", "Order"),
+    ("refunds-reason", "Reason"),
+    ("refunds-reason-cancelled", "Cancellation"),
+    ("refunds-reason-requested", "Single"),
+    ("refunds-succeeded", "Refunded"),
+    ("refunds-status-failed", "Failed"),
+    // discounts
+    ("discounts-title", "Discounts"),
+    ("discounts-codes", "Discount codes"),
+    ("discounts-vouchers", "Vouchers"),
+    ("discounts-redemptions", "Redemptions"),
+    ("discounts-invalid-value", "Invalid value"),
+    ("discounts-code", "Code"),
+    ("discounts-percent", "Percent"),
+    ("discounts-euro", "Euro"),
+    ("discounts-value", "Value"),
+    ("discounts-value-euro", "Value in euros"),
+    ("discounts-scope", "Applies to"),
+    ("discounts-scope-all", "Everything"),
+    ("discounts-scope-event", "Event"),
+    ("discounts-scope-ticket-type", "Ticket type"),
+    ("discounts-scope-id", "Event or ticket type ID"),
+    ("discounts-max-uses", "Max."),
+    ("discounts-discount", "Discount"),
+    ("discounts-voucher", "Voucher"),
+    ("discounts-used", "Used"),
+    ("discounts-period", "Period"),
+    ("discounts-activate", "Activate"),
+    ("discounts-deactivate", "Deactivate"),
+    ("discounts-create-voucher", "Create voucher"),
+    ("discounts-created", "Created"),
+    ("discounts-balance", "Balance"),
+    ("discounts-kind", "Kind"),
+    ("discounts-orders", "Orders"),
+    // feed
+    ("feed-title", "Feed"),
+    ("feed-item-title", "Title"),
+    ("feed-link", "Link, e.g. /shop"),
+    ("feed-text", "Text"),
+    ("feed-announce", "Announce"),
+    ("feed-event", "Event"),
+    ("feed-announcement", "Announcement"),
+    ("feed-update", "Update"),
+    ("feed-publish", "Publish"),
+    ("feed-published", "Published"),
+    ("feed-kind", "Kind"),
+    ("feed-updated", "Updated"),
+    // media
+    ("media-title", "Media"),
+    ("media-only-unused", "Unused only"),
+    ("media-uploading", "Uploading …"),
+    ("media-upload", "Upload"),
+    ("media-hint", "JPEG, PNG or WebP up to 20 MB. Location and other metadata are removed."),
+    ("media-stray", "{count} files in storage without a record"),
+    ("media-clean-up", "Clean up"),
+    ("media-unused", "Not used"),
+    ("media-unreadable", "{name} could not be read"),
+    // packing
+    ("packing-title", "Packing list"),
+    ("packing-export", "Export"),
+    ("packing-empty", "Nothing to pack."),
+    ("packing-shipped", "Shipped"),
+    ("packing-picked-up", "Picked up"),
+    ("packing-shipping", "Shipping"),
+    ("packing-pickup", "Pickup"),
+    // products
+    ("products-title", "Products"),
+    ("products-description", "Description"),
+    ("products-images", "Images, comma separated"),
+    ("products-hide", "Hide"),
+    ("products-show", "Show"),
+    ("products-variant", "Variant"),
+    ("products-stock", "Stock"),
+    ("products-low", "low"),
+    ("products-price-invalid", "Invalid price"),
+    ("products-size", "Size"),
+    ("products-colour", "Colour"),
+    ("products-grams", "Grams"),
+    // fake payment
+    ("fake-payment-title", "Test payment"),
+    ("fake-payment-id", "Payment {payment}"),
+    ("fake-payment-pay", "Pay"),
+    ("fake-payment-cancel", "Cancel"),
+    ("fake-payment-failed", "Payment could not be completed"),
+    // waitlists
+    ("admin-choose-event", "Choose event"),
+    ("waitlists-title", "Waitlists"),
+    ("waitlists-places", "Places"),
+    ("waitlists-status", "Status"),
+    ("waitlists-offer-until", "Offer until"),
+    ("waitlists-log", "Log"),
+    ("waitlists-waiting", "Waiting"),
+    ("waitlists-offered", "Offered"),
+    ("waitlists-claimed", "Claimed"),
+    ("waitlists-expired", "Expired"),
+    ("waitlists-left", "Left"),
+    ("waitlists-joined", "Joined"),
+    ("waitlists-released", "Released"),
+    // artists admin
+    ("admin-edit", "Edit"),
+    ("artists-new", "New"),
+    ("artists-lineups", "Lineups"),
+    ("artists-photo", "Photo"),
+    ("artists-genres", "Genres, comma separated"),
+    ("artists-links", "Links, one per line"),
+    ("artists-bio", "Bio in Markdown"),
+    ("artists-remove", "Remove"),
+    ("artists-choose", "Choose artist"),
+    ("artists-floor", "Floor"),
+    ("artists-set-start", "Start of the set"),
+    ("artists-set-end", "End of the set"),
+    ("artists-add", "Add"),
+    // news admin
+    ("news-posts", "Posts"),
+    ("news-new", "New post"),
+    ("news-draft", "Draft"),
+    ("news-post-title", "Title"),
+    ("news-cover", "Cover image"),
+    ("news-published-hint", "Leave empty for a draft"),
+    ("news-body", "Text in Markdown"),
+    // newsletter admin
+    ("newsletter-title", "Newsletter"),
+    ("newsletter-counts", "{confirmed} confirmed · {pending} unconfirmed · {unsubscribed} unsubscribed"),
+    ("newsletter-subject", "Subject"),
+    ("newsletter-body", "Text; the unsubscribe link is added automatically."),
+    ("newsletter-send-confirm", "Really send to everyone?"),
+    ("newsletter-send", "Send"),
+    ("newsletter-sent", "Sent"),
+    ("newsletter-failed", "Failed"),
+    // notices
+    ("notice-sold-out", "Sold out: {name}"),
+    ("notice-low-stock", "Low stock: {product} ({count} left)"),
+    ("notice-waitlist-offer", "Tickets for {event} are free for you"),
+    // mails
+    ("mail-payout-provider", "{amount} will be refunded to your original payment method."),
+    ("mail-payout-voucher", "{amount} will be credited to your voucher."),
+    ("mail-payout-both", "{amount} will be refunded to your original payment method and {voucher} credited to your voucher."),
+    ("mail-refund-subject", "Refund for your order"),
+    ("mail-refund-body", "Hello,\n\nwe have cancelled a ticket of your order {order}. {payout}\n\nYour Stampffabrik"),
+    ("mail-cancelled-subject", "Cancelled: {event}"),
+    ("mail-cancelled-body", "Hello,\n\nunfortunately we have to call off {event} on {date}. Your tickets are no longer valid. {payout}\n\nYour Stampffabrik"),
+    ("mail-settings-footer", "You can choose which mails you get from us in your account:\n{link}"),
+    ("mail-reminder-subject", "Coming up soon: {event}"),
+    ("mail-reminder-body", "Hello {name},\n\n{event} is on {date} – we are looking forward to seeing you!\n\nDoors: {doors}\nStart: {starts}\nVenue: {venue}\n\nYour tickets ({tickets}) are in your account, just show the QR code at the door:\n{account}\n\nPlease read our house rules beforehand:\n{rules}\n\nYour Stampffabrik\n\n{footer}"),
+    ("mail-follow-up-subject", "Thanks for {event}"),
+    ("mail-follow-up-thanks", "Hello {name},\n\nthanks for being at {event}!"),
+    ("mail-follow-up-photos", "The photos of the night are here:\n{link}"),
+    ("mail-follow-up-survey", "How was it? Tell us what you think, it takes a few minutes:\n{link}"),
+    ("mail-follow-up-closing", "See you next time!\nYour Stampffabrik"),
+    ("mail-newsletter-confirm-subject", "Please confirm your newsletter subscription"),
+    ("mail-newsletter-confirm-body", "Hello,\n\nsomeone, hopefully you, signed this address up for the Stampffabrik newsletter. Please confirm with one click:\n\n{link}\n\nIf that was not you, just ignore this mail.\n\nYour Stampffabrik"),
+    ("mail-newsletter-footer", "You get this mail because you subscribed to the Stampffabrik newsletter. Unsubscribe: {link}"),
+    ("mail-shipped-subject", "Your order is on its way"),
+    ("mail-shipped-body", "Hello {name},\n\nyour order {order} has just left us.\n\nYour Stampffabrik"),
+    ("mail-vouchers-subject", "Your vouchers"),
+    ("mail-vouchers-body", "Hello,\n\nthank you for your purchase. Here are your voucher codes:\n\n{codes}\n\nThey can be redeemed in the cart at checkout, in parts too.\n\nYour Stampffabrik"),
+    ("mail-waitlist-subject", "Tickets for {event} available"),
+    ("mail-waitlist-body", "Hello,\n\nit's your turn on the waitlist for {event}: we are holding {quantity} × {ticket_type} for you.\n\nYou can buy them here until {expires}, after that they go to the next in line:\n{link}\n\nYour Stampffabrik"),
+    ("mail-low-stock-subject", "Low stock: {product} {sku}"),
+    ("mail-low-stock-body", "Only {count} of {product} ({variant}, SKU {sku}) are left in stock.\n\nThe stock can be changed under /admin/products."),
+];
@@ -0,0 +1,222 @@
+//! Translations of the UI. Texts are looked up by key in the catalogues of
+//! the supported languages; keys missing from a catalogue fall back to
+//! German. The language is picked once per request on the server, from the
+//! `lang` cookie or else `Accept-Language`, and written to `<html lang>`,
+//! where the client picks it up when it hydrates. Mails are written in the
+//! language kept with their recipient: the account's, or the one an order or
+//! newsletter signup was made in.
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime, Timelike};
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+
+mod de;
+mod en;
+
+/// Holds the language chosen with the switcher.
+pub const LOCALE_COOKIE: &str = "lang";
+
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+pub enum Locale {
+    #[default]
+    De,
+    En,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::De, Locale::En];
+
+    /// Language tag for `<html lang>` and the cookie.
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::De => "de",
+            Locale::En => "en",
+        }
+    }
+
+    /// Reads a language tag, regional variants included, e.g. `en-GB`.
+    pub fn from_code(code: &str) -> Option<Locale> {
+        let language = code.trim().split(['-', '_']).next().unwrap_or_default().to_lowercase();
+        Locale::ALL.into_iter().find(|locale| locale.code() == language)
+    }
+
+    /// For `og:locale`.
+    pub fn territory(self) -> &'static str {
+        match self {
+            Locale::De => "de_DE",
+            Locale::En => "en_GB",
+        }
+    }
+
+    fn catalogue(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Locale::De => de::TEXTS,
+            Locale::En => en::TEXTS,
+        }
+    }
+
+    /// The text for `key`; the key itself if no catalogue has it.
+    pub fn text<'a>(self, key: &'a str) -> &'a str {
+        let find = |texts: &[(&'static str, &'static str)]| {
+            texts.iter().find(|(k, _)| *k == key).map(|(_, text)| *text)
+        };
+        find(self.catalogue()).or_else(|| find(de::TEXTS)).unwrap_or(key)
+    }
+
+    /// The text for `key` with its `{name}` placeholders filled in.
+    pub fn format(self, key: &str, args: &[(&str, &str)]) -> String {
+        args.iter().fold(self.text(key).to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{name}}}"), value)
+        })
+    }
+
+    pub fn month(self, month0: u32) -> &'static str {
+        let months = match self {
+            Locale::De => de::MONTHS,
+            Locale::En => en::MONTHS,
+        };
+        months[month0 as usize % 12]
+    }
+
+    /// Short name of the weekday, Monday first.
+    pub fn weekday(self, days_from_monday: u32) -> &'static str {
+        let weekdays = match self {
+            Locale::De => de::WEEKDAYS,
+            Locale::En => en::WEEKDAYS,
+        };
+        weekdays[days_from_monday as usize % 7]
+    }
+
+    pub fn date(self, date: NaiveDate) -> String {
+        match self {
+            Locale::De => date.format("%d.%m.%Y").to_string(),
+            Locale::En => format!("{} {} {}", date.day(), &self.month(date.month0())[..3], date.year()),
+        }
+    }
+
+    pub fn time(self, time: NaiveDateTime) -> String {
+        match self {
+            Locale::De => time.format("%H:%M").to_string(),
+            Locale::En => {
+                let (pm, hour) = time.hour12();
+                format!("{hour}:{:02} {}", time.minute(), if pm { "pm" } else { "am" })
+            }
+        }
+    }
+
+    pub fn date_time(self, time: NaiveDateTime) -> String {
+        match self {
+            Locale::De => format!("{}, {} Uhr", self.date(time.date()), self.time(time)),
+            Locale::En => format!("{}, {}", self.date(time.date()), self.time(time)),
+        }
+    }
+
+    /// Euros, e.g. `12,50 €` or `€12.50`.
+    pub fn price(self, cents: i64) -> String {
+        let sign = if cents < 0 { "-" } else { "" };
+        let (euros, cents) = (cents.abs() / 100, cents.abs() % 100);
+        match self {
+            Locale::De => format!("{sign}{euros},{cents:02} €"),
+            Locale::En => format!("{sign}€{euros}.{cents:02}"),
+        }
+    }
+}
+
+/// Best supported match for an `Accept-Language` header, German if there
+/// is none.
+pub fn negotiate(accept_language: &str) -> Locale {
+    let mut ranges: Vec<(u32, Locale)> = accept_language
+        .split(',')
+        .filter_map(|range| {
+            let mut parts = range.split(';');
+            let locale = Locale::from_code(parts.next()?)?;
+            let quality = parts
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .and_then(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+            Some(((quality * 1000.0) as u32, locale))
+        })
+        .filter(|(quality, _)| *quality > 0)
+        .collect();
+    // stable, so equal weights keep the order of the header
+    ranges.sort_by(|a, b| b.0.cmp(&a.0));
+    ranges.first().map(|(_, locale)| *locale).unwrap_or_default()
+}
+
+/// Language of the current request: the cookie if set, else the browser's
+/// preference.
+fn initial_locale() -> Locale {
+    cfg_if::cfg_if! {
+        if #[cfg(feature = "ssr")] {
+            use actix_web::{http::header::ACCEPT_LANGUAGE, HttpRequest};
+
+            let Some(request) = use_context::<HttpRequest>() else {
+                return Locale::default();
+            };
+            if let Some(locale) = request.cookie(LOCALE_COOKIE).and_then(|c| Locale::from_code(c.value())) {
+                return locale;
+            }
+            request
+                .headers()
+                .get(ACCEPT_LANGUAGE)
+                .and_then(|value| value.to_str().ok())
+                .map(negotiate)
+                .unwrap_or_default()
+        } else {
+            document()
+                .document_element()
+                .and_then(|html| html.get_attribute("lang"))
+                .and_then(|lang| Locale::from_code(&lang))
+                .unwrap_or_default()
+        }
+    }
+}
+
+/// Provides the language as context, see the module docs.
+pub fn provide_locale() {
+    provide_context(RwSignal::new(initial_locale()));
+}
+
+/// Language of the request a server function answers, for the messages it
+/// sends back. German outside of requests.
+pub fn request_locale() -> Locale {
+    initial_locale()
+}
+
+/// The text for `key` in the language of the current request.
+pub fn request_text(key: &'static str) -> String {
+    request_locale().text(key).to_string()
+}
+
+pub fn use_locale() -> RwSignal<Locale> {
+    expect_context::<RwSignal<Locale>>()
+}
+
+/// The text for `key` in the current language, for use in views.
+pub fn t(key: &'static str) -> impl Fn() -> &'static str + Copy + Send + Sync + 'static {
+    let locale = use_locale();
+    move || locale.get().text(key)
+}
+
+/// Remembers the chosen language for the next requests, and on the account
+/// for mails.
+#[server(SetLocale, "/api")]
+pub async fn set_locale(locale: Locale) -> Result<(), ServerFnError> {
+    use actix_web::cookie::{time::Duration, Cookie, SameSite};
+    use actix_web::http::header::{self, HeaderValue};
+    use leptos_actix::ResponseOptions;
+
+    let cookie = Cookie::build(LOCALE_COOKIE, locale.code())
+        .secure(true)
+        .same_site(SameSite::Lax)
+        .path("/")
+        .max_age(Duration::days(365))
+        .finish();
+    if let Ok(cookie) = HeaderValue::from_str(&cookie.to_string()) {
+        expect_context::<ResponseOptions>().insert_header(header::SET_COOKIE, cookie);
+    }
+    if let Some(user) = crate::app::auth::current_user().await {
+        crate::app::database::set_user_locale(user.uuid, locale).await;
+    }
+    Ok(())
+}
@@ -0,0 +1,238 @@
+//! iCalendar (RFC 5545) export of events for the public feed and the
+//! per-event download. Times are written as Europe/Berlin wall clock time
+//! together with the zone's rules, so calendar apps get DST right without
+//! relying on their own zone database.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, Utc};
+
+use crate::app::model::Event;
+use crate::app::time::stamp;
+
+pub const TIMEZONE: &str = "Europe/Berlin";
+
+/// Events without `ends_at` are assumed to run this long.
+const DEFAULT_HOURS: i64 = 6;
+
+/// Where events take place, for `LOCATION`.
+const VENUE: &str = "Stampffabrik";
+
+/// The EU rules in force since 1996: CEST from the last Sunday in March,
+/// 02:00 local, to the last Sunday in October, 03:00 local.
+const VTIMEZONE: [&str; 18] = [
+    "BEGIN:VTIMEZONE",
+    "TZID:Europe/Berlin",
+    "X-LIC-LOCATION:Europe/Berlin",
+    "BEGIN:DAYLIGHT",
+    "TZOFFSETFROM:+0100",
+    "TZOFFSETTO:+0200",
+    "TZNAME:CEST",
+    "DTSTART:19700329T020000",
+    "RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=-1SU",
+    "END:DAYLIGHT",
+    "BEGIN:STANDARD",
+    "TZOFFSETFROM:+0200",
+    "TZOFFSETTO:+0100",
+    "TZNAME:CET",
+    "DTSTART:19701025T030000",
+    "RRULE:FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU",
+    "END:STANDARD",
+    "END:VTIMEZONE",
+];
+
+/// Last Sunday of `month`, the day clocks change. Only used for March and
+/// October, so there is always a following month in the same year.
+fn last_sunday(year: i32, month: u32) -> NaiveDate {
+    let last = NaiveDate::from_ymd_opt(year, month + 1, 1)
+        .and_then(|first| first.pred_opt())
+        .unwrap_or_default();
+    last - Duration::days(last.weekday().num_days_from_sunday() as i64)
+}
+
+/// Wall clock time in Berlin. Clocks change at 01:00 UTC on both days.
+pub fn berlin_time(time: DateTime<Utc>) -> NaiveDateTime {
+    let utc = time.naive_utc();
+    let change = |month| last_sunday(utc.year(), month).and_hms_opt(1, 0, 0).unwrap_or_default();
+    let offset = if utc >= change(3) && utc < change(10) { 2 } else { 1 };
+    utc + Duration::hours(offset)
+}
+
+/// The instant a Berlin wall clock time stands for. Times in the hour skipped
+/// in spring are read as winter time, times in the doubled autumn hour as
+/// the first of the two.
+pub fn from_berlin(local: NaiveDateTime) -> DateTime<Utc> {
+    let summer = (local - Duration::hours(2)).and_utc();
+    if berlin_time(summer) == local {
+        summer
+    } else {
+        (local - Duration::hours(1)).and_utc()
+    }
+}
+
+fn parse(time: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(time).ok().map(|t| t.with_timezone(&Utc))
+}
+
+/// Berlin wall clock time of an RFC 3339 timestamp, for display.
+pub fn parse_berlin(time: &str) -> Option<NaiveDateTime> {
+    parse(time).map(berlin_time)
+}
+
+/// Format of `<input type="datetime-local">`, read as Berlin time.
+pub const INPUT_FORMAT: &str = "%Y-%m-%dT%H:%M";
+
+/// Value for a date input: stored times are shown in Berlin time, what
+/// was just typed is shown as typed.
+pub fn input_value(time: &Option<String>) -> String {
+    match time {
+        Some(time) => parse_berlin(time).map(|t| t.format(INPUT_FORMAT).to_string()).unwrap_or_else(|| time.clone()),
+        None => String::new(),
+    }
+}
+
+/// A time from a date input as stored, see `time`. Stored times pass
+/// through unchanged.
+pub fn from_input(time: &str) -> Option<String> {
+    if DateTime::parse_from_rfc3339(time).is_ok() {
+        return Some(time.to_string());
+    }
+    let local = NaiveDateTime::parse_from_str(time, INPUT_FORMAT).ok()?;
+    Some(stamp(from_berlin(local)))
+}
+
+/// Escapes a TEXT value.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\r', "")
+        .replace('\n', "\\n")
+}
+
+/// Ends a content line, folding it so no line exceeds 75 octets.
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + 8);
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded + "\r\n"
+}
+
+fn local(time: DateTime<Utc>) -> String {
+    berlin_time(time).format("%Y%m%dT%H%M%S").to_string()
+}
+
+/// When the night is over, `DEFAULT_HOURS` after the start if not given.
+pub fn end_of(event: &Event) -> Option<DateTime<Utc>> {
+    let starts_at = parse(&event.starts_at)?;
+    let ends_at = event
+        .ends_at
+        .as_deref()
+        .and_then(parse)
+        .filter(|ends_at| *ends_at > starts_at)
+        .unwrap_or(starts_at + Duration::hours(DEFAULT_HOURS));
+    Some(ends_at)
+}
+
+/// Content lines of one VEVENT, or `None` if its times can't be read.
+fn event_lines(event: &Event, site_url: &str, stamp: &str) -> Option<Vec<String>> {
+    let starts_at = parse(&event.starts_at)?;
+    let ends_at = end_of(event)?;
+    let url = format!("{site_url}/events/{}", event.slug);
+    let mut description = String::new();
+    if let Some(doors_at) = parse(&event.doors_at) {
+        description = format!("Einlass {}\n", berlin_time(doors_at).format("%H:%M"));
+    }
+    description.push_str(&url);
+
+    Some(vec![
+        String::from("BEGIN:VEVENT"),
+        format!("UID:event-{}@stampffabrik", event.uuid),
+        format!("DTSTAMP:{stamp}"),
+        format!("DTSTART;TZID={TIMEZONE}:{}", local(starts_at)),
+        format!("DTEND;TZID={TIMEZONE}:{}", local(ends_at)),
+        format!("SUMMARY:{}", escape(&event.title)),
+        format!("DESCRIPTION:{}", escape(&description)),
+        format!("LOCATION:{}", escape(VENUE)),
+        format!("URL:{url}"),
+        // a higher sequence makes clients replace the copy they have
+        format!("SEQUENCE:{}", if event.cancelled { 1 } else { 0 }),
+        format!("STATUS:{}", if event.cancelled { "CANCELLED" } else { "CONFIRMED" }),
+        String::from("END:VEVENT"),
+    ])
+}
+
+/// A complete VCALENDAR with `events`, written at `now`.
+pub fn calendar(events: &[Event], site_url: &str, now: DateTime<Utc>) -> String {
+    let stamp = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines: Vec<String> = [
+        "BEGIN:VCALENDAR",
+        "VERSION:2.0",
+        "PRODID:-//Stampffabrik//Events//DE",
+        "CALSCALE:GREGORIAN",
+        "METHOD:PUBLISH",
+        "X-WR-CALNAME:Stampffabrik",
+        "X-WR-TIMEZONE:Europe/Berlin",
+        "REFRESH-INTERVAL;VALUE=DURATION:PT6H",
+        "X-PUBLISHED-TTL:PT6H",
+    ]
+    .iter()
+    .chain(VTIMEZONE.iter())
+    .map(|line| line.to_string())
+    .collect();
+    for event in events {
+        lines.extend(event_lines(event, site_url, &stamp).unwrap_or_default());
+    }
+    lines.push(String::from("END:VCALENDAR"));
+    lines.iter().map(|line| fold(line)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(time: &str) -> DateTime<Utc> {
+        NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M").unwrap().and_utc()
+    }
+
+    fn local(time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn berlin_time_around_changes() {
+        assert_eq!(berlin_time(utc("2026-01-15 12:00")), local("2026-01-15 13:00"));
+        assert_eq!(berlin_time(utc("2026-07-01 12:00")), local("2026-07-01 14:00"));
+        // spring: 02:00 to 03:00 local
+        assert_eq!(berlin_time(utc("2026-03-29 00:59")), local("2026-03-29 01:59"));
+        assert_eq!(berlin_time(utc("2026-03-29 01:00")), local("2026-03-29 03:00"));
+        // autumn: 03:00 back to 02:00 local
+        assert_eq!(berlin_time(utc("2026-10-25 00:30")), local("2026-10-25 02:30"));
+        assert_eq!(berlin_time(utc("2026-10-25 01:30")), local("2026-10-25 02:30"));
+    }
+
+    #[test]
+    fn from_berlin_around_changes() {
+        assert_eq!(from_berlin(local("2026-01-15 13:00")), utc("2026-01-15 12:00"));
+        assert_eq!(from_berlin(local("2026-07-01 14:00")), utc("2026-07-01 12:00"));
+        // skipped hour read as winter time
+        assert_eq!(from_berlin(local("2026-03-29 02:30")), utc("2026-03-29 01:30"));
+        assert_eq!(from_berlin(local("2026-03-29 03:00")), utc("2026-03-29 01:00"));
+        // doubled hour read as the first
+        assert_eq!(from_berlin(local("2026-10-25 02:30")), utc("2026-10-25 00:30"));
+        assert_eq!(from_berlin(local("2026-10-25 03:00")), utc("2026-10-25 02:00"));
+    }
+
+    #[test]
+    fn inputs_stored_in_utc() {
+        assert_eq!(from_input("2026-07-01T20:00").as_deref(), Some("2026-07-01T18:00:00.000Z"));
+        assert_eq!(from_input("2026-12-31T23:30").as_deref(), Some("2026-12-31T22:30:00.000Z"));
+        assert_eq!(from_input("2026-07-01T20:00:00+02:00").as_deref(), Some("2026-07-01T20:00:00+02:00"));
+        assert_eq!(from_input("tomorrow"), None);
+    }
+}
@@ -0,0 +1,195 @@
+//! Resized AVIF and WebP copies of local images, made on the first request
+//! for them and kept on disk. Pages show images with `ResponsiveImage`,
+//! which lists the copies in a `<picture>` so browsers load the smallest
+//! one that fits.
+
+use leptos::prelude::*;
+
+/// Widths copies are made in. Others are refused, which keeps the cache from
+/// growing with every width someone asks for.
+pub const WIDTHS: [u32; 5] = [320, 480, 768, 1024, 1600];
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ImageFormat {
+    Avif,
+    Webp,
+}
+
+impl ImageFormat {
+    /// Best compression first, the order browsers should try them in.
+    pub const ALL: [ImageFormat; 2] = [ImageFormat::Avif, ImageFormat::Webp];
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Avif => "avif",
+            ImageFormat::Webp => "webp",
+        }
+    }
+
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            ImageFormat::Avif => "image/avif",
+            ImageFormat::Webp => "image/webp",
+        }
+    }
+
+    pub fn from_extension(extension: &str) -> Option<ImageFormat> {
+        ImageFormat::ALL.into_iter().find(|format| format.extension() == extension)
+    }
+}
+
+/// Images served by this site. Others are shown as they are.
+fn is_local(src: &str) -> bool {
+    !src.contains("://") && !src.starts_with("//") && !src.starts_with("data:")
+}
+
+/// Address of the copy of `src` in `width` and `format`.
+pub fn rendition_url(src: &str, width: u32, format: ImageFormat) -> String {
+    format!("/img/{width}/{}.{}", src.trim_start_matches('/'), format.extension())
+}
+
+fn srcset(src: &str, widths: &[u32], format: ImageFormat) -> String {
+    widths
+        .iter()
+        .map(|width| format!("{} {width}w", rendition_url(src, *width, format)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// An image with AVIF and WebP copies in several widths; browsers without
+/// support for either get `src`.
+#[component]
+pub fn ResponsiveImage(
+    #[prop(into)] src: String,
+    #[prop(into, optional)] alt: String,
+    #[prop(into, optional)] class: String,
+    /// Width of the original. Copies are not made wider than this.
+    #[prop(optional)] width: Option<u32>,
+    /// Space the image takes up, for the browser to pick a copy; the whole
+    /// viewport width if not given.
+    #[prop(into, optional)] sizes: Option<String>,
+    /// For images at the top of the page, which shouldn't wait for the
+    /// lazy loading.
+    #[prop(optional)] eager: bool,
+) -> impl IntoView {
+    let loading = if eager { "eager" } else { "lazy" };
+    if !is_local(&src) {
+        return view! { <img src=src alt=alt class=class loading=loading decoding="async"/> }.into_any();
+    }
+
+    let mut widths: Vec<u32> = WIDTHS.into_iter().filter(|w| *w <= width.unwrap_or(u32::MAX)).collect();
+    if widths.is_empty() {
+        widths.push(WIDTHS[0]);
+    }
+    let sizes = sizes.unwrap_or_else(|| String::from("100vw"));
+    let src = format!("/{}", src.trim_start_matches('/'));
+
+    view! {
+        <picture>
+            {ImageFormat::ALL.into_iter().map(|format| view! {
+                <source type=format.mime_type() srcset=srcset(&src, &widths, format) sizes=sizes.clone()/>
+            }).collect_view()}
+            <img src=src.clone() alt=alt class=class loading=loading decoding="async"/>
+        </picture>
+    }
+    .into_any()
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use std::fs::File;
+        use std::io::{BufWriter, Write};
+        use std::path::{Component, Path, PathBuf};
+
+        use image::codecs::avif::AvifEncoder;
+        use image::codecs::webp::WebPEncoder;
+        use image::imageops::FilterType;
+        use image::{DynamicImage, ImageError};
+
+        use crate::app::media;
+
+        /// 1 is slowest and smallest, 10 fastest.
+        const AVIF_SPEED: u8 = 6;
+        const AVIF_QUALITY: u8 = 70;
+
+        fn site_root() -> PathBuf {
+            PathBuf::from(std::env::var("LEPTOS_SITE_ROOT").unwrap_or_else(|_| String::from("target/site")))
+        }
+
+        /// `IMAGE_CACHE_DIR`, outside the site root since cargo-leptos
+        /// empties that on every build.
+        pub fn cache_dir() -> PathBuf {
+            PathBuf::from(std::env::var("IMAGE_CACHE_DIR").unwrap_or_else(|_| String::from("image_cache")))
+        }
+
+        /// File behind a local image path. `/assets` is served from the site
+        /// root; paths leaving it are refused.
+        pub fn source_file(path: &str) -> Option<PathBuf> {
+            let path = Path::new(path.trim_start_matches('/'));
+            if !path.components().all(|c| matches!(c, Component::Normal(_))) {
+                return None;
+            }
+            let path = path.strip_prefix("assets").unwrap_or(path);
+            Some(site_root().join(path))
+        }
+
+        fn encode(source: &Path, target: &Path, width: u32, format: ImageFormat) -> Result<(), ImageError> {
+            let image = image::open(source)?;
+            // never made larger than the original
+            let image = if image.width() > width {
+                image.resize(width, u32::MAX, FilterType::Lanczos3)
+            } else {
+                image
+            };
+            let image = DynamicImage::ImageRgba8(image.to_rgba8());
+
+            if let Some(dir) = target.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            // written beside the target and renamed, so a request at the same
+            // time never gets half a file
+            let partial = target.with_extension(format!("{}.part", format.extension()));
+            let mut file = BufWriter::new(File::create(&partial)?);
+            match format {
+                ImageFormat::Avif => image.write_with_encoder(
+                    AvifEncoder::new_with_speed_quality(&mut file, AVIF_SPEED, AVIF_QUALITY),
+                )?,
+                ImageFormat::Webp => image.write_with_encoder(WebPEncoder::new_lossless(&mut file))?,
+            }
+            file.flush()?;
+            std::fs::rename(partial, target)?;
+            Ok(())
+        }
+
+        /// The cached copy of the image at `path`, made first if there is
+        /// none or the original changed since.
+        pub async fn rendition(path: String, width: u32, format: ImageFormat) -> Option<PathBuf> {
+            if !WIDTHS.contains(&width) {
+                return None;
+            }
+            let source = match path.trim_start_matches('/').strip_prefix("media/") {
+                Some(key) => media::local_file(key).await?,
+                None => source_file(&path)?,
+            };
+            let target = cache_dir()
+                .join(width.to_string())
+                .join(format!("{}.{}", path.trim_start_matches('/'), format.extension()));
+
+            let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+            let source_modified = modified(&source)?;
+            if modified(&target).is_some_and(|cached| cached >= source_modified) {
+                return Some(target);
+            }
+
+            let file = target.clone();
+            match tokio::task::spawn_blocking(move || encode(&source, &file, width, format)).await {
+                Ok(Ok(())) => Some(target),
+                Ok(Err(e)) => {
+                    println!("error in resizing {path}: {:?}", e);
+                    None
+                }
+                Err(_) => None,
+            }
+        }
+    }
+}
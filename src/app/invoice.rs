@@ -0,0 +1,233 @@
+//! Renders invoices as PDF and, on the server, issues them. Invoices are never
+//! changed after issue; a wrong one is reversed by a cancellation invoice.
+
+use crate::app::ical::parse_berlin;
+use crate::app::model::{Address, Invoice};
+use crate::app::model::cart::format_cents;
+use crate::app::model::invoice::{display_number, InvoiceKind};
+use crate::app::model::shipping::country_name;
+use crate::app::pdf::{Font, Pdf, PAGE_HEIGHT, PAGE_WIDTH};
+
+const MARGIN: f32 = 56.0;
+const RIGHT: f32 = PAGE_WIDTH - MARGIN;
+const LINE: f32 = 14.0;
+const TABLE_END: f32 = PAGE_HEIGHT - 100.0;
+
+fn date(timestamp: &str) -> String {
+    parse_berlin(timestamp)
+        .map(|t| t.format("%d.%m.%Y").to_string())
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
+fn address_lines(address: &Address) -> Vec<String> {
+    let mut lines = vec![address.line1.clone()];
+    lines.extend(address.line2.clone().filter(|l| !l.is_empty()));
+    lines.push(format!("{} {}", address.zipcode, address.city).trim().to_string());
+    lines.push(country_name(&address.country).to_string());
+    lines.into_iter().filter(|l| !l.is_empty()).collect()
+}
+
+fn footer(pdf: &mut Pdf, invoice: &Invoice) {
+    let seller = &invoice.seller;
+    let mut parts = vec![seller.name.clone()];
+    parts.extend(address_lines(&seller.address));
+    if !seller.vat_id.is_empty() {
+        parts.push(format!("USt-IdNr. {}", seller.vat_id));
+    }
+    pdf.line(MARGIN, PAGE_HEIGHT - 60.0, RIGHT, PAGE_HEIGHT - 60.0);
+    pdf.text(MARGIN, PAGE_HEIGHT - 46.0, 8.0, Font::Regular, &parts.join(" · "));
+}
+
+pub fn render_pdf(invoice: &Invoice) -> Vec<u8> {
+    let mut pdf = Pdf::new();
+    let seller = &invoice.seller;
+
+    pdf.text(MARGIN, 70.0, 18.0, Font::Bold, &seller.name);
+    let mut y = 70.0;
+    for line in address_lines(&seller.address) {
+        pdf.text_right(RIGHT, y, 9.0, Font::Regular, &line);
+        y += 11.0;
+    }
+
+    let mut y = 150.0;
+    for line in [invoice.buyer_name.clone()]
+        .into_iter()
+        .chain(invoice.buyer_address.iter().flat_map(address_lines))
+        .chain([invoice.buyer_email.clone()])
+        .filter(|l| !l.is_empty())
+    {
+        pdf.text(MARGIN, y, 10.0, Font::Regular, &line);
+        y += LINE;
+    }
+
+    let title = match invoice.kind {
+        InvoiceKind::Invoice => "Rechnung",
+        InvoiceKind::Cancellation => "Stornorechnung",
+    };
+    pdf.text(MARGIN, 260.0, 16.0, Font::Bold, &format!("{} {}", title, invoice.display_number()));
+    let mut y = 280.0;
+    let mut service_dates = invoice.service_dates.iter().map(|d| date(d)).collect::<Vec<_>>();
+    service_dates.dedup();
+    if service_dates.is_empty() {
+        service_dates.push(date(&invoice.issued_at));
+    }
+    let mut facts = vec![
+        format!("Rechnungsdatum: {}", date(&invoice.issued_at)),
+        format!("Leistungsdatum: {}", service_dates.join(", ")),
+        format!("Bestellung: {}", invoice.order),
+    ];
+    if let Some(cancels) = invoice.cancels {
+        facts.push(format!("Storno zu Rechnung {}", display_number(cancels)));
+    }
+    for fact in facts {
+        pdf.text(MARGIN, y, 10.0, Font::Regular, &fact);
+        y += LINE;
+    }
+
+    y += LINE;
+    let header = |pdf: &mut Pdf, y: f32| {
+        pdf.text(MARGIN, y, 10.0, Font::Bold, "Pos.");
+        pdf.text(MARGIN + 36.0, y, 10.0, Font::Bold, "Beschreibung");
+        pdf.text_right(MARGIN + 300.0, y, 10.0, Font::Bold, "Menge");
+        pdf.text_right(MARGIN + 370.0, y, 10.0, Font::Bold, "Einzelpreis");
+        pdf.text_right(MARGIN + 410.0, y, 10.0, Font::Bold, "USt.");
+        pdf.text_right(RIGHT, y, 10.0, Font::Bold, "Betrag");
+        pdf.line(MARGIN, y + 4.0, RIGHT, y + 4.0);
+    };
+    header(&mut pdf, y);
+    y += LINE + 4.0;
+
+    for (position, item) in invoice.items.iter().enumerate() {
+        if y > TABLE_END {
+            footer(&mut pdf, invoice);
+            pdf.add_page();
+            y = 70.0;
+            header(&mut pdf, y);
+            y += LINE + 4.0;
+        }
+        pdf.text(MARGIN, y, 10.0, Font::Regular, &(position + 1).to_string());
+        let name = match item.discount_cents {
+            0 => item.name.clone(),
+            discount => format!("{} (abzgl. Rabatt {})", item.name, format_cents(discount)),
+        };
+        pdf.text(MARGIN + 36.0, y, 10.0, Font::Regular, &name);
+        pdf.text_right(MARGIN + 300.0, y, 10.0, Font::Regular, &item.quantity.to_string());
+        pdf.text_right(MARGIN + 370.0, y, 10.0, Font::Regular, &format_cents(item.unit_price_cents));
+        pdf.text_right(MARGIN + 410.0, y, 10.0, Font::Regular, &format!("{} %", item.vat_rate));
+        pdf.text_right(RIGHT, y, 10.0, Font::Regular, &format_cents(item.total_cents));
+        y += LINE;
+    }
+
+    pdf.line(MARGIN, y - 10.0, RIGHT, y - 10.0);
+    y += 4.0;
+    for vat in &invoice.vat {
+        pdf.text_right(MARGIN + 410.0, y, 10.0, Font::Regular, &format!("Nettobetrag {} %", vat.rate));
+        pdf.text_right(RIGHT, y, 10.0, Font::Regular, &format_cents(vat.net_cents));
+        y += LINE;
+        pdf.text_right(MARGIN + 410.0, y, 10.0, Font::Regular, &format!("zzgl. USt. {} %", vat.rate));
+        pdf.text_right(RIGHT, y, 10.0, Font::Regular, &format_cents(vat.vat_cents));
+        y += LINE;
+    }
+    pdf.text_right(MARGIN + 410.0, y, 11.0, Font::Bold, "Gesamtbetrag");
+    pdf.text_right(RIGHT, y, 11.0, Font::Bold, &format_cents(invoice.total_cents));
+
+    footer(&mut pdf, invoice);
+    pdf.finish()
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use std::env;
+        use uuid::Uuid;
+
+        use crate::app::database;
+        use crate::app::errors::ResponseError;
+        use crate::app::model::Order;
+        use crate::app::model::cart::ItemKind;
+        use crate::app::model::invoice::Seller;
+        use crate::app::model::order::OrderStatus;
+        use crate::app::model::shipping::HOME_COUNTRY;
+        use crate::app::time;
+
+        /// Seller details printed on new invoices, configured via `INVOICE_SELLER_*`.
+        pub fn seller_from_env() -> Seller {
+            let var = |name: &str, default: &str| env::var(name).unwrap_or_else(|_| default.to_string());
+            Seller {
+                name: var("INVOICE_SELLER_NAME", "Stampffabrik"),
+                address: Address::new(
+                    var("INVOICE_SELLER_COUNTRY", HOME_COUNTRY),
+                    var("INVOICE_SELLER_STREET", ""),
+                    None,
+                    var("INVOICE_SELLER_CITY", ""),
+                    String::new(),
+                    var("INVOICE_SELLER_ZIPCODE", ""),
+                ),
+                vat_id: var("INVOICE_SELLER_VAT_ID", ""),
+            }
+        }
+
+        /// Tickets are rendered on the night of their event, everything else
+        /// when the invoice is issued: orders with merch are invoiced once
+        /// handed over, vouchers on sale.
+        async fn service_dates(order: &Order, issued_at: &str) -> Vec<String> {
+            let items = order.billable_items();
+            let mut events = Vec::new();
+            for item in items.iter().filter(|i| i.kind == ItemKind::Ticket) {
+                if let Some(ticket_type) = database::get_ticket_type(item.reference.clone()).await {
+                    events.push(ticket_type.event);
+                }
+            }
+            let mut dates = database::get_events(events)
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .map(|event| event.starts_at)
+                .collect::<Vec<_>>();
+            if items.iter().any(|i| i.kind != ItemKind::Ticket) {
+                dates.push(issued_at.to_string());
+            }
+            dates.sort();
+            dates.dedup();
+            dates
+        }
+
+        /// Issues the invoice for a paid order, once.
+        pub async fn issue_for_order(order: &Order) -> Result<Invoice, ResponseError> {
+            if let Some(invoice) = database::get_invoice_for_order(order.uuid.clone()).await {
+                return Ok(invoice);
+            }
+            let issued_at = time::now();
+            let invoice = Invoice::for_order(
+                Uuid::new_v4().to_string(),
+                order,
+                seller_from_env(),
+                issued_at.clone(),
+                service_dates(order, &issued_at).await,
+            );
+            database::add_invoice(invoice).await
+        }
+
+        pub async fn cancel(invoice: String) -> Result<Invoice, ResponseError> {
+            let invoice = database::get_invoice(invoice).await.ok_or(ResponseError::InvoiceNotFound)?;
+            if invoice.kind != InvoiceKind::Invoice {
+                return Err(ResponseError::InvoiceCreationFailure);
+            }
+            let cancellation = invoice.cancellation(Uuid::new_v4().to_string(), time::now());
+            database::add_invoice(cancellation).await
+        }
+
+        /// Corrects the invoice of an order after a refund: the current one is
+        /// cancelled and, if anything is left to pay, a new one is issued.
+        /// Merch that was not handed over yet is invoiced when it is.
+        pub async fn reissue(order: &Order) -> Result<(), ResponseError> {
+            if let Some(current) = database::get_invoice_for_order(order.uuid.clone()).await {
+                cancel(current.uuid).await?;
+            }
+            let handed_over = !order.has_merch() || order.status == OrderStatus::Fulfilled;
+            if handed_over && !order.billable_items().is_empty() {
+                issue_for_order(order).await?;
+            }
+            Ok(())
+        }
+    }
+}
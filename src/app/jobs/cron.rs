@@ -0,0 +1,146 @@
+//! Cron expressions for recurring jobs: minute, hour, day of month, month
+//! and day of week, each `*`, a number, a range `a-b`, a step `*/n` or
+//! `a-b/n`, or a list of those. Like cron, a day matches either field when
+//! both day fields are restricted. Weekdays count from Sunday as 0 or 7.
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Timelike};
+
+/// Schedules that never match, like February 30th, are given up after this.
+const SEARCH_DAYS: i64 = 5 * 366;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Schedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+/// The values a field allows, as bits.
+fn field(spec: &str, min: u32, max: u32) -> Option<u64> {
+    let mut bits = 0u64;
+    for part in spec.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().ok().filter(|s| *s > 0)?),
+            None => (part, 1),
+        };
+        let (from, to) = match (range, range.split_once('-')) {
+            ("*", _) => (min, max),
+            (_, Some((from, to))) => (from.parse().ok()?, to.parse().ok()?),
+            // `5/15` runs from 5 to the end
+            (from, None) => {
+                let from = from.parse().ok()?;
+                (from, if step > 1 { max } else { from })
+            }
+        };
+        if from < min || to > max || from > to {
+            return None;
+        }
+        for value in (from..=to).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Some(bits)
+}
+
+impl Schedule {
+    pub fn parse(expression: &str) -> Option<Schedule> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minutes, hours, days, months, weekdays] = fields[..] else {
+            return None;
+        };
+        let mut weekday_bits = field(weekdays, 0, 7)?;
+        if weekday_bits & 1 << 7 != 0 {
+            weekday_bits = (weekday_bits | 1) & !(1 << 7);
+        }
+        Some(Schedule {
+            minutes: field(minutes, 0, 59)?,
+            hours: field(hours, 0, 23)?,
+            days: field(days, 1, 31)?,
+            months: field(months, 1, 12)?,
+            weekdays: weekday_bits,
+            any_day: days.starts_with('*'),
+            any_weekday: weekdays.starts_with('*'),
+        })
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let day = self.days & 1 << date.day() != 0;
+        let weekday = self.weekdays & 1 << date.weekday().num_days_from_sunday() != 0;
+        match (self.any_day, self.any_weekday) {
+            (true, true) => true,
+            (true, false) => weekday,
+            (false, true) => day,
+            (false, false) => day || weekday,
+        }
+    }
+
+    /// The first matching minute after `after`.
+    pub fn next(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let start = after.date().and_hms_opt(after.hour(), after.minute(), 0)? + Duration::minutes(1);
+        let end = start + Duration::days(SEARCH_DAYS);
+        let mut time = start;
+        while time < end {
+            if self.months & 1 << time.month() == 0 || !self.day_matches(time.date()) {
+                time = time.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+            } else if self.hours & 1 << time.hour() == 0 {
+                time = time.date().and_hms_opt(time.hour(), 0, 0)? + Duration::hours(1);
+            } else if self.minutes & 1 << time.minute() == 0 {
+                time += Duration::minutes(1);
+            } else {
+                return Some(time);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn next(expression: &str, after: &str) -> Option<NaiveDateTime> {
+        Schedule::parse(expression).unwrap().next(at(after))
+    }
+
+    #[test]
+    fn steps() {
+        assert_eq!(next("*/15 * * * *", "2026-10-19 10:07"), Some(at("2026-10-19 10:15")));
+        assert_eq!(next("*/15 * * * *", "2026-10-19 10:45"), Some(at("2026-10-19 11:00")));
+        assert_eq!(next("5/20 * * * *", "2026-10-19 10:30"), Some(at("2026-10-19 10:45")));
+        assert_eq!(next("0 8-18/5 * * *", "2026-10-19 13:00"), Some(at("2026-10-19 18:00")));
+    }
+
+    #[test]
+    fn either_day_field() {
+        // the 1st or any Monday
+        assert_eq!(next("0 0 1 * 1", "2026-10-19 12:00"), Some(at("2026-10-26 00:00")));
+        assert_eq!(next("0 0 1 * 1", "2026-10-27 12:00"), Some(at("2026-11-01 00:00")));
+        // only one restricted day field counts alone
+        assert_eq!(next("0 0 1 * *", "2026-10-19 12:00"), Some(at("2026-11-01 00:00")));
+        assert_eq!(next("0 0 * * 1", "2026-10-19 12:00"), Some(at("2026-10-26 00:00")));
+    }
+
+    #[test]
+    fn sunday_as_seven() {
+        assert_eq!(Schedule::parse("30 9 * * 7"), Schedule::parse("30 9 * * 0"));
+        assert_eq!(next("30 9 * * 7", "2026-10-19 12:00"), Some(at("2026-10-25 09:30")));
+        assert_eq!(next("0 0 * * 5-7", "2026-10-19 12:00"), Some(at("2026-10-23 00:00")));
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(Schedule::parse("60 * * * *"), None);
+        assert_eq!(Schedule::parse("*/0 * * * *"), None);
+        assert_eq!(Schedule::parse("0 0 * *"), None);
+        assert_eq!(Schedule::parse("0 0 5-1 * *"), None);
+        assert_eq!(next("0 0 30 2 *", "2026-10-19 12:00"), None);
+    }
+}
@@ -0,0 +1,173 @@
+//! Background jobs. Jobs wait in the database until `run_at`, then a pool of
+//! workers started from `main.rs` picks them up; failed ones are retried
+//! with growing pauses and marked dead after `Job.max_attempts`. Recurring
+//! jobs follow a cron schedule in Berlin time and queue their next run
+//! when one ends. A job key keeps the same work from being queued twice.
+
+pub mod cron;
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use chrono::{Duration, Utc};
+        use uuid::Uuid;
+
+        use cron::Schedule;
+        use crate::app::database;
+        use crate::app::errors::ResponseError;
+        use crate::app::event_mail;
+        use crate::app::ical::{berlin_time, from_berlin};
+        use crate::app::model::job::{Job, JobKind, RecurringJob};
+        use crate::app::payment;
+        use crate::app::time::{now, stamp};
+        use crate::app::waitlist;
+
+        /// Name, cron schedule and work of the recurring jobs.
+        pub const RECURRING: [(&str, &str, JobKind); 4] = [
+            ("waitlist-offers", "* * * * *", JobKind::ExpireWaitlistOffers),
+            ("pending-orders", "*/5 * * * *", JobKind::ExpirePendingOrders),
+            ("purge-jobs", "0 4 * * *", JobKind::PurgeJobs),
+            ("event-mails", "*/15 * * * *", JobKind::EventMails),
+        ];
+
+        /// Idle workers look for due jobs this often.
+        const POLL: std::time::Duration = std::time::Duration::from_secs(5);
+        /// Recurring jobs are checked and cut off jobs requeued this often.
+        const HOUSEKEEPING: std::time::Duration = std::time::Duration::from_secs(60);
+        /// Jobs running longer were cut off, e.g. by a restart.
+        const STALE_MINUTES: i64 = 30;
+        /// Finished jobs are kept this long.
+        const KEEP_DAYS: i64 = 30;
+        const MAX_BACKOFF_SECONDS: i64 = 3600;
+
+        fn workers() -> usize {
+            std::env::var("JOB_WORKERS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(2)
+        }
+
+        fn recurring_key(name: &str) -> String {
+            format!("recurring:{name}")
+        }
+
+        /// The next run of a cron schedule after now.
+        pub fn next_run(schedule: &str) -> Option<String> {
+            let next = Schedule::parse(schedule)?.next(berlin_time(Utc::now()))?;
+            Some(stamp(from_berlin(next)))
+        }
+
+        /// 30 seconds, doubled with every attempt.
+        fn backoff(attempts: u32) -> Duration {
+            Duration::seconds((30i64 << attempts.saturating_sub(1).min(10)).min(MAX_BACKOFF_SECONDS))
+        }
+
+        /// Queues a job for `run_at`. With a key, nothing is queued while a
+        /// job with that key is queued or running; returns whether it was.
+        pub async fn enqueue(kind: JobKind, run_at: String, key: Option<String>) -> Result<bool, ResponseError> {
+            database::add_job(Job::new(Uuid::new_v4().to_string(), kind, key, run_at, now())).await
+        }
+
+        pub fn recurring() -> Vec<RecurringJob> {
+            RECURRING
+                .iter()
+                .map(|(name, schedule, kind)| RecurringJob {
+                    name: name.to_string(),
+                    schedule: schedule.to_string(),
+                    kind: kind.clone(),
+                    next_run: next_run(schedule),
+                })
+                .collect()
+        }
+
+        /// Queues the next run of each recurring job that has none.
+        async fn schedule_recurring() {
+            for job in recurring() {
+                let Some(run_at) = job.next_run else {
+                    println!("error in recurring job {}: invalid schedule {}", job.name, job.schedule);
+                    continue;
+                };
+                if let Err(e) = enqueue(job.kind, run_at, Some(recurring_key(&job.name))).await {
+                    println!("error in scheduling recurring job {}: {:?}", job.name, e);
+                }
+            }
+        }
+
+        async fn perform(kind: JobKind) -> Result<(), ResponseError> {
+            match kind {
+                JobKind::ExpireWaitlistOffers => {
+                    waitlist::expire_offers().await;
+                    Ok(())
+                }
+                JobKind::ExpirePendingOrders => payment::cancel_abandoned().await,
+                JobKind::PurgeJobs => {
+                    database::purge_jobs(stamp(Utc::now() - Duration::days(KEEP_DAYS))).await;
+                    Ok(())
+                }
+                JobKind::EventMails => event_mail::send_due().await,
+            }
+        }
+
+        async fn execute(job: Job) {
+            // in its own task, so a panic fails the job instead of the worker
+            let result = match actix_web::rt::spawn(database::background(perform(job.kind.clone()))).await {
+                Ok(result) => result.map_err(|e| format!("{:?}", e)),
+                Err(_) => Err(String::from("panicked")),
+            };
+            match result {
+                Ok(_) => database::finish_job(job.uuid.clone(), now()).await,
+                Err(error) => {
+                    println!("error in job {} ({:?}, attempt {}): {}", job.uuid, job.kind, job.attempts, error);
+                    let retry_at = (job.attempts < job.max_attempts)
+                        .then(|| stamp(Utc::now() + backoff(job.attempts)));
+                    let retrying = retry_at.is_some();
+                    database::fail_job(job.uuid.clone(), error, retry_at, now()).await;
+                    if retrying {
+                        return;
+                    }
+                }
+            }
+            // the key is free again, so the next run can be queued
+            if job.key.as_deref().is_some_and(|key| key.starts_with("recurring:")) {
+                schedule_recurring().await;
+            }
+        }
+
+        async fn work() {
+            loop {
+                let mut started = None;
+                for due in database::get_due_jobs(now(), workers() as u32).await.unwrap_or_default() {
+                    // another worker may have taken it meanwhile
+                    started = database::start_job(due.uuid, now()).await;
+                    if started.is_some() {
+                        break;
+                    }
+                }
+                match started {
+                    Some(job) => execute(job).await,
+                    None => tokio::time::sleep(POLL).await,
+                }
+            }
+        }
+
+        async fn housekeeping() {
+            loop {
+                let before = stamp(Utc::now() - Duration::minutes(STALE_MINUTES));
+                for job in database::requeue_stale_jobs(before, now()).await.unwrap_or_default() {
+                    println!("error in job {} ({:?}): cut off too often", job.uuid, job.kind);
+                }
+                schedule_recurring().await;
+                tokio::time::sleep(HOUSEKEEPING).await;
+            }
+        }
+
+        /// Starts the workers; they run until the process ends, on the
+        /// background database session.
+        pub fn start() {
+            actix_web::rt::spawn(database::background(housekeeping()));
+            for _ in 0..workers() {
+                actix_web::rt::spawn(database::background(work()));
+            }
+        }
+    }
+}
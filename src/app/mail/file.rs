@@ -0,0 +1,44 @@
+//! Writes every mail as an `.eml` file into `MAIL_DIR`, where it can be opened
+//! with any mail client.
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use std::path::PathBuf;
+        use uuid::Uuid;
+
+        use super::{message, Mail, MailSink};
+        use crate::app::errors::ResponseError;
+
+        pub struct FileSink {
+            dir: PathBuf,
+        }
+
+        impl FileSink {
+            pub fn from_env() -> FileSink {
+                FileSink {
+                    dir: PathBuf::from(std::env::var("MAIL_DIR").unwrap_or_else(|_| String::from("mail"))),
+                }
+            }
+        }
+
+        impl MailSink for FileSink {
+            async fn deliver(&self, mail: &Mail) -> Result<(), ResponseError> {
+                let message = message(mail);
+                let path = self.dir.join(format!(
+                    "{}-{}.eml",
+                    chrono::Local::now().format("%Y%m%d%H%M%S"),
+                    Uuid::new_v4().simple(),
+                ));
+
+                let written = async {
+                    tokio::fs::create_dir_all(&self.dir).await?;
+                    tokio::fs::write(&path, message).await
+                }.await;
+                written.map_err(|e| {
+                    println!("error in writing mail to {:?}: {:?}", path, e);
+                    ResponseError::MailFailure
+                })
+            }
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! Outgoing email. Mails are queued in the database and handed to a sink by
+//! a throttled sender. `MAIL_SINK` picks the sink: `smtp` delivers through a
+//! relay, `file` (the default) writes them to disk for development and tests.
+
+use serde::{Deserialize, Serialize};
+
+pub mod file;
+pub mod queue;
+pub mod smtp;
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct Mail {
+    pub to: String,
+    pub subject: String,
+    /// Plain text.
+    pub body: String,
+}
+
+impl Mail {
+    pub fn new(to: String, subject: String, body: String) -> Mail {
+        Mail { to, subject, body }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+pub enum MailStatus {
+    Queued,
+    Sent,
+    /// Gave up after too many attempts.
+    Failed,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct QueuedMail {
+    pub uuid: String,
+    pub mail: Mail,
+    /// `Campaign.uuid` for newsletters.
+    pub campaign: Option<String>,
+    /// Bulk mail waits until all transactional mail is out.
+    pub bulk: bool,
+    pub status: MailStatus,
+    pub attempts: u32,
+    pub created_at: String,
+    /// Not sent before this, used to back off after failures.
+    pub send_after: String,
+    pub sent_at: Option<String>,
+}
+
+impl QueuedMail {
+    pub fn new(uuid: String, mail: Mail, created_at: String) -> QueuedMail {
+        QueuedMail {
+            uuid,
+            mail,
+            campaign: None,
+            bulk: false,
+            status: MailStatus::Queued,
+            attempts: 0,
+            send_after: created_at.clone(),
+            created_at,
+            sent_at: None,
+        }
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use std::future::Future;
+        use base64::Engine;
+
+        use crate::app::errors::ResponseError;
+
+        pub use file::FileSink;
+        pub use smtp::SmtpSink;
+
+        pub trait MailSink {
+            fn deliver(&self, mail: &Mail) -> impl Future<Output = Result<(), ResponseError>> + Send;
+        }
+
+        pub enum Sink {
+            File(FileSink),
+            Smtp(SmtpSink),
+        }
+
+        impl MailSink for Sink {
+            async fn deliver(&self, mail: &Mail) -> Result<(), ResponseError> {
+                match self {
+                    Sink::File(sink) => sink.deliver(mail).await,
+                    Sink::Smtp(sink) => sink.deliver(mail).await,
+                }
+            }
+        }
+
+        /// The sink configured via `MAIL_SINK`.
+        pub fn sink() -> Sink {
+            match std::env::var("MAIL_SINK").as_deref() {
+                Ok("smtp") => Sink::Smtp(SmtpSink::from_env()),
+                _ => Sink::File(FileSink::from_env()),
+            }
+        }
+
+        /// RFC 2047 encoded word, so umlauts survive in headers.
+        fn encode_header(value: &str) -> String {
+            if value.is_ascii() {
+                return value.to_string();
+            }
+            format!("=?UTF-8?B?{}?=", base64::engine::general_purpose::STANDARD.encode(value))
+        }
+
+        /// The mail as an RFC 5322 message with CRLF line ends.
+        pub fn message(mail: &Mail) -> String {
+            format!(
+                "From: {}\r\nTo: {}\r\nSubject: {}\r\nDate: {}\r\nMIME-Version: 1.0\r\n\
+                 Content-Type: text/plain; charset=utf-8\r\nContent-Transfer-Encoding: 8bit\r\n\r\n{}\r\n",
+                sender(),
+                mail.to,
+                encode_header(&mail.subject),
+                chrono::Local::now().to_rfc2822(),
+                mail.body.replace("\r\n", "\n").replace('\n', "\r\n"),
+            )
+        }
+
+        /// Queues a mail for the sender.
+        pub async fn send(mail: Mail) -> Result<(), ResponseError> {
+            queue::enqueue(mail).await
+        }
+
+        /// Sender address, configured via `MAIL_FROM`.
+        pub fn sender() -> String {
+            std::env::var("MAIL_FROM").unwrap_or_else(|_| String::from("Stampffabrik <mail@stampffabrik.de>"))
+        }
+
+        /// Public address of the site for links in mails, configured via `SITE_URL`.
+        pub fn site_url() -> String {
+            std::env::var("SITE_URL")
+                .unwrap_or_else(|_| String::from("https://stampffabrik.de"))
+                .trim_end_matches('/')
+                .to_string()
+        }
+    }
+}
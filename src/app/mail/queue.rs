@@ -0,0 +1,75 @@
+//! The mail queue and its sender. Mails wait in the database until the sender
+//! picks them up, at most `MAIL_PER_MINUTE` a minute so bulk mail doesn't get
+//! us throttled by the relay. Failed deliveries are retried with growing pauses.
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use std::time::Duration;
+        use uuid::Uuid;
+
+        use super::{sink, Mail, MailSink, QueuedMail};
+        use crate::app::database;
+        use crate::app::errors::ResponseError;
+        use crate::app::time::{now, stamp};
+
+        const BATCH: u32 = 50;
+        const IDLE: Duration = Duration::from_secs(10);
+        const MAX_ATTEMPTS: u32 = 5;
+
+        fn per_minute() -> u64 {
+            std::env::var("MAIL_PER_MINUTE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(60)
+        }
+
+        pub async fn enqueue(mail: Mail) -> Result<(), ResponseError> {
+            let queued = QueuedMail::new(Uuid::new_v4().to_string(), mail, now());
+            database::add_queued_mails(vec![queued]).await
+        }
+
+        /// Queues mails behind all transactional mail.
+        pub async fn enqueue_bulk(mails: Vec<Mail>, campaign: Option<String>) -> Result<(), ResponseError> {
+            let created_at = now();
+            let queued = mails
+                .into_iter()
+                .map(|mail| QueuedMail {
+                    campaign: campaign.clone(),
+                    bulk: true,
+                    ..QueuedMail::new(Uuid::new_v4().to_string(), mail, created_at.clone())
+                })
+                .collect();
+            database::add_queued_mails(queued).await
+        }
+
+        /// Delivers queued mail until the process ends. Polls all the time,
+        /// so it is started in `database::background`.
+        pub async fn run() {
+            let pause = Duration::from_millis(60_000 / per_minute());
+            loop {
+                let due = database::get_due_mails(now(), BATCH).await.unwrap_or_default();
+                if due.is_empty() {
+                    tokio::time::sleep(IDLE).await;
+                    continue;
+                }
+                for queued in due {
+                    deliver(queued).await;
+                    tokio::time::sleep(pause).await;
+                }
+            }
+        }
+
+        async fn deliver(queued: QueuedMail) {
+            let attempts = queued.attempts + 1;
+            match sink().deliver(&queued.mail).await {
+                Ok(_) => database::set_mail_sent(queued.uuid, now()).await,
+                Err(e) => {
+                    println!("error in delivering mail {} (attempt {}): {:?}", queued.uuid, attempts, e);
+                    let retry_at = chrono::Utc::now() + chrono::Duration::minutes((attempts * attempts) as i64);
+                    database::set_mail_failed(queued.uuid, attempts, attempts >= MAX_ATTEMPTS, stamp(retry_at)).await;
+                }
+            }
+        }
+    }
+}
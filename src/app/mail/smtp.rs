@@ -0,0 +1,272 @@
+//! Hands every mail to an SMTP relay. Configured via `SMTP_HOST`, `SMTP_PORT`,
+//! `SMTP_USER` and `SMTP_PASSWORD`; `SMTP_TLS` is `starttls` (the default),
+//! `tls` for TLS from the first byte, usually on port 465, or `none` for a
+//! relay on the same machine.
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use std::time::Duration;
+        use base64::Engine;
+        use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+        use tokio::net::TcpStream;
+        use tokio_native_tls::{native_tls, TlsConnector, TlsStream};
+
+        use super::{message, sender, site_url, Mail, MailSink};
+        use crate::app::errors::ResponseError;
+
+        /// A relay that takes longer for one mail is given up on.
+        const TIMEOUT: Duration = Duration::from_secs(30);
+
+        #[derive(PartialEq, Eq, Clone, Copy)]
+        enum Encryption {
+            StartTls,
+            Implicit,
+            None,
+        }
+
+        pub struct SmtpSink {
+            host: String,
+            port: u16,
+            user: Option<String>,
+            password: String,
+            encryption: Encryption,
+        }
+
+        impl SmtpSink {
+            pub fn from_env() -> SmtpSink {
+                let encryption = match std::env::var("SMTP_TLS").as_deref() {
+                    Ok("tls") => Encryption::Implicit,
+                    Ok("none") => Encryption::None,
+                    _ => Encryption::StartTls,
+                };
+                let default_port = match encryption {
+                    Encryption::StartTls => 587,
+                    Encryption::Implicit => 465,
+                    Encryption::None => 25,
+                };
+                SmtpSink {
+                    host: std::env::var("SMTP_HOST").unwrap_or_else(|_| String::from("127.0.0.1")),
+                    port: std::env::var("SMTP_PORT").ok().and_then(|v| v.parse().ok()).unwrap_or(default_port),
+                    user: std::env::var("SMTP_USER").ok().filter(|user| !user.is_empty()),
+                    password: std::env::var("SMTP_PASSWORD").unwrap_or_default(),
+                    encryption,
+                }
+            }
+
+            async fn secure<S: Stream + 'static>(&self, stream: S) -> Result<TlsStream<S>, String> {
+                let connector = native_tls::TlsConnector::new().map_err(|e| e.to_string())?;
+                TlsConnector::from(connector)
+                    .connect(&self.host, stream)
+                    .await
+                    .map_err(|e| e.to_string())
+            }
+
+            async fn send(&self, mail: &Mail) -> Result<(), String> {
+                // the address goes into commands and headers as it is
+                if mail.to.contains(['\r', '\n']) {
+                    return Err(format!("invalid recipient {:?}", mail.to));
+                }
+                let tcp = TcpStream::connect((self.host.as_str(), self.port)).await.map_err(|e| e.to_string())?;
+                let mut connection = match self.encryption {
+                    Encryption::Implicit => Connection::new(Box::new(self.secure(tcp).await?)),
+                    Encryption::StartTls | Encryption::None => Connection::new(Box::new(tcp)),
+                };
+                connection.reply(220).await?;
+                let hello = format!("EHLO {}", hello_name());
+                connection.command(&hello, 250).await?;
+                if self.encryption == Encryption::StartTls {
+                    connection.command("STARTTLS", 220).await?;
+                    let stream = connection.stream.into_inner();
+                    connection = Connection::new(Box::new(self.secure(stream).await?));
+                    connection.command(&hello, 250).await?;
+                }
+                if let Some(user) = &self.user {
+                    let credentials = base64::engine::general_purpose::STANDARD
+                        .encode(format!("\0{user}\0{}", self.password));
+                    connection.command(&format!("AUTH PLAIN {credentials}"), 235).await?;
+                }
+                connection.command(&format!("MAIL FROM:<{}>", address(&sender())), 250).await?;
+                connection.command(&format!("RCPT TO:<{}>", address(&mail.to)), 250).await?;
+                connection.command("DATA", 354).await?;
+                connection.command(&format!("{}.", dot_stuffed(&message(mail))), 250).await?;
+                // the mail is accepted, a relay hanging up early is fine
+                let _ = connection.command("QUIT", 221).await;
+                Ok(())
+            }
+        }
+
+        impl MailSink for SmtpSink {
+            async fn deliver(&self, mail: &Mail) -> Result<(), ResponseError> {
+                let sent = match tokio::time::timeout(TIMEOUT, self.send(mail)).await {
+                    Ok(sent) => sent,
+                    Err(_) => Err(String::from("timed out")),
+                };
+                sent.map_err(|e| {
+                    println!("error in sending mail via {}:{}: {}", self.host, self.port, e);
+                    ResponseError::MailFailure
+                })
+            }
+        }
+
+        trait Stream: AsyncRead + AsyncWrite + Unpin + Send {}
+
+        impl<T: AsyncRead + AsyncWrite + Unpin + Send> Stream for T {}
+
+        struct Connection {
+            stream: BufReader<Box<dyn Stream>>,
+        }
+
+        impl Connection {
+            fn new(stream: Box<dyn Stream>) -> Connection {
+                Connection { stream: BufReader::new(stream) }
+            }
+
+            /// Reads a reply, which may span several lines, and checks its code.
+            async fn reply(&mut self, code: u16) -> Result<String, String> {
+                let mut reply = String::new();
+                loop {
+                    let mut line = String::new();
+                    if self.stream.read_line(&mut line).await.map_err(|e| e.to_string())? == 0 {
+                        return Err(String::from("connection closed"));
+                    }
+                    reply.push_str(&line);
+                    // "250-" is followed by more lines, "250 " is the last
+                    if line.as_bytes().get(3) != Some(&b'-') {
+                        break;
+                    }
+                }
+                match reply.get(..3).and_then(|c| c.parse::<u16>().ok()) {
+                    Some(c) if c == code => Ok(reply),
+                    _ => Err(reply.trim_end().to_string()),
+                }
+            }
+
+            async fn command(&mut self, command: &str, code: u16) -> Result<String, String> {
+                let stream = self.stream.get_mut();
+                stream.write_all(format!("{command}\r\n").as_bytes()).await.map_err(|e| e.to_string())?;
+                stream.flush().await.map_err(|e| e.to_string())?;
+                self.reply(code).await
+            }
+        }
+
+        /// Our name in `EHLO`, the host of the site.
+        fn hello_name() -> String {
+            reqwest::Url::parse(&site_url())
+                .ok()
+                .and_then(|url| url.host_str().map(String::from))
+                .unwrap_or_else(|| String::from("localhost"))
+        }
+
+        /// The bare address of `Name <address>`.
+        fn address(mailbox: &str) -> &str {
+            match (mailbox.rfind('<'), mailbox.rfind('>')) {
+                (Some(start), Some(end)) if start < end => &mailbox[start + 1..end],
+                _ => mailbox.trim(),
+            }
+        }
+
+        /// Doubles dots at line starts, so no line of the message ends `DATA`.
+        fn dot_stuffed(message: &str) -> String {
+            message
+                .split("\r\n")
+                .map(|line| if line.starts_with('.') { format!(".{line}") } else { line.to_string() })
+                .collect::<Vec<_>>()
+                .join("\r\n")
+        }
+    }
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    /// Answers one session like a relay would and returns the commands and
+    /// the lines of the message as they arrived.
+    async fn relay(listener: TcpListener) -> (Vec<String>, Vec<String>) {
+        let (stream, _) = listener.accept().await.unwrap();
+        let (read, mut write) = stream.into_split();
+        let mut lines = BufReader::new(read).lines();
+        let (mut commands, mut data) = (Vec::new(), Vec::new());
+        let mut in_data = false;
+        write.write_all(b"220 relay ready\r\n").await.unwrap();
+        while let Some(line) = lines.next_line().await.unwrap() {
+            if in_data {
+                if line == "." {
+                    in_data = false;
+                    write.write_all(b"250 queued\r\n").await.unwrap();
+                } else {
+                    data.push(line);
+                }
+                continue;
+            }
+            commands.push(line.clone());
+            let reply: &[u8] = match line.split(' ').next().unwrap_or_default() {
+                "EHLO" => b"250-relay\r\n250 AUTH PLAIN\r\n",
+                "AUTH" => b"235 ok\r\n",
+                "DATA" => {
+                    in_data = true;
+                    b"354 go ahead\r\n"
+                }
+                "QUIT" => b"221 bye\r\n",
+                _ => b"250 ok\r\n",
+            };
+            write.write_all(reply).await.unwrap();
+        }
+        (commands, data)
+    }
+
+    #[test]
+    fn bare_address() {
+        assert_eq!(address("Stampffabrik <mail@stampffabrik.de>"), "mail@stampffabrik.de");
+        assert_eq!(address(" guest@example.org "), "guest@example.org");
+    }
+
+    #[test]
+    fn dots_at_line_starts() {
+        assert_eq!(dot_stuffed("a\r\n.\r\n..b\r\nc.d\r\n"), "a\r\n..\r\n...b\r\nc.d\r\n");
+    }
+
+    #[tokio::test]
+    async fn delivers_to_a_relay() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let relay = tokio::spawn(relay(listener));
+        let sink = SmtpSink {
+            host: String::from("127.0.0.1"),
+            port,
+            user: Some(String::from("user")),
+            password: String::from("secret"),
+            encryption: Encryption::None,
+        };
+        let mail = Mail::new(
+            String::from("guest@example.org"),
+            String::from("Hallo"),
+            String::from("first\n.second"),
+        );
+
+        assert!(sink.deliver(&mail).await.is_ok());
+        let (commands, data) = relay.await.unwrap();
+        let credentials = base64::engine::general_purpose::STANDARD.encode("\0user\0secret");
+        assert_eq!(commands[1], format!("AUTH PLAIN {credentials}"));
+        assert_eq!(commands[3], "RCPT TO:<guest@example.org>");
+        assert_eq!(commands.last().map(String::as_str), Some("QUIT"));
+        assert!(data.contains(&String::from("Subject: Hallo")));
+        assert!(data.ends_with(&[String::from("first"), String::from("..second")]));
+    }
+
+    #[tokio::test]
+    async fn refuses_recipients_with_line_breaks() {
+        let sink = SmtpSink {
+            host: String::from("127.0.0.1"),
+            port: 9,
+            user: None,
+            password: String::new(),
+            encryption: Encryption::None,
+        };
+        let mail = Mail::new(String::from("a@example.org\r\nRCPT TO:<b@example.org>"), String::new(), String::new());
+        assert!(matches!(sink.deliver(&mail).await, Err(ResponseError::MailFailure)));
+    }
+}
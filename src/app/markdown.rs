@@ -0,0 +1,60 @@
+//! Markdown to HTML for posts. The same rendering runs in the editor preview,
+//! so what authors see is what gets published.
+
+use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag, TagEnd};
+
+/// Link targets allowed in posts; anything else, e.g. `javascript:`, is
+/// dropped.
+fn safe_url(url: &str) -> bool {
+    let url = url.trim().to_lowercase();
+    !url.contains(':') || ["http:", "https:", "mailto:"].iter().any(|scheme| url.starts_with(scheme))
+}
+
+/// Renders `markdown`. Raw HTML in the source is shown as text, not
+/// interpreted.
+pub fn to_html(markdown: &str) -> String {
+    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH;
+    let events = Parser::new_ext(markdown, options).map(|event| match event {
+        Event::Html(html) | Event::InlineHtml(html) => Event::Text(html),
+        Event::Start(Tag::Link { link_type, dest_url, title, id }) if !safe_url(&dest_url) => {
+            Event::Start(Tag::Link { link_type, dest_url: CowStr::from(""), title, id })
+        }
+        Event::Start(Tag::Image { link_type, dest_url, title, id }) if !safe_url(&dest_url) => {
+            Event::Start(Tag::Image { link_type, dest_url: CowStr::from(""), title, id })
+        }
+        event => event,
+    });
+    let mut output = String::with_capacity(markdown.len() * 3 / 2);
+    html::push_html(&mut output, events);
+    output
+}
+
+/// Plain text of the first paragraph, cut to `max_chars`, for overviews and
+/// feed summaries.
+pub fn excerpt(markdown: &str, max_chars: usize) -> String {
+    let mut text = String::new();
+    for event in Parser::new(markdown) {
+        match event {
+            Event::Text(t) | Event::Code(t) => text.push_str(&t),
+            Event::SoftBreak | Event::HardBreak | Event::End(TagEnd::Heading(_)) => text.push(' '),
+            Event::End(TagEnd::Paragraph) if !text.trim().is_empty() => break,
+            _ => (),
+        }
+    }
+    let text = text.trim();
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(max_chars).collect();
+    format!("{}…", cut.trim_end())
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        /// What is served to readers: the rendering run through a whitelist
+        /// sanitiser, in case the renderer lets something through.
+        pub fn render(markdown: &str) -> String {
+            ammonia::clean(&to_html(markdown))
+        }
+    }
+}
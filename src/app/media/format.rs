@@ -0,0 +1,263 @@
+//! Recognising uploads by their content and removing the metadata cameras
+//! and editors put into them: location, device, author, thumbnails.
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MediaType {
+    Jpeg,
+    Png,
+    Webp,
+}
+
+impl MediaType {
+    pub const ALL: [MediaType; 3] = [MediaType::Jpeg, MediaType::Png, MediaType::Webp];
+
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            MediaType::Jpeg => "image/jpeg",
+            MediaType::Png => "image/png",
+            MediaType::Webp => "image/webp",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            MediaType::Jpeg => "jpg",
+            MediaType::Png => "png",
+            MediaType::Webp => "webp",
+        }
+    }
+
+    pub fn from_extension(extension: &str) -> Option<MediaType> {
+        MediaType::ALL.into_iter().find(|t| t.extension() == extension)
+    }
+
+    /// The type by the file's magic bytes. Names and the type the browser
+    /// sends are not trusted.
+    pub fn sniff(bytes: &[u8]) -> Option<MediaType> {
+        if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            Some(MediaType::Jpeg)
+        } else if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some(MediaType::Png)
+        } else if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+            Some(MediaType::Webp)
+        } else {
+            None
+        }
+    }
+}
+
+/// `bytes` without EXIF, XMP, IPTC and comments. Pixel data is copied as it
+/// is, so nothing is re-compressed. `None` if the file is damaged.
+pub fn strip_metadata(media_type: MediaType, bytes: &[u8]) -> Option<Vec<u8>> {
+    match media_type {
+        MediaType::Jpeg => strip_jpeg(bytes),
+        MediaType::Png => strip_png(bytes),
+        MediaType::Webp => strip_webp(bytes),
+    }
+}
+
+fn strip_jpeg(bytes: &[u8]) -> Option<Vec<u8>> {
+    // APP1 holds EXIF and XMP, APP13 IPTC, then comments. APP2 stays, it
+    // carries the colour profile.
+    const DROPPED: [u8; 3] = [0xE1, 0xED, 0xFE];
+
+    let mut out = Vec::with_capacity(bytes.len());
+    out.extend_from_slice(bytes.get(0..2)?);
+    let mut pos = 2;
+    loop {
+        if *bytes.get(pos)? != 0xFF {
+            return None;
+        }
+        let marker = *bytes.get(pos + 1)?;
+        match marker {
+            // padding before a marker
+            0xFF => {
+                pos += 1;
+                continue;
+            }
+            // start of scan: the rest is image data
+            0xDA => {
+                out.extend_from_slice(&bytes[pos..]);
+                return Some(out);
+            }
+            0x01 | 0xD0..=0xD7 => {
+                out.extend_from_slice(&bytes[pos..pos + 2]);
+                pos += 2;
+                continue;
+            }
+            _ => {}
+        }
+        let length = u16::from_be_bytes([*bytes.get(pos + 2)?, *bytes.get(pos + 3)?]) as usize;
+        let end = pos + 2 + length;
+        let segment = bytes.get(pos..end)?;
+        if !DROPPED.contains(&marker) {
+            out.extend_from_slice(segment);
+        }
+        pos = end;
+    }
+}
+
+fn strip_png(bytes: &[u8]) -> Option<Vec<u8>> {
+    const DROPPED: [&[u8; 4]; 5] = [b"eXIf", b"tEXt", b"iTXt", b"zTXt", b"tIME"];
+
+    let mut out = Vec::with_capacity(bytes.len());
+    out.extend_from_slice(bytes.get(0..8)?);
+    let mut pos = 8;
+    while pos < bytes.len() {
+        let length = u32::from_be_bytes(bytes.get(pos..pos + 4)?.try_into().ok()?) as usize;
+        // length, type, data and CRC
+        let end = pos + 12 + length;
+        let chunk = bytes.get(pos..end)?;
+        if !DROPPED.iter().any(|kind| &chunk[4..8] == *kind) {
+            out.extend_from_slice(chunk);
+        }
+        if &chunk[4..8] == b"IEND" {
+            break;
+        }
+        pos = end;
+    }
+    Some(out)
+}
+
+fn strip_webp(bytes: &[u8]) -> Option<Vec<u8>> {
+    // flags in the extended header announcing the dropped chunks
+    const EXIF_FLAG: u8 = 0x08;
+    const XMP_FLAG: u8 = 0x04;
+
+    let mut out = Vec::with_capacity(bytes.len());
+    out.extend_from_slice(bytes.get(0..12)?);
+    let mut pos = 12;
+    while pos < bytes.len() {
+        let kind = bytes.get(pos..pos + 4)?;
+        let length = u32::from_le_bytes(bytes.get(pos + 4..pos + 8)?.try_into().ok()?) as usize;
+        // chunks are padded to an even length
+        let end = (pos + 8 + length + (length & 1)).min(bytes.len());
+        let chunk = bytes.get(pos..end)?;
+        match kind {
+            b"EXIF" | b"XMP " => {}
+            b"VP8X" => {
+                let mut chunk = chunk.to_vec();
+                *chunk.get_mut(8)? &= !(EXIF_FLAG | XMP_FLAG);
+                out.extend_from_slice(&chunk);
+            }
+            _ => out.extend_from_slice(chunk),
+        }
+        pos = end;
+    }
+    let riff_size = u32::try_from(out.len() - 8).ok()?;
+    out[4..8].copy_from_slice(&riff_size.to_le_bytes());
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A JPEG segment: marker, big-endian length including itself, data.
+    fn segment(marker: u8, data: &[u8]) -> Vec<u8> {
+        let mut segment = vec![0xFF, marker];
+        segment.extend_from_slice(&(data.len() as u16 + 2).to_be_bytes());
+        segment.extend_from_slice(data);
+        segment
+    }
+
+    /// A PNG chunk with a dummy CRC, which is not checked.
+    fn png_chunk(kind: &[u8; 4], data: &[u8]) -> Vec<u8> {
+        let mut chunk = (data.len() as u32).to_be_bytes().to_vec();
+        chunk.extend_from_slice(kind);
+        chunk.extend_from_slice(data);
+        chunk.extend_from_slice(&[0; 4]);
+        chunk
+    }
+
+    fn webp_chunk(kind: &[u8; 4], data: &[u8]) -> Vec<u8> {
+        let mut chunk = kind.to_vec();
+        chunk.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        chunk.extend_from_slice(data);
+        if data.len() % 2 == 1 {
+            chunk.push(0);
+        }
+        chunk
+    }
+
+    fn webp(chunks: &[Vec<u8>]) -> Vec<u8> {
+        let body: Vec<u8> = chunks.concat();
+        let mut file = b"RIFF".to_vec();
+        file.extend_from_slice(&(body.len() as u32 + 4).to_le_bytes());
+        file.extend_from_slice(b"WEBP");
+        file.extend_from_slice(&body);
+        file
+    }
+
+    #[test]
+    fn sniffs_by_content() {
+        assert_eq!(MediaType::sniff(&[0xFF, 0xD8, 0xFF, 0xE0]), Some(MediaType::Jpeg));
+        assert_eq!(MediaType::sniff(b"\x89PNG\r\n\x1a\n...."), Some(MediaType::Png));
+        assert_eq!(MediaType::sniff(b"RIFF\0\0\0\0WEBPVP8 "), Some(MediaType::Webp));
+        assert_eq!(MediaType::sniff(b"RIFF\0\0\0\0WAVE"), None);
+        assert_eq!(MediaType::sniff(b"<svg xmlns="), None);
+        assert_eq!(MediaType::sniff(&[]), None);
+    }
+
+    #[test]
+    fn jpeg_keeps_image_and_profile() {
+        let scan = [0xFF, 0xDA, 0x00, 0x02, 0x12, 0x34, 0xFF, 0xD9];
+        let jfif = segment(0xE0, b"JFIF\0");
+        let profile = segment(0xE2, b"ICC_PROFILE\0");
+        let file = [
+            vec![0xFF, 0xD8],
+            jfif.clone(),
+            segment(0xE1, b"Exif\0\0GPS"),
+            profile.clone(),
+            segment(0xED, b"Photoshop 3.0\0"),
+            segment(0xFE, b"made with love"),
+            scan.to_vec(),
+        ]
+        .concat();
+        let stripped = [vec![0xFF, 0xD8], jfif, profile, scan.to_vec()].concat();
+        assert_eq!(strip_metadata(MediaType::Jpeg, &file), Some(stripped));
+    }
+
+    #[test]
+    fn damaged_jpeg() {
+        let truncated = [vec![0xFF, 0xD8], segment(0xE1, b"Exif")[..5].to_vec()].concat();
+        assert_eq!(strip_metadata(MediaType::Jpeg, &truncated), None);
+        assert_eq!(strip_metadata(MediaType::Jpeg, &[0xFF, 0xD8, 0x00, 0x00]), None);
+    }
+
+    #[test]
+    fn png_drops_text_and_exif() {
+        let header = png_chunk(b"IHDR", &[0; 13]);
+        let data = png_chunk(b"IDAT", b"pixels");
+        let end = png_chunk(b"IEND", &[]);
+        let file = [
+            b"\x89PNG\r\n\x1a\n".to_vec(),
+            header.clone(),
+            png_chunk(b"tEXt", b"Author\0Anna"),
+            png_chunk(b"eXIf", b"MM\0*"),
+            data.clone(),
+            png_chunk(b"tIME", &[0; 7]),
+            end.clone(),
+            b"trailing".to_vec(),
+        ]
+        .concat();
+        let stripped = [b"\x89PNG\r\n\x1a\n".to_vec(), header, data, end].concat();
+        assert_eq!(strip_metadata(MediaType::Png, &file), Some(stripped));
+    }
+
+    #[test]
+    fn webp_drops_metadata_and_fixes_header() {
+        let mut extended = [0u8; 10];
+        extended[0] = 0x08 | 0x04 | 0x10;
+        let file = webp(&[
+            webp_chunk(b"VP8X", &extended),
+            webp_chunk(b"VP8 ", b"frame"),
+            webp_chunk(b"EXIF", b"MM\0*"),
+            webp_chunk(b"XMP ", b"<x:xmpmeta/>"),
+        ]);
+        let mut cleared = extended;
+        cleared[0] = 0x10;
+        let stripped = webp(&[webp_chunk(b"VP8X", &cleared), webp_chunk(b"VP8 ", b"frame")]);
+        assert_eq!(strip_metadata(MediaType::Webp, &file), Some(stripped));
+    }
+}
@@ -0,0 +1,81 @@
+//! Keeps media in a directory on the server, `MEDIA_DIR`.
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use std::path::PathBuf;
+
+        use super::{valid_key, StorageBackend};
+        use crate::app::errors::ResponseError;
+
+        pub struct LocalStorage {
+            dir: PathBuf,
+        }
+
+        impl LocalStorage {
+            pub fn from_env() -> LocalStorage {
+                LocalStorage {
+                    dir: PathBuf::from(std::env::var("MEDIA_DIR").unwrap_or_else(|_| String::from("media"))),
+                }
+            }
+
+            fn path(&self, key: &str) -> Result<PathBuf, ResponseError> {
+                if !valid_key(key) {
+                    return Err(ResponseError::MediaNotFound);
+                }
+                Ok(self.dir.join(key))
+            }
+        }
+
+        impl StorageBackend for LocalStorage {
+            async fn put(&self, key: &str, bytes: Vec<u8>, _mime_type: &str) -> Result<(), ResponseError> {
+                let path = self.path(key)?;
+                // written beside the target and renamed, so readers never
+                // see half a file
+                let partial = self.dir.join(format!("{key}.part"));
+                let written = async {
+                    tokio::fs::create_dir_all(&self.dir).await?;
+                    tokio::fs::write(&partial, bytes).await?;
+                    tokio::fs::rename(&partial, &path).await
+                }.await;
+                written.map_err(|e| {
+                    println!("error in writing media to {:?}: {:?}", path, e);
+                    ResponseError::MediaStorageFailure
+                })
+            }
+
+            async fn get(&self, key: &str) -> Result<Vec<u8>, ResponseError> {
+                tokio::fs::read(self.path(key)?).await.map_err(|_| ResponseError::MediaNotFound)
+            }
+
+            async fn delete(&self, key: &str) -> Result<(), ResponseError> {
+                match tokio::fs::remove_file(self.path(key)?).await {
+                    Ok(_) => Ok(()),
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                    Err(e) => {
+                        println!("error in deleting media {key}: {:?}", e);
+                        Err(ResponseError::MediaStorageFailure)
+                    }
+                }
+            }
+
+            async fn list(&self) -> Result<Vec<String>, ResponseError> {
+                let mut entries = match tokio::fs::read_dir(&self.dir).await {
+                    Ok(entries) => entries,
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+                    Err(_) => return Err(ResponseError::MediaStorageFailure),
+                };
+                let mut keys = Vec::new();
+                while let Ok(Some(entry)) = entries.next_entry().await {
+                    if let Some(key) = entry.file_name().to_str().filter(|key| valid_key(key)) {
+                        keys.push(key.to_string());
+                    }
+                }
+                Ok(keys)
+            }
+
+            fn local_path(&self, key: &str) -> Option<PathBuf> {
+                self.path(key).ok()
+            }
+        }
+    }
+}
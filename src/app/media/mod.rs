@@ -0,0 +1,240 @@
+//! Uploaded posters, product photos and post images. Uploads are checked by
+//! their content, stripped of metadata and stored under their SHA-256, so
+//! the same file uploaded twice is stored once. Where the files live is up
+//! to a `StorageBackend`: a local directory or an S3 compatible bucket,
+//! chosen with `MEDIA_STORAGE` (`local` or `s3`).
+
+pub mod format;
+pub mod local;
+pub mod s3;
+
+pub use format::MediaType;
+
+/// Largest file accepted, after which uploads are cut off.
+pub const MAX_UPLOAD_BYTES: usize = 20 * 1024 * 1024;
+
+/// Keys are a hex SHA-256 and a known extension. Nothing else reaches the
+/// backends, which keeps paths and object names out of users' hands.
+pub fn valid_key(key: &str) -> bool {
+    match key.split_once('.') {
+        Some((hash, extension)) => {
+            hash.len() == 64
+                && hash.chars().all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c))
+                && MediaType::from_extension(extension).is_some()
+        }
+        None => false,
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use std::collections::HashSet;
+        use std::future::Future;
+        use std::io::Cursor;
+        use std::path::PathBuf;
+
+        use image::metadata::Orientation;
+        use image::{DynamicImage, ImageDecoder, ImageError, ImageReader};
+        use sha2::{Digest, Sha256};
+        use uuid::Uuid;
+
+        use crate::app::database;
+        use crate::app::errors::ResponseError;
+        use crate::app::images;
+        use crate::app::model::media::{MediaFile, MediaLibrary, MediaUsage};
+        use crate::app::time::now;
+        use crate::app::signing::to_hex;
+
+        pub use local::LocalStorage;
+        pub use s3::S3Storage;
+
+        pub trait StorageBackend {
+            /// Stores `bytes` under `key`, replacing what was there.
+            fn put(&self, key: &str, bytes: Vec<u8>, mime_type: &str)
+                -> impl Future<Output = Result<(), ResponseError>> + Send;
+
+            fn get(&self, key: &str) -> impl Future<Output = Result<Vec<u8>, ResponseError>> + Send;
+
+            /// Deleting a key that isn't there is no error.
+            fn delete(&self, key: &str) -> impl Future<Output = Result<(), ResponseError>> + Send;
+
+            /// All keys in the backend.
+            fn list(&self) -> impl Future<Output = Result<Vec<String>, ResponseError>> + Send;
+
+            /// The file on this server's disk, for backends that have one.
+            fn local_path(&self, _key: &str) -> Option<PathBuf> {
+                None
+            }
+        }
+
+        pub enum Storage {
+            Local(LocalStorage),
+            S3(S3Storage),
+        }
+
+        impl StorageBackend for Storage {
+            async fn put(&self, key: &str, bytes: Vec<u8>, mime_type: &str) -> Result<(), ResponseError> {
+                match self {
+                    Storage::Local(storage) => storage.put(key, bytes, mime_type).await,
+                    Storage::S3(storage) => storage.put(key, bytes, mime_type).await,
+                }
+            }
+
+            async fn get(&self, key: &str) -> Result<Vec<u8>, ResponseError> {
+                match self {
+                    Storage::Local(storage) => storage.get(key).await,
+                    Storage::S3(storage) => storage.get(key).await,
+                }
+            }
+
+            async fn delete(&self, key: &str) -> Result<(), ResponseError> {
+                match self {
+                    Storage::Local(storage) => storage.delete(key).await,
+                    Storage::S3(storage) => storage.delete(key).await,
+                }
+            }
+
+            async fn list(&self) -> Result<Vec<String>, ResponseError> {
+                match self {
+                    Storage::Local(storage) => storage.list().await,
+                    Storage::S3(storage) => storage.list().await,
+                }
+            }
+
+            fn local_path(&self, key: &str) -> Option<PathBuf> {
+                match self {
+                    Storage::Local(storage) => storage.local_path(key),
+                    Storage::S3(storage) => storage.local_path(key),
+                }
+            }
+        }
+
+        /// The backend configured via `MEDIA_STORAGE`.
+        pub fn storage() -> Storage {
+            match std::env::var("MEDIA_STORAGE").as_deref() {
+                Ok("s3") => Storage::S3(S3Storage::from_env()),
+                _ => Storage::Local(LocalStorage::from_env()),
+            }
+        }
+
+        /// Checks the file, strips its metadata and reads its size. Photos
+        /// that are only upright by their EXIF orientation are turned for
+        /// real and re-encoded, since the orientation goes with the rest.
+        fn prepare(media_type: MediaType, bytes: Vec<u8>) -> Result<(Vec<u8>, u32, u32), ResponseError> {
+            let invalid = |e: ImageError| {
+                println!("error in reading upload: {:?}", e);
+                ResponseError::MediaInvalid
+            };
+            let image_format = match media_type {
+                MediaType::Jpeg => image::ImageFormat::Jpeg,
+                MediaType::Png => image::ImageFormat::Png,
+                MediaType::Webp => image::ImageFormat::WebP,
+            };
+
+            let mut decoder = ImageReader::with_format(Cursor::new(&bytes), image_format).into_decoder().map_err(invalid)?;
+            let orientation = decoder.orientation().map_err(invalid)?;
+            if orientation == Orientation::NoTransforms {
+                let (width, height) = decoder.dimensions();
+                let stripped = format::strip_metadata(media_type, &bytes).ok_or(ResponseError::MediaInvalid)?;
+                return Ok((stripped, width, height));
+            }
+
+            let mut image = DynamicImage::from_decoder(decoder).map_err(invalid)?;
+            image.apply_orientation(orientation);
+            let mut encoded = Vec::new();
+            image.write_to(&mut Cursor::new(&mut encoded), image_format).map_err(invalid)?;
+            Ok((encoded, image.width(), image.height()))
+        }
+
+        /// Stores an upload. Content that was uploaded before gets the
+        /// existing record back.
+        pub async fn upload(original_name: String, bytes: Vec<u8>) -> Result<MediaFile, ResponseError> {
+            if bytes.len() > MAX_UPLOAD_BYTES {
+                return Err(ResponseError::MediaInvalid);
+            }
+            let media_type = MediaType::sniff(&bytes).ok_or(ResponseError::MediaInvalid)?;
+            let (bytes, width, height) = tokio::task::spawn_blocking(move || prepare(media_type, bytes))
+                .await
+                .map_err(|_| ResponseError::MediaStorageFailure)??;
+
+            let hash = to_hex(&Sha256::digest(&bytes));
+            if let Some(existing) = database::get_media_by_hash(hash.clone()).await {
+                return Ok(existing);
+            }
+            let file = MediaFile {
+                uuid: Uuid::new_v4().to_string(),
+                key: format!("{hash}.{}", media_type.extension()),
+                hash: hash.clone(),
+                mime_type: media_type.mime_type().to_string(),
+                size: bytes.len() as u64,
+                original_name,
+                width,
+                height,
+                created_at: now(),
+            };
+            storage().put(&file.key, bytes, &file.mime_type).await?;
+            match database::add_media(file).await {
+                Some(file) => Ok(file),
+                // uploaded at the same time by someone else
+                None => database::get_media_by_hash(hash).await.ok_or(ResponseError::MediaStorageFailure),
+            }
+        }
+
+        /// Every file with the events, products and posts using it, and
+        /// the keys in storage nobody has a record of.
+        pub async fn library() -> MediaLibrary {
+            let mut sources: Vec<(String, String)> = Vec::new();
+            for event in database::get_events_since(String::new()).await.unwrap_or_default() {
+                sources.push((format!("Event: {}", event.title), event.poster.unwrap_or_default()));
+            }
+            for product in database::get_products().await.unwrap_or_default() {
+                sources.push((format!("Produkt: {}", product.name), product.images.join(" ")));
+            }
+            for post in database::get_posts().await.unwrap_or_default() {
+                let text = format!("{} {}", post.cover.unwrap_or_default(), post.body);
+                sources.push((format!("Beitrag: {}", post.title), text));
+            }
+
+            let files: Vec<MediaUsage> = database::get_media().await.unwrap_or_default()
+                .into_iter()
+                .map(|file| {
+                    let references = sources.iter()
+                        .filter(|(_, text)| text.contains(&file.key))
+                        .map(|(label, _)| label.clone())
+                        .collect();
+                    MediaUsage { file, references }
+                })
+                .collect();
+
+            let known: HashSet<&str> = files.iter().map(|usage| usage.file.key.as_str()).collect();
+            let stray = storage().list().await.unwrap_or_default()
+                .into_iter()
+                .filter(|key| !known.contains(key.as_str()))
+                .collect();
+            MediaLibrary { files, stray }
+        }
+
+        /// The file on disk to make image copies from. Remote files are
+        /// fetched into the image cache once; content never changes under
+        /// a key, so the copy stays good.
+        pub async fn local_file(key: &str) -> Option<PathBuf> {
+            if !valid_key(key) {
+                return None;
+            }
+            let storage = storage();
+            if let Some(path) = storage.local_path(key) {
+                return Some(path);
+            }
+            let path = images::cache_dir().join("originals").join(key);
+            if tokio::fs::try_exists(&path).await.unwrap_or(false) {
+                return Some(path);
+            }
+            let bytes = storage.get(key).await.ok()?;
+            let partial = path.with_extension("part");
+            tokio::fs::create_dir_all(path.parent()?).await.ok()?;
+            tokio::fs::write(&partial, bytes).await.ok()?;
+            tokio::fs::rename(&partial, &path).await.ok()?;
+            Some(path)
+        }
+    }
+}
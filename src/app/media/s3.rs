@@ -0,0 +1,189 @@
+//! Keeps media in a bucket of an S3 compatible object store. Requests use
+//! path-style addresses and Signature Version 4, which AWS, MinIO and the
+//! usual stand-ins all accept. Configured via `S3_ENDPOINT`, `S3_BUCKET`,
+//! `S3_REGION`, `S3_ACCESS_KEY` and `S3_SECRET_KEY`.
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use chrono::Utc;
+        use hmac::{Hmac, Mac};
+        use reqwest::{header, Client, Method, Response, StatusCode, Url};
+        use sha2::{Digest, Sha256};
+
+        use super::{valid_key, StorageBackend};
+        use crate::app::errors::ResponseError;
+        use crate::app::signing::to_hex;
+
+        const SIGNED_HEADERS: &str = "host;x-amz-content-sha256;x-amz-date";
+
+        pub struct S3Storage {
+            client: Client,
+            endpoint: Url,
+            bucket: String,
+            region: String,
+            access_key: String,
+            secret_key: String,
+        }
+
+        impl S3Storage {
+            pub fn from_env() -> S3Storage {
+                let var = |name: &str, default: &str| std::env::var(name).unwrap_or_else(|_| default.to_string());
+                S3Storage {
+                    client: Client::new(),
+                    endpoint: Url::parse(&var("S3_ENDPOINT", "http://127.0.0.1:9000"))
+                        .expect("S3_ENDPOINT must be a URL"),
+                    bucket: var("S3_BUCKET", "stampffabrik"),
+                    region: var("S3_REGION", "us-east-1"),
+                    access_key: var("S3_ACCESS_KEY", ""),
+                    secret_key: var("S3_SECRET_KEY", ""),
+                }
+            }
+
+            fn object_path(&self, key: &str) -> Result<String, ResponseError> {
+                if !valid_key(key) {
+                    return Err(ResponseError::MediaNotFound);
+                }
+                Ok(format!("/{}/{}", uri_encode(&self.bucket), key))
+            }
+
+            /// Signs and sends a request to `path` of the endpoint.
+            async fn send(&self, method: Method, path: &str, query: &[(&str, &str)], body: Vec<u8>, mime_type: Option<&str>)
+                -> Result<Response, ResponseError> {
+
+                let now = Utc::now();
+                let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+                let date = now.format("%Y%m%d").to_string();
+                let payload_hash = to_hex(&Sha256::digest(&body));
+                let host = match (self.endpoint.host_str(), self.endpoint.port()) {
+                    (Some(host), Some(port)) => format!("{host}:{port}"),
+                    (Some(host), None) => host.to_string(),
+                    (None, _) => return Err(ResponseError::MediaStorageFailure),
+                };
+
+                let mut query: Vec<(String, String)> = query.iter()
+                    .map(|(name, value)| (uri_encode(name), uri_encode(value)))
+                    .collect();
+                query.sort();
+                let query = query.iter().map(|(name, value)| format!("{name}={value}")).collect::<Vec<_>>().join("&");
+
+                let canonical_request = format!(
+                    "{method}\n{path}\n{query}\nhost:{host}\nx-amz-content-sha256:{payload_hash}\nx-amz-date:{amz_date}\n\n\
+                     {SIGNED_HEADERS}\n{payload_hash}"
+                );
+                let scope = format!("{date}/{}/s3/aws4_request", self.region);
+                let string_to_sign = format!(
+                    "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+                    to_hex(&Sha256::digest(canonical_request.as_bytes())),
+                );
+                let signing_key = [date.as_str(), self.region.as_str(), "s3", "aws4_request"]
+                    .into_iter()
+                    .fold(format!("AWS4{}", self.secret_key).into_bytes(), |key, part| hmac(&key, part));
+                let authorization = format!(
+                    "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={SIGNED_HEADERS}, Signature={}",
+                    self.access_key,
+                    to_hex(&hmac(&signing_key, &string_to_sign)),
+                );
+
+                let mut url = self.endpoint.clone();
+                url.set_path(path);
+                url.set_query((!query.is_empty()).then_some(query.as_str()));
+                let mut request = self.client.request(method, url)
+                    .header("x-amz-date", amz_date)
+                    .header("x-amz-content-sha256", payload_hash)
+                    .header(header::AUTHORIZATION, authorization);
+                if let Some(mime_type) = mime_type {
+                    request = request.header(header::CONTENT_TYPE, mime_type);
+                }
+                request.body(body).send().await.map_err(|e| {
+                    println!("error in request to object store: {:?}", e);
+                    ResponseError::MediaStorageFailure
+                })
+            }
+        }
+
+        fn hmac(key: &[u8], message: &str) -> Vec<u8> {
+            let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+            mac.update(message.as_bytes());
+            mac.finalize().into_bytes().to_vec()
+        }
+
+        /// Percent-encoding as SigV4 wants it: everything but unreserved characters.
+        fn uri_encode(value: &str) -> String {
+            value.bytes()
+                .map(|b| match b {
+                    b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+                    _ => format!("%{:02X}", b),
+                })
+                .collect()
+        }
+
+        /// Contents of every `<tag>` in an XML response. Listings are simple
+        /// enough to not need a parser.
+        fn xml_values<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
+            let open = format!("<{tag}>");
+            let close = format!("</{tag}>");
+            xml.split(open.as_str())
+                .skip(1)
+                .filter_map(|rest| rest.split_once(close.as_str()).map(|(value, _)| value))
+                .collect()
+        }
+
+        fn check(response: &Response, key: &str) -> Result<(), ResponseError> {
+            match response.status() {
+                status if status.is_success() => Ok(()),
+                StatusCode::NOT_FOUND => Err(ResponseError::MediaNotFound),
+                status => {
+                    println!("error from object store for {key}: {status}");
+                    Err(ResponseError::MediaStorageFailure)
+                }
+            }
+        }
+
+        impl StorageBackend for S3Storage {
+            async fn put(&self, key: &str, bytes: Vec<u8>, mime_type: &str) -> Result<(), ResponseError> {
+                let response = self.send(Method::PUT, &self.object_path(key)?, &[], bytes, Some(mime_type)).await?;
+                check(&response, key)
+            }
+
+            async fn get(&self, key: &str) -> Result<Vec<u8>, ResponseError> {
+                let response = self.send(Method::GET, &self.object_path(key)?, &[], Vec::new(), None).await?;
+                check(&response, key)?;
+                response.bytes().await
+                    .map(|bytes| bytes.to_vec())
+                    .map_err(|_| ResponseError::MediaStorageFailure)
+            }
+
+            async fn delete(&self, key: &str) -> Result<(), ResponseError> {
+                let response = self.send(Method::DELETE, &self.object_path(key)?, &[], Vec::new(), None).await?;
+                match check(&response, key) {
+                    Err(ResponseError::MediaNotFound) => Ok(()),
+                    result => result,
+                }
+            }
+
+            async fn list(&self) -> Result<Vec<String>, ResponseError> {
+                let path = format!("/{}", uri_encode(&self.bucket));
+                let mut keys = Vec::new();
+                let mut token: Option<String> = None;
+                loop {
+                    let mut query = vec![("list-type", "2")];
+                    if let Some(token) = token.as_deref() {
+                        query.push(("continuation-token", token));
+                    }
+                    let response = self.send(Method::GET, &path, &query, Vec::new(), None).await?;
+                    check(&response, &self.bucket)?;
+                    let xml = response.text().await.map_err(|_| ResponseError::MediaStorageFailure)?;
+
+                    keys.extend(xml_values(&xml, "Key").into_iter().filter(|key| valid_key(key)).map(String::from));
+                    token = match xml_values(&xml, "IsTruncated").first() {
+                        Some(&"true") => xml_values(&xml, "NextContinuationToken").first().map(|t| t.to_string()),
+                        _ => None,
+                    };
+                    if token.is_none() {
+                        return Ok(keys);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::app::model::Event;
+
+/// A band, DJ or act that plays or played at the Stampffabrik.
+#[derive(Debug, Validate, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct Artist {
+    pub uuid: String,
+    #[validate(length(min = 1))]
+    pub slug: String,
+    #[validate(length(min = 1))]
+    pub name: String,
+    /// Markdown.
+    pub bio: String,
+    pub photo: Option<String>,
+    /// Lower case, e.g. "techno", "post-punk".
+    pub genres: Vec<String>,
+    /// Website, Bandcamp, SoundCloud and the like.
+    pub links: Vec<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl Artist {
+    pub fn new(uuid: String, slug: String, name: String) -> Artist {
+        Artist {
+            uuid,
+            slug,
+            name,
+            bio: String::new(),
+            photo: None,
+            genres: Vec::new(),
+            links: Vec::new(),
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+}
+
+/// An artist's slot in the lineup of an event. An artist can play several
+/// events and an event has several artists.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct Performance {
+    pub uuid: String,
+    /// `Event.uuid`.
+    pub event: String,
+    /// `Artist.uuid`.
+    pub artist: String,
+    /// Set times; unset until the running order is announced.
+    pub starts_at: Option<String>,
+    pub ends_at: Option<String>,
+    /// Where the set is played, empty if there is only one floor.
+    pub floor: String,
+}
+
+impl Performance {
+    pub fn new(uuid: String, event: String, artist: String) -> Performance {
+        Performance {
+            uuid,
+            event,
+            artist,
+            starts_at: None,
+            ends_at: None,
+            floor: String::new(),
+        }
+    }
+}
+
+/// A slot of an event's lineup with its artist.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct LineupEntry {
+    pub performance: Performance,
+    pub artist: Artist,
+}
+
+/// A slot of an artist's appearances with its event.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct Appearance {
+    pub performance: Performance,
+    pub event: Event,
+}
+
+/// An artist as readers get it.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct ArtistView {
+    pub artist: Artist,
+    /// Sanitised HTML of `artist.bio`.
+    pub html: String,
+    /// Soonest first.
+    pub upcoming: Vec<Appearance>,
+    /// Latest first.
+    pub past: Vec<Appearance>,
+}
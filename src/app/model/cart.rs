@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::app::model::Address;
+use crate::app::model::shipping::DeliveryMethod;
+
+pub const MAX_QUANTITY: u32 = 10;
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+pub enum ItemKind {
+    Ticket,
+    Merch,
+    /// Gift voucher; the reference is its value in cents.
+    Voucher,
+    /// Shipping costs, added at checkout; the reference is the country.
+    Shipping,
+}
+
+/// Everything the buyer entered at checkout.
+#[derive(Debug, Validate, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct CheckoutRequest {
+    pub cart: Cart,
+    #[validate(email)]
+    pub email: String,
+    pub buyer_name: String,
+    pub billing_address: Option<Address>,
+    pub discount_code: Option<String>,
+    pub voucher_code: Option<String>,
+    /// Required if the cart holds merch.
+    pub delivery_method: Option<DeliveryMethod>,
+    pub shipping_address: Option<Address>,
+}
+
+impl CheckoutRequest {
+    pub fn new(cart: Cart, email: String, buyer_name: String) -> CheckoutRequest {
+        CheckoutRequest {
+            cart,
+            email,
+            buyer_name,
+            billing_address: None,
+            discount_code: None,
+            voucher_code: None,
+            delivery_method: None,
+            shipping_address: None,
+        }
+    }
+}
+
+/// What the buyer picked. Names and prices are for display only, the server
+/// looks both up again at checkout.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct CartItem {
+    pub kind: ItemKind,
+    /// `TicketType.uuid` for tickets, the SKU for merch, the value for vouchers.
+    pub reference: String,
+    pub name: String,
+    pub quantity: u32,
+    pub unit_price_cents: i64,
+}
+
+impl CartItem {
+    pub fn new(kind: ItemKind, reference: String, name: String, unit_price_cents: i64) -> CartItem {
+        CartItem {
+            kind,
+            reference,
+            name,
+            quantity: 1,
+            unit_price_cents,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct Cart {
+    pub items: Vec<CartItem>,
+}
+
+impl Cart {
+    pub fn add(&mut self, item: CartItem) {
+        match self.items.iter_mut().find(|i| i.kind == item.kind && i.reference == item.reference) {
+            Some(existing) => existing.quantity = (existing.quantity + item.quantity).min(MAX_QUANTITY),
+            None => self.items.push(item),
+        }
+    }
+
+    pub fn set_quantity(&mut self, reference: &str, quantity: u32) {
+        if quantity == 0 {
+            self.items.retain(|i| i.reference != reference);
+        } else if let Some(item) = self.items.iter_mut().find(|i| i.reference == reference) {
+            item.quantity = quantity.min(MAX_QUANTITY);
+        }
+    }
+
+    pub fn count(&self) -> u32 {
+        self.items.iter().map(|i| i.quantity).sum()
+    }
+
+    pub fn total_cents(&self) -> i64 {
+        self.items.iter().map(|i| i.unit_price_cents * i.quantity as i64).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+/// Formats cents as euros, e.g. `12,50 €`.
+pub fn format_cents(cents: i64) -> String {
+    let sign = if cents < 0 { "-" } else { "" };
+    format!("{}{},{:02} €", sign, cents.abs() / 100, cents.abs() % 100)
+}
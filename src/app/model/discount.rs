@@ -0,0 +1,235 @@
+use chrono::DateTime;
+use rand::{distributions::Alphanumeric, Rng};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::app::ical::from_input;
+
+const VOUCHER_CODE_LENGTH: usize = 12;
+
+/// Values gift vouchers are sold in.
+pub const VOUCHER_VALUES: [i64; 3] = [2500, 5000, 10000];
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+pub enum DiscountKind {
+    Percent(u32),
+    /// Off the applicable lines as a whole.
+    FixedCents(i64),
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub enum DiscountScope {
+    /// Tickets and merch.
+    All,
+    /// Tickets of this event.
+    Event(String),
+    TicketType(String),
+}
+
+#[derive(Debug, Validate, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct DiscountCode {
+    #[validate(length(min = 3, max = 32))]
+    pub code: String,
+    pub kind: DiscountKind,
+    pub scope: DiscountScope,
+    pub max_uses: Option<u32>,
+    /// Checkouts that took the code, including unpaid ones still pending.
+    pub uses: u32,
+    pub valid_from: Option<String>,
+    pub valid_until: Option<String>,
+    pub active: bool,
+}
+
+impl DiscountCode {
+    pub fn new(code: String, kind: DiscountKind, scope: DiscountScope) -> DiscountCode {
+        DiscountCode {
+            code: normalize_code(&code),
+            kind,
+            scope,
+            max_uses: None,
+            uses: 0,
+            valid_from: None,
+            valid_until: None,
+            active: true,
+        }
+    }
+
+    /// Whether the code may be used at `now`, an RFC 3339 timestamp. Bounds
+    /// saved as typed, before they were stored in UTC, are read as Berlin time.
+    pub fn is_valid_at(&self, now: &str) -> bool {
+        let time = |time: &str| from_input(time).and_then(|time| DateTime::parse_from_rfc3339(&time).ok());
+        let now = time(now);
+        self.active
+            && self.max_uses.is_none_or(|max| self.uses < max)
+            && self.valid_from.as_deref().is_none_or(|from| time(from) <= now)
+            && self.valid_until.as_deref().is_none_or(|until| now <= time(until))
+    }
+
+    /// Splits the discount over the gross totals of the lines it applies to.
+    /// Returns one amount per line, in the same order.
+    pub fn allocate(&self, totals: &[i64]) -> Vec<i64> {
+        match self.kind {
+            DiscountKind::Percent(percent) => totals
+                .iter()
+                .map(|total| (total * percent.min(100) as i64 + 50) / 100)
+                .collect(),
+            DiscountKind::FixedCents(cents) => {
+                let mut left = cents.max(0);
+                totals
+                    .iter()
+                    .map(|total| {
+                        let share = left.min(*total);
+                        left -= share;
+                        share
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Codes are matched case-insensitively.
+pub fn normalize_code(code: &str) -> String {
+    code.trim().to_uppercase()
+}
+
+/// Stored-value voucher that pays for (part of) an order.
+#[derive(Debug, Validate, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct GiftVoucher {
+    pub code: String,
+    pub initial_cents: i64,
+    pub balance_cents: i64,
+    /// Order the voucher was bought with, if it was sold in the shop.
+    pub order: Option<String>,
+    pub created_at: String,
+}
+
+impl GiftVoucher {
+    pub fn new(initial_cents: i64, order: Option<String>, created_at: String) -> GiftVoucher {
+        GiftVoucher {
+            code: GiftVoucher::generate_code(),
+            initial_cents,
+            balance_cents: initial_cents,
+            order,
+            created_at,
+        }
+    }
+
+    pub fn generate_code() -> String {
+        let code: String = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(VOUCHER_CODE_LENGTH)
+            .map(char::from)
+            .collect::<String>()
+            .to_uppercase();
+        format!("GV-{code}")
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+pub enum RedemptionKind {
+    Discount,
+    Voucher,
+}
+
+/// A code used on a paid order.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct Redemption {
+    pub uuid: String,
+    pub code: String,
+    pub kind: RedemptionKind,
+    pub order: String,
+    pub amount_cents: i64,
+    pub created_at: String,
+}
+
+impl Redemption {
+    pub fn new(
+        uuid: String,
+        code: String,
+        kind: RedemptionKind,
+        order: String,
+        amount_cents: i64,
+        created_at: String,
+    ) -> Redemption {
+        Redemption {
+            uuid,
+            code,
+            kind,
+            order,
+            amount_cents,
+            created_at,
+        }
+    }
+}
+
+/// Redemptions of one code, summed up.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct RedemptionReport {
+    pub code: String,
+    pub kind: RedemptionKind,
+    pub redemptions: u32,
+    pub total_cents: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code(kind: DiscountKind) -> DiscountCode {
+        DiscountCode::new(String::from(" summer "), kind, DiscountScope::All)
+    }
+
+    #[test]
+    fn codes_are_matched_case_insensitively() {
+        assert_eq!(code(DiscountKind::Percent(10)).code, "SUMMER");
+        assert_eq!(normalize_code(" Summer\n"), "SUMMER");
+    }
+
+    #[test]
+    fn validity() {
+        let mut discount = code(DiscountKind::Percent(10));
+        discount.valid_from = Some(String::from("2026-10-01T00:00:00.000Z"));
+        discount.valid_until = Some(String::from("2026-10-31T22:59:59.999Z"));
+        assert!(discount.is_valid_at("2026-10-19T08:00:00.000Z"));
+        assert!(!discount.is_valid_at("2026-09-30T23:59:59.999Z"));
+        assert!(!discount.is_valid_at("2026-10-31T23:00:00.000Z"));
+
+        discount.max_uses = Some(2);
+        discount.uses = 2;
+        assert!(!discount.is_valid_at("2026-10-19T08:00:00.000Z"));
+        discount.max_uses = None;
+        discount.active = false;
+        assert!(!discount.is_valid_at("2026-10-19T08:00:00.000Z"));
+    }
+
+    #[test]
+    fn bounds_as_typed_are_berlin_time() {
+        let mut discount = code(DiscountKind::Percent(10));
+        discount.valid_until = Some(String::from("2026-10-19T12:00"));
+        assert!(discount.is_valid_at("2026-10-19T09:59:00.000Z"));
+        assert!(!discount.is_valid_at("2026-10-19T10:01:00.000Z"));
+    }
+
+    #[test]
+    fn percent_is_rounded_per_line() {
+        assert_eq!(code(DiscountKind::Percent(10)).allocate(&[1000, 333]), vec![100, 33]);
+        assert_eq!(code(DiscountKind::Percent(15)).allocate(&[333]), vec![50]);
+        assert_eq!(code(DiscountKind::Percent(150)).allocate(&[1000]), vec![1000]);
+    }
+
+    #[test]
+    fn fixed_amounts_fill_lines_in_order() {
+        assert_eq!(code(DiscountKind::FixedCents(1200)).allocate(&[1000, 500, 300]), vec![1000, 200, 0]);
+        assert_eq!(code(DiscountKind::FixedCents(5000)).allocate(&[1000, 500]), vec![1000, 500]);
+        assert_eq!(code(DiscountKind::FixedCents(-500)).allocate(&[1000]), vec![0]);
+    }
+
+    #[test]
+    fn voucher_codes() {
+        let code = GiftVoucher::generate_code();
+        assert!(code.starts_with("GV-"));
+        assert_eq!(code.len(), 3 + VOUCHER_CODE_LENGTH);
+        assert_eq!(code, code.to_uppercase());
+    }
+}
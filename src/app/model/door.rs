@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+
+use crate::app::model::{Event, Ticket};
+use crate::app::model::ticket::ScanOutcome;
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+pub enum DoorMode {
+    In,
+    Out,
+}
+
+/// Everything a door device needs to keep admitting guests without a connection.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct DoorSnapshot {
+    pub event: Event,
+    pub tickets: Vec<Ticket>,
+    /// SHA-256 of the codes held back for tickets sold after the list was
+    /// taken, so the device can admit them without knowing any secret.
+    #[serde(default)]
+    pub codes: Vec<String>,
+    pub taken_at: String,
+}
+
+/// A scan recorded on a door device while offline, waiting to be synced.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct LocalScan {
+    pub uuid: String,
+    pub event: String,
+    pub code: String,
+    pub device: String,
+    pub mode: DoorMode,
+    pub scanned_at: String,
+    pub outcome: ScanOutcome,
+}
+
+impl LocalScan {
+    pub fn new(
+        uuid: String,
+        event: String,
+        code: String,
+        device: String,
+        mode: DoorMode,
+        scanned_at: String,
+        outcome: ScanOutcome,
+    ) -> LocalScan {
+        LocalScan {
+            uuid,
+            event,
+            code,
+            device,
+            mode,
+            scanned_at,
+            outcome,
+        }
+    }
+
+    /// Order in which the server applies scans, independent of sync order.
+    pub fn order_key(&self) -> (String, String, String) {
+        (self.scanned_at.clone(), self.device.clone(), self.uuid.clone())
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+pub enum ConflictKind {
+    /// Admitted on more than one device; the earliest scan wins.
+    DuplicateEntry,
+    Refunded,
+    Cancelled,
+    Unknown,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct ScanConflict {
+    pub code: String,
+    pub kind: ConflictKind,
+    pub holder_name: Option<String>,
+    pub scan: String,
+    pub device: String,
+    pub scanned_at: String,
+    pub winner_device: Option<String>,
+    pub winner_at: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct SyncReport {
+    pub applied: u32,
+    pub conflicts: Vec<ScanConflict>,
+}
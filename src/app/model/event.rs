@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Reduced German VAT rate for admission to concerts.
+pub const TICKET_VAT_RATE: u32 = 7;
+
+#[derive(Debug, Validate, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct Event {
+    pub uuid: String,
+    #[validate(length(min = 1))]
+    pub slug: String,
+    #[validate(length(min = 1))]
+    pub title: String,
+    pub doors_at: String,
+    pub starts_at: String,
+    /// When the night is over; calendars assume a few hours if unset.
+    #[serde(default)]
+    pub ends_at: Option<String>,
+    pub poster: Option<String>,
+    #[serde(default)]
+    pub cancelled: bool,
+    /// Photos of the night, linked in the mail after it.
+    #[serde(default)]
+    pub gallery_url: Option<String>,
+    /// Feedback survey for the night; `FEEDBACK_SURVEY_URL` if unset.
+    #[serde(default)]
+    pub survey_url: Option<String>,
+}
+
+impl Event {
+    pub fn new(
+        uuid: String,
+        slug: String,
+        title: String,
+        doors_at: String,
+        starts_at: String,
+    ) -> Event {
+        Event {
+            uuid,
+            slug,
+            title,
+            doors_at,
+            starts_at,
+            ends_at: None,
+            poster: None,
+            cancelled: false,
+            gallery_url: None,
+            survey_url: None,
+        }
+    }
+}
+
+/// A sellable kind of admission for an event, e.g. presale or box office.
+#[derive(Debug, Validate, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct TicketType {
+    pub uuid: String,
+    pub event: String,
+    #[validate(length(min = 1))]
+    pub name: String,
+    /// Gross price including VAT.
+    pub price_cents: i64,
+    pub vat_rate: u32,
+    pub capacity: u32,
+    pub sold: u32,
+}
+
+impl TicketType {
+    pub fn new(
+        uuid: String,
+        event: String,
+        name: String,
+        price_cents: i64,
+        capacity: u32,
+    ) -> TicketType {
+        TicketType {
+            uuid,
+            event,
+            name,
+            price_cents,
+            vat_rate: TICKET_VAT_RATE,
+            capacity,
+            sold: 0,
+        }
+    }
+
+    pub fn available(&self) -> u32 {
+        self.capacity.saturating_sub(self.sold)
+    }
+}
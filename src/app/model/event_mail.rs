@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+pub enum EventMailKind {
+    /// The day before doors.
+    Reminder,
+    /// After the night.
+    FollowUp,
+}
+
+impl EventMailKind {
+    pub fn key(self) -> &'static str {
+        match self {
+            EventMailKind::Reminder => "reminder",
+            EventMailKind::FollowUp => "follow-up",
+        }
+    }
+}
+
+/// A mail sent to the attendees of an event. There is one per event and
+/// kind, which keeps it from going out twice.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct EventMail {
+    /// `{kind}:{event}`.
+    pub uuid: String,
+    pub event: String,
+    pub kind: EventMailKind,
+    pub recipients: u32,
+    pub sent_at: String,
+}
+
+impl EventMail {
+    pub fn new(event: String, kind: EventMailKind, sent_at: String) -> EventMail {
+        EventMail {
+            uuid: format!("{}:{}", kind.key(), event),
+            event,
+            kind,
+            recipients: 0,
+            sent_at,
+        }
+    }
+}
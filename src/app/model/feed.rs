@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+pub enum FeedKind {
+    Announcement,
+    Event,
+}
+
+/// Media attached to an entry, e.g. an event poster.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct Enclosure {
+    pub url: String,
+    pub mime_type: String,
+    /// Size in bytes, 0 if unknown.
+    pub length: u64,
+}
+
+/// An entry of the public Atom and RSS feeds. An event has a single entry
+/// that is updated when the event changes, so readers don't see it twice.
+#[derive(Debug, Validate, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct FeedItem {
+    pub uuid: String,
+    pub kind: FeedKind,
+    /// `Event.uuid` for events.
+    pub reference: Option<String>,
+    #[validate(length(min = 1))]
+    pub title: String,
+    pub summary: String,
+    /// Path on the site or absolute URL.
+    pub link: String,
+    pub enclosure: Option<Enclosure>,
+    pub published_at: String,
+    pub updated_at: String,
+}
+
+impl FeedItem {
+    pub fn new(uuid: String, kind: FeedKind, title: String, summary: String, link: String) -> FeedItem {
+        FeedItem {
+            uuid,
+            kind,
+            reference: None,
+            title,
+            summary,
+            link,
+            enclosure: None,
+            published_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Debug, Validate, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct GuestListEntry {
+    pub uuid: String,
+    pub event: String,
+    #[validate(length(min = 1))]
+    pub name: String,
+    /// Companions coming along on the same spot.
+    #[validate(range(max = 20))]
+    pub plus: u32,
+    /// Artist or staff member the guest is on the list for.
+    #[validate(length(min = 1))]
+    pub invited_by: String,
+    pub note: String,
+    pub added_by: String,
+    pub added_at: String,
+    pub checked_in: u32,
+    pub checked_in_at: Option<String>,
+}
+
+impl GuestListEntry {
+    pub fn new(
+        uuid: String,
+        event: String,
+        name: String,
+        plus: u32,
+        invited_by: String,
+        note: String,
+    ) -> GuestListEntry {
+        GuestListEntry {
+            uuid,
+            event,
+            name,
+            plus,
+            invited_by,
+            note,
+            added_by: String::new(),
+            added_at: String::new(),
+            checked_in: 0,
+            checked_in_at: None,
+        }
+    }
+
+    /// Spots this entry takes from the inviter's quota.
+    pub fn spots(&self) -> u32 {
+        self.plus + 1
+    }
+}
+
+#[derive(Debug, Validate, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct GuestQuota {
+    pub event: String,
+    #[validate(length(min = 1))]
+    pub invited_by: String,
+    pub spots: u32,
+}
+
+impl GuestQuota {
+    pub fn new(event: String, invited_by: String, spots: u32) -> GuestQuota {
+        GuestQuota {
+            event,
+            invited_by,
+            spots,
+        }
+    }
+}
+
+/// Spots an inviter has handed out against their quota, if one is set.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct QuotaUsage {
+    pub invited_by: String,
+    pub used: u32,
+    pub spots: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+pub enum GuestAction {
+    Added,
+    Imported,
+    Removed,
+    CheckedIn,
+}
+
+/// Who changed the guest list and when.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct GuestAudit {
+    pub uuid: String,
+    pub event: String,
+    pub entry: String,
+    pub action: GuestAction,
+    pub guest_name: String,
+    pub invited_by: String,
+    pub actor: String,
+    pub at: String,
+}
+
+impl GuestAudit {
+    pub fn new(entry: &GuestListEntry, action: GuestAction, uuid: String, actor: String, at: String) -> GuestAudit {
+        GuestAudit {
+            uuid,
+            event: entry.event.clone(),
+            entry: entry.uuid.clone(),
+            action,
+            guest_name: entry.name.clone(),
+            invited_by: entry.invited_by.clone(),
+            actor,
+            at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct ImportReport {
+    pub imported: u32,
+    pub skipped: Vec<String>,
+}
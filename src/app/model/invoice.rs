@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::app::model::{Address, Order};
+use crate::app::model::order::{vat_breakdown, LineItem, VatAmount};
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+pub enum InvoiceKind {
+    Invoice,
+    /// Reverses an issued invoice; the only way to correct one.
+    Cancellation,
+}
+
+#[derive(Debug, Validate, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct Seller {
+    #[validate(length(min = 1))]
+    pub name: String,
+    pub address: Address,
+    /// USt-IdNr., or the tax number if there is none.
+    pub vat_id: String,
+}
+
+/// An issued invoice. Everything printed on it is copied in at issue time, so
+/// later changes to the order, the buyer or the seller don't alter it.
+#[derive(Debug, Validate, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct Invoice {
+    pub uuid: String,
+    /// Consecutive across invoices and cancellations, assigned by the database.
+    pub number: u64,
+    pub kind: InvoiceKind,
+    pub order: String,
+    pub user: Option<String>,
+    /// Number of the invoice a cancellation reverses.
+    pub cancels: Option<u64>,
+    pub issued_at: String,
+    /// When the events of the tickets take place and the rest was handed
+    /// over, printed as Leistungsdatum. Sorted.
+    #[serde(default)]
+    pub service_dates: Vec<String>,
+    pub seller: Seller,
+    pub buyer_name: String,
+    pub buyer_email: String,
+    pub buyer_address: Option<Address>,
+    pub items: Vec<LineItem>,
+    pub vat: Vec<VatAmount>,
+    pub total_cents: i64,
+}
+
+impl Invoice {
+    /// Invoices what is left of the order after refunds.
+    pub fn for_order(uuid: String, order: &Order, seller: Seller, issued_at: String, service_dates: Vec<String>) -> Invoice {
+        let items = order.billable_items();
+        Invoice {
+            uuid,
+            number: 0,
+            kind: InvoiceKind::Invoice,
+            order: order.uuid.clone(),
+            user: order.user.clone(),
+            cancels: None,
+            issued_at,
+            service_dates,
+            seller,
+            buyer_name: order.buyer_name.clone(),
+            buyer_email: order.email.clone(),
+            buyer_address: order.billing_address.clone(),
+            vat: vat_breakdown(&items),
+            total_cents: items.iter().map(|i| i.total_cents).sum(),
+            items,
+        }
+    }
+
+    /// Cancellation with the same lines and negated amounts.
+    pub fn cancellation(&self, uuid: String, issued_at: String) -> Invoice {
+        Invoice {
+            uuid,
+            number: 0,
+            kind: InvoiceKind::Cancellation,
+            cancels: Some(self.number),
+            issued_at,
+            items: self.items.iter().map(|item| LineItem {
+                unit_price_cents: -item.unit_price_cents,
+                total_cents: -item.total_cents,
+                ..item.clone()
+            }).collect(),
+            vat: self.vat.iter().map(|vat| VatAmount {
+                rate: vat.rate,
+                net_cents: -vat.net_cents,
+                vat_cents: -vat.vat_cents,
+                gross_cents: -vat.gross_cents,
+            }).collect(),
+            total_cents: -self.total_cents,
+            ..self.clone()
+        }
+    }
+
+    pub fn display_number(&self) -> String {
+        display_number(self.number)
+    }
+}
+
+pub fn display_number(number: u64) -> String {
+    format!("RE-{:06}", number)
+}
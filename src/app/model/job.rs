@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+
+/// Work done in the background by `jobs`.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub enum JobKind {
+    /// Passes on waitlist offers nobody claimed in time.
+    ExpireWaitlistOffers,
+    /// Cancels orders that were never paid, so their tickets go back.
+    ExpirePendingOrders,
+    /// Deletes finished jobs after a while.
+    PurgeJobs,
+    /// Reminders and thanks to attendees of events that are due.
+    EventMails,
+}
+
+impl JobKind {
+    /// Catalogue key of the name in the admin view.
+    pub fn label_key(&self) -> &'static str {
+        match self {
+            JobKind::ExpireWaitlistOffers => "job-expire-offers",
+            JobKind::ExpirePendingOrders => "job-expire-orders",
+            JobKind::PurgeJobs => "job-purge",
+            JobKind::EventMails => "job-event-mails",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+pub enum JobStatus {
+    /// Waiting for `run_at`, also between retries.
+    Queued,
+    Running,
+    Done,
+    /// Failed `max_attempts` times; stays until retried by hand.
+    Dead,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct Job {
+    pub uuid: String,
+    pub kind: JobKind,
+    /// At most one queued or running job per key; jobs with a key that is
+    /// taken are not added.
+    pub key: Option<String>,
+    pub status: JobStatus,
+    pub attempts: u32,
+    pub max_attempts: u32,
+    pub run_at: String,
+    pub created_at: String,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+    pub last_error: Option<String>,
+}
+
+impl Job {
+    pub fn new(uuid: String, kind: JobKind, key: Option<String>, run_at: String, created_at: String) -> Job {
+        Job {
+            uuid,
+            kind,
+            key,
+            status: JobStatus::Queued,
+            attempts: 0,
+            max_attempts: 5,
+            run_at,
+            created_at,
+            started_at: None,
+            finished_at: None,
+            last_error: None,
+        }
+    }
+}
+
+/// A job that comes back on a cron schedule, see `jobs::RECURRING`.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct RecurringJob {
+    pub name: String,
+    /// Cron expression in Berlin time.
+    pub schedule: String,
+    pub kind: JobKind,
+    pub next_run: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct JobOverview {
+    pub recurring: Vec<RecurringJob>,
+    /// Latest first.
+    pub jobs: Vec<Job>,
+}
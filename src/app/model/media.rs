@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+/// An uploaded file. Stored under its content hash, so uploading the same
+/// file twice gives the same record.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct MediaFile {
+    pub uuid: String,
+    /// Name in the storage backend: the SHA-256 of the content plus the
+    /// extension of its type.
+    pub key: String,
+    /// Hex SHA-256 of the stored content, after metadata was stripped.
+    pub hash: String,
+    pub mime_type: String,
+    pub size: u64,
+    /// Name of the file on the uploader's computer.
+    pub original_name: String,
+    pub width: u32,
+    pub height: u32,
+    pub created_at: String,
+}
+
+impl MediaFile {
+    /// Address the file is served at, for posters, product images and posts.
+    pub fn url(&self) -> String {
+        format!("/media/{}", self.key)
+    }
+}
+
+/// A file with the events, products and posts using it.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct MediaUsage {
+    pub file: MediaFile,
+    /// Human readable, e.g. "Event: Prisma".
+    pub references: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct MediaLibrary {
+    pub files: Vec<MediaUsage>,
+    /// Keys in the storage backend without a record, left behind by
+    /// interrupted uploads.
+    pub stray: Vec<String>,
+}
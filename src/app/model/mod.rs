@@ -1,5 +1,35 @@
 pub mod user;
 pub mod address;
+pub mod event;
+pub mod ticket;
+pub mod door;
+pub mod guest;
+pub mod cart;
+pub mod order;
+pub mod product;
+pub mod invoice;
+pub mod refund;
+pub mod discount;
+pub mod shipping;
+pub mod newsletter;
+pub mod feed;
+pub mod post;
+pub mod media;
+pub mod artist;
+pub mod venue;
+pub mod waitlist;
+pub mod job;
+pub mod event_mail;
 
 pub use user::User;
-pub use address::Address;
\ No newline at end of file
+pub use address::Address;
+pub use event::Event;
+pub use ticket::Ticket;
+pub use guest::GuestListEntry;
+pub use cart::Cart;
+pub use order::Order;
+pub use invoice::Invoice;
+pub use refund::Refund;
+pub use product::Product;
+pub use post::Post;
+pub use artist::Artist;
\ No newline at end of file
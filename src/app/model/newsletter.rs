@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::app::i18n::Locale;
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+pub enum SubscriberStatus {
+    /// Signed up, confirmation link not clicked yet.
+    Pending,
+    Confirmed,
+    Unsubscribed,
+}
+
+/// A newsletter recipient. Kept after unsubscribing, as proof of what was
+/// consented to and when.
+#[derive(Debug, Validate, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct Subscriber {
+    pub uuid: String,
+    #[validate(email)]
+    pub email: String,
+    pub status: SubscriberStatus,
+    /// `User.uuid`, if they were logged in when signing up.
+    pub user: Option<String>,
+    /// Where the signup came from, e.g. the footer form.
+    pub source: String,
+    pub requested_at: String,
+    /// When the confirmation link was clicked.
+    pub consent_at: Option<String>,
+    pub unsubscribed_at: Option<String>,
+    /// Language of the signup, for the mails.
+    #[serde(default)]
+    pub locale: Locale,
+}
+
+impl Subscriber {
+    pub fn new(uuid: String, email: String, source: String, requested_at: String) -> Subscriber {
+        Subscriber {
+            uuid,
+            email: normalize_email(&email),
+            status: SubscriberStatus::Pending,
+            user: None,
+            source,
+            requested_at,
+            consent_at: None,
+            unsubscribed_at: None,
+            locale: Locale::default(),
+        }
+    }
+}
+
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+#[derive(Debug, Validate, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct SubscribeRequest {
+    #[validate(email)]
+    pub email: String,
+    pub source: String,
+}
+
+impl SubscribeRequest {
+    pub fn new(email: String, source: String) -> SubscribeRequest {
+        SubscribeRequest { email, source }
+    }
+}
+
+/// A newsletter sent to all confirmed subscribers.
+#[derive(Debug, Validate, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct Campaign {
+    pub uuid: String,
+    #[validate(length(min = 1))]
+    pub subject: String,
+    #[validate(length(min = 1))]
+    pub body: String,
+    pub created_by: String,
+    pub created_at: String,
+    pub recipients: u32,
+}
+
+impl Campaign {
+    pub fn new(uuid: String, subject: String, body: String) -> Campaign {
+        Campaign {
+            uuid,
+            subject,
+            body,
+            created_by: String::new(),
+            created_at: String::new(),
+            recipients: 0,
+        }
+    }
+}
+
+/// Delivery progress of a campaign.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct CampaignStats {
+    pub campaign: Campaign,
+    pub sent: u32,
+    pub failed: u32,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct SubscriberCounts {
+    pub pending: u32,
+    pub confirmed: u32,
+    pub unsubscribed: u32,
+}
@@ -0,0 +1,358 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::app::i18n::Locale;
+use crate::app::model::Address;
+use crate::app::model::cart::ItemKind;
+use crate::app::model::shipping::Delivery;
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+pub enum OrderStatus {
+    Pending,
+    Paid,
+    Fulfilled,
+    Refunded,
+    Cancelled,
+}
+
+impl OrderStatus {
+    pub const ALL: [OrderStatus; 5] = [
+        OrderStatus::Pending,
+        OrderStatus::Paid,
+        OrderStatus::Fulfilled,
+        OrderStatus::Refunded,
+        OrderStatus::Cancelled,
+    ];
+
+    pub fn can_become(self, next: OrderStatus) -> bool {
+        use OrderStatus::*;
+        matches!(
+            (self, next),
+            (Pending, Paid)
+                | (Pending, Cancelled)
+                | (Paid, Fulfilled)
+                | (Paid, Refunded)
+                | (Fulfilled, Refunded)
+        )
+    }
+
+    /// States an order may be in to move to `next`.
+    pub fn sources(next: OrderStatus) -> Vec<OrderStatus> {
+        OrderStatus::ALL.into_iter().filter(|s| s.can_become(next)).collect()
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct LineItem {
+    pub kind: ItemKind,
+    pub reference: String,
+    pub name: String,
+    pub quantity: u32,
+    /// Gross unit price including VAT.
+    pub unit_price_cents: i64,
+    pub vat_rate: u32,
+    /// Gross total after discount.
+    pub total_cents: i64,
+    #[serde(default)]
+    pub discount_cents: i64,
+    /// How many of `quantity` were refunded since.
+    #[serde(default)]
+    pub refunded_quantity: u32,
+}
+
+impl LineItem {
+    pub fn new(
+        kind: ItemKind,
+        reference: String,
+        name: String,
+        quantity: u32,
+        unit_price_cents: i64,
+        vat_rate: u32,
+    ) -> LineItem {
+        LineItem {
+            kind,
+            reference,
+            name,
+            quantity,
+            unit_price_cents,
+            vat_rate,
+            total_cents: unit_price_cents * quantity as i64,
+            discount_cents: 0,
+            refunded_quantity: 0,
+        }
+    }
+
+    pub fn apply_discount(&mut self, discount_cents: i64) {
+        let discount_cents = discount_cents.clamp(0, self.total_cents);
+        self.discount_cents += discount_cents;
+        self.total_cents -= discount_cents;
+    }
+
+    /// Share of the total for `quantity` units. Shares of all units add up
+    /// to the total exactly, so a discount is paid back in full on refund.
+    fn total_for(&self, quantity: u32) -> i64 {
+        if self.quantity == 0 {
+            return 0;
+        }
+        self.total_cents * quantity as i64 / self.quantity as i64
+    }
+
+    pub fn refundable_quantity(&self) -> u32 {
+        self.quantity.saturating_sub(self.refunded_quantity)
+    }
+}
+
+/// VAT contained in the gross amounts of one rate.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct VatAmount {
+    pub rate: u32,
+    pub net_cents: i64,
+    pub vat_cents: i64,
+    pub gross_cents: i64,
+}
+
+impl VatAmount {
+    pub fn from_gross(rate: u32, gross_cents: i64) -> VatAmount {
+        let vat_cents = vat_from_gross(gross_cents, rate);
+        VatAmount {
+            rate,
+            net_cents: gross_cents - vat_cents,
+            vat_cents,
+            gross_cents,
+        }
+    }
+}
+
+/// VAT share of a gross amount, rounded half away from zero.
+pub fn vat_from_gross(gross_cents: i64, rate: u32) -> i64 {
+    let rate = rate as i64;
+    let divisor = 100 + rate;
+    let doubled = gross_cents * rate * 2;
+    (doubled + doubled.signum() * divisor) / (2 * divisor)
+}
+
+/// Groups line totals by rate, computing VAT once per rate.
+pub fn vat_breakdown(items: &[LineItem]) -> Vec<VatAmount> {
+    let mut rates: Vec<u32> = items.iter().map(|i| i.vat_rate).collect();
+    rates.sort_unstable();
+    rates.dedup();
+    rates
+        .into_iter()
+        .map(|rate| {
+            let gross = items.iter().filter(|i| i.vat_rate == rate).map(|i| i.total_cents).sum();
+            VatAmount::from_gross(rate, gross)
+        })
+        .collect()
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct PaymentRef {
+    pub provider: String,
+    pub payment_id: String,
+}
+
+#[derive(Debug, Validate, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct Order {
+    pub uuid: String,
+    /// `User.uuid` of the buyer, if they were logged in.
+    pub user: Option<String>,
+    #[validate(email)]
+    pub email: String,
+    /// Name and address printed on the invoice.
+    #[serde(default)]
+    pub buyer_name: String,
+    #[serde(default)]
+    pub billing_address: Option<Address>,
+    pub status: OrderStatus,
+    pub items: Vec<LineItem>,
+    pub vat: Vec<VatAmount>,
+    pub total_cents: i64,
+    #[serde(default)]
+    pub refunded_cents: i64,
+    #[serde(default)]
+    pub discount_code: Option<String>,
+    /// Gift voucher paying `voucher_cents` of the total.
+    #[serde(default)]
+    pub voucher_code: Option<String>,
+    #[serde(default)]
+    pub voucher_cents: i64,
+    /// Set for orders with merch.
+    #[serde(default)]
+    pub delivery: Option<Delivery>,
+    pub payment: Option<PaymentRef>,
+    /// Language of the buyer, for mails about the order.
+    #[serde(default)]
+    pub locale: Locale,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl Order {
+    pub fn new(
+        uuid: String,
+        user: Option<String>,
+        email: String,
+        items: Vec<LineItem>,
+        created_at: String,
+    ) -> Order {
+        let vat = vat_breakdown(&items);
+        let total_cents = items.iter().map(|i| i.total_cents).sum();
+        Order {
+            uuid,
+            user,
+            email,
+            buyer_name: String::new(),
+            billing_address: None,
+            status: OrderStatus::Pending,
+            items,
+            vat,
+            total_cents,
+            refunded_cents: 0,
+            discount_code: None,
+            voucher_code: None,
+            voucher_cents: 0,
+            delivery: None,
+            payment: None,
+            locale: Locale::default(),
+            updated_at: created_at.clone(),
+            created_at,
+        }
+    }
+
+    pub fn has_merch(&self) -> bool {
+        self.items.iter().any(|i| i.kind == ItemKind::Merch)
+    }
+
+    /// The lines minus everything refunded, as they are invoiced.
+    pub fn billable_items(&self) -> Vec<LineItem> {
+        self.items
+            .iter()
+            .filter(|i| i.refundable_quantity() > 0)
+            .map(|i| {
+                let quantity = i.refundable_quantity();
+                let total_cents = i.total_for(quantity);
+                LineItem {
+                    quantity,
+                    total_cents,
+                    discount_cents: i.unit_price_cents * quantity as i64 - total_cents,
+                    refunded_quantity: 0,
+                    ..i.clone()
+                }
+            })
+            .collect()
+    }
+
+    /// Left to pay through the payment provider after the voucher.
+    pub fn amount_due(&self) -> i64 {
+        self.total_cents - self.voucher_cents
+    }
+
+    /// Marks `quantity` more of the line for `reference` as refunded and
+    /// returns the amount, or `None` if not that many are left.
+    pub fn refund_line(&mut self, reference: &str, quantity: u32) -> Option<i64> {
+        let item = self.items.iter_mut().find(|i| i.reference == reference)?;
+        if quantity == 0 || item.refundable_quantity() < quantity {
+            return None;
+        }
+        let amount = item.total_for(item.refundable_quantity()) - item.total_for(item.refundable_quantity() - quantity);
+        item.refunded_quantity += quantity;
+        self.refunded_cents += amount;
+        Some(amount)
+    }
+
+    pub fn fully_refunded(&self) -> bool {
+        self.items.iter().all(|i| i.refundable_quantity() == 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ticket(reference: &str, quantity: u32, unit_price_cents: i64) -> LineItem {
+        LineItem::new(ItemKind::Ticket, reference.to_string(), String::from("Konzert"), quantity, unit_price_cents, 7)
+    }
+
+    fn order(items: Vec<LineItem>) -> Order {
+        Order::new(String::from("o1"), None, String::from("guest@example.org"), items, String::from("2026-10-19T12:00:00.000Z"))
+    }
+
+    #[test]
+    fn status_transitions() {
+        use OrderStatus::*;
+        assert!(Pending.can_become(Paid));
+        assert!(Pending.can_become(Cancelled));
+        assert!(Paid.can_become(Fulfilled));
+        assert!(Fulfilled.can_become(Refunded));
+        assert!(!Paid.can_become(Pending));
+        assert!(!Cancelled.can_become(Paid));
+        assert!(!Pending.can_become(Refunded));
+        assert!(OrderStatus::ALL.into_iter().all(|next| !Refunded.can_become(next)));
+        assert_eq!(OrderStatus::sources(Refunded), vec![Paid, Fulfilled]);
+        assert!(OrderStatus::sources(Pending).is_empty());
+    }
+
+    #[test]
+    fn refunds_add_up_to_the_discounted_total() {
+        let mut item = ticket("t1", 3, 1000);
+        item.apply_discount(100);
+        let mut order = order(vec![item]);
+        assert_eq!(order.total_cents, 2900);
+
+        assert_eq!(order.refund_line("t1", 1), Some(967));
+        let billable = order.billable_items();
+        assert_eq!(billable[0].quantity, 2);
+        assert_eq!(billable[0].total_cents, 1933);
+        assert_eq!(billable[0].discount_cents, 67);
+
+        assert_eq!(order.refund_line("t1", 2), Some(1933));
+        assert_eq!(order.refunded_cents, order.total_cents);
+        assert!(order.fully_refunded());
+        assert!(order.billable_items().is_empty());
+    }
+
+    #[test]
+    fn refunds_beyond_the_line_are_refused() {
+        let mut order = order(vec![ticket("t1", 2, 1500)]);
+        assert_eq!(order.refund_line("t1", 0), None);
+        assert_eq!(order.refund_line("t1", 3), None);
+        assert_eq!(order.refund_line("t2", 1), None);
+        assert_eq!(order.refund_line("t1", 2), Some(3000));
+        assert_eq!(order.refund_line("t1", 1), None);
+        assert_eq!(order.refunded_cents, 3000);
+    }
+
+    #[test]
+    fn vat_is_rounded_half_away_from_zero() {
+        assert_eq!(vat_from_gross(1190, 19), 190);
+        assert_eq!(vat_from_gross(1000, 19), 160);
+        assert_eq!(vat_from_gross(-1000, 19), -160);
+        assert_eq!(vat_from_gross(1000, 7), 65);
+        assert_eq!(vat_from_gross(1000, 0), 0);
+        assert_eq!(vat_from_gross(0, 19), 0);
+    }
+
+    #[test]
+    fn vat_is_grouped_by_rate() {
+        let shirt = LineItem::new(ItemKind::Merch, String::from("m1"), String::from("Shirt"), 1, 1190, 19);
+        let poster = LineItem::new(ItemKind::Merch, String::from("m2"), String::from("Poster"), 1, 1000, 19);
+        let vat = vat_breakdown(&[shirt, ticket("t1", 1, 1070), poster]);
+        assert_eq!(
+            vat,
+            vec![
+                VatAmount { rate: 7, net_cents: 1000, vat_cents: 70, gross_cents: 1070 },
+                VatAmount { rate: 19, net_cents: 1840, vat_cents: 350, gross_cents: 2190 },
+            ]
+        );
+    }
+
+    #[test]
+    fn discounts_stop_at_the_line_total() {
+        let mut item = ticket("t1", 1, 500);
+        item.apply_discount(800);
+        assert_eq!(item.total_cents, 0);
+        assert_eq!(item.discount_cents, 500);
+        item.apply_discount(-100);
+        assert_eq!(item.discount_cents, 500);
+    }
+}
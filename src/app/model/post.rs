@@ -0,0 +1,53 @@
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// A news post: lineup reveals, house rules, closures.
+#[derive(Debug, Validate, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct Post {
+    pub uuid: String,
+    #[validate(length(min = 1))]
+    pub slug: String,
+    #[validate(length(min = 1))]
+    pub title: String,
+    /// Markdown.
+    pub body: String,
+    /// Image shown above the post and in the overview.
+    pub cover: Option<String>,
+    /// `User.uuid`.
+    pub author: String,
+    /// Visible from then on; `None` keeps the post a draft.
+    pub published_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl Post {
+    pub fn new(uuid: String, slug: String, title: String, body: String) -> Post {
+        Post {
+            uuid,
+            slug,
+            title,
+            body,
+            cover: None,
+            author: String::new(),
+            published_at: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    pub fn is_published(&self, now: &str) -> bool {
+        let time = |time: &str| DateTime::parse_from_rfc3339(time).ok();
+        self.published_at.as_deref().and_then(time).is_some_and(|published_at| Some(published_at) <= time(now))
+    }
+}
+
+/// A post as readers get it, rendered and with the author's name.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct PostView {
+    pub post: Post,
+    pub author_name: String,
+    /// Sanitised HTML of `post.body`.
+    pub html: String,
+}
@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Standard German VAT rate, applies to merchandise.
+pub const MERCH_VAT_RATE: u32 = 19;
+
+/// Stock at or below which admins are told to reorder, unless a variant says otherwise.
+pub const LOW_STOCK_THRESHOLD: u32 = 5;
+
+/// Something sold in the shop, e.g. a shirt or a record.
+#[derive(Debug, Validate, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct Product {
+    pub uuid: String,
+    #[validate(length(min = 1))]
+    pub slug: String,
+    #[validate(length(min = 1))]
+    pub name: String,
+    pub description: String,
+    /// Image paths, the first one is used in listings.
+    pub images: Vec<String>,
+    pub vat_rate: u32,
+    /// Hidden from the shop when false.
+    pub active: bool,
+}
+
+impl Product {
+    pub fn new(uuid: String, slug: String, name: String) -> Product {
+        Product {
+            uuid,
+            slug,
+            name,
+            description: String::new(),
+            images: Vec::new(),
+            vat_rate: MERCH_VAT_RATE,
+            active: true,
+        }
+    }
+}
+
+/// One size and colour of a product, with its own SKU and stock.
+#[derive(Debug, Validate, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct ProductVariant {
+    #[validate(length(min = 1))]
+    pub sku: String,
+    /// `Product.uuid`
+    pub product: String,
+    pub size: Option<String>,
+    pub colour: Option<String>,
+    /// Gross price including VAT.
+    pub price_cents: i64,
+    pub stock: u32,
+    pub low_stock_threshold: u32,
+    /// Shipping weight of one piece.
+    #[serde(default)]
+    pub weight_grams: u32,
+}
+
+impl ProductVariant {
+    pub fn new(sku: String, product: String, price_cents: i64, stock: u32) -> ProductVariant {
+        ProductVariant {
+            sku,
+            product,
+            size: None,
+            colour: None,
+            price_cents,
+            stock,
+            low_stock_threshold: LOW_STOCK_THRESHOLD,
+            weight_grams: 0,
+        }
+    }
+
+    /// "M, Schwarz", or empty for products without variants.
+    pub fn label(&self) -> String {
+        [self.size.as_deref(), self.colour.as_deref()]
+            .into_iter()
+            .flatten()
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    pub fn is_low(&self) -> bool {
+        self.stock <= self.low_stock_threshold
+    }
+}
+
+/// A product with all its variants.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct ProductDetails {
+    pub product: Product,
+    pub variants: Vec<ProductVariant>,
+}
+
+impl ProductDetails {
+    /// Name of a variant as it appears in the cart and on the invoice.
+    pub fn item_name(&self, variant: &ProductVariant) -> String {
+        match variant.label() {
+            label if label.is_empty() => self.product.name.clone(),
+            label => format!("{} ({})", self.product.name, label),
+        }
+    }
+
+    pub fn lowest_price_cents(&self) -> Option<i64> {
+        self.variants.iter().map(|v| v.price_cents).min()
+    }
+
+    pub fn in_stock(&self) -> bool {
+        self.variants.iter().any(|v| v.stock > 0)
+    }
+}
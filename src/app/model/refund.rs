@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+pub enum RefundStatus {
+    Succeeded,
+    /// The provider refused; the tickets stay invalid and staff has to pay back by hand.
+    Failed,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+pub enum RefundReason {
+    EventCancelled,
+    Requested,
+}
+
+/// Money paid back for part or all of an order.
+#[derive(Debug, Validate, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct Refund {
+    pub uuid: String,
+    pub event: String,
+    pub order: String,
+    /// Tickets invalidated by this refund.
+    pub tickets: Vec<String>,
+    pub amount_cents: i64,
+    /// Part of `amount_cents` credited back to the gift voucher the order was paid with.
+    #[serde(default)]
+    pub voucher_cents: i64,
+    pub reason: RefundReason,
+    pub status: RefundStatus,
+    /// Id of the refund at the payment provider.
+    pub provider_refund: Option<String>,
+    /// `User.uuid` of whoever triggered it.
+    pub created_by: String,
+    pub created_at: String,
+}
+
+impl Refund {
+    pub fn new(
+        uuid: String,
+        event: String,
+        order: String,
+        tickets: Vec<String>,
+        amount_cents: i64,
+        reason: RefundReason,
+        created_by: String,
+        created_at: String,
+    ) -> Refund {
+        Refund {
+            uuid,
+            event,
+            order,
+            tickets,
+            amount_cents,
+            voucher_cents: 0,
+            reason,
+            status: RefundStatus::Failed,
+            provider_refund: None,
+            created_by,
+            created_at,
+        }
+    }
+}
+
+/// Outcome of cancelling an event.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct CancellationReport {
+    pub orders: u32,
+    pub refunded_cents: i64,
+    pub failed: Vec<String>,
+    pub mailed: u32,
+}
@@ -0,0 +1,269 @@
+use serde::{Deserialize, Serialize};
+
+use crate::app::i18n::Locale;
+use crate::app::model::Address;
+
+/// Country the venue is in; pickup and domestic rates refer to it.
+pub const HOME_COUNTRY: &str = "DE";
+
+/// Packaging added to the weight of the items.
+pub const PACKAGING_GRAMS: u32 = 150;
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+pub enum ShippingZone {
+    Domestic,
+    Eu,
+    Europe,
+    World,
+}
+
+/// How a postal code is written in a country.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum ZipFormat {
+    /// Exactly this many digits.
+    Digits(usize),
+    /// `1234 AB`, as in the Netherlands.
+    DigitsLetters(usize, usize),
+    /// `12-345`, as in Poland.
+    Split(usize, usize, char),
+    /// Letters, digits and spaces of the given length range.
+    Alphanumeric(usize, usize),
+    /// Five digits with an optional `-1234`.
+    Zip,
+}
+
+impl ZipFormat {
+    fn matches(self, zipcode: &str) -> bool {
+        let digits = |s: &str, n: usize| s.len() == n && s.chars().all(|c| c.is_ascii_digit());
+        match self {
+            ZipFormat::Digits(n) => digits(zipcode, n),
+            ZipFormat::DigitsLetters(n, m) => {
+                let compact: String = zipcode.chars().filter(|c| *c != ' ').collect();
+                compact.is_ascii()
+                    && compact.len() == n + m
+                    && digits(&compact[..n], n)
+                    && compact[n..].chars().all(|c| c.is_ascii_alphabetic())
+            }
+            ZipFormat::Split(n, m, separator) => match zipcode.split_once(separator) {
+                Some((first, second)) => digits(first, n) && digits(second, m),
+                None => false,
+            },
+            ZipFormat::Alphanumeric(min, max) => {
+                (min..=max).contains(&zipcode.len())
+                    && zipcode.chars().all(|c| c.is_ascii_alphanumeric() || c == ' ')
+            }
+            ZipFormat::Zip => match zipcode.split_once('-') {
+                Some((zip, plus4)) => digits(zip, 5) && digits(plus4, 4),
+                None => digits(zipcode, 5),
+            },
+        }
+    }
+}
+
+pub struct Country {
+    /// ISO 3166-1 alpha-2, as stored in `Address.country`.
+    pub code: &'static str,
+    pub name: &'static str,
+    pub zone: ShippingZone,
+    zip: ZipFormat,
+    /// Whether addresses need `Address.state`.
+    needs_state: bool,
+}
+
+const fn country(code: &'static str, name: &'static str, zone: ShippingZone, zip: ZipFormat) -> Country {
+    Country { code, name, zone, zip, needs_state: false }
+}
+
+/// Countries we ship to.
+pub static COUNTRIES: [Country; 14] = [
+    country("DE", "Deutschland", ShippingZone::Domestic, ZipFormat::Digits(5)),
+    country("AT", "Österreich", ShippingZone::Eu, ZipFormat::Digits(4)),
+    country("BE", "Belgien", ShippingZone::Eu, ZipFormat::Digits(4)),
+    country("CZ", "Tschechien", ShippingZone::Eu, ZipFormat::Split(3, 2, ' ')),
+    country("DK", "Dänemark", ShippingZone::Eu, ZipFormat::Digits(4)),
+    country("ES", "Spanien", ShippingZone::Eu, ZipFormat::Digits(5)),
+    country("FR", "Frankreich", ShippingZone::Eu, ZipFormat::Digits(5)),
+    country("IT", "Italien", ShippingZone::Eu, ZipFormat::Digits(5)),
+    country("LU", "Luxemburg", ShippingZone::Eu, ZipFormat::Digits(4)),
+    country("NL", "Niederlande", ShippingZone::Eu, ZipFormat::DigitsLetters(4, 2)),
+    country("PL", "Polen", ShippingZone::Eu, ZipFormat::Split(2, 3, '-')),
+    country("CH", "Schweiz", ShippingZone::Europe, ZipFormat::Digits(4)),
+    country("GB", "Vereinigtes Königreich", ShippingZone::Europe, ZipFormat::Alphanumeric(5, 8)),
+    Country {
+        code: "US",
+        name: "USA",
+        zone: ShippingZone::World,
+        zip: ZipFormat::Zip,
+        needs_state: true,
+    },
+];
+
+pub fn find_country(code: &str) -> Option<&'static Country> {
+    COUNTRIES.iter().find(|c| c.code.eq_ignore_ascii_case(code.trim()))
+}
+
+/// Name for addresses; codes we don't know are shown as entered.
+pub fn country_name(code: &str) -> &str {
+    find_country(code).map(|c| c.name).unwrap_or(code)
+}
+
+/// Rates per zone as `(up to grams, gross cents)`, lightest first.
+const RATES: [(ShippingZone, &[(u32, i64)]); 4] = [
+    (ShippingZone::Domestic, &[(1000, 495), (2000, 595), (5000, 695), (10000, 995), (31500, 1695)]),
+    (ShippingZone::Eu, &[(1000, 1095), (2000, 1395), (5000, 1895), (10000, 2795)]),
+    (ShippingZone::Europe, &[(1000, 1495), (2000, 1895), (5000, 2895), (10000, 3995)]),
+    (ShippingZone::World, &[(1000, 1995), (2000, 2895), (5000, 4495)]),
+];
+
+/// Cost of a parcel of `weight_grams` into `zone`, or `None` if it is too heavy.
+pub fn shipping_rate(zone: ShippingZone, weight_grams: u32) -> Option<i64> {
+    RATES
+        .iter()
+        .find(|(z, _)| *z == zone)
+        .and_then(|(_, brackets)| brackets.iter().find(|(max, _)| weight_grams <= *max))
+        .map(|(_, cents)| *cents)
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AddressError {
+    /// Holds the key of the field's label.
+    Missing(&'static str),
+    UnknownCountry,
+    Zipcode,
+}
+
+impl AddressError {
+    pub fn message(self, locale: Locale) -> String {
+        match self {
+            AddressError::Missing(field) => locale.format("error-address-missing", &[("field", locale.text(field))]),
+            AddressError::UnknownCountry => locale.text("error-address-country").to_string(),
+            AddressError::Zipcode => locale.text("error-address-zipcode").to_string(),
+        }
+    }
+}
+
+/// Checks an address against the rules of its country.
+pub fn validate_address(address: &Address) -> Result<&'static Country, AddressError> {
+    let country = find_country(&address.country).ok_or(AddressError::UnknownCountry)?;
+    if address.line1.trim().is_empty() {
+        return Err(AddressError::Missing("cart-address-line1"));
+    }
+    if address.city.trim().is_empty() {
+        return Err(AddressError::Missing("city"));
+    }
+    if country.needs_state && address.state.trim().is_empty() {
+        return Err(AddressError::Missing("cart-state"));
+    }
+    if !country.zip.matches(address.zipcode.trim()) {
+        return Err(AddressError::Zipcode);
+    }
+    Ok(country)
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+pub enum DeliveryMethod {
+    Shipping,
+    /// Collected at the bar.
+    Pickup,
+}
+
+/// How the merch of an order gets to the buyer.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct Delivery {
+    pub method: DeliveryMethod,
+    /// Where to ship to, `None` for pickup.
+    pub address: Option<Address>,
+    pub weight_grams: u32,
+    pub cost_cents: i64,
+}
+
+impl Delivery {
+    pub fn pickup() -> Delivery {
+        Delivery {
+            method: DeliveryMethod::Pickup,
+            address: None,
+            weight_grams: 0,
+            cost_cents: 0,
+        }
+    }
+
+    pub fn shipping(address: Address, weight_grams: u32, cost_cents: i64) -> Delivery {
+        Delivery {
+            method: DeliveryMethod::Shipping,
+            address: Some(address),
+            weight_grams,
+            cost_cents,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address(country: &str, zipcode: &str) -> Address {
+        Address::new(
+            country.to_string(),
+            String::from("Hauptstraße 1"),
+            None,
+            String::from("Ort"),
+            String::new(),
+            zipcode.to_string(),
+        )
+    }
+
+    #[test]
+    fn postal_codes() {
+        assert!(validate_address(&address("DE", "10115")).is_ok());
+        assert!(validate_address(&address("de", " 10115 ")).is_ok());
+        assert!(validate_address(&address("NL", "1234 ab")).is_ok());
+        assert!(validate_address(&address("NL", "1234AB")).is_ok());
+        assert!(validate_address(&address("PL", "00-950")).is_ok());
+        assert!(validate_address(&address("CZ", "110 00")).is_ok());
+        assert!(validate_address(&address("GB", "SW1A 1AA")).is_ok());
+        assert_eq!(validate_address(&address("DE", "1011")).err(), Some(AddressError::Zipcode));
+        assert_eq!(validate_address(&address("AT", "1O10")).err(), Some(AddressError::Zipcode));
+        assert_eq!(validate_address(&address("NL", "12345A")).err(), Some(AddressError::Zipcode));
+        assert_eq!(validate_address(&address("PL", "00950")).err(), Some(AddressError::Zipcode));
+        assert_eq!(validate_address(&address("GB", "SW1A-1AA")).err(), Some(AddressError::Zipcode));
+    }
+
+    #[test]
+    fn address_errors() {
+        assert_eq!(validate_address(&address("XX", "10115")).err(), Some(AddressError::UnknownCountry));
+        let mut missing = address("DE", "10115");
+        missing.line1 = String::from(" ");
+        assert_eq!(validate_address(&missing).err(), Some(AddressError::Missing("cart-address-line1")));
+        missing.line1 = String::from("Hauptstraße 1");
+        missing.city = String::new();
+        assert_eq!(validate_address(&missing).err(), Some(AddressError::Missing("city")));
+
+        let mut us = address("US", "10001-1234");
+        assert_eq!(validate_address(&us).err(), Some(AddressError::Missing("cart-state")));
+        us.state = String::from("NY");
+        assert_eq!(validate_address(&us).map(|c| c.zone), Ok(ShippingZone::World));
+    }
+
+    #[test]
+    fn address_error_messages() {
+        assert_eq!(AddressError::Missing("city").message(Locale::De), "Ort fehlt");
+        assert_eq!(AddressError::Missing("city").message(Locale::En), "City is missing");
+        assert_eq!(AddressError::Zipcode.message(Locale::En), "Postcode does not match the country");
+    }
+
+    #[test]
+    fn rates_by_weight() {
+        assert_eq!(shipping_rate(ShippingZone::Domestic, 1000), Some(495));
+        assert_eq!(shipping_rate(ShippingZone::Domestic, 1001), Some(595));
+        assert_eq!(shipping_rate(ShippingZone::Eu, PACKAGING_GRAMS), Some(1095));
+        assert_eq!(shipping_rate(ShippingZone::World, 5000), Some(4495));
+        assert_eq!(shipping_rate(ShippingZone::World, 5001), None);
+        assert_eq!(shipping_rate(ShippingZone::Domestic, 31501), None);
+    }
+
+    #[test]
+    fn country_names() {
+        assert_eq!(country_name("at"), "Österreich");
+        assert_eq!(country_name("Atlantis"), "Atlantis");
+    }
+}
@@ -0,0 +1,177 @@
+use rand::{distributions::Alphanumeric, Rng};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+const CODE_LENGTH: usize = 12;
+
+/// Whether an admission was sold as a ticket with a scannable code or put on
+/// the list by name only.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+pub enum TicketKind {
+    Ticket,
+    Name,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+pub enum TicketStatus {
+    Valid,
+    CheckedIn,
+    /// Left the venue with a re-entry stamp.
+    Out,
+    Refunded,
+    Cancelled,
+}
+
+#[derive(Debug, Validate, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct Ticket {
+    pub uuid: String,
+    pub event: String,
+    pub code: String,
+    #[validate(length(min = 1))]
+    pub holder_name: String,
+    pub kind: TicketKind,
+    pub status: TicketStatus,
+    pub checked_in_at: Option<String>,
+    /// Door device that admitted the ticket first.
+    #[serde(default)]
+    pub checked_in_by: Option<String>,
+    pub reentries: u32,
+    /// `User.uuid` of the buyer, for tickets sold through the shop.
+    #[serde(default)]
+    pub owner: Option<String>,
+    #[serde(default)]
+    pub order: Option<String>,
+    #[serde(default)]
+    pub ticket_type: Option<String>,
+    /// Where the ticket is sent. Only `owner` puts it into a wallet, as
+    /// addresses of accounts are not verified.
+    #[serde(default)]
+    pub holder_email: Option<String>,
+    /// The ticket that replaced this one when it was passed on.
+    #[serde(default)]
+    pub transferred_to: Option<String>,
+}
+
+impl Ticket {
+    pub fn new(
+        uuid: String,
+        event: String,
+        code: String,
+        holder_name: String,
+        kind: TicketKind,
+    ) -> Ticket {
+        Ticket {
+            uuid,
+            event,
+            code,
+            holder_name,
+            kind,
+            status: TicketStatus::Valid,
+            checked_in_at: None,
+            checked_in_by: None,
+            reentries: 0,
+            owner: None,
+            order: None,
+            ticket_type: None,
+            holder_email: None,
+            transferred_to: None,
+        }
+    }
+
+    /// Random upper-case code that is printed on the ticket and scanned at the door.
+    pub fn generate_code() -> String {
+        rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(CODE_LENGTH)
+            .map(char::from)
+            .collect::<String>()
+            .to_uppercase()
+    }
+
+    /// The ticket replacing this one when it is passed on to `email`. It gets a
+    /// fresh code, the old one is revoked when the transfer is stored.
+    pub fn transfer(&self, uuid: String, code: String, email: String, owner: Option<String>) -> Ticket {
+        Ticket {
+            uuid,
+            code,
+            holder_name: email.clone(),
+            holder_email: Some(email),
+            owner,
+            status: TicketStatus::Valid,
+            checked_in_at: None,
+            checked_in_by: None,
+            reentries: 0,
+            transferred_to: None,
+            ..self.clone()
+        }
+    }
+
+    /// Applies an entry scan to this ticket, mirroring the server-side check-in.
+    pub fn admit(&mut self, now: String, device: String) -> ScanOutcome {
+        match self.status {
+            TicketStatus::Valid => {
+                self.status = TicketStatus::CheckedIn;
+                self.checked_in_at = Some(now);
+                self.checked_in_by = Some(device);
+                ScanOutcome::Admitted
+            }
+            TicketStatus::Out => {
+                self.status = TicketStatus::CheckedIn;
+                self.reentries += 1;
+                ScanOutcome::ReEntry
+            }
+            TicketStatus::CheckedIn => ScanOutcome::Duplicate,
+            TicketStatus::Refunded => ScanOutcome::Refunded,
+            TicketStatus::Cancelled => ScanOutcome::Cancelled,
+        }
+    }
+
+    pub fn stamp_out(&mut self) -> Option<ScanOutcome> {
+        match self.status {
+            TicketStatus::CheckedIn => {
+                self.status = TicketStatus::Out;
+                Some(ScanOutcome::StampedOut)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+pub enum ScanOutcome {
+    Admitted,
+    ReEntry,
+    StampedOut,
+    /// Not in the local list, but one of the codes reserved for later sales.
+    Provisional,
+    Duplicate,
+    Refunded,
+    Cancelled,
+    Unknown,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct ScanResult {
+    pub code: String,
+    pub outcome: ScanOutcome,
+    pub holder_name: Option<String>,
+    pub checked_in_at: Option<String>,
+}
+
+impl ScanResult {
+    pub fn new(code: String, outcome: ScanOutcome, ticket: Option<&Ticket>) -> ScanResult {
+        ScanResult {
+            code,
+            outcome,
+            holder_name: ticket.map(|t| t.holder_name.clone()),
+            checked_in_at: ticket.and_then(|t| t.checked_in_at.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct DoorCounts {
+    pub inside: u32,
+    pub out: u32,
+    pub expected: u32,
+}
@@ -1,6 +1,16 @@
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
+use crate::app::i18n::Locale;
+
+
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Role {
+    #[default]
+    Member,
+    Staff,
+    Admin,
+}
 
 #[derive(Debug, Validate, Deserialize, Serialize, PartialEq, Eq, Clone)]
 pub struct User {
@@ -11,6 +21,31 @@ pub struct User {
     pub joined_date: String,
     pub name: String,
     pub last_name: String,
+    #[serde(default)]
+    pub role: Role,
+    #[serde(default)]
+    pub notifications: NotificationSettings,
+    /// Language last chosen with the switcher, for mails.
+    #[serde(default)]
+    pub locale: Locale,
+}
+
+/// Which mails about their events a user wants, see `event_mail`.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+pub struct NotificationSettings {
+    /// The day before doors.
+    pub event_reminders: bool,
+    /// Thanks, photos and the survey after the night.
+    pub event_follow_ups: bool,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> NotificationSettings {
+        NotificationSettings {
+            event_reminders: true,
+            event_follow_ups: true,
+        }
+    }
 }
 
 impl User {
@@ -27,6 +62,9 @@ impl User {
             joined_date,
             name: String::new(),
             last_name: String::new(),
+            role: Role::default(),
+            notifications: NotificationSettings::default(),
+            locale: Locale::default(),
         }
     }
 }
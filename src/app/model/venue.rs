@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Staff get a warning from this share of the capacity on.
+pub const WARNING_PERCENT: u32 = 90;
+/// The public indicator says it's busy from this share on.
+pub const BUSY_PERCENT: u32 = 60;
+
+/// A room of the venue with the number of people it may legally hold and
+/// the number door staff counted in.
+#[derive(Debug, Validate, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct Area {
+    pub uuid: String,
+    #[validate(length(min = 1))]
+    pub name: String,
+    #[validate(range(min = 1))]
+    pub capacity: u32,
+    pub inside: u32,
+    /// Order on the staff views.
+    pub position: u32,
+}
+
+impl Area {
+    pub fn new(uuid: String, name: String, capacity: u32) -> Area {
+        Area {
+            uuid,
+            name,
+            capacity,
+            inside: 0,
+            position: 0,
+        }
+    }
+
+    pub fn percent(&self) -> u32 {
+        (self.inside * 100).checked_div(self.capacity).unwrap_or(100)
+    }
+
+    pub fn level(&self) -> OccupancyLevel {
+        if self.inside >= self.capacity {
+            OccupancyLevel::Full
+        } else if self.percent() >= WARNING_PERCENT {
+            OccupancyLevel::Warning
+        } else {
+            OccupancyLevel::Normal
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+pub enum OccupancyLevel {
+    Normal,
+    Warning,
+    /// Nobody may be let in until someone leaves.
+    Full,
+}
+
+/// One in or out click of door staff, kept so the counts can be traced.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct OccupancyClick {
+    pub uuid: String,
+    /// `Area.uuid`.
+    pub area: String,
+    /// +1 for in, -1 for out, 0 for a reset.
+    pub delta: i32,
+    /// Count after the click.
+    pub inside: u32,
+    /// `User.uuid` of the staff member.
+    pub user: String,
+    pub created_at: String,
+}
+
+/// How full the venue is, for guests deciding whether to come.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+pub enum CrowdLevel {
+    Quiet,
+    Busy,
+    Full,
+}
+
+impl CrowdLevel {
+    /// `None` while nobody is counted in, i.e. the venue is closed.
+    pub fn of(areas: &[Area]) -> Option<CrowdLevel> {
+        let inside: u32 = areas.iter().map(|area| area.inside).sum();
+        let capacity: u32 = areas.iter().map(|area| area.capacity).sum();
+        if inside == 0 || capacity == 0 {
+            return None;
+        }
+        let percent = inside * 100 / capacity;
+        Some(if percent >= WARNING_PERCENT {
+            CrowdLevel::Full
+        } else if percent >= BUSY_PERCENT {
+            CrowdLevel::Busy
+        } else {
+            CrowdLevel::Quiet
+        })
+    }
+}
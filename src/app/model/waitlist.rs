@@ -0,0 +1,129 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Places one entry may ask for.
+pub const MAX_PLACES: u32 = 2;
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+pub enum WaitlistStatus {
+    Waiting,
+    /// Places are held for the entry until the offer expires.
+    Offered,
+    Claimed,
+    Expired,
+    Left,
+}
+
+impl WaitlistStatus {
+    /// Still in line or holding places.
+    pub fn is_active(self) -> bool {
+        matches!(self, WaitlistStatus::Waiting | WaitlistStatus::Offered)
+    }
+}
+
+/// Freed places held for an entry.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct WaitlistOffer {
+    /// `TicketType.uuid` of the places.
+    pub ticket_type: String,
+    /// Up to what the entry asked for, fewer if fewer were freed.
+    pub quantity: u32,
+    pub offered_at: String,
+    pub expires_at: String,
+    /// The order the offer was claimed with.
+    pub order: Option<String>,
+}
+
+impl WaitlistOffer {
+    /// Whether the places are still held at `now`. Compared as instants,
+    /// offers stored with a different offset than today's still count.
+    pub fn is_open_at(&self, now: DateTime<Utc>) -> bool {
+        DateTime::parse_from_rfc3339(&self.expires_at).is_ok_and(|expires| expires.with_timezone(&Utc) > now)
+    }
+}
+
+/// A user's place in line for a sold out event.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct WaitlistEntry {
+    pub uuid: String,
+    /// `Event.uuid`.
+    pub event: String,
+    /// `User.uuid`.
+    pub user: String,
+    pub email: String,
+    pub quantity: u32,
+    /// Given out in joining order across all events, which decides who is
+    /// offered places first.
+    pub number: u64,
+    pub status: WaitlistStatus,
+    pub joined_at: String,
+    pub offer: Option<WaitlistOffer>,
+}
+
+impl WaitlistEntry {
+    pub fn new(uuid: String, event: String, user: String, email: String, quantity: u32, joined_at: String) -> WaitlistEntry {
+        WaitlistEntry {
+            uuid,
+            event,
+            user,
+            email,
+            quantity,
+            number: 0,
+            status: WaitlistStatus::Waiting,
+            joined_at,
+            offer: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+pub enum WaitlistAction {
+    Joined,
+    Offered,
+    Claimed,
+    Expired,
+    Left,
+    /// Places nobody in line wanted went back on sale.
+    Released,
+}
+
+/// Everything that happened on a waitlist, so offers can be traced.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct WaitlistAudit {
+    pub uuid: String,
+    pub event: String,
+    /// `WaitlistEntry.uuid`, unset for releases.
+    pub entry: Option<String>,
+    pub action: WaitlistAction,
+    pub ticket_type: Option<String>,
+    pub quantity: u32,
+    pub created_at: String,
+}
+
+/// The waitlist of an event as the logged in user sees it.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct WaitlistState {
+    /// The user's active entry.
+    pub entry: Option<WaitlistEntry>,
+    /// Entries still waiting ahead of the user's.
+    pub ahead: u32,
+    /// Claim link while places are offered.
+    pub claim: Option<String>,
+}
+
+/// An event's waitlist for admins.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct WaitlistReport {
+    pub entries: Vec<WaitlistEntry>,
+    /// Oldest first.
+    pub audit: Vec<WaitlistAudit>,
+}
+
+/// An offer as shown behind its claim link.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct OfferDetails {
+    pub entry: WaitlistEntry,
+    pub event_title: String,
+    pub ticket_name: String,
+    pub price_cents: i64,
+}
@@ -0,0 +1,53 @@
+//! Live occupancy of the venue's areas. Door staff click people in and out,
+//! every click is logged and the new counts are pushed to all open staff
+//! views, see `push`.
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use uuid::Uuid;
+
+        use crate::app::database;
+        use crate::app::errors::ResponseError;
+        use crate::app::model::user::Role;
+        use crate::app::model::venue::{Area, OccupancyClick};
+        use crate::app::time;
+        use crate::app::push::{self, PushMessage, Topic};
+
+        /// Applies a click, +1 in, -1 out, and tells all staff views.
+        pub async fn count(area: String, delta: i32, user: String) -> Result<Area, ResponseError> {
+            let updated = if delta > 0 {
+                database::enter_area(area).await?
+            } else {
+                database::leave_area(area).await?
+            };
+            record(&updated, delta.signum(), user).await;
+            Ok(updated)
+        }
+
+        /// Sets the count back to zero, e.g. after closing.
+        pub async fn reset(area: String, user: String) -> Result<Area, ResponseError> {
+            let updated = database::reset_area(area).await?;
+            record(&updated, 0, user).await;
+            Ok(updated)
+        }
+
+        async fn record(area: &Area, delta: i32, user: String) {
+            database::add_occupancy_click(OccupancyClick {
+                uuid: Uuid::new_v4().to_string(),
+                area: area.uuid.clone(),
+                delta,
+                inside: area.inside,
+                user,
+                created_at: time::now(),
+            })
+            .await;
+            publish().await;
+        }
+
+        /// Sends the current counts of all areas, also after areas change.
+        pub async fn publish() {
+            let areas = database::get_areas().await.unwrap_or_default();
+            push::publish(Topic::Role(Role::Staff), PushMessage::Occupancy(areas));
+        }
+    }
+}
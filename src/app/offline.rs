@@ -0,0 +1,325 @@
+//! Local state of a door device: the preloaded admission list, the hashes
+//! of the codes reserved for later sales and the scans that still have to be
+//! synced. Kept in IndexedDB so the door keeps working without a connection
+//! and survives reloads.
+
+use serde::{de::DeserializeOwned, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+use crate::app::i18n::Locale;
+use crate::app::model::Ticket;
+use crate::app::model::door::{DoorMode, DoorSnapshot, LocalScan};
+use crate::app::model::ticket::{DoorCounts, ScanOutcome, ScanResult, TicketKind, TicketStatus};
+use crate::app::signing;
+
+const SNAPSHOTS: &str = "snapshots";
+const TICKETS: &str = "tickets";
+const SCANS: &str = "scans";
+const DEVICE_KEY: &str = "door_device";
+
+fn ticket_key(event: &str, code: &str) -> String {
+    format!("{event}/{code}")
+}
+
+/// Stores the event and code hashes under the route slug and every ticket on its own,
+/// so a scan only rewrites the ticket it touched. Tickets with scans that are
+/// not synced yet keep their local state, the server does not know it yet.
+pub async fn save_snapshot(slug: &str, mut snapshot: DoorSnapshot) -> Result<(), String> {
+    let pending = pending_scans(&snapshot.event.uuid)
+        .await?
+        .into_iter()
+        .map(|scan| scan.code)
+        .collect::<HashSet<_>>();
+    let tickets = std::mem::take(&mut snapshot.tickets)
+        .into_iter()
+        .filter(|ticket| !pending.contains(&ticket.code))
+        .map(|ticket| Ok((ticket_key(&ticket.event, &ticket.code), to_json(&ticket)?)))
+        .collect::<Result<Vec<_>, String>>()?;
+    store::put_all(TICKETS, tickets).await?;
+    store::put_all(SNAPSHOTS, vec![(slug.to_owned(), to_json(&snapshot)?)]).await
+}
+
+/// The stored snapshot without its tickets.
+pub async fn load_snapshot(slug: &str) -> Result<Option<DoorSnapshot>, String> {
+    match store::get(SNAPSHOTS, slug).await? {
+        Some(json) => from_json(&json).map(Some),
+        None => Ok(None),
+    }
+}
+
+pub async fn scan(snapshot: &DoorSnapshot, code: String, mode: DoorMode, locale: Locale) -> Result<ScanResult, String> {
+    let event = snapshot.event.uuid.clone();
+    let code = code.trim().to_uppercase();
+    let key = ticket_key(&event, &code);
+    let device = device_id();
+    let now = now();
+
+    let mut ticket: Option<Ticket> = match store::get(TICKETS, &key).await? {
+        Some(json) => Some(from_json(&json)?),
+        None => None,
+    };
+
+    // a reserved code that is missing locally was sold after the list was loaded
+    let provisional = mode == DoorMode::In
+        && ticket.is_none()
+        && snapshot.codes.contains(&signing::digest(&code));
+    if provisional {
+        ticket = Some(Ticket::new(
+            Uuid::new_v4().to_string(),
+            event.clone(),
+            code.clone(),
+            String::new(),
+            TicketKind::Ticket,
+        ));
+    }
+
+    let outcome = match (mode, ticket.as_mut()) {
+        (DoorMode::In, Some(ticket)) => ticket.admit(now.clone(), device.clone()),
+        (DoorMode::In, None) => ScanOutcome::Unknown,
+        (DoorMode::Out, Some(ticket)) => match ticket.stamp_out() {
+            Some(outcome) => outcome,
+            None => return Err(locale.text("offline-not-inside").to_string()),
+        },
+        (DoorMode::Out, None) => return Err(locale.text("offline-not-inside").to_string()),
+    };
+    let outcome = match outcome {
+        ScanOutcome::Admitted if provisional => ScanOutcome::Provisional,
+        outcome => outcome,
+    };
+
+    let changed = matches!(
+        outcome,
+        ScanOutcome::Admitted | ScanOutcome::ReEntry | ScanOutcome::Provisional | ScanOutcome::StampedOut
+    );
+    if let (true, Some(ticket)) = (changed, ticket.as_ref()) {
+        let scan = LocalScan::new(
+            Uuid::new_v4().to_string(),
+            event,
+            code.clone(),
+            device,
+            mode,
+            now,
+            outcome,
+        );
+        store::put_all(TICKETS, vec![(key, to_json(ticket)?)]).await?;
+        store::put_all(SCANS, vec![(scan.uuid.clone(), to_json(&scan)?)]).await?;
+    }
+
+    Ok(ScanResult::new(code, outcome, ticket.as_ref()))
+}
+
+pub async fn pending_scans(event: &str) -> Result<Vec<LocalScan>, String> {
+    let mut scans = store::get_all(SCANS)
+        .await?
+        .iter()
+        .map(|json| from_json::<LocalScan>(json))
+        .collect::<Result<Vec<_>, String>>()?;
+    scans.retain(|scan| scan.event == event);
+    scans.sort_by_key(|scan| scan.order_key());
+    Ok(scans)
+}
+
+pub async fn forget_scans(scans: &[LocalScan]) -> Result<(), String> {
+    store::delete_all(SCANS, scans.iter().map(|scan| scan.uuid.clone()).collect()).await
+}
+
+pub async fn local_counts(event: &str) -> Result<DoorCounts, String> {
+    let mut counts = DoorCounts::default();
+    for json in store::get_all(TICKETS).await? {
+        let ticket: Ticket = from_json(&json)?;
+        if ticket.event != event {
+            continue;
+        }
+        match ticket.status {
+            TicketStatus::CheckedIn => counts.inside += 1,
+            TicketStatus::Out => counts.out += 1,
+            TicketStatus::Refunded | TicketStatus::Cancelled => continue,
+            TicketStatus::Valid => (),
+        }
+        counts.expected += 1;
+    }
+    Ok(counts)
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, String> {
+    leptos::serde_json::to_string(value).map_err(|e| e.to_string())
+}
+
+fn from_json<T: DeserializeOwned>(json: &str) -> Result<T, String> {
+    leptos::serde_json::from_str(json).map_err(|e| e.to_string())
+}
+
+/// Name this device reports its scans under, generated once per browser.
+#[cfg(feature = "hydrate")]
+pub fn device_id() -> String {
+    use leptos::prelude::window;
+
+    let storage = window().local_storage().ok().flatten();
+    if let Some(id) = storage.as_ref().and_then(|s| s.get_item(DEVICE_KEY).ok().flatten()) {
+        return id;
+    }
+    let id = format!("door-{}", &Uuid::new_v4().simple().to_string()[..8]);
+    if let Some(storage) = storage {
+        let _ = storage.set_item(DEVICE_KEY, &id);
+    }
+    id
+}
+
+#[cfg(not(feature = "hydrate"))]
+pub fn device_id() -> String {
+    String::from(DEVICE_KEY)
+}
+
+#[cfg(feature = "hydrate")]
+fn now() -> String {
+    js_sys::Date::new_0().to_iso_string().into()
+}
+
+#[cfg(not(feature = "hydrate"))]
+fn now() -> String {
+    crate::app::time::now()
+}
+
+#[cfg(feature = "hydrate")]
+mod store {
+    use leptos::prelude::window;
+    use leptos::web_sys::{self, IdbDatabase, IdbOpenDbRequest, IdbRequest, IdbTransactionMode, IdbVersionChangeEvent};
+    use wasm_bindgen::{prelude::*, JsCast};
+    use wasm_bindgen_futures::JsFuture;
+
+    use super::{SCANS, SNAPSHOTS, TICKETS};
+
+    const DB_NAME: &str = "stampffabrik_door";
+    const DB_VERSION: u32 = 1;
+
+    fn error(e: JsValue) -> String {
+        e.as_string().unwrap_or_else(|| String::from("IndexedDB error"))
+    }
+
+    /// Resolves once the request fires `success`, with the request's result.
+    async fn done(request: &IdbRequest) -> Result<JsValue, JsValue> {
+        let promise = js_sys::Promise::new(&mut |resolve, reject| {
+            let on_success = Closure::once_into_js(move |event: web_sys::Event| {
+                let result = event
+                    .target()
+                    .and_then(|target| target.dyn_into::<IdbRequest>().ok())
+                    .and_then(|request| request.result().ok())
+                    .unwrap_or(JsValue::UNDEFINED);
+                let _ = resolve.call1(&JsValue::NULL, &result);
+            });
+            let on_error = Closure::once_into_js(move |_: web_sys::Event| {
+                let _ = reject.call1(&JsValue::NULL, &JsValue::from_str("IndexedDB request failed"));
+            });
+            request.set_onsuccess(Some(on_success.unchecked_ref()));
+            request.set_onerror(Some(on_error.unchecked_ref()));
+        });
+        JsFuture::from(promise).await
+    }
+
+    async fn open() -> Result<IdbDatabase, JsValue> {
+        let factory = window()
+            .indexed_db()?
+            .ok_or_else(|| JsValue::from_str("IndexedDB is not available"))?;
+        let request: IdbOpenDbRequest = factory.open_with_u32(DB_NAME, DB_VERSION)?;
+        let on_upgrade = Closure::once_into_js(move |event: IdbVersionChangeEvent| {
+            let db = event
+                .target()
+                .and_then(|target| target.dyn_into::<IdbOpenDbRequest>().ok())
+                .and_then(|request| request.result().ok())
+                .and_then(|db| db.dyn_into::<IdbDatabase>().ok());
+            if let Some(db) = db {
+                for store in [SNAPSHOTS, TICKETS, SCANS] {
+                    if !db.object_store_names().contains(store) {
+                        let _ = db.create_object_store(store);
+                    }
+                }
+            }
+        });
+        request.set_onupgradeneeded(Some(on_upgrade.unchecked_ref()));
+        done(&request).await?.dyn_into()
+    }
+
+    pub async fn put_all(store: &str, entries: Vec<(String, String)>) -> Result<(), String> {
+        let db = open().await.map_err(error)?;
+        let result = async {
+            let tx = db.transaction_with_str_and_mode(store, IdbTransactionMode::Readwrite)?;
+            let object_store = tx.object_store(store)?;
+            let mut last = None;
+            for (key, value) in entries {
+                last = Some(object_store.put_with_key(&JsValue::from_str(&value), &JsValue::from_str(&key))?);
+            }
+            // requests in one transaction complete in order
+            if let Some(request) = last {
+                done(&request).await?;
+            }
+            Ok::<(), JsValue>(())
+        }.await;
+        db.close();
+        result.map_err(error)
+    }
+
+    pub async fn delete_all(store: &str, keys: Vec<String>) -> Result<(), String> {
+        let db = open().await.map_err(error)?;
+        let result = async {
+            let tx = db.transaction_with_str_and_mode(store, IdbTransactionMode::Readwrite)?;
+            let object_store = tx.object_store(store)?;
+            let mut last = None;
+            for key in keys {
+                last = Some(object_store.delete(&JsValue::from_str(&key))?);
+            }
+            if let Some(request) = last {
+                done(&request).await?;
+            }
+            Ok::<(), JsValue>(())
+        }.await;
+        db.close();
+        result.map_err(error)
+    }
+
+    pub async fn get(store: &str, key: &str) -> Result<Option<String>, String> {
+        let db = open().await.map_err(error)?;
+        let result = async {
+            let tx = db.transaction_with_str(store)?;
+            let request = tx.object_store(store)?.get(&JsValue::from_str(key))?;
+            done(&request).await
+        }.await;
+        db.close();
+        result.map(|value| value.as_string()).map_err(error)
+    }
+
+    pub async fn get_all(store: &str) -> Result<Vec<String>, String> {
+        let db = open().await.map_err(error)?;
+        let result = async {
+            let tx = db.transaction_with_str(store)?;
+            let request = tx.object_store(store)?.get_all()?;
+            done(&request).await
+        }.await;
+        db.close();
+        result
+            .map(|values| js_sys::Array::from(&values).iter().filter_map(|v| v.as_string()).collect())
+            .map_err(error)
+    }
+}
+
+/// Offline storage only exists in the browser.
+#[cfg(not(feature = "hydrate"))]
+mod store {
+    const UNAVAILABLE: &str = "offline storage is only available in the browser";
+
+    pub async fn put_all(_store: &str, _entries: Vec<(String, String)>) -> Result<(), String> {
+        Err(String::from(UNAVAILABLE))
+    }
+
+    pub async fn delete_all(_store: &str, _keys: Vec<String>) -> Result<(), String> {
+        Err(String::from(UNAVAILABLE))
+    }
+
+    pub async fn get(_store: &str, _key: &str) -> Result<Option<String>, String> {
+        Err(String::from(UNAVAILABLE))
+    }
+
+    pub async fn get_all(_store: &str) -> Result<Vec<String>, String> {
+        Err(String::from(UNAVAILABLE))
+    }
+}
@@ -1,19 +1,90 @@
-use leptos::{logging::log, prelude::*};
+use leptos::{logging::log, prelude::*, task::spawn_local};
 
+use crate::app::i18n::{t, use_locale};
 use crate::app::model::User;
+use crate::app::model::user::NotificationSettings;
+use crate::app::page::invoices::InvoiceList;
+use crate::app::page::order::OrderHistory;
+use crate::app::page::wallet::TicketWallet;
+use crate::app::seo::PageMeta;
 
+stylance::import_style!(style, "../../style/cart.module.scss");
 
 #[leptos::component]
 pub fn AccountPage() -> impl IntoView {
     let (get_user, set_user) = expect_context::<(ReadSignal<Option<User>>, WriteSignal<Option<User>>)>();
     let user = get_user().unwrap();
     view! {
+        <PageMeta title=use_locale().get_untracked().text("account-title") noindex=true/>
         <div>
         {user.name}
         {user.email}
         </div>
         // <h2>{user.name}</h2>
         // <h3>{user.email}</h3>
-        <h3>Address</h3>
+        <h3>{t("account-address")}</h3>
+        <TicketWallet/>
+        <OrderHistory/>
+        <InvoiceList/>
+        <NotificationSettingsForm user=get_user set_user/>
+    }
+}
+
+/// Which mails about their events the user gets.
+#[component]
+fn NotificationSettingsForm(user: ReadSignal<Option<User>>, set_user: WriteSignal<Option<User>>) -> impl IntoView {
+    let settings = RwSignal::new(user.get_untracked().map(|u| u.notifications).unwrap_or_default());
+    let (message, set_message) = signal(String::new());
+    let locale = use_locale();
+
+    let on_save = move |_| {
+        spawn_local(async move {
+            match save_notification_settings(settings.get_untracked()).await {
+                Ok(updated) => {
+                    set_user(Some(updated));
+                    set_message(locale.get_untracked().text("settings-saved").to_string());
+                }
+                Err(e) => set_message(e.to_string()),
+            }
+        });
+    };
+
+    view! {
+        <div class="h2">{t("settings-title")}</div>
+        <label>
+            <input type="checkbox"
+                prop:checked=move || settings.with(|s| s.event_reminders)
+                on:change=move |e| settings.update(|s| s.event_reminders = event_target_checked(&e))
+            />
+            " "{t("settings-event-reminders")}
+        </label>
+        <label>
+            <input type="checkbox"
+                prop:checked=move || settings.with(|s| s.event_follow_ups)
+                on:change=move |e| settings.update(|s| s.event_follow_ups = event_target_checked(&e))
+            />
+            " "{t("settings-event-follow-ups")}
+        </label>
+        <button class=style::button on:click=on_save>{t("settings-save")}</button>
+        <span>{message}</span>
+    }
+}
+
+#[server(SaveNotificationSettings, "/api")]
+pub async fn save_notification_settings(settings: NotificationSettings) -> Result<User, ServerFnError> {
+    let Some(user) = current_user().await else {
+        return Err(ServerFnError::Args(request_text("error-not-logged-in")));
+    };
+    database::set_notification_settings(user.uuid, settings)
+        .await
+        .ok_or_else(|| ServerFnError::Args(ErrorMessage::create(ResponseError::UserUpdateFailure)))
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use crate::app::auth::current_user;
+        use crate::app::database;
+        use crate::app::errors::{ErrorMessage, ResponseError, ResponseErrorTrait};
+        use crate::app::i18n::request_text;
     }
 }
\ No newline at end of file
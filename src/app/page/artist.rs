@@ -0,0 +1,582 @@
+use leptos::{prelude::*, task::spawn_local};
+use leptos_router::hooks::use_params_map;
+
+use crate::app::i18n::{t, use_locale, Locale};
+use crate::app::ical::{input_value, parse_berlin};
+use crate::app::images::ResponsiveImage;
+use crate::app::markdown;
+use crate::app::model::{Artist, Event};
+use crate::app::model::artist::{Appearance, ArtistView, LineupEntry, Performance};
+use crate::app::seo::PageMeta;
+
+stylance::import_style!(style, "../../style/artist.module.scss");
+stylance::import_style!(shop_style, "../../style/shop.module.scss");
+stylance::import_style!(news_style, "../../style/news.module.scss");
+stylance::import_style!(cart_style, "../../style/cart.module.scss");
+
+/// Characters of a bio in its meta description.
+const DESCRIPTION_LENGTH: usize = 160;
+
+/// Set times in Berlin time, e.g. "23:00–01:00"; empty until announced.
+fn set_times(performance: &Performance, locale: Locale) -> String {
+    let time = |time: &Option<String>| time.as_deref().and_then(parse_berlin).map(|t| locale.time(t));
+    match (time(&performance.starts_at), time(&performance.ends_at)) {
+        (Some(starts), Some(ends)) => format!("{starts}–{ends}"),
+        (Some(starts), None) => starts,
+        _ => String::new(),
+    }
+}
+
+/// A link by its site, e.g. "bandcamp.com".
+fn link_label(url: &str) -> String {
+    let host = url.split("://").last().unwrap_or(url);
+    let host = host.split('/').next().unwrap_or(host);
+    host.trim_start_matches("www.").to_string()
+}
+
+#[leptos::component]
+pub fn ArtistsPage() -> impl IntoView {
+    let artists = Resource::new(|| (), |_| artists());
+    let (genre, set_genre) = signal::<Option<String>>(None);
+    let locale = use_locale().get_untracked();
+
+    view! {
+        <PageMeta
+            title=locale.text("artists-title")
+            path="/artists"
+            description=locale.text("artists-description")
+        />
+        <div class=format!("container {}", shop_style::shop)>
+            <div class="h2">{t("artists-title")}</div>
+            <Suspense fallback=move || view! { <p>{t("loading")}</p> }>
+                {move || artists.get().map(|artists| match artists {
+                    Ok(artists) if artists.is_empty() => view! { <span>{t("artists-empty")}</span> }.into_any(),
+                    Ok(artists) => {
+                        let mut genres: Vec<String> = artists.iter().flat_map(|a| a.genres.clone()).collect();
+                        genres.sort();
+                        genres.dedup();
+                        view! {
+                            <div class=style::tags>
+                                <span
+                                    class=move || format!("{} {}", style::tag, if genre.get().is_none() { style::tag_active } else { "" })
+                                    on:click=move |_| set_genre(None)
+                                >{t("artists-all-genres")}</span>
+                                {genres.into_iter().map(|name| {
+                                    let selected = name.clone();
+                                    let active = name.clone();
+                                    view! {
+                                        <span
+                                            class=move || format!("{} {}", style::tag, if genre.get().as_ref() == Some(&active) { style::tag_active } else { "" })
+                                            on:click=move |_| set_genre(Some(selected.clone()))
+                                        >{name}</span>
+                                    }
+                                }).collect_view()}
+                            </div>
+                            <div class=shop_style::grid>
+                                {move || artists.iter()
+                                    .filter(|artist| genre.with(|g| g.as_ref().is_none_or(|g| artist.genres.contains(g))))
+                                    .map(|artist| view! { <ArtistCard artist=artist.clone()/> })
+                                    .collect_view()}
+                            </div>
+                        }.into_any()
+                    }
+                    Err(e) => view! { <span class=cart_style::error>{e.to_string()}</span> }.into_any(),
+                })}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+fn ArtistCard(artist: Artist) -> impl IntoView {
+    view! {
+        <a class=shop_style::card href=format!("/artists/{}", artist.slug)>
+            {artist.photo.map(|photo| view! {
+                <ResponsiveImage class=shop_style::image src=photo alt=artist.name.clone() sizes="200pt"/>
+            })}
+            <span class=shop_style::name>{artist.name}</span>
+            <span class=news_style::meta>{artist.genres.join(" · ")}</span>
+        </a>
+    }
+}
+
+#[leptos::component]
+pub fn ArtistPage() -> impl IntoView {
+    let params = use_params_map();
+    let slug = move || params.read().get("slug").unwrap_or_default();
+    let artist = Resource::new(slug, artist_view);
+    let locale = use_locale();
+
+    view! {
+        <div class=format!("container {}", news_style::news)>
+            <Suspense fallback=move || view! { <p>{t("loading")}</p> }>
+                {move || artist.get().map(|artist| match artist {
+                    Ok(ArtistView { artist, html, upcoming, past }) => {
+                        let description = match markdown::excerpt(&artist.bio, DESCRIPTION_LENGTH) {
+                            bio if bio.is_empty() => locale.get_untracked().format("artist-description", &[("name", &artist.name)]),
+                            bio => bio,
+                        };
+                        view! {
+                            <PageMeta
+                                title=artist.name.clone()
+                                path=format!("/artists/{}", artist.slug)
+                                description
+                                image=artist.photo.clone().unwrap_or_default()
+                                og_type="profile"
+                            />
+                            {artist.photo.map(|photo| view! {
+                                <ResponsiveImage class=news_style::cover src=photo alt=artist.name.clone() eager=true/>
+                            })}
+                            <div class="h2">{artist.name}</div>
+                            <span class=news_style::meta>{artist.genres.join(" · ")}</span>
+                            <div class=news_style::body inner_html=html></div>
+                            <div class=style::links>
+                                {artist.links.into_iter().map(|link| view! {
+                                    <a href=link.clone() rel="external noopener" target="_blank">{link_label(&link)}</a>
+                                }).collect_view()}
+                            </div>
+                            <h3>{t("artist-upcoming")}</h3>
+                            {if upcoming.is_empty() {
+                                view! { <span>{t("artist-no-dates")}</span> }.into_any()
+                            } else {
+                                view! { <Appearances appearances=upcoming/> }.into_any()
+                            }}
+                            {(!past.is_empty()).then(|| view! {
+                                <h3>{t("artist-past")}</h3>
+                                <Appearances appearances=past/>
+                            })}
+                            <a href="/artists">{t("artists-all")}</a>
+                        }.into_any()
+                    }
+                    Err(e) => view! { <span class=cart_style::error>{e.to_string()}</span> }.into_any(),
+                })}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+fn Appearances(appearances: Vec<Appearance>) -> impl IntoView {
+    let locale = use_locale();
+
+    view! {
+        <table class=style::lineup>
+            {appearances.into_iter().map(|Appearance { performance, event }| {
+                let starts_at = parse_berlin(&event.starts_at);
+                view! {
+                    <tr>
+                        <td class=style::set>{move || starts_at.map(|t| locale.get().date(t.date())).unwrap_or_default()}</td>
+                        <td><a href=format!("/events/{}", event.slug)>{event.title}</a></td>
+                        <td>{performance.floor.clone()}</td>
+                        <td class=style::set>{move || set_times(&performance, locale.get())}</td>
+                    </tr>
+                }
+            }).collect_view()}
+        </table>
+    }
+}
+
+/// Running order of an event, for its page.
+#[component]
+pub fn Lineup(lineup: Vec<LineupEntry>) -> impl IntoView {
+    let locale = use_locale();
+
+    (!lineup.is_empty()).then(|| view! {
+        <h3>{t("lineup-title")}</h3>
+        <table class=style::lineup>
+            {lineup.into_iter().map(|LineupEntry { performance, artist }| view! {
+                <tr>
+                    <td><a href=format!("/artists/{}", artist.slug)>{artist.name}</a></td>
+                    <td>{performance.floor.clone()}</td>
+                    <td class=style::set>{move || set_times(&performance, locale.get())}</td>
+                </tr>
+            }).collect_view()}
+        </table>
+    })
+}
+
+fn blank_artist() -> Artist {
+    Artist::new(String::new(), String::new(), String::new())
+}
+
+#[leptos::component]
+pub fn ArtistsAdminPage() -> impl IntoView {
+    let version = RwSignal::new(0u32);
+    let artists = Resource::new(move || version.get(), |_| artists());
+    let editing = RwSignal::new(blank_artist());
+
+    view! {
+        <PageMeta title=use_locale().get_untracked().text("artists-title") noindex=true/>
+        <div class=format!("container {}", news_style::news)>
+            <div class=cart_style::row>
+                <div class="h2">{t("artists-title")}</div>
+                <button class=cart_style::button on:click=move |_| editing.set(blank_artist())>{t("artists-new")}</button>
+            </div>
+            <Transition fallback=move || view! { <p>{t("loading")}</p> }>
+                {move || artists.get().map(|artists| match artists {
+                    Ok(artists) => view! {
+                        <table class=cart_style::table>
+                            {artists.into_iter().map(|artist| {
+                                let name = artist.name.clone();
+                                let genres = artist.genres.join(", ");
+                                view! {
+                                    <tr>
+                                        <td>{name}</td>
+                                        <td>{genres}</td>
+                                        <td class=cart_style::amount>
+                                            <button class=cart_style::button on:click=move |_| editing.set(artist.clone())>
+                                                {t("admin-edit")}
+                                            </button>
+                                        </td>
+                                    </tr>
+                                }
+                            }).collect_view()}
+                        </table>
+                    }.into_any(),
+                    Err(e) => view! { <span class=cart_style::error>{e.to_string()}</span> }.into_any(),
+                })}
+            </Transition>
+            <ArtistEditor editing version/>
+            <div class="h2">{t("artists-lineups")}</div>
+            <LineupEditor version/>
+        </div>
+    }
+}
+
+#[component]
+fn ArtistEditor(editing: RwSignal<Artist>, version: RwSignal<u32>) -> impl IntoView {
+    let (error_message, set_error_message) = signal(String::new());
+
+    let on_save = move |_| {
+        let artist = editing.get_untracked();
+        spawn_local(async move {
+            match save_artist(artist).await {
+                Ok(saved) => {
+                    editing.set(saved);
+                    set_error_message(String::new());
+                    version.update(|v| *v += 1);
+                }
+                Err(e) => set_error_message(e.to_string()),
+            }
+        });
+    };
+
+    view! {
+        <div class=cart_style::row>
+            <input type="text" placeholder=t("name") class=cart_style::input
+                prop:value=move || editing.with(|a| a.name.clone())
+                on:input=move |e| editing.update(|a| a.name = event_target_value(&e))
+            />
+            <input type="text" placeholder="Slug" class=cart_style::input
+                prop:value=move || editing.with(|a| a.slug.clone())
+                on:input=move |e| editing.update(|a| a.slug = event_target_value(&e))
+            />
+        </div>
+        <div class=cart_style::row>
+            <input type="text" placeholder=t("artists-photo") class=cart_style::input
+                prop:value=move || editing.with(|a| a.photo.clone().unwrap_or_default())
+                on:input=move |e| {
+                    let photo = event_target_value(&e);
+                    editing.update(|a| a.photo = (!photo.trim().is_empty()).then(|| photo.trim().to_string()));
+                }
+            />
+            // split when leaving the field, so commas can be typed
+            <input type="text" placeholder=t("artists-genres") class=cart_style::input
+                prop:value=move || editing.with(|a| a.genres.join(", "))
+                on:change=move |e| editing.update(|a| a.genres = event_target_value(&e)
+                    .split(',')
+                    .map(|genre| genre.trim().to_lowercase())
+                    .filter(|genre| !genre.is_empty())
+                    .collect())
+            />
+        </div>
+        <textarea class=cart_style::input rows="3" placeholder=t("artists-links")
+            prop:value=move || editing.with(|a| a.links.join("\n"))
+            on:change=move |e| editing.update(|a| a.links = event_target_value(&e)
+                .lines()
+                .map(|link| link.trim().to_string())
+                .filter(|link| !link.is_empty())
+                .collect())
+        ></textarea>
+        <div class=news_style::editor>
+            <textarea class=cart_style::input rows="12" placeholder=t("artists-bio")
+                prop:value=move || editing.with(|a| a.bio.clone())
+                on:input=move |e| editing.update(|a| a.bio = event_target_value(&e))
+            ></textarea>
+            <div class=news_style::body inner_html=move || editing.with(|a| markdown::to_html(&a.bio))></div>
+        </div>
+        <div class=cart_style::row>
+            <span class=cart_style::error>{error_message}</span>
+            <button class=cart_style::button on:click=on_save>{t("admin-save")}</button>
+        </div>
+    }
+}
+
+#[component]
+fn LineupEditor(version: RwSignal<u32>) -> impl IntoView {
+    let locale = use_locale();
+    let events = Resource::new(|| (), |_| lineup_events());
+    let artists = Resource::new(move || version.get(), |_| artists());
+    let (event, set_event) = signal(String::new());
+    let lineup_version = RwSignal::new(0u32);
+    let lineup = Resource::new(move || (event.get(), lineup_version.get()), |(event, _)| event_lineup(event));
+    let adding = RwSignal::new(Performance::new(String::new(), String::new(), String::new()));
+    let (error_message, set_error_message) = signal(String::new());
+
+    let on_add = move |_| {
+        let performance = Performance { event: event.get_untracked(), ..adding.get_untracked() };
+        spawn_local(async move {
+            match save_performance(performance).await {
+                Ok(_) => {
+                    adding.update(|p| *p = Performance::new(String::new(), String::new(), p.artist.clone()));
+                    set_error_message(String::new());
+                    lineup_version.update(|v| *v += 1);
+                }
+                Err(e) => set_error_message(e.to_string()),
+            }
+        });
+    };
+
+    let on_remove = move |uuid: String| {
+        spawn_local(async move {
+            match remove_performance(uuid).await {
+                Ok(_) => lineup_version.update(|v| *v += 1),
+                Err(e) => set_error_message(e.to_string()),
+            }
+        });
+    };
+
+    view! {
+        <Transition fallback=move || ()>
+            <select class=cart_style::input on:change=move |e| set_event(event_target_value(&e))>
+                <option value="">{t("admin-choose-event")}</option>
+                {move || events.get().and_then(Result::ok).unwrap_or_default().into_iter().map(|event: Event| view! {
+                    <option value=event.uuid>
+                        {format!("{} · {}", input_value(&Some(event.starts_at)).replace('T', " "), event.title)}
+                    </option>
+                }).collect_view()}
+            </select>
+        </Transition>
+        <Show when=move || !event.get().is_empty()>
+            <Transition fallback=move || ()>
+                <table class=cart_style::table>
+                    {move || lineup.get().and_then(Result::ok).unwrap_or_default().into_iter().map(|entry| {
+                        let uuid = entry.performance.uuid.clone();
+                        view! {
+                            <tr>
+                                <td>{entry.artist.name}</td>
+                                <td>{entry.performance.floor.clone()}</td>
+                                <td>{set_times(&entry.performance, locale.get())}</td>
+                                <td class=cart_style::amount>
+                                    <button class=cart_style::button on:click=move |_| on_remove(uuid.clone())>{t("artists-remove")}</button>
+                                </td>
+                            </tr>
+                        }
+                    }).collect_view()}
+                </table>
+            </Transition>
+            <div class=cart_style::row>
+                <Transition fallback=move || ()>
+                    <select class=cart_style::input
+                        prop:value=move || adding.with(|p| p.artist.clone())
+                        on:change=move |e| adding.update(|p| p.artist = event_target_value(&e))
+                    >
+                        <option value="">{t("artists-choose")}</option>
+                        {move || artists.get().and_then(Result::ok).unwrap_or_default().into_iter().map(|artist| view! {
+                            <option value=artist.uuid>{artist.name}</option>
+                        }).collect_view()}
+                    </select>
+                </Transition>
+                <input type="text" placeholder=t("artists-floor") class=cart_style::input
+                    prop:value=move || adding.with(|p| p.floor.clone())
+                    on:input=move |e| adding.update(|p| p.floor = event_target_value(&e))
+                />
+            </div>
+            <div class=cart_style::row>
+                <input type="datetime-local" class=cart_style::input title=t("artists-set-start")
+                    prop:value=move || adding.with(|p| input_value(&p.starts_at))
+                    on:input=move |e| {
+                        let time = event_target_value(&e);
+                        adding.update(|p| p.starts_at = (!time.is_empty()).then_some(time));
+                    }
+                />
+                <input type="datetime-local" class=cart_style::input title=t("artists-set-end")
+                    prop:value=move || adding.with(|p| input_value(&p.ends_at))
+                    on:input=move |e| {
+                        let time = event_target_value(&e);
+                        adding.update(|p| p.ends_at = (!time.is_empty()).then_some(time));
+                    }
+                />
+                <button class=cart_style::button on:click=on_add>{t("artists-add")}</button>
+            </div>
+            <span class=cart_style::error>{error_message}</span>
+        </Show>
+    }
+}
+
+#[server(Artists, "/api")]
+pub async fn artists() -> Result<Vec<Artist>, ServerFnError> {
+    Ok(database::get_artists().await.unwrap_or_default())
+}
+
+#[server(ArtistDetails, "/api")]
+pub async fn artist_view(slug: String) -> Result<ArtistView, ServerFnError> {
+    let Some(artist) = database::get_artist_by_slug(slug).await else {
+        return Err(ServerFnError::Args(ErrorMessage::create(ResponseError::ArtistNotFound)));
+    };
+    let performances = database::get_artist_performances(artist.uuid.clone()).await.unwrap_or_default();
+    let uuids = performances.iter().map(|p| p.event.clone()).collect();
+    let events = database::get_events(uuids).await.unwrap_or_default();
+
+    let now = Local::now();
+    let mut appearances: Vec<Appearance> = performances
+        .into_iter()
+        .filter_map(|performance| {
+            let event = events.iter().find(|e| e.uuid == performance.event)?.clone();
+            Some(Appearance { performance, event })
+        })
+        .collect();
+    appearances.sort_by_key(|a| DateTime::parse_from_rfc3339(&a.event.starts_at).ok());
+    // the night counts as upcoming until it is over
+    let (upcoming, mut past): (Vec<_>, Vec<_>) = appearances.into_iter().partition(|a| {
+        DateTime::parse_from_rfc3339(&a.event.starts_at)
+            .map(|starts_at| starts_at + Duration::hours(12) > now)
+            .unwrap_or(true)
+    });
+    past.reverse();
+
+    Ok(ArtistView {
+        html: markdown::render(&artist.bio),
+        artist,
+        upcoming,
+        past,
+    })
+}
+
+/// Creates the artist or saves changes to it.
+#[server(SaveArtist, "/api")]
+pub async fn save_artist(artist: Artist) -> Result<Artist, ServerFnError> {
+    require_role(Role::Admin).await?;
+    let now = time::now();
+    let artist = Artist {
+        slug: artist.slug.trim().to_lowercase(),
+        name: artist.name.trim().to_string(),
+        updated_at: now.clone(),
+        ..artist
+    };
+    if artist.validate().is_err() || !artist.slug.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(ServerFnError::Args(request_text("error-artist-invalid")));
+    }
+    if artist.links.iter().any(|link| !link.starts_with("https://") && !link.starts_with("http://")) {
+        return Err(ServerFnError::Args(request_text("error-links-https")));
+    }
+    if database::get_artist_by_slug(artist.slug.clone()).await.is_some_and(|other| other.uuid != artist.uuid) {
+        return Err(ServerFnError::Args(request_text("error-slug-taken")));
+    }
+
+    let existing = match artist.uuid.as_str() {
+        "" => None,
+        uuid => database::get_artist(uuid.to_string()).await,
+    };
+    let saved = match existing {
+        Some(existing) => database::update_artist(Artist { created_at: existing.created_at, ..artist }).await,
+        None => database::add_artist(Artist { uuid: Uuid::new_v4().to_string(), created_at: now, ..artist }).await,
+    };
+    saved.ok_or_else(|| ServerFnError::Args(ErrorMessage::create(ResponseError::ArtistNotFound)))
+}
+
+/// Events to edit lineups of, soonest first.
+#[server(LineupEvents, "/api")]
+pub async fn lineup_events() -> Result<Vec<Event>, ServerFnError> {
+    require_role(Role::Admin).await?;
+    Ok(database::get_events_since(String::new()).await.unwrap_or_default())
+}
+
+#[server(EventLineup, "/api")]
+pub async fn event_lineup(event: String) -> Result<Vec<LineupEntry>, ServerFnError> {
+    Ok(lineup(event).await)
+}
+
+/// Adds an artist to an event's lineup, or changes the slot.
+#[server(SavePerformance, "/api")]
+pub async fn save_performance(performance: Performance) -> Result<Performance, ServerFnError> {
+    require_role(Role::Admin).await?;
+    if database::get_events(vec![performance.event.clone()]).await.unwrap_or_default().is_empty() {
+        return Err(ServerFnError::Args(ErrorMessage::create(ResponseError::EventNotFound)));
+    }
+    if database::get_artist(performance.artist.clone()).await.is_none() {
+        return Err(ServerFnError::Args(ErrorMessage::create(ResponseError::ArtistNotFound)));
+    }
+    let time = |time: Option<String>| match time.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+        Some(time) => from_input(time).map(Some).ok_or(ServerFnError::Args(request_text("error-set-time"))),
+        None => Ok(None),
+    };
+    let performance = Performance {
+        uuid: match performance.uuid.as_str() {
+            "" => Uuid::new_v4().to_string(),
+            uuid => uuid.to_string(),
+        },
+        starts_at: time(performance.starts_at)?,
+        ends_at: time(performance.ends_at)?,
+        floor: performance.floor.trim().to_string(),
+        ..performance
+    };
+    if let (Some(starts_at), Some(ends_at)) = (timestamp(&performance.starts_at), timestamp(&performance.ends_at)) {
+        if ends_at < starts_at {
+            return Err(ServerFnError::Args(request_text("error-set-order")));
+        }
+    }
+    database::save_performance(performance)
+        .await
+        .ok_or_else(|| ServerFnError::Args(ErrorMessage::create(ResponseError::ArtistNotFound)))
+}
+
+#[server(RemovePerformance, "/api")]
+pub async fn remove_performance(uuid: String) -> Result<(), ServerFnError> {
+    require_role(Role::Admin).await?;
+    database::delete_performance(uuid).await;
+    Ok(())
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use chrono::{DateTime, Duration, FixedOffset, Local};
+        use uuid::Uuid;
+        use validator::Validate;
+
+        use crate::app::auth::require_role;
+        use crate::app::database;
+        use crate::app::errors::{ErrorMessage, ResponseError, ResponseErrorTrait};
+        use crate::app::i18n::request_text;
+        use crate::app::ical::from_input;
+        use crate::app::model::user::Role;
+        use crate::app::time;
+
+        /// Stored times can carry different offsets across a clock change,
+        /// so they are compared as instants.
+        fn timestamp(time: &Option<String>) -> Option<DateTime<FixedOffset>> {
+            time.as_deref().and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        }
+
+        /// An event's lineup in running order, slots without set times last.
+        pub async fn lineup(event: String) -> Vec<LineupEntry> {
+            let performances = database::get_event_performances(event).await.unwrap_or_default();
+            let artists = database::get_artists().await.unwrap_or_default();
+            let mut lineup: Vec<LineupEntry> = performances
+                .into_iter()
+                .filter_map(|performance| {
+                    let artist = artists.iter().find(|a| a.uuid == performance.artist)?.clone();
+                    Some(LineupEntry { performance, artist })
+                })
+                .collect();
+            lineup.sort_by(|a, b| {
+                let key = |entry: &LineupEntry| {
+                    let starts_at = timestamp(&entry.performance.starts_at);
+                    (starts_at.is_none(), starts_at, entry.performance.floor.clone())
+                };
+                key(a).cmp(&key(b))
+            });
+            lineup
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, Utc, Weekday};
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::app::i18n::{t, use_locale};
+use crate::app::ical::berlin_time;
+use crate::app::model::Event;
+use crate::app::seo::PageMeta;
+
+stylance::import_style!(style, "../../style/calendar.module.scss");
+stylance::import_style!(cart_style, "../../style/cart.module.scss");
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct CalendarData {
+    /// `YYYY-MM-DD` in Berlin, so the month view doesn't depend on the
+    /// visitor's clock.
+    pub today: String,
+    /// `webcal://` address of the feed, which calendar apps open as a
+    /// subscription.
+    pub feed_url: String,
+    pub events: Vec<Event>,
+}
+
+/// Berlin wall clock time the event starts at.
+fn starts(event: &Event) -> Option<NaiveDateTime> {
+    DateTime::parse_from_rfc3339(&event.starts_at)
+        .ok()
+        .map(|t| berlin_time(t.with_timezone(&Utc)))
+}
+
+/// First of the month `by` months after `month`.
+fn shift_month(month: NaiveDate, by: i32) -> NaiveDate {
+    let index = month.year() * 12 + month.month0() as i32 + by;
+    NaiveDate::from_ymd_opt(index.div_euclid(12), index.rem_euclid(12) as u32 + 1, 1).unwrap_or(month)
+}
+
+/// Days of the weeks covering the month starting at `first`, Monday first.
+fn month_days(first: NaiveDate) -> Vec<NaiveDate> {
+    let start = first - Duration::days(first.weekday().num_days_from_monday() as i64);
+    let next = shift_month(first, 1);
+    start
+        .iter_days()
+        .take_while(|day| *day < next || day.weekday() != Weekday::Mon)
+        .collect()
+}
+
+#[leptos::component]
+pub fn CalendarPage() -> impl IntoView {
+    let calendar = Resource::new(|| (), |_| calendar_events());
+    let (list, set_list) = signal(false);
+    let locale = use_locale().get_untracked();
+
+    view! {
+        <PageMeta title=locale.text("calendar-title") description=locale.text("calendar-description")/>
+        <div class=format!("container {}", style::calendar)>
+            <div class=cart_style::row>
+                <div class="h2">{t("calendar-title")}</div>
+                <div class=cart_style::actions>
+                    <button class=cart_style::button on:click=move |_| set_list(false)>{t("calendar-month")}</button>
+                    <button class=cart_style::button on:click=move |_| set_list(true)>{t("calendar-list")}</button>
+                </div>
+            </div>
+            <Suspense fallback=move || view! { <p>{t("loading")}</p> }>
+                {move || calendar.get().map(|calendar| match calendar {
+                    Ok(CalendarData { today, feed_url, events }) => {
+                        let today = NaiveDate::parse_from_str(&today, "%Y-%m-%d").unwrap_or_default();
+                        view! {
+                            {move || if list.get() {
+                                view! { <EventList today events=events.clone()/> }.into_any()
+                            } else {
+                                view! { <MonthView today events=events.clone()/> }.into_any()
+                            }}
+                            <a class=cart_style::button href=feed_url rel="external">
+                                <i class="bi bi-calendar-week"></i>" "{t("calendar-subscribe")}
+                            </a>
+                        }.into_any()
+                    }
+                    Err(e) => view! { <span class=cart_style::error>{e.to_string()}</span> }.into_any(),
+                })}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+fn MonthView(today: NaiveDate, events: Vec<Event>) -> impl IntoView {
+    let month = RwSignal::new(today.with_day(1).unwrap_or(today));
+    let locale = use_locale();
+    let step = move |by: i32| month.update(|m| *m = shift_month(*m, by));
+
+    view! {
+        <div class=cart_style::row>
+            <button class=cart_style::button on:click=move |_| step(-1)>
+                <i class="bi bi-chevron-left"></i>
+            </button>
+            <span class=cart_style::total>
+                {move || {
+                    let month = month.get();
+                    format!("{} {}", locale.get().month(month.month0()), month.year())
+                }}
+            </span>
+            <button class=cart_style::button on:click=move |_| step(1)>
+                <i class="bi bi-chevron-right"></i>
+            </button>
+        </div>
+        <div class=style::month>
+            {(0..7).map(|day| view! { <span class=style::weekday>{move || locale.get().weekday(day)}</span> }).collect_view()}
+            {move || {
+                let first = month.get();
+                month_days(first).into_iter().map(|day| {
+                    let mut class = String::from(style::day);
+                    if day.month() != first.month() {
+                        class = format!("{class} {}", style::outside);
+                    }
+                    if day == today {
+                        class = format!("{class} {}", style::today);
+                    }
+                    let on_day = events
+                        .iter()
+                        .filter(|event| starts(event).map(|s| s.date()) == Some(day))
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    view! {
+                        <div class=class>
+                            <span>{day.day()}</span>
+                            {on_day.into_iter().map(|event| view! {
+                                <a class={if event.cancelled { style::cancelled } else { "" }}
+                                    href=format!("/events/{}", event.slug)
+                                >
+                                    {event.title}
+                                </a>
+                            }).collect_view()}
+                        </div>
+                    }
+                }).collect_view()
+            }}
+        </div>
+    }
+}
+
+#[component]
+fn EventList(today: NaiveDate, events: Vec<Event>) -> impl IntoView {
+    let locale = use_locale();
+    let upcoming: Vec<(NaiveDateTime, Event)> = events
+        .into_iter()
+        .filter_map(|event| Some((starts(&event)?, event)))
+        .filter(|(starts_at, _)| starts_at.date() >= today)
+        .collect();
+    if upcoming.is_empty() {
+        return view! { <span>{t("calendar-empty")}</span> }.into_any();
+    }
+
+    view! {
+        <table class=cart_style::table>
+            {upcoming.into_iter().map(|(starts_at, event)| view! {
+                <tr>
+                    <td>
+                        {move || {
+                            let locale = locale.get();
+                            format!("{} {}", locale.weekday(starts_at.weekday().num_days_from_monday()), locale.date_time(starts_at))
+                        }}
+                    </td>
+                    <td><a href=format!("/events/{}", event.slug)>{event.title}</a></td>
+                    <td class=cart_style::amount>
+                        {if event.cancelled {
+                            view! { <span class=cart_style::status>{t("cancelled")}</span> }.into_any()
+                        } else {
+                            view! { <AddToCalendar slug=event.slug/> }.into_any()
+                        }}
+                    </td>
+                </tr>
+            }).collect_view()}
+        </table>
+    }
+    .into_any()
+}
+
+/// Download of a single event. The `.ics` is served by actix, so the router
+/// must not handle the link.
+#[component]
+pub fn AddToCalendar(slug: String) -> impl IntoView {
+    view! {
+        <a class=cart_style::button href=format!("/events/{slug}.ics") rel="external" download=format!("{slug}.ics")>
+            <i class="bi bi-calendar-plus"></i>" "{t("calendar-add")}
+        </a>
+    }
+}
+
+#[server(CalendarEvents, "/api")]
+pub async fn calendar_events() -> Result<CalendarData, ServerFnError> {
+    let now = Utc::now();
+    let from = time::stamp(now - Duration::days(HISTORY_DAYS));
+    let events = database::get_events_since(from).await.unwrap_or_default();
+    let site_url = mail::site_url();
+    let host = site_url.split_once("://").map(|(_, host)| host).unwrap_or(&site_url);
+    Ok(CalendarData {
+        today: berlin_time(now).format("%Y-%m-%d").to_string(),
+        feed_url: format!("webcal://{host}/events.ics"),
+        events,
+    })
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use crate::app::database;
+        use crate::app::mail;
+        use crate::app::time;
+
+        /// How far back the month view can be paged.
+        const HISTORY_DAYS: i64 = 365;
+    }
+}
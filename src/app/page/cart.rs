@@ -0,0 +1,606 @@
+use leptos::{prelude::*, task::spawn_local};
+use leptos::logging::log;
+use serde::{Deserialize, Serialize};
+
+use crate::app::i18n::{t, use_locale};
+use crate::app::model::{Address, Cart, User};
+use crate::app::model::cart::{CartItem, CheckoutRequest, ItemKind, MAX_QUANTITY};
+use crate::app::model::discount::VOUCHER_VALUES;
+use crate::app::model::shipping::{DeliveryMethod, COUNTRIES, HOME_COUNTRY};
+use crate::app::seo::PageMeta;
+
+stylance::import_style!(style, "../../style/cart.module.scss");
+
+const CART_KEY: &str = "cart";
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct CheckoutResult {
+    pub order: String,
+    pub redirect_url: String,
+}
+
+/// Provides the cart as context and keeps it in local storage, so it survives
+/// reloads and the round trip to the payment provider.
+pub fn provide_cart() {
+    let cart = RwSignal::new(Cart::default());
+    provide_context(cart);
+
+    Effect::new(move |restored: Option<()>| {
+        let storage = window().local_storage().ok().flatten();
+        if restored.is_none() {
+            let stored = storage.as_ref()
+                .and_then(|s| s.get_item(CART_KEY).ok().flatten())
+                .and_then(|json| leptos::serde_json::from_str::<Cart>(&json).ok());
+            if let Some(stored) = stored {
+                cart.set(stored);
+            }
+        }
+        let json = cart.with(leptos::serde_json::to_string);
+        if let (true, Some(storage), Ok(json)) = (restored.is_some(), storage, json) {
+            let _ = storage.set_item(CART_KEY, &json);
+        }
+    });
+}
+
+pub fn use_cart() -> RwSignal<Cart> {
+    expect_context::<RwSignal<Cart>>()
+}
+
+#[leptos::component]
+pub fn CartPage() -> impl IntoView {
+    let cart = use_cart();
+    let user = use_context::<(ReadSignal<Option<User>>, WriteSignal<Option<User>>)>().map(|u| u.0);
+
+    let (email, set_email) = signal(String::new());
+    let (name, set_name) = signal(String::new());
+    let (street, set_street) = signal(String::new());
+    let (zipcode, set_zipcode) = signal(String::new());
+    let (city, set_city) = signal(String::new());
+    let (discount_code, set_discount_code) = signal(String::new());
+    let (voucher_code, set_voucher_code) = signal(String::new());
+    let delivery_method = RwSignal::new(DeliveryMethod::Pickup);
+    let shipping_address = RwSignal::new(Address::new(
+        HOME_COUNTRY.to_string(),
+        String::new(),
+        None,
+        String::new(),
+        String::new(),
+        String::new(),
+    ));
+    let has_merch = move || cart.with(|c| c.items.iter().any(|i| i.kind == ItemKind::Merch));
+    let (error_message, set_error_message) = signal(String::new());
+    let (pending, set_pending) = signal(false);
+    let locale = use_locale();
+
+    Effect::new(move |_| {
+        if let Some(user) = user.and_then(|u| u.get()) {
+            if email.get_untracked().is_empty() {
+                set_email(user.email);
+            }
+            if name.get_untracked().is_empty() {
+                set_name(format!("{} {}", user.name, user.last_name).trim().to_string());
+            }
+        }
+    });
+
+    let on_checkout = move |_| {
+        set_pending(true);
+        set_error_message(String::new());
+        // the address is optional, small invoices don't need one
+        let address = (!street.get_untracked().is_empty()).then(|| Address::new(
+            HOME_COUNTRY.to_string(),
+            street.get_untracked(),
+            None,
+            city.get_untracked(),
+            String::new(),
+            zipcode.get_untracked(),
+        ));
+        let mut request = CheckoutRequest::new(cart.get_untracked(), email.get_untracked(), name.get_untracked());
+        request.billing_address = address;
+        request.discount_code = Some(discount_code.get_untracked());
+        request.voucher_code = Some(voucher_code.get_untracked());
+        if has_merch() {
+            let method = delivery_method.get_untracked();
+            request.delivery_method = Some(method);
+            if method == DeliveryMethod::Shipping {
+                let mut address = shipping_address.get_untracked();
+                address.line2 = address.line2.filter(|l| !l.trim().is_empty());
+                request.shipping_address = Some(address);
+            }
+        }
+        spawn_local(async move {
+            match checkout(request).await {
+                Ok(result) => {
+                    cart.set(Cart::default());
+                    let _ = window().location().set_href(&result.redirect_url);
+                }
+                Err(e) => {
+                    log!("Error {:?}", e);
+                    set_error_message(e.to_string());
+                    set_pending(false);
+                }
+            }
+        });
+    };
+
+    view! {
+        <PageMeta title=locale.get_untracked().text("cart-title") noindex=true/>
+        <div class=format!("container {}", style::cart)>
+            <div class="h2">{t("cart-title")}</div>
+            <Show
+                when=move || !cart.with(|c| c.is_empty())
+                fallback=|| view! { <span>{t("cart-empty")}</span> }
+            >
+                <table class=style::table>
+                    <tr>
+                        <th>{t("column-item")}</th><th>{t("column-quantity")}</th>
+                        <th class=style::amount>{t("column-price")}</th><th class=style::amount>{t("column-sum")}</th>
+                    </tr>
+                    {move || cart.get().items.into_iter().map(|item| {
+                        let reference = item.reference.clone();
+                        let remove = item.reference.clone();
+                        view! {
+                            <tr>
+                                <td>
+                                    <i class=match item.kind {
+                                        ItemKind::Ticket => "bi bi-ticket-perforated",
+                                        ItemKind::Merch => "bi bi-bag",
+                                        ItemKind::Voucher => "bi bi-gift",
+                                        ItemKind::Shipping => "bi bi-truck",
+                                    }></i>
+                                    " "{item.name}
+                                </td>
+                                <td>
+                                    <input type="number" min="0" max=MAX_QUANTITY class=style::input_small
+                                        prop:value=item.quantity
+                                        on:change=move |e| {
+                                            let quantity = event_target_value(&e).parse().unwrap_or(0);
+                                            cart.update(|c| c.set_quantity(&reference, quantity));
+                                        }
+                                    />
+                                    <button class=style::button on:click=move |_| cart.update(|c| c.set_quantity(&remove, 0))>
+                                        <i class="bi bi-trash"></i>
+                                    </button>
+                                </td>
+                                <td class=style::amount>{move || locale.get().price(item.unit_price_cents)}</td>
+                                <td class=style::amount>{move || locale.get().price(item.unit_price_cents * item.quantity as i64)}</td>
+                            </tr>
+                        }
+                    }).collect_view()}
+                    <tr class=style::total>
+                        <td>{t("total")}</td><td></td><td></td>
+                        <td class=style::amount>{move || locale.get().price(cart.with(|c| c.total_cents()))}</td>
+                    </tr>
+                </table>
+                <div class=style::row>
+                    <input type="text" placeholder=t("name") class=style::input
+                        prop:value=name
+                        on:input=move |e| set_name(event_target_value(&e))
+                    />
+                    <input type="email" placeholder=t("email") class=style::input
+                        prop:value=email
+                        on:input=move |e| set_email(event_target_value(&e))
+                    />
+                </div>
+                <div class=style::row>
+                    <input type="text" placeholder=t("cart-street") class=style::input
+                        prop:value=street
+                        on:input=move |e| set_street(event_target_value(&e))
+                    />
+                    <input type="text" placeholder=t("zipcode") class=style::input_small
+                        prop:value=zipcode
+                        on:input=move |e| set_zipcode(event_target_value(&e))
+                    />
+                    <input type="text" placeholder=t("city") class=style::input
+                        prop:value=city
+                        on:input=move |e| set_city(event_target_value(&e))
+                    />
+                </div>
+                <Show when=has_merch>
+                    <DeliveryForm method=delivery_method address=shipping_address/>
+                </Show>
+                <div class=style::row>
+                    <input type="text" placeholder=t("cart-discount-code") class=style::input
+                        prop:value=discount_code
+                        on:input=move |e| set_discount_code(event_target_value(&e))
+                    />
+                    <input type="text" placeholder=t("cart-voucher-code") class=style::input
+                        prop:value=voucher_code
+                        on:input=move |e| set_voucher_code(event_target_value(&e))
+                    />
+                </div>
+                <div class=style::row>
+                    <span></span>
+                    <button class=style::button on:click=on_checkout disabled=pending>{t("cart-checkout")}</button>
+                </div>
+            </Show>
+            <span class=style::error>{error_message}</span>
+            <VoucherShop/>
+        </div>
+    }
+}
+
+/// Shipping or pickup for the merch in the cart.
+#[component]
+fn DeliveryForm(method: RwSignal<DeliveryMethod>, address: RwSignal<Address>) -> impl IntoView {
+    let cart = use_cart();
+    let quote = Resource::new(
+        move || (cart.get(), address.with(|a| a.country.clone()), method.get()),
+        |(cart, country, method)| async move {
+            match method {
+                DeliveryMethod::Shipping => shipping_quote(cart, country).await.map(Some),
+                DeliveryMethod::Pickup => Ok(None),
+            }
+        },
+    );
+    let shipping = move || method() == DeliveryMethod::Shipping;
+    let locale = use_locale();
+
+    view! {
+        <div class=style::row>
+            <label>
+                <input type="radio" name="delivery"
+                    prop:checked=move || method() == DeliveryMethod::Pickup
+                    on:change=move |_| method.set(DeliveryMethod::Pickup)
+                />
+                " "{t("pickup")}
+            </label>
+            <label>
+                <input type="radio" name="delivery"
+                    prop:checked=shipping
+                    on:change=move |_| method.set(DeliveryMethod::Shipping)
+                />
+                " "{t("cart-shipping")}
+            </label>
+            <Transition>
+                {move || quote.get().map(|quote| match quote {
+                    Ok(Some(cents)) => view! {
+                        <span>{move || locale.get().format("cart-shipping-cost", &[("price", &locale.get().price(cents))])}</span>
+                    }.into_any(),
+                    Ok(None) => view! { <span>{t("cart-free")}</span> }.into_any(),
+                    Err(e) => view! { <span class=style::error>{e.to_string()}</span> }.into_any(),
+                })}
+            </Transition>
+        </div>
+        <Show when=shipping>
+            <div class=style::row>
+                <select class=style::input on:change=move |e| address.update(|a| a.country = event_target_value(&e))>
+                    {COUNTRIES.iter().map(|country| view! {
+                        <option value=country.code selected=move || address.with(|a| a.country == country.code)>
+                            {country.name}
+                        </option>
+                    }).collect_view()}
+                </select>
+                <input type="text" placeholder=t("cart-state") class=style::input_small
+                    prop:value=move || address.with(|a| a.state.clone())
+                    on:input=move |e| address.update(|a| a.state = event_target_value(&e))
+                />
+            </div>
+            <div class=style::row>
+                <input type="text" placeholder=t("cart-address-line1") class=style::input
+                    prop:value=move || address.with(|a| a.line1.clone())
+                    on:input=move |e| address.update(|a| a.line1 = event_target_value(&e))
+                />
+                <input type="text" placeholder=t("cart-address-line2") class=style::input
+                    prop:value=move || address.with(|a| a.line2.clone().unwrap_or_default())
+                    on:input=move |e| address.update(|a| a.line2 = Some(event_target_value(&e)))
+                />
+            </div>
+            <div class=style::row>
+                <input type="text" placeholder=t("zipcode") class=style::input_small
+                    prop:value=move || address.with(|a| a.zipcode.clone())
+                    on:input=move |e| address.update(|a| a.zipcode = event_target_value(&e))
+                />
+                <input type="text" placeholder=t("city") class=style::input
+                    prop:value=move || address.with(|a| a.city.clone())
+                    on:input=move |e| address.update(|a| a.city = event_target_value(&e))
+                />
+            </div>
+        </Show>
+    }
+}
+
+#[component]
+fn VoucherShop() -> impl IntoView {
+    let cart = use_cart();
+    let locale = use_locale();
+
+    view! {
+        <div class="h2">{t("cart-vouchers")}</div>
+        <div class=style::actions>
+            {VOUCHER_VALUES.into_iter().map(|value| {
+                // named in the language it was added in, checkout names it again
+                let item = move || CartItem::new(
+                    ItemKind::Voucher,
+                    value.to_string(),
+                    locale.get_untracked().format("cart-voucher", &[("price", &locale.get_untracked().price(value))]),
+                    value,
+                );
+                view! {
+                    <button class=style::button on:click=move |_| cart.update(|c| c.add(item()))>
+                        {move || locale.get().price(value)}
+                    </button>
+                }
+            }).collect_view()}
+        </div>
+    }
+}
+
+/// Shipping costs for the merch in `cart` to `country`.
+#[server(ShippingQuote, "/api")]
+pub async fn shipping_quote(cart: Cart, country: String) -> Result<i64, ServerFnError> {
+    let Some(country) = find_country(&country) else {
+        return Err(ServerFnError::Args(AddressError::UnknownCountry.message(request_locale())));
+    };
+    let mut weight_grams = PACKAGING_GRAMS;
+    for item in cart.items.iter().filter(|i| i.kind == ItemKind::Merch) {
+        if let Some(variant) = database::get_variant(item.reference.clone()).await {
+            weight_grams += variant.weight_grams * item.quantity.min(MAX_QUANTITY);
+        }
+    }
+    shipping_rate(country.zone, weight_grams)
+        .ok_or(ServerFnError::Args(request_text("error-too-heavy")))
+}
+
+/// Turns the cart into a pending order. Prices, discounts and vouchers are
+/// looked up again here, what the cart says is only what the buyer saw.
+#[server(Checkout, "/api")]
+pub async fn checkout(checkout_request: CheckoutRequest) -> Result<CheckoutResult, ServerFnError> {
+    if checkout_request.validate().is_err() {
+        return Err(ServerFnError::Args(request_text("error-invalid-email")));
+    }
+    if checkout_request.cart.is_empty() {
+        return Err(ServerFnError::Args(request_text("error-cart-empty")));
+    }
+    if checkout_request.delivery_method == Some(DeliveryMethod::Shipping) {
+        let checked = checkout_request.shipping_address.as_ref().map(validate_address);
+        if let Some(Err(e)) = checked {
+            return Err(ServerFnError::Args(e.message(request_locale())));
+        }
+    }
+
+    let mut reservations = Reservations::default();
+    let order = match build_order(checkout_request, &mut reservations).await {
+        Ok(order) => order,
+        Err(e) => {
+            reservations.undo().await;
+            return Err(ServerFnError::Args(ErrorMessage::create(e)));
+        }
+    };
+
+    let redirect_url = payment::start(&order)
+        .await
+        .map_err(|e| ServerFnError::Args(ErrorMessage::create(e)))?;
+    Ok(CheckoutResult {
+        order: order.uuid,
+        redirect_url,
+    })
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use uuid::Uuid;
+        use validator::Validate;
+
+        use crate::app::auth::current_user;
+        use crate::app::database;
+        use crate::app::errors::{ErrorMessage, ResponseError, ResponseErrorTrait};
+        use crate::app::i18n::{request_locale, request_text};
+        use crate::app::model::Order;
+        use crate::app::model::discount::{normalize_code, DiscountScope, VOUCHER_VALUES};
+        use crate::app::model::event::TicketType;
+        use crate::app::model::order::LineItem;
+        use crate::app::model::product::{ProductDetails, MERCH_VAT_RATE};
+        use crate::app::model::user::Role;
+        use crate::app::model::shipping::{
+            find_country, shipping_rate, validate_address, AddressError, Delivery, PACKAGING_GRAMS,
+        };
+        use crate::app::payment;
+        use crate::app::time;
+        use crate::app::push::{self, PushMessage, Topic};
+        use crate::app::stock;
+
+        /// What checkout took so far, given back if it fails before the order exists.
+        #[derive(Default)]
+        struct Reservations {
+            tickets: Vec<(String, u32)>,
+            stock: Vec<(String, u32)>,
+            discount: Option<String>,
+            voucher: Option<(String, i64)>,
+        }
+
+        impl Reservations {
+            async fn undo(self) {
+                for (ticket_type, quantity) in self.tickets {
+                    database::release_tickets(ticket_type, quantity).await;
+                }
+                for (sku, quantity) in self.stock {
+                    stock::put_back(sku, quantity).await;
+                }
+                if let Some(code) = self.discount {
+                    database::release_discount(code).await;
+                }
+                if let Some((code, amount)) = self.voucher {
+                    database::credit_voucher(code, amount).await;
+                }
+            }
+        }
+
+        /// Tells open event pages to stop selling the type, and the admins.
+        fn announce_sold_out(ticket_type: &TicketType) {
+            push::publish(Topic::Everyone, PushMessage::SoldOut {
+                event: ticket_type.event.clone(),
+                ticket_type: ticket_type.uuid.clone(),
+            });
+            push::publish(Topic::Role(Role::Admin), PushMessage::Notification {
+                key: String::from("notice-sold-out"),
+                args: vec![(String::from("name"), ticket_type.name.clone())],
+                link: None,
+            });
+        }
+
+        async fn build_order(request: CheckoutRequest, reservations: &mut Reservations) -> Result<Order, ResponseError> {
+            let now = time::now();
+            // line names stay as the buyer saw them, on the invoice too
+            let locale = request_locale();
+            let mut items: Vec<LineItem> = Vec::new();
+            // event of every line, for event scoped discounts
+            let mut events: Vec<Option<String>> = Vec::new();
+            let mut weight_grams = 0;
+
+            // the same item on several lines counts once against the limit
+            let mut lines: Vec<CartItem> = Vec::new();
+            for item in request.cart.items {
+                match lines.iter_mut().find(|line| line.kind == item.kind && line.reference == item.reference) {
+                    Some(line) => line.quantity = line.quantity.saturating_add(item.quantity),
+                    None => lines.push(item),
+                }
+            }
+
+            for item in lines {
+                let quantity = item.quantity.min(MAX_QUANTITY);
+                if quantity == 0 {
+                    continue;
+                }
+                match item.kind {
+                    ItemKind::Ticket => {
+                        let ticket_type = database::get_ticket_type(item.reference).await
+                            .ok_or(ResponseError::TicketNotFound)?;
+                        let reserved = database::reserve_tickets(ticket_type.uuid.clone(), quantity).await?;
+                        if reserved.available() == 0 {
+                            announce_sold_out(&reserved);
+                        }
+                        reservations.tickets.push((ticket_type.uuid.clone(), quantity));
+                        events.push(Some(ticket_type.event.clone()));
+                        items.push(LineItem::new(
+                            ItemKind::Ticket,
+                            ticket_type.uuid,
+                            ticket_type.name,
+                            quantity,
+                            ticket_type.price_cents,
+                            ticket_type.vat_rate,
+                        ));
+                    }
+                    ItemKind::Merch => {
+                        let variant = database::get_variant(item.reference).await
+                            .ok_or(ResponseError::ProductNotFound)?;
+                        let product = database::get_product(variant.product.clone()).await
+                            .filter(|p| p.active)
+                            .ok_or(ResponseError::ProductNotFound)?;
+                        stock::take(variant.sku.clone(), quantity).await?;
+                        reservations.stock.push((variant.sku.clone(), quantity));
+                        weight_grams += variant.weight_grams * quantity;
+                        events.push(None);
+                        let details = ProductDetails { product, variants: Vec::new() };
+                        items.push(LineItem::new(
+                            ItemKind::Merch,
+                            variant.sku.clone(),
+                            details.item_name(&variant),
+                            quantity,
+                            variant.price_cents,
+                            details.product.vat_rate,
+                        ));
+                    }
+                    ItemKind::Shipping => continue,
+                    ItemKind::Voucher => {
+                        let value = item.reference.parse::<i64>().ok()
+                            .filter(|value| VOUCHER_VALUES.contains(value))
+                            .ok_or(ResponseError::OrderCreationFailure)?;
+                        events.push(None);
+                        // vouchers are taxed when redeemed, not when sold
+                        items.push(LineItem::new(
+                            ItemKind::Voucher,
+                            value.to_string(),
+                            locale.format("cart-voucher-item", &[("value", &locale.price(value))]),
+                            quantity,
+                            value,
+                            0,
+                        ));
+                    }
+                }
+            }
+            if items.is_empty() {
+                return Err(ResponseError::OrderCreationFailure);
+            }
+
+            let discount_code = request.discount_code.map(|c| normalize_code(&c)).filter(|c| !c.is_empty());
+            if let Some(code) = discount_code.clone() {
+                let discount = database::get_discount(code.clone()).await
+                    .filter(|d| d.is_valid_at(&now))
+                    .ok_or(ResponseError::DiscountInvalid)?;
+                let applicable: Vec<usize> = items
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, item)| match &discount.scope {
+                        DiscountScope::All => item.kind != ItemKind::Voucher,
+                        DiscountScope::Event(event) => events[*i].as_ref() == Some(event),
+                        DiscountScope::TicketType(ticket_type) => {
+                            item.kind == ItemKind::Ticket && &item.reference == ticket_type
+                        }
+                    })
+                    .map(|(i, _)| i)
+                    .collect();
+                if applicable.is_empty() || !database::use_discount(code.clone()).await {
+                    return Err(ResponseError::DiscountInvalid);
+                }
+                reservations.discount = Some(code);
+                let totals: Vec<i64> = applicable.iter().map(|i| items[*i].total_cents).collect();
+                for (i, amount) in applicable.into_iter().zip(discount.allocate(&totals)) {
+                    items[i].apply_discount(amount);
+                }
+            }
+
+            // after the discounts, which don't apply to shipping
+            let has_merch = items.iter().any(|i| i.kind == ItemKind::Merch);
+            let delivery = match (has_merch, request.delivery_method) {
+                (false, _) => None,
+                (true, None) => return Err(ResponseError::DeliveryInvalid),
+                (true, Some(DeliveryMethod::Pickup)) => Some(Delivery::pickup()),
+                (true, Some(DeliveryMethod::Shipping)) => {
+                    let address = request.shipping_address.ok_or(ResponseError::DeliveryInvalid)?;
+                    let country = validate_address(&address).map_err(|_| ResponseError::DeliveryInvalid)?;
+                    let weight_grams = weight_grams + PACKAGING_GRAMS;
+                    let cost_cents = shipping_rate(country.zone, weight_grams).ok_or(ResponseError::DeliveryInvalid)?;
+                    items.push(LineItem::new(
+                        ItemKind::Shipping,
+                        country.code.to_string(),
+                        locale.format("cart-shipping-item", &[("country", country.name)]),
+                        1,
+                        cost_cents,
+                        MERCH_VAT_RATE,
+                    ));
+                    let address = Address { country: country.code.to_string(), ..address };
+                    Some(Delivery::shipping(address, weight_grams, cost_cents))
+                }
+            };
+
+            let user = current_user().await.map(|u| u.uuid);
+            let mut order = Order::new(Uuid::new_v4().to_string(), user, request.email, items, now);
+            order.buyer_name = request.buyer_name.trim().to_string();
+            order.billing_address = request.billing_address;
+            order.discount_code = discount_code;
+            order.delivery = delivery;
+            order.locale = locale;
+
+            let voucher_code = request.voucher_code.map(|c| normalize_code(&c)).filter(|c| !c.is_empty());
+            if let Some(code) = voucher_code {
+                let voucher = database::get_voucher(code.clone()).await.ok_or(ResponseError::VoucherInvalid)?;
+                // a voucher can't buy vouchers, that would only move its balance
+                let payable: i64 = order.items
+                    .iter()
+                    .filter(|i| i.kind != ItemKind::Voucher)
+                    .map(|i| i.total_cents)
+                    .sum();
+                let amount = voucher.balance_cents.min(payable);
+                if amount <= 0 || !database::debit_voucher(code.clone(), amount).await {
+                    return Err(ResponseError::VoucherInvalid);
+                }
+                reservations.voucher = Some((code.clone(), amount));
+                order.voucher_code = Some(code);
+                order.voucher_cents = amount;
+            }
+
+            database::add_order(order).await.ok_or(ResponseError::OrderCreationFailure)
+        }
+    }
+}
@@ -0,0 +1,365 @@
+use leptos::{prelude::*, task::spawn_local};
+
+use crate::app::i18n::{t, use_locale};
+use crate::app::ical::{input_value, parse_berlin};
+use crate::app::model::discount::{
+    DiscountCode, DiscountKind, DiscountScope, GiftVoucher, RedemptionKind, RedemptionReport,
+};
+use crate::app::seo::PageMeta;
+
+stylance::import_style!(style, "../../style/cart.module.scss");
+
+#[leptos::component]
+pub fn DiscountsAdminPage() -> impl IntoView {
+    let version = RwSignal::new(0u32);
+    let codes = Resource::new(move || version.get(), |_| discount_codes());
+    let vouchers = Resource::new(move || version.get(), |_| gift_vouchers());
+    let report = Resource::new(move || version.get(), |_| redemption_report());
+
+    view! {
+        <PageMeta title=use_locale().get_untracked().text("discounts-title") noindex=true/>
+        <div class=format!("container {}", style::cart)>
+            <div class="h2">{t("discounts-codes")}</div>
+            <DiscountForm version/>
+            <Transition fallback=move || view! { <p>{t("loading")}</p> }>
+                {move || codes.get().map(|codes| match codes {
+                    Ok(codes) => view! { <DiscountTable codes version/> }.into_any(),
+                    Err(e) => view! { <span class=style::error>{e.to_string()}</span> }.into_any(),
+                })}
+            </Transition>
+            <div class="h2">{t("discounts-vouchers")}</div>
+            <VoucherForm version/>
+            <Transition fallback=move || view! { <p>{t("loading")}</p> }>
+                {move || vouchers.get().map(|vouchers| match vouchers {
+                    Ok(vouchers) => view! { <VoucherTable vouchers/> }.into_any(),
+                    Err(e) => view! { <span class=style::error>{e.to_string()}</span> }.into_any(),
+                })}
+            </Transition>
+            <div class="h2">{t("discounts-redemptions")}</div>
+            <Transition fallback=move || view! { <p>{t("loading")}</p> }>
+                {move || report.get().map(|report| match report {
+                    Ok(report) => view! { <RedemptionTable report/> }.into_any(),
+                    Err(e) => view! { <span class=style::error>{e.to_string()}</span> }.into_any(),
+                })}
+            </Transition>
+        </div>
+    }
+}
+
+#[component]
+fn DiscountForm(version: RwSignal<u32>) -> impl IntoView {
+    let (code, set_code) = signal(String::new());
+    let (kind, set_kind) = signal(String::from("percent"));
+    let (value, set_value) = signal(String::new());
+    let (scope, set_scope) = signal(String::from("all"));
+    let (scope_id, set_scope_id) = signal(String::new());
+    let (max_uses, set_max_uses) = signal(String::new());
+    let (valid_from, set_valid_from) = signal(String::new());
+    let (valid_until, set_valid_until) = signal(String::new());
+    let (error_message, set_error_message) = signal(String::new());
+    let locale = use_locale();
+
+    let on_submit = move |_| {
+        set_error_message(String::new());
+        let kind = match (kind.get_untracked().as_str(), value.get_untracked().trim().replace(',', ".").parse::<f64>()) {
+            ("percent", Ok(percent)) => DiscountKind::Percent(percent as u32),
+            (_, Ok(euros)) => DiscountKind::FixedCents((euros * 100.0).round() as i64),
+            _ => {
+                set_error_message(locale.get_untracked().text("discounts-invalid-value").to_string());
+                return;
+            }
+        };
+        let scope = match scope.get_untracked().as_str() {
+            "event" => DiscountScope::Event(scope_id.get_untracked().trim().to_string()),
+            "ticket_type" => DiscountScope::TicketType(scope_id.get_untracked().trim().to_string()),
+            _ => DiscountScope::All,
+        };
+        let optional = |s: String| (!s.is_empty()).then_some(s);
+        let mut discount = DiscountCode::new(code.get_untracked(), kind, scope);
+        discount.max_uses = max_uses.get_untracked().trim().parse().ok();
+        discount.valid_from = optional(valid_from.get_untracked());
+        discount.valid_until = optional(valid_until.get_untracked());
+        spawn_local(async move {
+            match add_discount_code(discount).await {
+                Ok(_) => {
+                    set_code(String::new());
+                    version.update(|v| *v += 1);
+                }
+                Err(e) => set_error_message(e.to_string()),
+            }
+        });
+    };
+
+    view! {
+        <div class=style::row>
+            <input type="text" placeholder=t("discounts-code") class=style::input
+                prop:value=code
+                on:input=move |e| set_code(event_target_value(&e))
+            />
+            <select class=style::input on:change=move |e| set_kind(event_target_value(&e))>
+                <option value="percent">{t("discounts-percent")}</option>
+                <option value="fixed">{t("discounts-euro")}</option>
+            </select>
+            <input type="text" placeholder=t("discounts-value") class=style::input_small
+                prop:value=value
+                on:input=move |e| set_value(event_target_value(&e))
+            />
+        </div>
+        <div class=style::row>
+            <select class=style::input on:change=move |e| set_scope(event_target_value(&e))>
+                <option value="all">{t("discounts-scope-all")}</option>
+                <option value="event">{t("discounts-scope-event")}</option>
+                <option value="ticket_type">{t("discounts-scope-ticket-type")}</option>
+            </select>
+            <input type="text" placeholder=t("discounts-scope-id") class=style::input
+                prop:value=scope_id
+                disabled=move || scope() == "all"
+                on:input=move |e| set_scope_id(event_target_value(&e))
+            />
+            <input type="number" placeholder=t("discounts-max-uses") class=style::input_small
+                prop:value=max_uses
+                on:input=move |e| set_max_uses(event_target_value(&e))
+            />
+        </div>
+        <div class=style::row>
+            <input type="datetime-local" class=style::input
+                prop:value=valid_from
+                on:input=move |e| set_valid_from(event_target_value(&e))
+            />
+            <input type="datetime-local" class=style::input
+                prop:value=valid_until
+                on:input=move |e| set_valid_until(event_target_value(&e))
+            />
+            <button class=style::button on:click=on_submit>{t("admin-create")}</button>
+        </div>
+        <span class=style::error>{error_message}</span>
+    }
+}
+
+#[component]
+fn DiscountTable(codes: Vec<DiscountCode>, version: RwSignal<u32>) -> impl IntoView {
+    let locale = use_locale();
+    let on_toggle = Callback::new(move |(code, active): (String, bool)| {
+        spawn_local(async move {
+            if set_discount_code_active(code, active).await.is_ok() {
+                version.update(|v| *v += 1);
+            }
+        });
+    });
+
+    view! {
+        <table class=style::table>
+            <tr>
+                <th>{t("discounts-code")}</th><th>{t("discounts-discount")}</th><th>{t("discounts-scope")}</th>
+                <th>{t("discounts-used")}</th><th>{t("discounts-period")}</th><th></th>
+            </tr>
+            {codes.into_iter().map(|discount| {
+                let code = discount.code.clone();
+                let active = discount.active;
+                view! {
+                    <tr>
+                        <td>{discount.code}</td>
+                        <td>{move || match discount.kind {
+                            DiscountKind::Percent(percent) => format!("{percent} %"),
+                            DiscountKind::FixedCents(cents) => locale.get().price(cents),
+                        }}</td>
+                        <td>{move || {
+                            let locale = locale.get();
+                            let short = |id: &String| id.get(..8).unwrap_or(id.as_str()).to_string();
+                            match &discount.scope {
+                                DiscountScope::All => locale.text("discounts-scope-all").to_string(),
+                                DiscountScope::Event(event) => format!("{} {}", locale.text("discounts-scope-event"), short(event)),
+                                DiscountScope::TicketType(id) => format!("{} {}", locale.text("discounts-scope-ticket-type"), short(id)),
+                            }
+                        }}</td>
+                        <td>{match discount.max_uses {
+                            Some(max) => format!("{} / {}", discount.uses, max),
+                            None => discount.uses.to_string(),
+                        }}</td>
+                        <td>{format!(
+                            "{} – {}",
+                            input_value(&discount.valid_from).replace('T', " "),
+                            input_value(&discount.valid_until).replace('T', " "),
+                        )}</td>
+                        <td>
+                            <button class=style::button on:click=move |_| on_toggle.run((code.clone(), !active))>
+                                {t(if active { "discounts-deactivate" } else { "discounts-activate" })}
+                            </button>
+                        </td>
+                    </tr>
+                }
+            }).collect_view()}
+        </table>
+    }
+}
+
+#[component]
+fn VoucherForm(version: RwSignal<u32>) -> impl IntoView {
+    let (value, set_value) = signal(String::new());
+    let (error_message, set_error_message) = signal(String::new());
+    let locale = use_locale();
+
+    let on_submit = move |_| {
+        let Ok(euros) = value.get_untracked().trim().replace(',', ".").parse::<f64>() else {
+            set_error_message(locale.get_untracked().text("discounts-invalid-value").to_string());
+            return;
+        };
+        spawn_local(async move {
+            match add_gift_voucher((euros * 100.0).round() as i64).await {
+                Ok(_) => {
+                    set_value(String::new());
+                    set_error_message(String::new());
+                    version.update(|v| *v += 1);
+                }
+                Err(e) => set_error_message(e.to_string()),
+            }
+        });
+    };
+
+    view! {
+        <div class=style::row>
+            <input type="text" placeholder=t("discounts-value-euro") class=style::input
+                prop:value=value
+                on:input=move |e| set_value(event_target_value(&e))
+            />
+            <button class=style::button on:click=on_submit>{t("discounts-create-voucher")}</button>
+        </div>
+        <span class=style::error>{error_message}</span>
+    }
+}
+
+#[component]
+fn VoucherTable(vouchers: Vec<GiftVoucher>) -> impl IntoView {
+    let locale = use_locale();
+
+    view! {
+        <table class=style::table>
+            <tr>
+                <th>{t("discounts-code")}</th><th>{t("discounts-created")}</th><th>{t("refunds-order")}</th>
+                <th class=style::amount>{t("discounts-value")}</th><th class=style::amount>{t("discounts-balance")}</th>
+            </tr>
+            {vouchers.into_iter().map(|voucher| view! {
+                <tr>
+                    <td>{voucher.code}</td>
+                    <td>{move || parse_berlin(&voucher.created_at).map(|time| locale.get().date(time.date())).unwrap_or_default()}</td>
+                    <td>{voucher.order.map(|order| view! {
+                        <a href=format!("/orders/{order}")>{order.get(..8).unwrap_or_default().to_string()}</a>
+                    })}</td>
+                    <td class=style::amount>{move || locale.get().price(voucher.initial_cents)}</td>
+                    <td class=style::amount>{move || locale.get().price(voucher.balance_cents)}</td>
+                </tr>
+            }).collect_view()}
+        </table>
+    }
+}
+
+#[component]
+fn RedemptionTable(report: Vec<RedemptionReport>) -> impl IntoView {
+    let locale = use_locale();
+
+    view! {
+        <table class=style::table>
+            <tr>
+                <th>{t("discounts-code")}</th><th>{t("discounts-kind")}</th><th>{t("discounts-orders")}</th>
+                <th class=style::amount>{t("column-sum")}</th>
+            </tr>
+            {report.into_iter().map(|row| view! {
+                <tr>
+                    <td>{row.code}</td>
+                    <td>{t(match row.kind {
+                        RedemptionKind::Discount => "discounts-discount",
+                        RedemptionKind::Voucher => "discounts-voucher",
+                    })}</td>
+                    <td>{row.redemptions}</td>
+                    <td class=style::amount>{move || locale.get().price(row.total_cents)}</td>
+                </tr>
+            }).collect_view()}
+        </table>
+    }
+}
+
+#[server(DiscountCodes, "/api")]
+pub async fn discount_codes() -> Result<Vec<DiscountCode>, ServerFnError> {
+    require_role(Role::Admin).await?;
+    Ok(database::get_discounts().await.unwrap_or_default())
+}
+
+#[server(AddDiscountCode, "/api")]
+pub async fn add_discount_code(discount: DiscountCode) -> Result<DiscountCode, ServerFnError> {
+    require_role(Role::Admin).await?;
+    let mut discount = DiscountCode {
+        code: normalize_code(&discount.code),
+        uses: 0,
+        active: true,
+        ..discount
+    };
+    if discount.validate().is_err() {
+        return Err(ServerFnError::Args(request_text("error-code-length")));
+    }
+    let scope_empty = matches!(&discount.scope, DiscountScope::Event(id) | DiscountScope::TicketType(id) if id.is_empty());
+    let value_invalid = match discount.kind {
+        DiscountKind::Percent(percent) => percent == 0 || percent > 100,
+        DiscountKind::FixedCents(cents) => cents <= 0,
+    };
+    if scope_empty || value_invalid {
+        return Err(ServerFnError::Args(ErrorMessage::create(ResponseError::DiscountInvalid)));
+    }
+    if database::get_discount(discount.code.clone()).await.is_some() {
+        return Err(ServerFnError::Args(request_text("error-code-taken")));
+    }
+    let time = |time: Option<String>| match time.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+        Some(time) => from_input(time).map(Some).ok_or(ServerFnError::Args(ErrorMessage::create(ResponseError::DiscountInvalid))),
+        None => Ok(None),
+    };
+    discount.valid_from = time(discount.valid_from)?;
+    discount.valid_until = time(discount.valid_until)?;
+    database::add_discount(discount)
+        .await
+        .ok_or(ServerFnError::Args(ErrorMessage::create(ResponseError::DiscountInvalid)))
+}
+
+#[server(SetDiscountCodeActive, "/api")]
+pub async fn set_discount_code_active(code: String, active: bool) -> Result<(), ServerFnError> {
+    require_role(Role::Admin).await?;
+    database::set_discount_active(code, active)
+        .await
+        .map_err(|e| ServerFnError::Args(ErrorMessage::create(e)))
+}
+
+#[server(GiftVouchers, "/api")]
+pub async fn gift_vouchers() -> Result<Vec<GiftVoucher>, ServerFnError> {
+    require_role(Role::Admin).await?;
+    Ok(database::get_vouchers().await.unwrap_or_default())
+}
+
+#[server(AddGiftVoucher, "/api")]
+pub async fn add_gift_voucher(value_cents: i64) -> Result<GiftVoucher, ServerFnError> {
+    require_role(Role::Admin).await?;
+    if value_cents <= 0 {
+        return Err(ServerFnError::Args(ErrorMessage::create(ResponseError::VoucherInvalid)));
+    }
+    database::add_voucher(GiftVoucher::new(value_cents, None, time::now()))
+        .await
+        .ok_or(ServerFnError::Args(ErrorMessage::create(ResponseError::VoucherInvalid)))
+}
+
+#[server(RedemptionReports, "/api")]
+pub async fn redemption_report() -> Result<Vec<RedemptionReport>, ServerFnError> {
+    require_role(Role::Admin).await?;
+    Ok(database::get_redemption_report().await.unwrap_or_default())
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use validator::Validate;
+
+        use crate::app::auth::require_role;
+        use crate::app::database;
+        use crate::app::errors::{ErrorMessage, ResponseError, ResponseErrorTrait};
+        use crate::app::i18n::request_text;
+        use crate::app::ical::from_input;
+        use crate::app::model::discount::normalize_code;
+        use crate::app::model::user::Role;
+        use crate::app::time;
+    }
+}
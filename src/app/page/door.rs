@@ -0,0 +1,438 @@
+use leptos::{prelude::*, task::spawn_local};
+use leptos::logging::log;
+use leptos_router::hooks::use_params_map;
+use std::time::Duration;
+
+use crate::app::i18n::{t, use_locale};
+use crate::app::model::{Event, Ticket};
+use crate::app::model::ticket::{DoorCounts, ScanOutcome, ScanResult, TicketStatus};
+use crate::app::model::door::{DoorMode, DoorSnapshot, LocalScan, SyncReport};
+use crate::app::page::guest_list::GuestSearch;
+use crate::app::page::occupancy::OccupancyOverview;
+use crate::app::scanner::Scanner;
+use crate::app::seo::PageMeta;
+
+stylance::import_style!(style, "../../style/door.module.scss");
+
+const COUNTS_REFRESH: Duration = Duration::from_secs(5);
+
+/// Catalogue key of a ticket's status, for staff lists.
+pub fn status_key(status: TicketStatus) -> &'static str {
+    match status {
+        TicketStatus::Valid => "ticket-valid",
+        TicketStatus::CheckedIn => "ticket-checked-in",
+        TicketStatus::Out => "ticket-out",
+        TicketStatus::Refunded => "ticket-refunded",
+        TicketStatus::Cancelled => "ticket-cancelled",
+    }
+}
+
+#[leptos::component]
+pub fn DoorPage() -> impl IntoView {
+    let params = use_params_map();
+    let slug = move || params.read().get("event").unwrap_or_default();
+    let event = Resource::new(slug, door_event);
+
+    view! {
+        <PageMeta title=use_locale().get_untracked().text("door-title") noindex=true/>
+        <div class=format!("container {}", style::door)>
+            <Suspense fallback=move || view! { <p>{t("loading")}</p> }>
+                {move || event.get().map(|event| match event {
+                    Ok(event) => view! { <DoorControls event/> }.into_any(),
+                    Err(e) => view! { <span class=style::error_label>{e.to_string()}</span> }.into_any(),
+                })}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+fn DoorControls(event: Event) -> impl IntoView {
+    let uuid = event.uuid.clone();
+    let counts = Resource::new(|| (), {
+        let uuid = uuid.clone();
+        move |_| door_counts(uuid.clone())
+    });
+
+    Effect::new(move |_| {
+        if let Ok(handle) = set_interval_with_handle(move || counts.refetch(), COUNTS_REFRESH) {
+            on_cleanup(move || handle.clear());
+        }
+    });
+
+    let (mode, set_mode) = signal(DoorMode::In);
+    let (last_scan, set_last_scan) = signal::<Option<ScanResult>>(None);
+    let (error_message, set_error_message) = signal(String::new());
+
+    let on_scan = Callback::new({
+        let uuid = uuid.clone();
+        move |code: String| {
+            let event = uuid.clone();
+            let mode = mode.get_untracked();
+            spawn_local(async move {
+                let result = match mode {
+                    DoorMode::In => door_scan(event, code).await,
+                    DoorMode::Out => door_stamp_out(event, code).await,
+                };
+                match result {
+                    Ok(result) => {
+                        set_error_message(String::new());
+                        set_last_scan(Some(result));
+                        counts.refetch();
+                    }
+                    Err(e) => {
+                        log!("Error {:?}", e);
+                        set_error_message(e.to_string());
+                    }
+                }
+            });
+        }
+    });
+
+    view! {
+        <div class="h2">{event.title}</div>
+        <Transition fallback=move || view! { <CountsBar counts=DoorCounts::default()/> }>
+            {move || counts.get().map(|counts| view! { <CountsBar counts=counts.unwrap_or_default()/> })}
+        </Transition>
+        <OccupancyOverview/>
+        <div class=style::modes>
+            <button
+                class=move || if mode() == DoorMode::In { style::mode_active } else { style::mode }
+                on:click=move |_| set_mode(DoorMode::In)
+            >{t("door-mode-in")}</button>
+            <button
+                class=move || if mode() == DoorMode::Out { style::mode_active } else { style::mode }
+                on:click=move |_| set_mode(DoorMode::Out)
+            >{t("door-mode-out")}</button>
+        </div>
+        <Scanner on_scan/>
+        <span class=style::error_label>{error_message}</span>
+        {move || last_scan().map(|result| view! { <ScanPanel result/> })}
+        <NameSearch event=uuid.clone() on_scan/>
+        <GuestSearch event=uuid/>
+    }
+}
+
+#[component]
+pub fn CountsBar(counts: DoorCounts) -> impl IntoView {
+    view! {
+        <div class=style::counts>
+            <div class=style::count>
+                <span class=style::count_value>{counts.inside}</span>
+                <span>{t("door-inside")}</span>
+            </div>
+            <div class=style::count>
+                <span class=style::count_value>{counts.out}</span>
+                <span>{t("door-out")}</span>
+            </div>
+            <div class=style::count>
+                <span class=style::count_value>{counts.expected}</span>
+                <span>{t("door-expected")}</span>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+pub fn ScanPanel(result: ScanResult) -> impl IntoView {
+    let locale = use_locale();
+    let (class, label) = match result.outcome {
+        ScanOutcome::Admitted => (style::admitted, "scan-admitted"),
+        ScanOutcome::ReEntry => (style::admitted, "scan-re-entry"),
+        ScanOutcome::StampedOut => (style::stamped, "scan-stamped-out"),
+        ScanOutcome::Provisional => (style::admitted, "scan-provisional"),
+        ScanOutcome::Duplicate => (style::duplicate, "scan-duplicate"),
+        ScanOutcome::Refunded => (style::rejected, "scan-refunded"),
+        ScanOutcome::Cancelled => (style::rejected, "scan-cancelled"),
+        ScanOutcome::Unknown => (style::rejected, "scan-unknown"),
+    };
+
+    view! {
+        <div class=format!("{} {}", style::panel, class)>
+            <span class=style::panel_label>{t(label)}</span>
+            <span>{result.holder_name.unwrap_or_default()}</span>
+            <span class=style::panel_code>{result.code}</span>
+            {result.checked_in_at.map(|at| view! { <span>{move || locale.get().format("scan-since", &[("time", &at)])}</span> })}
+        </div>
+    }
+}
+
+/// Lookup for admissions that are on the list by name only.
+#[component]
+pub fn NameSearch(event: String, on_scan: Callback<String>) -> impl IntoView {
+    let (query, set_query) = signal(String::new());
+    let (new_name, set_new_name) = signal(String::new());
+    let results = Resource::new(query, {
+        let event = event.clone();
+        move |query| door_search(event.clone(), query)
+    });
+
+    let on_add = move |_| {
+        let name = new_name.get_untracked();
+        let event = event.clone();
+        spawn_local(async move {
+            match door_add_name(event, name).await {
+                Ok(ticket) => {
+                    set_new_name(String::new());
+                    set_query(ticket.holder_name);
+                }
+                Err(e) => log!("Error {:?}", e),
+            }
+        });
+    };
+
+    view! {
+        <div class=style::names>
+            <input type="search" placeholder=t("door-search")
+                prop:value=query
+                on:input=move |e| set_query(event_target_value(&e))
+                class=style::input
+            />
+            <Transition fallback=move || ()>
+                {move || results.get().map(|results| {
+                    results.unwrap_or_default().into_iter().map(|ticket| {
+                        let code = ticket.code.clone();
+                        let admissible = matches!(ticket.status, TicketStatus::Valid | TicketStatus::Out);
+                        view! {
+                            <div class=style::name_row>
+                                <span>{ticket.holder_name}</span>
+                                <span>{t(status_key(ticket.status))}</span>
+                                {admissible.then(|| view! {
+                                    <button class=style::button on:click=move |_| on_scan.run(code.clone())>
+                                        {t("door-check-in")}
+                                    </button>
+                                })}
+                            </div>
+                        }
+                    }).collect_view()
+                })}
+            </Transition>
+            <div class=style::name_row>
+                <input type="text" placeholder=t("door-add-name")
+                    prop:value=new_name
+                    on:input=move |e| set_new_name(event_target_value(&e))
+                    class=style::input
+                />
+                <button class=style::button on:click=on_add>"+"</button>
+            </div>
+        </div>
+    }
+}
+
+#[server(DoorEvent, "/api")]
+pub async fn door_event(slug: String) -> Result<Event, ServerFnError> {
+    require_role(Role::Staff).await?;
+    match database::get_event_by_slug(slug).await {
+        Some(event) => Ok(event),
+        None => Err(ServerFnError::Args(ErrorMessage::create(ResponseError::EventNotFound))),
+    }
+}
+
+#[server(DoorCountsFor, "/api")]
+pub async fn door_counts(event: String) -> Result<DoorCounts, ServerFnError> {
+    require_role(Role::Staff).await?;
+    match database::get_door_counts(event).await {
+        Some(counts) => Ok(counts),
+        None => Err(ServerFnError::Args(ErrorMessage::create(ResponseError::EventNotFound))),
+    }
+}
+
+#[server(DoorScan, "/api")]
+pub async fn door_scan(event: String, code: String) -> Result<ScanResult, ServerFnError> {
+    require_role(Role::Staff).await?;
+    let code = code.trim().to_uppercase();
+
+    let before = database::check_in_ticket(event.clone(), code.clone(), time::now(), String::from(ONLINE_DEVICE)).await
+        .map_err(|e| ServerFnError::Args(ErrorMessage::create(e)))?;
+    if let Some(ticket) = before {
+        let outcome = match ticket.status {
+            TicketStatus::Out => ScanOutcome::ReEntry,
+            _ => ScanOutcome::Admitted,
+        };
+        return Ok(ScanResult::new(code, outcome, Some(&ticket)));
+    }
+
+    let ticket = database::get_ticket_by_code(event, code.clone()).await;
+    let outcome = match ticket.as_ref().map(|t| t.status) {
+        Some(TicketStatus::Refunded) => ScanOutcome::Refunded,
+        Some(TicketStatus::Cancelled) => ScanOutcome::Cancelled,
+        // an admissible status here means another device admitted it first
+        Some(_) => ScanOutcome::Duplicate,
+        None => ScanOutcome::Unknown,
+    };
+    Ok(ScanResult::new(code, outcome, ticket.as_ref()))
+}
+
+#[server(DoorStampOut, "/api")]
+pub async fn door_stamp_out(event: String, code: String) -> Result<ScanResult, ServerFnError> {
+    require_role(Role::Staff).await?;
+    let code = code.trim().to_uppercase();
+    match database::stamp_out_ticket(event, code.clone()).await {
+        Ok(ticket) => Ok(ScanResult::new(code, ScanOutcome::StampedOut, Some(&ticket))),
+        Err(e) => Err(ServerFnError::Args(ErrorMessage::create(e))),
+    }
+}
+
+#[server(DoorSearch, "/api")]
+pub async fn door_search(event: String, query: String) -> Result<Vec<Ticket>, ServerFnError> {
+    require_role(Role::Staff).await?;
+    if query.trim().len() < 2 {
+        return Ok(Vec::new());
+    }
+    Ok(database::search_tickets_by_name(event, query.trim().to_owned()).await.unwrap_or_default())
+}
+
+#[server(DoorAddName, "/api")]
+pub async fn door_add_name(event: String, name: String) -> Result<Ticket, ServerFnError> {
+    require_role(Role::Staff).await?;
+    let ticket = Ticket::new(
+        Uuid::new_v4().to_string(),
+        event,
+        Ticket::generate_code(),
+        name.trim().to_owned(),
+        TicketKind::Name,
+    );
+    if ticket.validate().is_err() {
+        return Err(ServerFnError::Args(request_text("error-name-required")));
+    }
+    match database::add_ticket(ticket).await {
+        Some(ticket) => Ok(ticket),
+        None => Err(ServerFnError::Args(request_text("error-door-name-failed"))),
+    }
+}
+
+/// Hands a door device the admission list and the hashes of the codes
+/// reserved for tickets sold after it.
+#[server(DoorPreload, "/api")]
+pub async fn door_preload(slug: String) -> Result<DoorSnapshot, ServerFnError> {
+    require_role(Role::Staff).await?;
+    let event = match database::get_event_by_slug(slug).await {
+        Some(event) => event,
+        None => return Err(ServerFnError::Args(ErrorMessage::create(ResponseError::EventNotFound))),
+    };
+    let codes = match database::reserve_door_codes(event.uuid.clone(), RESERVED_CODES).await {
+        Some(codes) => codes.iter().map(|code| signing::digest(code)).collect(),
+        None => return Err(ServerFnError::Args(request_text("error-door-codes-failed"))),
+    };
+    let tickets = database::get_tickets_for_event(event.uuid.clone()).await.unwrap_or_default();
+    Ok(DoorSnapshot { event, tickets, codes, taken_at: time::now() })
+}
+
+/// Applies scans recorded offline. Scans are replayed in timestamp order, so
+/// the result does not depend on which device happens to sync first.
+#[server(DoorSync, "/api")]
+pub async fn door_sync(event: String, scans: Vec<LocalScan>) -> Result<SyncReport, ServerFnError> {
+    require_role(Role::Staff).await?;
+    let mut scans = scans;
+    scans.sort_by_key(|scan| scan.order_key());
+
+    let mut report = SyncReport::default();
+    for scan in scans.into_iter().filter(|scan| scan.event == event) {
+        if database::has_door_scan(scan.uuid.clone()).await {
+            // already synced on an earlier attempt
+            continue;
+        }
+        // recorded only once applied, so a failed scan is tried again on the next sync
+        match apply_offline_scan(scan.clone()).await {
+            Ok(None) => report.applied += 1,
+            Ok(Some(conflict)) => {
+                database::add_scan_conflict(conflict.clone()).await;
+                report.conflicts.push(conflict);
+            }
+            Err(e) => return Err(ServerFnError::Args(ErrorMessage::create(e))),
+        }
+        database::add_door_scan(scan).await;
+    }
+    Ok(report)
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use crate::app::auth::require_role;
+        use crate::app::database;
+        use crate::app::errors::{ ErrorMessage, ResponseError, ResponseErrorTrait };
+        use crate::app::i18n::request_text;
+        use crate::app::model::user::Role;
+        use crate::app::model::ticket::TicketKind;
+        use crate::app::model::door::{ConflictKind, ScanConflict};
+        use crate::app::signing;
+        use crate::app::time;
+        use uuid::Uuid;
+        use validator::Validate;
+
+        /// Device name recorded for scans made through the online door page.
+        const ONLINE_DEVICE: &str = "online";
+        /// Codes held back per event for sales while door devices are offline.
+        const RESERVED_CODES: usize = 500;
+
+        async fn apply_offline_scan(scan: LocalScan) -> Result<Option<ScanConflict>, ResponseError> {
+            if scan.mode == DoorMode::Out {
+                // a stamp for a ticket that is no longer inside changes nothing
+                let _ = database::stamp_out_ticket(scan.event, scan.code).await;
+                return Ok(None);
+            }
+
+            let before = database::check_in_ticket(
+                scan.event.clone(),
+                scan.code.clone(),
+                scan.scanned_at.clone(),
+                scan.device.clone(),
+            ).await?;
+            if before.is_some() {
+                return Ok(None);
+            }
+
+            let ticket = database::get_ticket_by_code(scan.event.clone(), scan.code.clone()).await;
+            let kind = match ticket.as_ref().map(|t| t.status) {
+                Some(TicketStatus::Refunded) => ConflictKind::Refunded,
+                Some(TicketStatus::Cancelled) => ConflictKind::Cancelled,
+                Some(_) => ConflictKind::DuplicateEntry,
+                None => ConflictKind::Unknown,
+            };
+
+            let first_device = ticket.as_ref().and_then(|t| t.checked_in_by.clone());
+            let first_at = ticket.as_ref().and_then(|t| t.checked_in_at.clone());
+            let holder_name = ticket.map(|t| t.holder_name);
+
+            if first_at.as_ref() == Some(&scan.scanned_at) && first_device.as_ref() == Some(&scan.device) {
+                // the admission is this very scan, applied before recording it failed
+                return Ok(None);
+            }
+
+            let earlier = match (&first_at, &first_device) {
+                (Some(at), Some(by)) => (&scan.scanned_at, &scan.device) < (at, by),
+                _ => true,
+            };
+            if kind == ConflictKind::DuplicateEntry && earlier {
+                // this scan becomes the admission and the one recorded so far the duplicate
+                database::set_check_in_origin(
+                    scan.event.clone(),
+                    scan.code.clone(),
+                    scan.scanned_at.clone(),
+                    scan.device.clone(),
+                ).await?;
+                return Ok(Some(ScanConflict {
+                    code: scan.code,
+                    kind,
+                    holder_name,
+                    scan: scan.uuid,
+                    device: first_device.unwrap_or_default(),
+                    scanned_at: first_at.unwrap_or_default(),
+                    winner_device: Some(scan.device),
+                    winner_at: Some(scan.scanned_at),
+                }));
+            }
+
+            Ok(Some(ScanConflict {
+                code: scan.code,
+                kind,
+                holder_name,
+                scan: scan.uuid,
+                device: scan.device,
+                scanned_at: scan.scanned_at,
+                winner_device: first_device,
+                winner_at: first_at,
+            }))
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use leptos::{prelude::*, task::spawn_local};
+use leptos_router::hooks::use_params_map;
+use serde::{Deserialize, Serialize};
+
+use crate::app::i18n::{t, use_locale, Locale};
+use crate::app::ical::parse_berlin;
+use crate::app::model::Event;
+use crate::app::model::cart::{CartItem, ItemKind};
+use crate::app::model::artist::LineupEntry;
+use crate::app::model::event::TicketType;
+use crate::app::page::artist::Lineup;
+use crate::app::page::calendar::AddToCalendar;
+use crate::app::page::cart::use_cart;
+use crate::app::page::waitlist::WaitlistPanel;
+use crate::app::push::{use_push, PushMessage};
+use crate::app::seo::{JsonLd, PageMeta};
+
+stylance::import_style!(style, "../../style/cart.module.scss");
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct EventDetails {
+    pub event: Event,
+    pub ticket_types: Vec<TicketType>,
+    /// Running order, empty until announced.
+    pub lineup: Vec<LineupEntry>,
+    /// schema.org `MusicEvent` of the event.
+    pub json_ld: String,
+}
+
+/// Start in Berlin time, for descriptions.
+fn event_date(event: &Event, locale: Locale) -> String {
+    parse_berlin(&event.starts_at).map(|t| locale.date_time(t)).unwrap_or_default()
+}
+
+/// Doors and start in Berlin time.
+fn event_times(event: &Event, locale: Locale) -> String {
+    let time = |time: &str| parse_berlin(time).map(|t| locale.time(t)).unwrap_or_default();
+    locale.format("event-times", &[("doors", &time(&event.doors_at)), ("starts", &time(&event.starts_at))])
+}
+
+#[leptos::component]
+pub fn EventPage() -> impl IntoView {
+    let params = use_params_map();
+    let slug = move || params.read().get("slug").unwrap_or_default();
+    let details = Resource::new(slug, event_details);
+    let locale = use_locale();
+
+    view! {
+        <div class=format!("container {}", style::cart)>
+            <Suspense fallback=move || view! { <p>{t("loading")}</p> }>
+                {move || details.get().map(|details| match details {
+                    Ok(details) => {
+                        let description = locale.get_untracked().format("event-description", &[
+                            ("title", &details.event.title),
+                            ("date", &event_date(&details.event, locale.get_untracked())),
+                        ]);
+                        let event = details.event.clone();
+                        view! {
+                            <PageMeta
+                                title=details.event.title.clone()
+                                path=format!("/events/{}", details.event.slug)
+                                description
+                                image=details.event.poster.clone().unwrap_or_default()
+                            />
+                            <JsonLd json=details.json_ld/>
+                            <div class="h2">{details.event.title}</div>
+                            <span>{move || event_times(&event, locale.get())}</span>
+                            <Lineup lineup=details.lineup/>
+                            {if details.event.cancelled {
+                                view! { <span class=style::status>{t("cancelled")}</span> }.into_any()
+                            } else {
+                                let sold_out = !details.ticket_types.is_empty()
+                                    && details.ticket_types.iter().all(|t| t.available() == 0);
+                                view! {
+                                    <AddToCalendar slug=details.event.slug/>
+                                    <TicketSelector ticket_types=details.ticket_types/>
+                                    {sold_out.then(|| view! { <WaitlistPanel event=details.event.uuid/> })}
+                                }.into_any()
+                            }}
+                        }.into_any()
+                    }
+                    Err(e) => view! { <span class=style::error>{e.to_string()}</span> }.into_any(),
+                })}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+fn TicketSelector(ticket_types: Vec<TicketType>) -> impl IntoView {
+    let cart = use_cart();
+    let locale = use_locale();
+    // types that sold out while the page is open
+    let sold_since = RwSignal::new(Vec::<String>::new());
+    use_push().on(move |message| {
+        if let PushMessage::SoldOut { ticket_type, .. } = message {
+            sold_since.update(|s| s.push(ticket_type));
+        }
+    });
+
+    view! {
+        <table class=style::table>
+            {ticket_types.into_iter().map(|ticket_type| {
+                let sold_out = {
+                    let uuid = ticket_type.uuid.clone();
+                    let sold_before = ticket_type.available() == 0;
+                    move || sold_before || sold_since.with(|s| s.contains(&uuid))
+                };
+                let item = CartItem::new(
+                    ItemKind::Ticket,
+                    ticket_type.uuid.clone(),
+                    ticket_type.name.clone(),
+                    ticket_type.price_cents,
+                );
+                view! {
+                    <tr>
+                        <td>{ticket_type.name}</td>
+                        <td class=style::amount>{move || locale.get().price(ticket_type.price_cents)}</td>
+                        <td class=style::amount>
+                            <button class=style::button disabled=sold_out.clone()
+                                on:click=move |_| cart.update(|c| c.add(item.clone()))
+                            >
+                                {move || locale.get().text(if sold_out() { "sold-out" } else { "add-to-cart" })}
+                            </button>
+                        </td>
+                    </tr>
+                }
+            }).collect_view()}
+        </table>
+        <a class=style::button href="/cart">{t("go-to-cart")}</a>
+    }
+}
+
+/// Edits what can change about an event after it was announced.
+#[leptos::component]
+pub fn EventAdminPage() -> impl IntoView {
+    let params = use_params_map();
+    let event = Resource::new(move || params.read().get("event").unwrap_or_default(), admin_event);
+    let locale = use_locale();
+
+    view! {
+        <PageMeta title=locale.get_untracked().text("event-edit-title") noindex=true/>
+        <div class=format!("container {}", style::cart)>
+            <Suspense fallback=move || view! { <p>{t("loading")}</p> }>
+                {move || event.get().map(|event| match event {
+                    Ok(event) => view! { <EventForm event/> }.into_any(),
+                    Err(e) => view! { <span class=style::error>{e.to_string()}</span> }.into_any(),
+                })}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+fn EventForm(event: Event) -> impl IntoView {
+    let editing = RwSignal::new(event);
+    let (message, set_message) = signal(String::new());
+    let locale = use_locale();
+
+    let on_save = move |_| {
+        let event = editing.get_untracked();
+        spawn_local(async move {
+            match update_event(event).await {
+                Ok(event) => {
+                    editing.set(event);
+                    set_message(locale.get_untracked().text("event-edit-saved").to_string());
+                }
+                Err(e) => set_message(e.to_string()),
+            }
+        });
+    };
+    // empty fields clear the link
+    let optional = |value: String| (!value.trim().is_empty()).then(|| value.trim().to_string());
+
+    view! {
+        <div class="h2">{move || editing.with(|e| e.title.clone())}</div>
+        <label>
+            {t("event-edit-name")}
+            <input type="text" class=style::input
+                prop:value=move || editing.with(|e| e.title.clone())
+                on:input=move |e| editing.update(|event| event.title = event_target_value(&e))
+            />
+        </label>
+        <label>
+            {t("event-edit-poster")}
+            <input type="text" class=style::input
+                prop:value=move || editing.with(|e| e.poster.clone().unwrap_or_default())
+                on:input=move |e| editing.update(|event| event.poster = optional(event_target_value(&e)))
+            />
+        </label>
+        <label>
+            {t("event-edit-gallery")}
+            <input type="url" class=style::input placeholder="https://"
+                prop:value=move || editing.with(|e| e.gallery_url.clone().unwrap_or_default())
+                on:input=move |e| editing.update(|event| event.gallery_url = optional(event_target_value(&e)))
+            />
+        </label>
+        <label>
+            {t("event-edit-survey")}
+            <input type="url" class=style::input placeholder="https://"
+                prop:value=move || editing.with(|e| e.survey_url.clone().unwrap_or_default())
+                on:input=move |e| editing.update(|event| event.survey_url = optional(event_target_value(&e)))
+            />
+        </label>
+        <span>{t("event-edit-links-hint")}</span>
+        <div class=style::row>
+            <span>{message}</span>
+            <button class=style::button on:click=on_save>{t("event-edit-save")}</button>
+        </div>
+    }
+}
+
+#[server(EventDetailsFor, "/api")]
+pub async fn event_details(slug: String) -> Result<EventDetails, ServerFnError> {
+    let Some(event) = database::get_event_by_slug(slug).await else {
+        return Err(ServerFnError::Args(ErrorMessage::create(ResponseError::EventNotFound)));
+    };
+    let ticket_types = database::get_ticket_types(event.uuid.clone()).await.unwrap_or_default();
+    let lineup = lineup(event.uuid.clone()).await;
+    let json_ld = seo::music_event(&event, &ticket_types, &lineup);
+    Ok(EventDetails { event, ticket_types, lineup, json_ld })
+}
+
+#[server(AdminEventFor, "/api")]
+pub async fn admin_event(slug: String) -> Result<Event, ServerFnError> {
+    require_role(Role::Admin).await?;
+    database::get_event_by_slug(slug)
+        .await
+        .ok_or_else(|| ServerFnError::Args(ErrorMessage::create(ResponseError::EventNotFound)))
+}
+
+/// Saves title, poster and the links mailed after the night. Dates and the
+/// slug stay, tickets and calendars rely on them.
+#[server(UpdateEvent, "/api")]
+pub async fn update_event(event: Event) -> Result<Event, ServerFnError> {
+    require_role(Role::Admin).await?;
+    let Some(stored) = database::get_events(vec![event.uuid.clone()]).await.and_then(|e| e.into_iter().next()) else {
+        return Err(ServerFnError::Args(ErrorMessage::create(ResponseError::EventNotFound)));
+    };
+    let trimmed = |value: Option<String>| value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+    let event = Event {
+        title: event.title.trim().to_string(),
+        poster: trimmed(event.poster),
+        gallery_url: trimmed(event.gallery_url),
+        survey_url: trimmed(event.survey_url),
+        ..stored
+    };
+    let web_link = |url: &String| url.starts_with("https://") || url.starts_with("http://");
+    let links_valid = [&event.gallery_url, &event.survey_url].into_iter().flatten().all(web_link);
+    if event.validate().is_err() || !links_valid {
+        return Err(ServerFnError::Args(ErrorMessage::create(ResponseError::EventInvalid)));
+    }
+    database::update_event(event)
+        .await
+        .ok_or_else(|| ServerFnError::Args(ErrorMessage::create(ResponseError::EventNotFound)))
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use validator::Validate;
+
+        use crate::app::auth::require_role;
+        use crate::app::database;
+        use crate::app::errors::{ErrorMessage, ResponseError, ResponseErrorTrait};
+        use crate::app::model::user::Role;
+        use crate::app::page::artist::lineup;
+        use crate::app::seo;
+    }
+}
@@ -0,0 +1,207 @@
+use leptos::{prelude::*, task::spawn_local};
+
+use crate::app::i18n::{t, use_locale};
+use crate::app::ical::parse_berlin;
+use crate::app::model::Event;
+use crate::app::model::feed::{FeedItem, FeedKind};
+use crate::app::seo::PageMeta;
+
+stylance::import_style!(style, "../../style/cart.module.scss");
+
+#[leptos::component]
+pub fn FeedAdminPage() -> impl IntoView {
+    let version = RwSignal::new(0u32);
+    let overview = Resource::new(move || version.get(), |_| feed_overview());
+
+    view! {
+        <PageMeta title=use_locale().get_untracked().text("feed-title") noindex=true/>
+        <div class=format!("container {}", style::cart)>
+            <div class=style::row>
+                <div class="h2">{t("feed-title")}</div>
+                <div class=style::actions>
+                    <a href="/feed.atom" rel="external">"Atom"</a>
+                    <a href="/feed.rss" rel="external">"RSS"</a>
+                </div>
+            </div>
+            <AnnouncementForm version/>
+            <Transition fallback=move || view! { <p>{t("loading")}</p> }>
+                {move || overview.get().map(|overview| match overview {
+                    Ok((items, events)) => {
+                        let published = items.clone();
+                        view! {
+                            <UpcomingEvents events published version/>
+                            <FeedTable items/>
+                        }.into_any()
+                    }
+                    Err(e) => view! { <span class=style::error>{e.to_string()}</span> }.into_any(),
+                })}
+            </Transition>
+        </div>
+    }
+}
+
+#[component]
+fn AnnouncementForm(version: RwSignal<u32>) -> impl IntoView {
+    let (title, set_title) = signal(String::new());
+    let (summary, set_summary) = signal(String::new());
+    let (link, set_link) = signal(String::new());
+    let (error_message, set_error_message) = signal(String::new());
+
+    let on_submit = move |_| {
+        let link = match link.get_untracked().trim() {
+            "" => String::from("/"),
+            link => link.to_string(),
+        };
+        let item = FeedItem::new(String::new(), FeedKind::Announcement, title.get_untracked(), summary.get_untracked(), link);
+        spawn_local(async move {
+            match add_announcement(item).await {
+                Ok(_) => {
+                    set_title(String::new());
+                    set_summary(String::new());
+                    set_link(String::new());
+                    set_error_message(String::new());
+                    version.update(|v| *v += 1);
+                }
+                Err(e) => set_error_message(e.to_string()),
+            }
+        });
+    };
+
+    view! {
+        <div class=style::row>
+            <input type="text" placeholder=t("feed-item-title") class=style::input
+                prop:value=title
+                on:input=move |e| set_title(event_target_value(&e))
+            />
+            <input type="text" placeholder=t("feed-link") class=style::input
+                prop:value=link
+                on:input=move |e| set_link(event_target_value(&e))
+            />
+        </div>
+        <textarea class=style::input rows="4" placeholder=t("feed-text")
+            prop:value=summary
+            on:input=move |e| set_summary(event_target_value(&e))
+        ></textarea>
+        <div class=style::row>
+            <span class=style::error>{error_message}</span>
+            <button class=style::button on:click=on_submit>{t("feed-announce")}</button>
+        </div>
+    }
+}
+
+#[component]
+fn UpcomingEvents(events: Vec<Event>, published: Vec<FeedItem>, version: RwSignal<u32>) -> impl IntoView {
+    let (error_message, set_error_message) = signal(String::new());
+    let locale = use_locale();
+    let on_publish = Callback::new(move |event: String| {
+        spawn_local(async move {
+            match publish_event_entry(event).await {
+                Ok(_) => version.update(|v| *v += 1),
+                Err(e) => set_error_message(e.to_string()),
+            }
+        });
+    });
+
+    view! {
+        <table class=style::table>
+            <tr><th>{t("column-date")}</th><th>{t("feed-event")}</th><th class=style::amount></th></tr>
+            {events.into_iter().map(|event| {
+                let in_feed = published.iter().any(|item| item.reference.as_ref() == Some(&event.uuid));
+                let uuid = event.uuid.clone();
+                view! {
+                    <tr>
+                        <td>{move || parse_berlin(&event.starts_at).map(|time| locale.get().date_time(time)).unwrap_or_default()}</td>
+                        <td>{event.title}</td>
+                        <td class=style::amount>
+                            <button class=style::button on:click=move |_| on_publish.run(uuid.clone())>
+                                {t(if in_feed { "feed-update" } else { "feed-publish" })}
+                            </button>
+                        </td>
+                    </tr>
+                }
+            }).collect_view()}
+        </table>
+        <span class=style::error>{error_message}</span>
+    }
+}
+
+#[component]
+fn FeedTable(items: Vec<FeedItem>) -> impl IntoView {
+    let locale = use_locale();
+
+    view! {
+        <table class=style::table>
+            <tr>
+                <th>{t("feed-published")}</th><th>{t("feed-kind")}</th><th>{t("feed-item-title")}</th><th>{t("feed-updated")}</th>
+            </tr>
+            {items.into_iter().map(|item| view! {
+                <tr>
+                    <td>{move || parse_berlin(&item.published_at).map(|time| locale.get().date_time(time)).unwrap_or_default()}</td>
+                    <td>{t(match item.kind {
+                        FeedKind::Announcement => "feed-announcement",
+                        FeedKind::Event => "feed-event",
+                    })}</td>
+                    <td><a href=item.link>{item.title}</a></td>
+                    <td>{move || parse_berlin(&item.updated_at).map(|time| locale.get().date_time(time)).unwrap_or_default()}</td>
+                </tr>
+            }).collect_view()}
+        </table>
+    }
+}
+
+/// The latest entries and the upcoming events that could be published.
+#[server(FeedOverview, "/api")]
+pub async fn feed_overview() -> Result<(Vec<FeedItem>, Vec<Event>), ServerFnError> {
+    require_role(Role::Admin).await?;
+    let now = time::now();
+    let items = database::get_feed_items(now.clone(), FEED_LENGTH).await.unwrap_or_default();
+    let events = database::get_events_since(now).await.unwrap_or_default();
+    Ok((items, events))
+}
+
+#[server(AddAnnouncement, "/api")]
+pub async fn add_announcement(item: FeedItem) -> Result<FeedItem, ServerFnError> {
+    require_role(Role::Admin).await?;
+    let now = time::now();
+    let item = FeedItem {
+        uuid: Uuid::new_v4().to_string(),
+        kind: FeedKind::Announcement,
+        reference: None,
+        title: item.title.trim().to_string(),
+        published_at: now.clone(),
+        updated_at: now,
+        ..item
+    };
+    if item.validate().is_err() {
+        return Err(ServerFnError::Args(request_text("error-title-required")));
+    }
+    database::save_feed_item(item)
+        .await
+        .ok_or(ServerFnError::Args(request_text("error-announcement-failed")))
+}
+
+#[server(PublishEventEntry, "/api")]
+pub async fn publish_event_entry(event: String) -> Result<FeedItem, ServerFnError> {
+    require_role(Role::Admin).await?;
+    let Some(event) = database::get_events(vec![event]).await.and_then(|events| events.into_iter().next()) else {
+        return Err(ServerFnError::Args(ErrorMessage::create(ResponseError::EventNotFound)));
+    };
+    feed::publish_event(&event)
+        .await
+        .ok_or(ServerFnError::Args(request_text("error-publish-failed")))
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use uuid::Uuid;
+        use validator::Validate;
+
+        use crate::app::auth::require_role;
+        use crate::app::database;
+        use crate::app::errors::{ErrorMessage, ResponseError, ResponseErrorTrait};
+        use crate::app::feed::{self, FEED_LENGTH};
+        use crate::app::i18n::request_text;
+        use crate::app::model::user::Role;
+        use crate::app::time;
+    }
+}
@@ -0,0 +1,642 @@
+use leptos::{prelude::*, task::spawn_local};
+use leptos::logging::log;
+use leptos_router::hooks::use_params_map;
+
+use crate::app::i18n::{t, use_locale};
+use crate::app::model::{Event, GuestListEntry};
+use crate::app::model::guest::{GuestAction, GuestAudit, GuestQuota, ImportReport, QuotaUsage};
+use crate::app::model::ticket::ScanOutcome;
+use crate::app::page::door::door_event;
+use crate::app::seo::PageMeta;
+
+stylance::import_style!(style, "../../style/guest_list.module.scss");
+
+#[leptos::component]
+pub fn GuestListPage() -> impl IntoView {
+    let params = use_params_map();
+    let slug = move || params.read().get("event").unwrap_or_default();
+    let event = Resource::new(slug, door_event);
+
+    view! {
+        <PageMeta title=use_locale().get_untracked().text("guests-title") noindex=true/>
+        <div class=format!("container {}", style::guest_list)>
+            <Suspense fallback=move || view! { <p>{t("loading")}</p> }>
+                {move || event.get().map(|event| match event {
+                    Ok(event) => view! { <GuestListManager event/> }.into_any(),
+                    Err(e) => view! { <span class=style::error_label>{e.to_string()}</span> }.into_any(),
+                })}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+fn GuestListManager(event: Event) -> impl IntoView {
+    let uuid = event.uuid.clone();
+    // bumped after every change so all lists reload
+    let version = RwSignal::new(0u32);
+
+    let entries = Resource::new(move || version.get(), {
+        let uuid = uuid.clone();
+        move |_| guest_entries(uuid.clone())
+    });
+    let quotas = Resource::new(move || version.get(), {
+        let uuid = uuid.clone();
+        move |_| guest_quotas(uuid.clone())
+    });
+    let audit = Resource::new(move || version.get(), {
+        let uuid = uuid.clone();
+        move |_| guest_audit_log(uuid.clone())
+    });
+
+    let (error_message, set_error_message) = signal(String::new());
+    let quota_event = uuid.clone();
+
+    let on_remove = Callback::new(move |guest: String| {
+        spawn_local(async move {
+            match remove_guest_entry(guest).await {
+                Ok(_) => version.update(|v| *v += 1),
+                Err(e) => set_error_message(e.to_string()),
+            }
+        });
+    });
+
+    view! {
+        <div class="h2">{event.title}" – "{t("guests-title")}</div>
+        <span class=style::error_label>{error_message}</span>
+        <AddGuestForm event=uuid.clone() quotas version set_error_message/>
+        <ImportExport event=uuid version set_error_message/>
+        <Transition fallback=move || ()>
+            {move || quotas.get().map(|quotas| view! {
+                <QuotaTable event=quota_event.clone() quotas=quotas.unwrap_or_default() version set_error_message/>
+            })}
+        </Transition>
+        <Transition fallback=move || ()>
+            {move || entries.get().map(|entries| view! {
+                <table class=style::table>
+                    <tr>
+                        <th>{t("name")}</th><th>"+"</th><th>{t("guests-invited-by")}</th><th>{t("guests-note")}</th>
+                        <th>{t("guests-added-by")}</th><th>{t("guests-inside")}</th><th></th>
+                    </tr>
+                    {entries.unwrap_or_default().into_iter().map(|entry| {
+                        let guest = entry.uuid.clone();
+                        view! {
+                            <tr>
+                                <td>{entry.name}</td>
+                                <td>{entry.plus}</td>
+                                <td>{entry.invited_by}</td>
+                                <td>{entry.note}</td>
+                                <td>{entry.added_by}</td>
+                                <td>{entry.checked_in}</td>
+                                <td>
+                                    <button class=style::button on:click=move |_| on_remove.run(guest.clone())>
+                                        <i class="bi bi-trash"></i>
+                                    </button>
+                                </td>
+                            </tr>
+                        }
+                    }).collect_view()}
+                </table>
+            })}
+        </Transition>
+        <div class="h2">{t("guests-audit")}</div>
+        <Transition fallback=move || ()>
+            {move || audit.get().map(|audit| view! {
+                <table class=style::table>
+                    {audit.unwrap_or_default().into_iter().map(|entry| view! {
+                        <tr>
+                            <td>{entry.at}</td>
+                            <td>{entry.actor}</td>
+                            <td>{t(action_key(entry.action))}</td>
+                            <td>{entry.guest_name}</td>
+                            <td>{entry.invited_by}</td>
+                        </tr>
+                    }).collect_view()}
+                </table>
+            })}
+        </Transition>
+    }
+}
+
+fn action_key(action: GuestAction) -> &'static str {
+    match action {
+        GuestAction::Added => "guests-action-added",
+        GuestAction::Imported => "guests-action-imported",
+        GuestAction::Removed => "guests-action-removed",
+        GuestAction::CheckedIn => "guests-action-checked-in",
+    }
+}
+
+#[component]
+fn AddGuestForm(
+    event: String,
+    quotas: Resource<Result<Vec<QuotaUsage>, ServerFnError>>,
+    version: RwSignal<u32>,
+    set_error_message: WriteSignal<String>,
+) -> impl IntoView {
+    let (name, set_name) = signal(String::new());
+    let (plus, set_plus) = signal(0u32);
+    let (invited_by, set_invited_by) = signal(String::new());
+    let (note, set_note) = signal(String::new());
+
+    let on_add = move |_| {
+        let entry = GuestListEntry::new(
+            String::new(),
+            event.clone(),
+            name.get_untracked(),
+            plus.get_untracked(),
+            invited_by.get_untracked(),
+            note.get_untracked(),
+        );
+        spawn_local(async move {
+            match add_guest_entry(entry).await {
+                Ok(_) => {
+                    set_error_message(String::new());
+                    set_name(String::new());
+                    set_plus(0);
+                    set_note(String::new());
+                    version.update(|v| *v += 1);
+                }
+                Err(e) => set_error_message(e.to_string()),
+            }
+        });
+    };
+
+    view! {
+        <div class=style::form>
+            <input type="text" placeholder=t("name") class=style::input
+                prop:value=name
+                on:input=move |e| set_name(event_target_value(&e))
+            />
+            <input type="number" min="0" class=style::input_small
+                prop:value=move || plus().to_string()
+                on:input=move |e| set_plus(event_target_value(&e).parse().unwrap_or(0))
+            />
+            <input type="text" placeholder=t("guests-invited-by-hint") class=style::input list="guest-inviters"
+                prop:value=invited_by
+                on:input=move |e| set_invited_by(event_target_value(&e))
+            />
+            <datalist id="guest-inviters">
+                <Transition fallback=move || ()>
+                    {move || quotas.get().map(|quotas| {
+                        quotas.unwrap_or_default()
+                            .into_iter()
+                            .filter(|usage| usage.spots.is_some())
+                            .map(|usage| view! { <option value=usage.invited_by/> })
+                            .collect_view()
+                    })}
+                </Transition>
+            </datalist>
+            <input type="text" placeholder=t("guests-note") class=style::input
+                prop:value=note
+                on:input=move |e| set_note(event_target_value(&e))
+            />
+            <button class=style::button on:click=on_add>{t("guests-add")}</button>
+        </div>
+    }
+}
+
+#[component]
+fn QuotaTable(
+    event: String,
+    quotas: Vec<QuotaUsage>,
+    version: RwSignal<u32>,
+    set_error_message: WriteSignal<String>,
+) -> impl IntoView {
+    let (invited_by, set_invited_by) = signal(String::new());
+    let (spots, set_spots) = signal(0u32);
+
+    let on_set = move |_| {
+        let quota = GuestQuota::new(event.clone(), invited_by.get_untracked(), spots.get_untracked());
+        spawn_local(async move {
+            match set_guest_quota(quota).await {
+                Ok(_) => version.update(|v| *v += 1),
+                Err(e) => set_error_message(e.to_string()),
+            }
+        });
+    };
+
+    view! {
+        <table class=style::table>
+            <tr><th>{t("guests-invited-by")}</th><th>{t("guests-used")}</th><th>{t("guests-quota")}</th></tr>
+            {quotas.into_iter().map(|usage| {
+                let full = usage.spots.is_some_and(|spots| usage.used >= spots);
+                view! {
+                    <tr class={if full { style::full } else { "" }}>
+                        <td>{usage.invited_by}</td>
+                        <td>{usage.used}</td>
+                        <td>{usage.spots.map(|s| s.to_string()).unwrap_or_else(|| String::from("–"))}</td>
+                    </tr>
+                }
+            }).collect_view()}
+        </table>
+        <div class=style::form>
+            <input type="text" placeholder=t("guests-invited-by") class=style::input
+                prop:value=invited_by
+                on:input=move |e| set_invited_by(event_target_value(&e))
+            />
+            <input type="number" min="0" class=style::input_small
+                prop:value=move || spots().to_string()
+                on:input=move |e| set_spots(event_target_value(&e).parse().unwrap_or(0))
+            />
+            <button class=style::button on:click=on_set>{t("guests-set-quota")}</button>
+        </div>
+    }
+}
+
+#[component]
+fn ImportExport(
+    event: String,
+    version: RwSignal<u32>,
+    set_error_message: WriteSignal<String>,
+) -> impl IntoView {
+    let (csv, set_csv) = signal(String::new());
+    let (report, set_report) = signal::<Option<ImportReport>>(None);
+    let (download, set_download) = signal::<Option<String>>(None);
+    let locale = use_locale();
+
+    let on_file = move |e: leptos::ev::Event| {
+        #[cfg(feature = "hydrate")]
+        {
+            let file = event_target::<leptos::web_sys::HtmlInputElement>(&e)
+                .files()
+                .and_then(|files| files.get(0));
+            if let Some(file) = file {
+                spawn_local(async move {
+                    if let Ok(text) = wasm_bindgen_futures::JsFuture::from(file.text()).await {
+                        set_csv(text.as_string().unwrap_or_default());
+                    }
+                });
+            }
+        }
+        #[cfg(not(feature = "hydrate"))]
+        let _ = e;
+    };
+
+    let on_import = {
+        let event = event.clone();
+        move |_| {
+            let event = event.clone();
+            let text = csv.get_untracked();
+            spawn_local(async move {
+                match import_guests(event, text).await {
+                    Ok(result) => {
+                        set_csv(String::new());
+                        set_report(Some(result));
+                        version.update(|v| *v += 1);
+                    }
+                    Err(e) => set_error_message(e.to_string()),
+                }
+            });
+        }
+    };
+
+    let on_export = move |_| {
+        let event = event.clone();
+        spawn_local(async move {
+            match export_guests(event).await {
+                Ok(text) => {
+                    let encoded: String = js_sys::encode_uri_component(&text).into();
+                    set_download(Some(format!("data:text/csv;charset=utf-8,{encoded}")));
+                }
+                Err(e) => log!("Error {:?}", e),
+            }
+        });
+    };
+
+    view! {
+        <div class=style::form>
+            <textarea class=style::textarea
+                placeholder="name,plus,invited_by,note"
+                prop:value=csv
+                on:input=move |e| set_csv(event_target_value(&e))
+            ></textarea>
+            <input type="file" accept=".csv,text/csv" on:change=on_file/>
+            <button class=style::button on:click=on_import>{t("guests-import")}</button>
+            <button class=style::button on:click=on_export>{t("guests-export")}</button>
+            {move || download().map(|href| view! {
+                <a href=href download=t("guests-csv")>{t("guests-csv")}</a>
+            })}
+        </div>
+        {move || report().map(|report| view! {
+            <div>
+                <span>{locale.get_untracked().format("guests-imported", &[("count", &report.imported.to_string())])}</span>
+                {report.skipped.into_iter().map(|reason| view! { <div class=style::error_label>{reason}</div> }).collect_view()}
+            </div>
+        })}
+    }
+}
+
+/// Guest list lookup for the door: finds guests by name or inviter and checks
+/// in the whole party.
+#[component]
+pub fn GuestSearch(event: String) -> impl IntoView {
+    let (query, set_query) = signal(String::new());
+    let (message, set_message) = signal(String::new());
+    let locale = use_locale();
+    let results = Resource::new(query, {
+        let event = event.clone();
+        move |query| guest_search(event.clone(), query)
+    });
+
+    let on_check_in = Callback::new(move |guest: String| {
+        let event = event.clone();
+        spawn_local(async move {
+            match guest_check_in(event, guest).await {
+                Ok((ScanOutcome::Duplicate, entry)) => set_message(locale.get_untracked().format("guests-already-inside", &[
+                    ("name", &entry.name),
+                    ("time", &entry.checked_in_at.unwrap_or_default()),
+                ])),
+                Ok((_, entry)) => {
+                    set_message(locale.get_untracked().format("guests-checked-in", &[
+                        ("name", &entry.name),
+                        ("plus", &entry.plus.to_string()),
+                    ]));
+                    results.refetch();
+                }
+                Err(e) => set_message(e.to_string()),
+            }
+        });
+    });
+
+    view! {
+        <div class=style::search>
+            <input type="search" placeholder=t("guests-search") class=style::input
+                prop:value=query
+                on:input=move |e| set_query(event_target_value(&e))
+            />
+            <span>{message}</span>
+            <Transition fallback=move || ()>
+                {move || results.get().map(|results| {
+                    results.unwrap_or_default().into_iter().map(|entry| {
+                        let guest = entry.uuid.clone();
+                        let open = entry.checked_in == 0;
+                        view! {
+                            <div class=style::row>
+                                <span>{format!("{} +{}", entry.name, entry.plus)}</span>
+                                <span>{entry.invited_by}</span>
+                                <span>{entry.note}</span>
+                                {if open {
+                                    view! {
+                                        <button class=style::button on:click=move |_| on_check_in.run(guest.clone())>
+                                            {t("door-check-in")}
+                                        </button>
+                                    }.into_any()
+                                } else {
+                                    view! { <span class=style::full>{t("guests-is-inside")}</span> }.into_any()
+                                }}
+                            </div>
+                        }
+                    }).collect_view()
+                })}
+            </Transition>
+        </div>
+    }
+}
+
+#[server(GuestEntries, "/api")]
+pub async fn guest_entries(event: String) -> Result<Vec<GuestListEntry>, ServerFnError> {
+    require_role(Role::Staff).await?;
+    Ok(database::get_guests(event).await.unwrap_or_default())
+}
+
+#[server(GuestQuotas, "/api")]
+pub async fn guest_quotas(event: String) -> Result<Vec<QuotaUsage>, ServerFnError> {
+    require_role(Role::Staff).await?;
+    Ok(quota_usage(event).await)
+}
+
+#[server(GuestAuditLog, "/api")]
+pub async fn guest_audit_log(event: String) -> Result<Vec<GuestAudit>, ServerFnError> {
+    require_role(Role::Staff).await?;
+    Ok(database::get_guest_audit(event).await.unwrap_or_default())
+}
+
+#[server(AddGuestEntry, "/api")]
+pub async fn add_guest_entry(entry: GuestListEntry) -> Result<GuestListEntry, ServerFnError> {
+    let user = require_role(Role::Staff).await?;
+    let (mut added, skipped) = insert_guests(vec![entry], &user, GuestAction::Added).await;
+    match skipped.into_iter().next() {
+        Some(reason) => Err(ServerFnError::Args(reason)),
+        None => added.pop().ok_or_else(|| ServerFnError::Args(ErrorMessage::create(ResponseError::GuestCreationFailure))),
+    }
+}
+
+#[server(RemoveGuestEntry, "/api")]
+pub async fn remove_guest_entry(guest: String) -> Result<(), ServerFnError> {
+    let user = require_role(Role::Staff).await?;
+    match database::delete_guest(guest).await {
+        Ok(Some(entry)) => {
+            audit(&entry, GuestAction::Removed, &user).await;
+            Ok(())
+        }
+        Ok(None) => Err(ServerFnError::Args(ErrorMessage::create(ResponseError::GuestNotFound))),
+        Err(e) => Err(ServerFnError::Args(ErrorMessage::create(e))),
+    }
+}
+
+#[server(SetGuestQuota, "/api")]
+pub async fn set_guest_quota(quota: GuestQuota) -> Result<GuestQuota, ServerFnError> {
+    require_role(Role::Staff).await?;
+    let quota = GuestQuota::new(quota.event, quota.invited_by.trim().to_owned(), quota.spots);
+    if quota.validate().is_err() {
+        return Err(ServerFnError::Args(request_text("error-fields-required")));
+    }
+    match database::set_guest_quota(quota).await {
+        Some(quota) => Ok(quota),
+        None => Err(ServerFnError::Args(request_text("error-quota-failed"))),
+    }
+}
+
+/// Imports `name,plus,invited_by,note` rows. A header row is skipped, rows
+/// that are invalid, name an inviter without a quota or exceed one are
+/// reported and left out.
+#[server(ImportGuests, "/api")]
+pub async fn import_guests(event: String, text: String) -> Result<ImportReport, ServerFnError> {
+    let user = require_role(Role::Staff).await?;
+    let mut skipped = Vec::new();
+    let mut entries = Vec::new();
+
+    for (line, record) in csv::parse(&text).into_iter().enumerate() {
+        let field = |i: usize| record.get(i).map(|f| f.trim().to_owned()).unwrap_or_default();
+        if line == 0 && field(0).eq_ignore_ascii_case("name") {
+            continue;
+        }
+        let plus = match field(1).trim_start_matches('+') {
+            "" => 0,
+            plus => match plus.parse() {
+                Ok(plus) => plus,
+                Err(_) => {
+                    skipped.push(request_locale().format("error-import-plus", &[("line", &(line + 1).to_string()), ("plus", &field(1))]));
+                    continue;
+                }
+            },
+        };
+        entries.push(GuestListEntry::new(String::new(), event.clone(), field(0), plus, field(2), field(3)));
+    }
+
+    let (added, rejected) = insert_guests(entries, &user, GuestAction::Imported).await;
+    skipped.extend(rejected);
+    Ok(ImportReport { imported: added.len() as u32, skipped })
+}
+
+#[server(ExportGuests, "/api")]
+pub async fn export_guests(event: String) -> Result<String, ServerFnError> {
+    require_role(Role::Staff).await?;
+    let mut records = vec![["name", "plus", "invited_by", "note", "added_by", "added_at", "checked_in"]
+        .iter()
+        .map(|h| h.to_string())
+        .collect::<Vec<_>>()];
+    for entry in database::get_guests(event).await.unwrap_or_default() {
+        records.push(vec![
+            entry.name,
+            entry.plus.to_string(),
+            entry.invited_by,
+            entry.note,
+            entry.added_by,
+            entry.added_at,
+            entry.checked_in.to_string(),
+        ]);
+    }
+    Ok(csv::write(&records))
+}
+
+#[server(GuestSearchFor, "/api")]
+pub async fn guest_search(event: String, query: String) -> Result<Vec<GuestListEntry>, ServerFnError> {
+    require_role(Role::Staff).await?;
+    if query.trim().len() < 2 {
+        return Ok(Vec::new());
+    }
+    Ok(database::search_guests(event, query.trim().to_owned()).await.unwrap_or_default())
+}
+
+#[server(GuestCheckIn, "/api")]
+pub async fn guest_check_in(event: String, guest: String) -> Result<(ScanOutcome, GuestListEntry), ServerFnError> {
+    let user = require_role(Role::Staff).await?;
+    match database::get_guest_by_id(guest.clone()).await {
+        Some(entry) if entry.event == event => (),
+        _ => return Err(ServerFnError::Args(ErrorMessage::create(ResponseError::GuestNotFound))),
+    }
+    let now = time::now();
+    let before = database::check_in_guest(guest.clone(), now).await
+        .map_err(|e| ServerFnError::Args(ErrorMessage::create(e)))?;
+    if before.is_some() {
+        let entry = database::get_guest_by_id(guest).await
+            .ok_or_else(|| ServerFnError::Args(ErrorMessage::create(ResponseError::GuestNotFound)))?;
+        audit(&entry, GuestAction::CheckedIn, &user).await;
+        return Ok((ScanOutcome::Admitted, entry));
+    }
+    match database::get_guest_by_id(guest).await {
+        Some(entry) => Ok((ScanOutcome::Duplicate, entry)),
+        None => Err(ServerFnError::Args(ErrorMessage::create(ResponseError::GuestNotFound))),
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use crate::app::auth::require_role;
+        use crate::app::csv;
+        use crate::app::database;
+        use crate::app::errors::{ ErrorMessage, ResponseError, ResponseErrorTrait };
+        use crate::app::i18n::{request_locale, request_text};
+        use crate::app::model::User;
+        use crate::app::model::user::Role;
+        use crate::app::time;
+        use std::collections::{BTreeMap, HashSet};
+        use uuid::Uuid;
+        use validator::Validate;
+
+        async fn quota_usage(event: String) -> Vec<QuotaUsage> {
+            let mut usage: BTreeMap<String, QuotaUsage> = BTreeMap::new();
+            for quota in database::get_guest_quotas(event.clone()).await.unwrap_or_default() {
+                usage.insert(quota.invited_by.clone(), QuotaUsage {
+                    invited_by: quota.invited_by,
+                    used: 0,
+                    spots: Some(quota.spots),
+                });
+            }
+            for entry in database::get_guests(event).await.unwrap_or_default() {
+                usage.entry(entry.invited_by.clone())
+                    .or_insert_with(|| QuotaUsage { invited_by: entry.invited_by.clone(), used: 0, spots: None })
+                    .used += entry.spots();
+            }
+            usage.into_values().collect()
+        }
+
+        /// Name guests count under when the staff member adding them leaves
+        /// the inviter empty.
+        fn inviter(user: &User) -> String {
+            match user.name.trim() {
+                "" => user.email.clone(),
+                name => name.to_owned(),
+            }
+        }
+
+        /// Adds entries in order, keeping every inviter within their quota.
+        /// Inviters have to be on the quota list, an empty one means the staff
+        /// member adding the entry. Returns the added entries and a reason for
+        /// each rejected one.
+        async fn insert_guests(entries: Vec<GuestListEntry>, user: &User, action: GuestAction)
+            -> (Vec<GuestListEntry>, Vec<String>) {
+
+            let mut added = Vec::new();
+            let mut skipped = Vec::new();
+            let Some(event) = entries.first().map(|e| e.event.clone()) else {
+                return (added, skipped);
+            };
+            let mut inviters: HashSet<String> = database::get_guest_quotas(event)
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .map(|quota| quota.invited_by)
+                .collect();
+            inviters.insert(inviter(user));
+
+            for mut entry in entries {
+                entry.name = entry.name.trim().to_owned();
+                entry.invited_by = match entry.invited_by.trim() {
+                    "" => inviter(user),
+                    invited_by => invited_by.to_owned(),
+                };
+                if let Err(errors) = entry.validate() {
+                    let key = if errors.field_errors().contains_key("name") { "error-guest-name" } else { "error-guest-plus" };
+                    skipped.push(request_locale().format(key, &[("name", &entry.name)]));
+                    continue;
+                }
+                if !inviters.contains(&entry.invited_by) {
+                    skipped.push(request_locale().format("error-no-quota", &[("name", &entry.name), ("inviter", &entry.invited_by)]));
+                    continue;
+                }
+
+                entry.uuid = Uuid::new_v4().to_string();
+                entry.added_by = user.email.clone();
+                entry.added_at = time::now();
+                entry.checked_in = 0;
+                entry.checked_in_at = None;
+
+                match database::add_guest(entry.clone()).await {
+                    Ok(created) => {
+                        audit(&created, action, user).await;
+                        added.push(created);
+                    }
+                    Err(ResponseError::GuestQuotaExceeded) => {
+                        skipped.push(request_locale().format("error-quota-used-up", &[("name", &entry.name), ("inviter", &entry.invited_by)]));
+                    }
+                    Err(e) => skipped.push(format!("{}: {}", entry.name, ErrorMessage::create(e))),
+                }
+            }
+            (added, skipped)
+        }
+
+        async fn audit(entry: &GuestListEntry, action: GuestAction, user: &User) {
+            let audit = GuestAudit::new(
+                entry,
+                action,
+                Uuid::new_v4().to_string(),
+                user.email.clone(),
+                time::now(),
+            );
+            database::add_guest_audit(audit).await;
+        }
+    }
+}
@@ -1,10 +1,16 @@
 use leptos::prelude::*;
 
+use crate::app::i18n::t;
+use crate::app::images::ResponsiveImage;
+use crate::app::page::occupancy::CrowdIndicator;
+use crate::app::seo::PageMeta;
+
 stylance::import_style!(style, "../../style/home.module.scss");
 
 #[component]
 pub fn HomePage() -> impl IntoView {
     view! {
+        <PageMeta title="" path="/"/>
         <div class="container">
             <Intro/>
             <Events/>
@@ -17,8 +23,8 @@ pub fn Intro() -> impl IntoView {
     view! {
         <div class=format!("component {}", style::component)>
         <div class=style::logo_container>
-            <img class=style::logo src="assets/stampffabrik_1024.png"/>
-            <img class=style::logo_glow src="assets/stampffabrik_1024_glow.png"/>
+            <ResponsiveImage class=style::logo src="assets/stampffabrik_1024.png" alt="Stampffabrik" width=1024 eager=true/>
+            <ResponsiveImage class=style::logo_glow src="assets/stampffabrik_1024_glow.png" width=1024 eager=true/>
         </div>
             <div class=style::arrow><a href="#events" class="bi bi-caret-down-fill"></a></div>
         </div>
@@ -29,10 +35,12 @@ pub fn Intro() -> impl IntoView {
 pub fn Events() -> impl IntoView {
     view! {
         <div class="component" id="events">
-            <div class="h2">Upcoming Events</div>
+            <CrowdIndicator/>
+            <div class="h2">{t("home-upcoming")}</div>
             <div class=style::event >
-                <img src="assets/event_prisma.png"/>
+                <ResponsiveImage src="assets/event_prisma.png" width=1024 sizes="(max-width: 1024px) 100vw, 1024px"/>
             </div>
+            <a href="/calendar">{t("home-all-dates")}</a>
         </div>
     }
 }
\ No newline at end of file
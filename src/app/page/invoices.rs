@@ -0,0 +1,164 @@
+use leptos::{prelude::*, task::spawn_local};
+use leptos::logging::log;
+
+use crate::app::i18n::{t, use_locale};
+use crate::app::ical::parse_berlin;
+use crate::app::model::Invoice;
+use crate::app::model::invoice::{display_number, InvoiceKind};
+use crate::app::seo::PageMeta;
+
+stylance::import_style!(style, "../../style/cart.module.scss");
+
+/// Invoices of the logged in user, shown on the account page.
+#[component]
+pub fn InvoiceList() -> impl IntoView {
+    let invoices = Resource::new(|| (), |_| my_invoices());
+
+    view! {
+        <div class="h2">{t("invoices-title")}</div>
+        <Transition fallback=move || ()>
+            {move || invoices.get().map(|invoices| view! {
+                <InvoiceTable invoices=invoices.unwrap_or_default() on_cancel=None/>
+            })}
+        </Transition>
+    }
+}
+
+#[leptos::component]
+pub fn InvoicesAdminPage() -> impl IntoView {
+    let version = RwSignal::new(0u32);
+    let invoices = Resource::new(move || version.get(), |_| all_invoices());
+    let (error_message, set_error_message) = signal(String::new());
+
+    let on_cancel = Callback::new(move |invoice: String| {
+        spawn_local(async move {
+            match cancel_invoice(invoice).await {
+                Ok(_) => version.update(|v| *v += 1),
+                Err(e) => set_error_message(e.to_string()),
+            }
+        });
+    });
+
+    view! {
+        <PageMeta title=use_locale().get_untracked().text("invoices-title") noindex=true/>
+        <div class=format!("container {}", style::cart)>
+            <div class="h2">{t("invoices-title")}</div>
+            <span class=style::error>{error_message}</span>
+            <Transition fallback=move || view! { <p>{t("loading")}</p> }>
+                {move || invoices.get().map(|invoices| match invoices {
+                    Ok(invoices) => view! { <InvoiceTable invoices on_cancel=Some(on_cancel)/> }.into_any(),
+                    Err(e) => view! { <span class=style::error>{e.to_string()}</span> }.into_any(),
+                })}
+            </Transition>
+        </div>
+    }
+}
+
+#[component]
+fn InvoiceTable(invoices: Vec<Invoice>, on_cancel: Option<Callback<String>>) -> impl IntoView {
+    // an invoice can be cancelled once, the cancellation is in the same list
+    let cancelled: Vec<u64> = invoices.iter().filter_map(|i| i.cancels).collect();
+    let locale = use_locale();
+
+    view! {
+        <table class=style::table>
+            <tr>
+                <th>{t("column-number")}</th><th>{t("column-date")}</th><th>{t("name")}</th>
+                <th class=style::amount>{t("column-amount")}</th><th></th>
+            </tr>
+            {invoices.into_iter().map(|invoice| {
+                let cancellable = invoice.kind == InvoiceKind::Invoice && !cancelled.contains(&invoice.number);
+                let uuid = invoice.uuid.clone();
+                let issued_at = parse_berlin(&invoice.issued_at);
+                let cancels = invoice.cancels.map(display_number);
+                view! {
+                    <tr>
+                        <td>
+                            {invoice.display_number()}
+                            {cancels.map(|number| {
+                                move || format!(" ({})", locale.get().format("invoice-cancels", &[("number", &number)]))
+                            })}
+                        </td>
+                        <td>{move || issued_at.map(|time| locale.get().date(time.date())).unwrap_or_default()}</td>
+                        <td>{invoice.buyer_name.clone()}</td>
+                        <td class=style::amount>{move || locale.get().price(invoice.total_cents)}</td>
+                        <td class=style::actions>
+                            <InvoiceDownload invoice=invoice.uuid.clone() number=invoice.display_number()/>
+                            {on_cancel.filter(|_| cancellable).map(|on_cancel| view! {
+                                <button class=style::button on:click=move |_| on_cancel.run(uuid.clone())>{t("invoice-cancel")}</button>
+                            })}
+                        </td>
+                    </tr>
+                }
+            }).collect_view()}
+        </table>
+    }
+}
+
+#[component]
+fn InvoiceDownload(invoice: String, number: String) -> impl IntoView {
+    let (download, set_download) = signal::<Option<String>>(None);
+
+    let on_download = move |_| {
+        let invoice = invoice.clone();
+        spawn_local(async move {
+            match invoice_pdf(invoice).await {
+                Ok(pdf) => set_download(Some(format!("data:application/pdf;base64,{pdf}"))),
+                Err(e) => log!("Error {:?}", e),
+            }
+        });
+    };
+
+    view! {
+        {move || match download() {
+            Some(href) => view! { <a class=style::button href=href download=format!("{number}.pdf")>{t("invoice-save")}</a> }.into_any(),
+            None => view! { <button class=style::button on:click=on_download.clone()>"PDF"</button> }.into_any(),
+        }}
+    }
+}
+
+#[server(MyInvoices, "/api")]
+pub async fn my_invoices() -> Result<Vec<Invoice>, ServerFnError> {
+    let Some(user) = current_user().await else {
+        return Err(ServerFnError::Args(request_text("error-not-logged-in")));
+    };
+    Ok(database::get_invoices_for_user(user.uuid).await.unwrap_or_default())
+}
+
+#[server(AllInvoices, "/api")]
+pub async fn all_invoices() -> Result<Vec<Invoice>, ServerFnError> {
+    require_role(Role::Admin).await?;
+    Ok(database::get_all_invoices().await.unwrap_or_default())
+}
+
+#[server(CancelInvoice, "/api")]
+pub async fn cancel_invoice(invoice: String) -> Result<Invoice, ServerFnError> {
+    require_role(Role::Admin).await?;
+    invoice::cancel(invoice).await.map_err(|e| ServerFnError::Args(ErrorMessage::create(e)))
+}
+
+/// The PDF, base64 encoded. Buyers get their own invoices, admins all of them.
+#[server(InvoicePdf, "/api")]
+pub async fn invoice_pdf(invoice: String) -> Result<String, ServerFnError> {
+    use base64::Engine;
+
+    let Some(user) = current_user().await else {
+        return Err(ServerFnError::Args(request_text("error-not-logged-in")));
+    };
+    let invoice = match database::get_invoice(invoice).await {
+        Some(invoice) if user.role >= Role::Admin || invoice.user.as_ref() == Some(&user.uuid) => invoice,
+        _ => return Err(ServerFnError::Args(ErrorMessage::create(ResponseError::InvoiceNotFound))),
+    };
+    Ok(base64::engine::general_purpose::STANDARD.encode(invoice::render_pdf(&invoice)))
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use crate::app::auth::{current_user, require_role};
+        use crate::app::database;
+        use crate::app::errors::{ErrorMessage, ResponseError, ResponseErrorTrait};
+        use crate::app::i18n::request_text;
+        use crate::app::invoice;
+        use crate::app::model::user::Role;
+    }
+}
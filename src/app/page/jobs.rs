@@ -0,0 +1,163 @@
+use leptos::{prelude::*, task::spawn_local};
+use serde::{Deserialize, Serialize};
+
+use crate::app::i18n::{t, use_locale};
+use crate::app::ical::input_value;
+use crate::app::model::job::{Job, JobOverview, JobStatus};
+use crate::app::seo::PageMeta;
+
+stylance::import_style!(style, "../../style/cart.module.scss");
+
+fn time(time: &Option<String>) -> String {
+    input_value(time).replace('T', " ")
+}
+
+fn status_key(job: &Job) -> &'static str {
+    match job.status {
+        JobStatus::Queued if job.attempts > 0 => "job-retrying",
+        JobStatus::Queued => "job-queued",
+        JobStatus::Running => "job-running",
+        JobStatus::Done => "job-done",
+        JobStatus::Dead => "job-dead",
+    }
+}
+
+#[leptos::component]
+pub fn JobsAdminPage() -> impl IntoView {
+    let (status, set_status) = signal::<Option<JobStatus>>(None);
+    let version = RwSignal::new(0u32);
+    let overview = Resource::new(move || (status.get(), version.get()), |(status, _)| job_overview(status));
+    let (error_message, set_error_message) = signal(String::new());
+
+    let on_action = move |action: JobAction, uuid: String| {
+        spawn_local(async move {
+            match change_job(action, uuid).await {
+                Ok(_) => {
+                    set_error_message(String::new());
+                    version.update(|v| *v += 1);
+                }
+                Err(e) => set_error_message(e.to_string()),
+            }
+        });
+    };
+
+    view! {
+        <PageMeta title=use_locale().get_untracked().text("jobs-title") noindex=true/>
+        <div class=format!("container {}", style::cart)>
+            <div class=style::row>
+                <div class="h2">{t("jobs-title")}</div>
+                <button class=style::button on:click=move |_| version.update(|v| *v += 1)>{t("jobs-refresh")}</button>
+            </div>
+            <span class=style::error>{error_message}</span>
+            <Transition fallback=move || view! { <p>{t("loading")}</p> }>
+                {move || overview.get().map(|overview| match overview {
+                    Ok(JobOverview { recurring, jobs }) => view! {
+                        <div class="h3">{t("jobs-recurring")}</div>
+                        <table class=style::table>
+                            <tr><th>{t("jobs-job")}</th><th>{t("jobs-schedule")}</th><th>{t("jobs-next-run")}</th></tr>
+                            {recurring.into_iter().map(|job| view! {
+                                <tr>
+                                    <td>{t(job.kind.label_key())}</td>
+                                    <td>{job.schedule}</td>
+                                    <td>{time(&job.next_run)}</td>
+                                </tr>
+                            }).collect_view()}
+                        </table>
+                        <div class=style::row>
+                            <div class="h3">{t("jobs-history")}</div>
+                            <select class=style::input on:change=move |e| set_status(match event_target_value(&e).as_str() {
+                                "queued" => Some(JobStatus::Queued),
+                                "running" => Some(JobStatus::Running),
+                                "done" => Some(JobStatus::Done),
+                                "dead" => Some(JobStatus::Dead),
+                                _ => None,
+                            })>
+                                <option value="" selected=move || status.get().is_none()>{t("jobs-all")}</option>
+                                <option value="queued" selected=move || status.get() == Some(JobStatus::Queued)>{t("jobs-filter-queued")}</option>
+                                <option value="running" selected=move || status.get() == Some(JobStatus::Running)>{t("jobs-filter-running")}</option>
+                                <option value="done" selected=move || status.get() == Some(JobStatus::Done)>{t("job-done")}</option>
+                                <option value="dead" selected=move || status.get() == Some(JobStatus::Dead)>{t("job-dead")}</option>
+                            </select>
+                        </div>
+                        <table class=style::table>
+                            <tr>
+                                <th>{t("jobs-job")}</th><th>{t("jobs-status")}</th><th>{t("jobs-attempts")}</th><th>{t("jobs-due")}</th><th>{t("jobs-error")}</th><th></th>
+                            </tr>
+                            {jobs.into_iter().map(|job| {
+                                let uuid = job.uuid.clone();
+                                let actions = match job.status {
+                                    JobStatus::Queued => view! {
+                                        <button class=style::button on:click={
+                                            let uuid = uuid.clone();
+                                            move |_| on_action(JobAction::RunNow, uuid.clone())
+                                        }>{t("jobs-run-now")}</button>
+                                        <button class=style::button on:click=move |_| on_action(JobAction::Delete, uuid.clone())>
+                                            {t("jobs-delete")}
+                                        </button>
+                                    }.into_any(),
+                                    JobStatus::Dead => view! {
+                                        <button class=style::button on:click=move |_| on_action(JobAction::Retry, uuid.clone())>
+                                            {t("jobs-retry")}
+                                        </button>
+                                    }.into_any(),
+                                    _ => ().into_any(),
+                                };
+                                view! {
+                                    <tr>
+                                        <td>{t(job.kind.label_key())}</td>
+                                        <td>{t(status_key(&job))}</td>
+                                        <td>{format!("{}/{}", job.attempts, job.max_attempts)}</td>
+                                        <td>{time(&Some(job.run_at.clone()))}</td>
+                                        <td>{job.last_error.clone().unwrap_or_default()}</td>
+                                        <td class=style::amount>{actions}</td>
+                                    </tr>
+                                }
+                            }).collect_view()}
+                        </table>
+                    }.into_any(),
+                    Err(e) => view! { <span class=style::error>{e.to_string()}</span> }.into_any(),
+                })}
+            </Transition>
+        </div>
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+pub enum JobAction {
+    RunNow,
+    Retry,
+    Delete,
+}
+
+#[server(JobOverviewFor, "/api")]
+pub async fn job_overview(status: Option<JobStatus>) -> Result<JobOverview, ServerFnError> {
+    require_role(Role::Admin).await?;
+    Ok(JobOverview {
+        recurring: jobs::recurring(),
+        jobs: database::get_jobs(status, JOB_LIMIT).await.unwrap_or_default(),
+    })
+}
+
+#[server(ChangeJob, "/api")]
+pub async fn change_job(action: JobAction, uuid: String) -> Result<(), ServerFnError> {
+    require_role(Role::Admin).await?;
+    match action {
+        JobAction::RunNow => database::run_job_now(uuid, now()).await,
+        JobAction::Retry => database::retry_job(uuid, now()).await,
+        JobAction::Delete => database::delete_job(uuid).await,
+    }
+    Ok(())
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use crate::app::auth::require_role;
+        use crate::app::database;
+        use crate::app::jobs;
+        use crate::app::model::user::Role;
+        use crate::app::time::now;
+
+        /// Jobs shown at most.
+        const JOB_LIMIT: u32 = 200;
+    }
+}
@@ -0,0 +1,224 @@
+use leptos::{prelude::*, task::spawn_local};
+
+use crate::app::i18n::{t, use_locale};
+use crate::app::images::ResponsiveImage;
+use crate::app::model::media::{MediaLibrary, MediaUsage};
+use crate::app::seo::PageMeta;
+
+stylance::import_style!(style, "../../style/media.module.scss");
+stylance::import_style!(cart_style, "../../style/cart.module.scss");
+
+fn file_size(bytes: u64) -> String {
+    match bytes {
+        0..=999_999 => format!("{} kB", bytes.div_ceil(1000)),
+        _ => format!("{:.1} MB", bytes as f64 / 1_000_000.0),
+    }
+}
+
+#[leptos::component]
+pub fn MediaAdminPage() -> impl IntoView {
+    let locale = use_locale();
+    let version = RwSignal::new(0u32);
+    let library = Resource::new(move || version.get(), |_| media_library());
+    let (only_unused, set_only_unused) = signal(false);
+    let (uploading, set_uploading) = signal(false);
+    let (error_message, set_error_message) = signal(String::new());
+
+    let on_files = move |e: leptos::ev::Event| {
+        #[cfg(feature = "hydrate")]
+        {
+            let input = event_target::<leptos::web_sys::HtmlInputElement>(&e);
+            if let Some(files) = input.files() {
+                set_uploading(true);
+                spawn_local(async move {
+                    match upload_files(files, locale.get_untracked()).await {
+                        Ok(_) => set_error_message(String::new()),
+                        Err(e) => set_error_message(e),
+                    }
+                    input.set_value("");
+                    set_uploading(false);
+                    version.update(|v| *v += 1);
+                });
+            }
+        }
+        #[cfg(not(feature = "hydrate"))]
+        let _ = e;
+    };
+
+    let on_clean_up = move |_| {
+        spawn_local(async move {
+            match remove_stray_media().await {
+                Ok(_) => version.update(|v| *v += 1),
+                Err(e) => set_error_message(e.to_string()),
+            }
+        });
+    };
+
+    view! {
+        <PageMeta title=use_locale().get_untracked().text("media-title") noindex=true/>
+        <div class=format!("container {}", cart_style::cart)>
+            <div class=cart_style::row>
+                <div class="h2">{t("media-title")}</div>
+                <label>
+                    <input type="checkbox"
+                        prop:checked=only_unused
+                        on:change=move |e| set_only_unused(event_target_checked(&e))
+                    />
+                    " "{t("media-only-unused")}
+                </label>
+                <label class=format!("{} {}", cart_style::button, style::upload)>
+                    {move || locale.get().text(if uploading() { "media-uploading" } else { "media-upload" })}
+                    <input type="file" multiple accept="image/jpeg,image/png,image/webp" on:change=on_files/>
+                </label>
+            </div>
+            <span>{t("media-hint")}</span>
+            <span class=cart_style::error>{error_message}</span>
+            <Transition fallback=move || view! { <p>{t("loading")}</p> }>
+                {move || library.get().map(|library| match library {
+                    Ok(MediaLibrary { files, stray }) => view! {
+                        {(!stray.is_empty()).then(|| view! {
+                            <div class=cart_style::row>
+                                <span>{move || locale.get().format("media-stray", &[("count", &stray.len().to_string())])}</span>
+                                <button class=cart_style::button on:click=on_clean_up>{t("media-clean-up")}</button>
+                            </div>
+                        })}
+                        <div class=style::library>
+                            {files.into_iter()
+                                .filter(|usage| !only_unused() || usage.references.is_empty())
+                                .map(|usage| view! { <MediaCard usage version set_error_message/> })
+                                .collect_view()}
+                        </div>
+                    }.into_any(),
+                    Err(e) => view! { <span class=cart_style::error>{e.to_string()}</span> }.into_any(),
+                })}
+            </Transition>
+        </div>
+    }
+}
+
+#[component]
+fn MediaCard(usage: MediaUsage, version: RwSignal<u32>, set_error_message: WriteSignal<String>) -> impl IntoView {
+    let MediaUsage { file, references } = usage;
+    let url = file.url();
+    let unused = references.is_empty();
+
+    let uuid = file.uuid.clone();
+    let on_delete = move |_| {
+        let uuid = uuid.clone();
+        spawn_local(async move {
+            match remove_media(uuid).await {
+                Ok(_) => version.update(|v| *v += 1),
+                Err(e) => set_error_message(e.to_string()),
+            }
+        });
+    };
+
+    view! {
+        <div class=style::card>
+            <ResponsiveImage class=style::thumb src=url.clone() alt=file.original_name.clone() width=file.width sizes="160pt"/>
+            <span>{file.original_name}</span>
+            <span class=style::details>
+                {format!("{} × {} · {}", file.width, file.height, file_size(file.size))}
+            </span>
+            <input type="text" readonly class=style::url prop:value=url
+                on:focus=move |e| {
+                    #[cfg(feature = "hydrate")]
+                    {
+                        event_target::<leptos::web_sys::HtmlInputElement>(&e).select();
+                    }
+                    #[cfg(not(feature = "hydrate"))]
+                    let _ = e;
+                }
+            />
+            {if unused {
+                view! {
+                    <span class=style::unused>{t("media-unused")}</span>
+                    <button class=cart_style::button on:click=on_delete>{t("admin-delete")}</button>
+                }.into_any()
+            } else {
+                references.into_iter().map(|reference| view! { <span>{reference}</span> }).collect_view().into_any()
+            }}
+        </div>
+    }
+}
+
+/// Sends the files to the upload route, which takes them as multipart form.
+#[cfg(feature = "hydrate")]
+async fn upload_files(files: leptos::web_sys::FileList, locale: crate::app::i18n::Locale) -> Result<(), String> {
+    use reqwest::multipart::{Form, Part};
+    use wasm_bindgen_futures::JsFuture;
+
+    let mut form = Form::new();
+    for i in 0..files.length() {
+        let Some(file) = files.get(i) else {
+            continue;
+        };
+        let buffer = JsFuture::from(file.array_buffer())
+            .await
+            .map_err(|_| locale.format("media-unreadable", &[("name", &file.name())]))?;
+        let bytes = js_sys::Uint8Array::new(&buffer).to_vec();
+        form = form.part("files", Part::bytes(bytes).file_name(file.name()));
+    }
+
+    let url = format!("{}/api/media", window().location().origin().unwrap_or_default());
+    let response = reqwest::Client::new()
+        .post(url)
+        .multipart(form)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(response.text().await.unwrap_or_default());
+    }
+    Ok(())
+}
+
+#[server(MediaLibraryFiles, "/api")]
+pub async fn media_library() -> Result<MediaLibrary, ServerFnError> {
+    require_role(Role::Admin).await?;
+    Ok(media::library().await)
+}
+
+/// Deletes a file nothing uses any more.
+#[server(RemoveMedia, "/api")]
+pub async fn remove_media(uuid: String) -> Result<(), ServerFnError> {
+    require_role(Role::Admin).await?;
+    let library = media::library().await;
+    let Some(usage) = library.files.into_iter().find(|usage| usage.file.uuid == uuid) else {
+        return Err(ServerFnError::Args(ErrorMessage::create(ResponseError::MediaNotFound)));
+    };
+    if !usage.references.is_empty() {
+        return Err(ServerFnError::Args(ErrorMessage::create(ResponseError::MediaInUse)));
+    }
+    media::storage()
+        .delete(&usage.file.key)
+        .await
+        .map_err(|e| ServerFnError::Args(ErrorMessage::create(e)))?;
+    database::delete_media(usage.file.hash).await;
+    Ok(())
+}
+
+/// Deletes files in storage that have no record, returning how many.
+#[server(RemoveStrayMedia, "/api")]
+pub async fn remove_stray_media() -> Result<usize, ServerFnError> {
+    require_role(Role::Admin).await?;
+    let storage = media::storage();
+    let mut removed = 0;
+    for key in media::library().await.stray {
+        match storage.delete(&key).await {
+            Ok(_) => removed += 1,
+            Err(e) => println!("error in deleting stray media {key}: {:?}", e),
+        }
+    }
+    Ok(removed)
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use crate::app::auth::require_role;
+        use crate::app::database;
+        use crate::app::errors::{ErrorMessage, ResponseError, ResponseErrorTrait};
+        use crate::app::media::{self, StorageBackend};
+        use crate::app::model::user::Role;
+    }
+}
@@ -2,4 +2,70 @@ pub mod home;
 pub use home::HomePage;
 
 pub mod account;
-pub use account::AccountPage;
\ No newline at end of file
+pub use account::AccountPage;
+
+pub mod door;
+pub use door::DoorPage;
+
+pub mod offline_door;
+pub use offline_door::OfflineDoorPage;
+
+pub mod guest_list;
+pub use guest_list::GuestListPage;
+
+pub mod cart;
+pub use cart::CartPage;
+
+pub mod event;
+pub use event::{EventPage, EventAdminPage};
+
+pub mod order;
+pub use order::OrderPage;
+
+pub mod invoices;
+pub use invoices::InvoicesAdminPage;
+
+pub mod wallet;
+pub use wallet::TicketWallet;
+
+pub mod refunds;
+pub use refunds::EventRefundsPage;
+
+pub mod discounts;
+pub use discounts::DiscountsAdminPage;
+
+pub mod shop;
+pub use shop::{ShopPage, ProductPage};
+
+pub mod products;
+pub use products::ProductsAdminPage;
+
+pub mod packing;
+pub use packing::PackingListPage;
+
+pub mod newsletter;
+pub use newsletter::{NewsletterConfirmPage, NewsletterUnsubscribePage, NewsletterAdminPage};
+
+pub mod calendar;
+pub use calendar::CalendarPage;
+
+pub mod feed;
+pub use feed::FeedAdminPage;
+
+pub mod news;
+pub use news::{NewsPage, PostPage, NewsAdminPage};
+
+pub mod media;
+pub use media::MediaAdminPage;
+
+pub mod artist;
+pub use artist::{ArtistsPage, ArtistPage, ArtistsAdminPage};
+
+pub mod occupancy;
+pub use occupancy::{OccupancyPage, AreasAdminPage};
+
+pub mod waitlist;
+pub use waitlist::{WaitlistPanel, WaitlistOfferPage, WaitlistAdminPage};
+
+pub mod jobs;
+pub use jobs::JobsAdminPage;
\ No newline at end of file
@@ -0,0 +1,309 @@
+use leptos::{prelude::*, task::spawn_local};
+use leptos_router::hooks::use_params_map;
+
+use crate::app::i18n::{t, use_locale, Locale};
+use crate::app::ical::{input_value, parse_berlin};
+use crate::app::images::ResponsiveImage;
+use crate::app::markdown;
+use crate::app::model::Post;
+use crate::app::model::post::PostView;
+use crate::app::seo::PageMeta;
+
+stylance::import_style!(style, "../../style/news.module.scss");
+stylance::import_style!(cart_style, "../../style/cart.module.scss");
+
+/// Characters of a post shown in the overview.
+const TEASER_LENGTH: usize = 320;
+
+/// Characters of a post in its meta description.
+const DESCRIPTION_LENGTH: usize = 160;
+
+fn date(time: &str, locale: Locale) -> String {
+    parse_berlin(time).map(|t| locale.date(t.date())).unwrap_or_default()
+}
+
+#[leptos::component]
+pub fn NewsPage() -> impl IntoView {
+    let posts = Resource::new(|| (), |_| news_posts());
+    let locale = use_locale().get_untracked();
+
+    view! {
+        <PageMeta title=locale.text("news-title") description=locale.text("news-description")/>
+        <div class=format!("container {}", style::news)>
+            <div class="h2">{t("news-title")}</div>
+            <Suspense fallback=move || view! { <p>{t("loading")}</p> }>
+                {move || posts.get().map(|posts| match posts {
+                    Ok(posts) if posts.is_empty() => view! { <span>{t("news-empty")}</span> }.into_any(),
+                    Ok(posts) => posts.into_iter()
+                        .map(|post| view! { <PostTeaser post/> })
+                        .collect_view()
+                        .into_any(),
+                    Err(e) => view! { <span class=cart_style::error>{e.to_string()}</span> }.into_any(),
+                })}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+fn PostTeaser(post: Post) -> impl IntoView {
+    let locale = use_locale();
+    let published_at = post.published_at.clone().unwrap_or_default();
+    let teaser = markdown::excerpt(&post.body, TEASER_LENGTH);
+
+    view! {
+        <a class=style::teaser href=format!("/news/{}", post.slug)>
+            {post.cover.map(|cover| view! { <ResponsiveImage class=style::cover src=cover/> })}
+            <span class=style::title>{post.title}</span>
+            <span class=style::meta>{move || date(&published_at, locale.get())}</span>
+            <span>{teaser}</span>
+        </a>
+    }
+}
+
+#[leptos::component]
+pub fn PostPage() -> impl IntoView {
+    let params = use_params_map();
+    let slug = move || params.read().get("slug").unwrap_or_default();
+    let post = Resource::new(slug, news_post);
+    let locale = use_locale();
+
+    view! {
+        <div class=format!("container {}", style::news)>
+            <Suspense fallback=move || view! { <p>{t("loading")}</p> }>
+                {move || post.get().map(|post| match post {
+                    Ok(PostView { post, author_name, html }) => {
+                        let published_at = post.published_at.clone().unwrap_or_default();
+                        view! {
+                            <PageMeta
+                                title=post.title.clone()
+                                path=format!("/news/{}", post.slug)
+                                description=markdown::excerpt(&post.body, DESCRIPTION_LENGTH)
+                                image=post.cover.clone().unwrap_or_default()
+                                og_type="article"
+                            />
+                            {post.cover.map(|cover| view! { <ResponsiveImage class=style::cover src=cover/> })}
+                            <div class="h2">{post.title}</div>
+                            <span class=style::meta>
+                                {move || date(&published_at, locale.get())}
+                                {(!author_name.is_empty()).then(|| format!(" · {author_name}"))}
+                            </span>
+                            <div class=style::body inner_html=html></div>
+                            <a href="/news">{t("news-all")}</a>
+                        }.into_any()
+                    }
+                    Err(e) => view! { <span class=cart_style::error>{e.to_string()}</span> }.into_any(),
+                })}
+            </Suspense>
+        </div>
+    }
+}
+
+fn blank_post() -> Post {
+    Post::new(String::new(), String::new(), String::new(), String::new())
+}
+
+#[leptos::component]
+pub fn NewsAdminPage() -> impl IntoView {
+    let locale = use_locale();
+    let version = RwSignal::new(0u32);
+    let posts = Resource::new(move || version.get(), |_| all_posts());
+    let editing = RwSignal::new(blank_post());
+
+    view! {
+        <PageMeta title=use_locale().get_untracked().text("news-posts") noindex=true/>
+        <div class=format!("container {}", style::news)>
+            <div class=cart_style::row>
+                <div class="h2">{t("news-posts")}</div>
+                <button class=cart_style::button on:click=move |_| editing.set(blank_post())>{t("news-new")}</button>
+            </div>
+            <Transition fallback=move || view! { <p>{t("loading")}</p> }>
+                {move || posts.get().map(|posts| match posts {
+                    Ok(posts) => view! {
+                        <table class=cart_style::table>
+                            {posts.into_iter().map(|post| {
+                                let status = match &post.published_at {
+                                    Some(time) => date(time, locale.get()),
+                                    None => locale.get().text("news-draft").to_string(),
+                                };
+                                let title = post.title.clone();
+                                view! {
+                                    <tr>
+                                        <td>{status}</td>
+                                        <td>{title}</td>
+                                        <td class=cart_style::amount>
+                                            <button class=cart_style::button on:click=move |_| editing.set(post.clone())>
+                                                {t("admin-edit")}
+                                            </button>
+                                        </td>
+                                    </tr>
+                                }
+                            }).collect_view()}
+                        </table>
+                    }.into_any(),
+                    Err(e) => view! { <span class=cart_style::error>{e.to_string()}</span> }.into_any(),
+                })}
+            </Transition>
+            <PostEditor editing version/>
+        </div>
+    }
+}
+
+#[component]
+fn PostEditor(editing: RwSignal<Post>, version: RwSignal<u32>) -> impl IntoView {
+    let (error_message, set_error_message) = signal(String::new());
+
+    let on_save = move |_| {
+        let post = editing.get_untracked();
+        spawn_local(async move {
+            match save_post(post).await {
+                Ok(saved) => {
+                    editing.set(saved);
+                    set_error_message(String::new());
+                    version.update(|v| *v += 1);
+                }
+                Err(e) => set_error_message(e.to_string()),
+            }
+        });
+    };
+
+    view! {
+        <div class=cart_style::row>
+            <input type="text" placeholder=t("news-post-title") class=cart_style::input
+                prop:value=move || editing.with(|p| p.title.clone())
+                on:input=move |e| editing.update(|p| p.title = event_target_value(&e))
+            />
+            <input type="text" placeholder="Slug" class=cart_style::input
+                prop:value=move || editing.with(|p| p.slug.clone())
+                on:input=move |e| editing.update(|p| p.slug = event_target_value(&e))
+            />
+        </div>
+        <div class=cart_style::row>
+            <input type="text" placeholder=t("news-cover") class=cart_style::input
+                prop:value=move || editing.with(|p| p.cover.clone().unwrap_or_default())
+                on:input=move |e| {
+                    let cover = event_target_value(&e);
+                    editing.update(|p| p.cover = (!cover.trim().is_empty()).then(|| cover.trim().to_string()));
+                }
+            />
+            <input type="datetime-local" class=cart_style::input title=t("news-published-hint")
+                prop:value=move || editing.with(|p| input_value(&p.published_at))
+                on:input=move |e| {
+                    let published_at = event_target_value(&e);
+                    editing.update(|p| p.published_at = (!published_at.is_empty()).then_some(published_at));
+                }
+            />
+        </div>
+        <div class=style::editor>
+            <textarea class=cart_style::input rows="20" placeholder=t("news-body")
+                prop:value=move || editing.with(|p| p.body.clone())
+                on:input=move |e| editing.update(|p| p.body = event_target_value(&e))
+            ></textarea>
+            <div class=style::body inner_html=move || editing.with(|p| markdown::to_html(&p.body))></div>
+        </div>
+        <div class=cart_style::row>
+            <span class=cart_style::error>{error_message}</span>
+            <button class=cart_style::button on:click=on_save>{t("admin-save")}</button>
+        </div>
+    }
+}
+
+#[server(NewsPosts, "/api")]
+pub async fn news_posts() -> Result<Vec<Post>, ServerFnError> {
+    Ok(database::get_published_posts(time::now()).await.unwrap_or_default())
+}
+
+#[server(NewsPost, "/api")]
+pub async fn news_post(slug: String) -> Result<PostView, ServerFnError> {
+    let now = time::now();
+    let Some(post) = database::get_post_by_slug(slug).await.filter(|p| p.is_published(&now)) else {
+        return Err(ServerFnError::Args(ErrorMessage::create(ResponseError::PostNotFound)));
+    };
+    let author_name = database::get_user_by_id(post.author.clone())
+        .await
+        .map(|user| format!("{} {}", user.name, user.last_name).trim().to_string())
+        .unwrap_or_default();
+    Ok(PostView {
+        html: markdown::render(&post.body),
+        author_name,
+        post,
+    })
+}
+
+#[server(AllPosts, "/api")]
+pub async fn all_posts() -> Result<Vec<Post>, ServerFnError> {
+    require_role(Role::Admin).await?;
+    Ok(database::get_posts().await.unwrap_or_default())
+}
+
+/// Creates the post or saves changes to it, and puts it into the feed or
+/// takes it out depending on whether it has a publish date.
+#[server(SavePost, "/api")]
+pub async fn save_post(post: Post) -> Result<Post, ServerFnError> {
+    let user = require_role(Role::Admin).await?;
+    let now = time::now();
+    let published_at = match post.published_at.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+        Some(time) => Some(from_input(time).ok_or(ServerFnError::Args(request_text("error-publish-date")))?),
+        None => None,
+    };
+    let post = Post {
+        slug: post.slug.trim().to_lowercase(),
+        title: post.title.trim().to_string(),
+        published_at,
+        updated_at: now.clone(),
+        ..post
+    };
+    if post.validate().is_err() || !post.slug.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(ServerFnError::Args(request_text("error-post-invalid")));
+    }
+    if database::get_post_by_slug(post.slug.clone()).await.is_some_and(|other| other.uuid != post.uuid) {
+        return Err(ServerFnError::Args(request_text("error-slug-taken")));
+    }
+
+    let existing = match post.uuid.as_str() {
+        "" => None,
+        uuid => database::get_post(uuid.to_string()).await,
+    };
+    let saved = match existing {
+        Some(existing) => database::update_post(Post {
+            author: existing.author,
+            created_at: existing.created_at,
+            ..post
+        }).await,
+        None => database::add_post(Post {
+            uuid: Uuid::new_v4().to_string(),
+            author: user.uuid,
+            created_at: now,
+            ..post
+        }).await,
+    };
+    let Some(saved) = saved else {
+        return Err(ServerFnError::Args(ErrorMessage::create(ResponseError::PostNotFound)));
+    };
+
+    match saved.published_at {
+        Some(_) => {
+            if feed::publish_post(&saved).await.is_none() {
+                println!("error in publishing post {} to the feed", saved.uuid);
+            }
+        }
+        None => feed::withdraw(saved.uuid.clone()).await,
+    }
+    Ok(saved)
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use uuid::Uuid;
+        use validator::Validate;
+
+        use crate::app::auth::require_role;
+        use crate::app::database;
+        use crate::app::errors::{ErrorMessage, ResponseError, ResponseErrorTrait};
+        use crate::app::feed;
+        use crate::app::i18n::request_text;
+        use crate::app::ical::from_input;
+        use crate::app::model::user::Role;
+        use crate::app::time;
+    }
+}
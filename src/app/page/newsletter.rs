@@ -0,0 +1,352 @@
+use leptos::{prelude::*, task::spawn_local};
+use leptos_router::hooks::use_params_map;
+
+use crate::app::i18n::{t, use_locale};
+use crate::app::ical::parse_berlin;
+use crate::app::model::User;
+use crate::app::model::newsletter::{Campaign, CampaignStats, SubscribeRequest, SubscriberCounts};
+use crate::app::seo::PageMeta;
+
+stylance::import_style!(style, "../../style/cart.module.scss");
+
+/// Signup form for the footer.
+#[component]
+pub fn NewsletterForm() -> impl IntoView {
+    let user = use_context::<(ReadSignal<Option<User>>, WriteSignal<Option<User>>)>().map(|u| u.0);
+    let (email, set_email) = signal(String::new());
+    let (message, set_message) = signal(String::new());
+    let locale = use_locale();
+
+    Effect::new(move |_| {
+        if let Some(user) = user.and_then(|u| u.get()) {
+            if email.get_untracked().is_empty() {
+                set_email(user.email);
+            }
+        }
+    });
+
+    let on_subscribe = move |_| {
+        let request = SubscribeRequest::new(email.get_untracked(), String::from("footer"));
+        spawn_local(async move {
+            match subscribe(request).await {
+                Ok(_) => set_message(locale.get_untracked().text("newsletter-pending").to_string()),
+                Err(e) => set_message(e.to_string()),
+            }
+        });
+    };
+
+    view! {
+        <div class=style::row>
+            <input type="email" placeholder=t("newsletter-placeholder") class=style::input
+                prop:value=email
+                on:input=move |e| set_email(event_target_value(&e))
+            />
+            <button class=style::button on:click=on_subscribe>{t("newsletter-subscribe")}</button>
+        </div>
+        <span>{message}</span>
+    }
+}
+
+#[leptos::component]
+pub fn NewsletterConfirmPage() -> impl IntoView {
+    let params = use_params_map();
+    let result = Resource::new(
+        move || (
+            params.read().get("subscriber").unwrap_or_default(),
+            params.read().get("token").unwrap_or_default(),
+        ),
+        |(subscriber, token)| confirm_subscription(subscriber, token),
+    );
+
+    view! {
+        <PageMeta title=use_locale().get_untracked().text("newsletter-confirm-title") noindex=true/>
+        <div class=format!("container {}", style::cart)>
+            <Suspense fallback=move || view! { <p>{t("loading")}</p> }>
+                {move || result.get().map(|result| match result {
+                    Ok(_) => view! { <span>{t("newsletter-confirmed")}</span> }.into_any(),
+                    Err(e) => view! { <span class=style::error>{e.to_string()}</span> }.into_any(),
+                })}
+            </Suspense>
+        </div>
+    }
+}
+
+#[leptos::component]
+pub fn NewsletterUnsubscribePage() -> impl IntoView {
+    let params = use_params_map();
+    let (done, set_done) = signal(false);
+    let (error_message, set_error_message) = signal(String::new());
+
+    // a click instead of the page load, so link scanners in mail filters don't unsubscribe anyone
+    let on_unsubscribe = move |_| {
+        let subscriber = params.read_untracked().get("subscriber").unwrap_or_default();
+        let token = params.read_untracked().get("token").unwrap_or_default();
+        spawn_local(async move {
+            match unsubscribe(subscriber, token).await {
+                Ok(_) => set_done(true),
+                Err(e) => set_error_message(e.to_string()),
+            }
+        });
+    };
+
+    view! {
+        <PageMeta title=use_locale().get_untracked().text("newsletter-unsubscribe-title") noindex=true/>
+        <div class=format!("container {}", style::cart)>
+            <Show
+                when=done
+                fallback=move || view! {
+                    <span>{t("newsletter-unsubscribe-question")}</span>
+                    <button class=style::button on:click=on_unsubscribe>{t("newsletter-unsubscribe")}</button>
+                }
+            >
+                <span>{t("newsletter-unsubscribed")}</span>
+            </Show>
+            <span class=style::error>{error_message}</span>
+        </div>
+    }
+}
+
+#[leptos::component]
+pub fn NewsletterAdminPage() -> impl IntoView {
+    let locale = use_locale();
+    let version = RwSignal::new(0u32);
+    let overview = Resource::new(move || version.get(), |_| newsletter_overview());
+    let (subject, set_subject) = signal(String::new());
+    let (body, set_body) = signal(String::new());
+    let (confirm, set_confirm) = signal(false);
+    let (error_message, set_error_message) = signal(String::new());
+
+    let on_send = move |_| {
+        if !confirm.get_untracked() {
+            set_confirm(true);
+            return;
+        }
+        set_confirm(false);
+        let campaign = Campaign::new(String::new(), subject.get_untracked(), body.get_untracked());
+        spawn_local(async move {
+            match send_campaign(campaign).await {
+                Ok(_) => {
+                    set_subject(String::new());
+                    set_body(String::new());
+                    set_error_message(String::new());
+                    version.update(|v| *v += 1);
+                }
+                Err(e) => set_error_message(e.to_string()),
+            }
+        });
+    };
+
+    view! {
+        <PageMeta title=use_locale().get_untracked().text("newsletter-title") noindex=true/>
+        <div class=format!("container {}", style::cart)>
+            <div class="h2">{t("newsletter-title")}</div>
+            <Transition fallback=move || view! { <p>{t("loading")}</p> }>
+                {move || overview.get().map(|overview| match overview {
+                    Ok((counts, campaigns)) => view! {
+                        <span>{move || locale.get().format("newsletter-counts", &[
+                            ("confirmed", &counts.confirmed.to_string()),
+                            ("pending", &counts.pending.to_string()),
+                            ("unsubscribed", &counts.unsubscribed.to_string()),
+                        ])}</span>
+                        <CampaignTable campaigns/>
+                    }.into_any(),
+                    Err(e) => view! { <span class=style::error>{e.to_string()}</span> }.into_any(),
+                })}
+            </Transition>
+            <input type="text" placeholder=t("newsletter-subject") class=style::input
+                prop:value=subject
+                on:input=move |e| set_subject(event_target_value(&e))
+            />
+            <textarea class=style::input rows="12"
+                placeholder=t("newsletter-body")
+                prop:value=body
+                on:input=move |e| set_body(event_target_value(&e))
+            ></textarea>
+            <div class=style::row>
+                <span class=style::error>{error_message}</span>
+                <button class=style::button on:click=on_send>
+                    {move || locale.get().text(if confirm() { "newsletter-send-confirm" } else { "newsletter-send" })}
+                </button>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn CampaignTable(campaigns: Vec<CampaignStats>) -> impl IntoView {
+    let locale = use_locale();
+
+    view! {
+        <table class=style::table>
+            <tr><th>{t("column-date")}</th><th>{t("newsletter-subject")}</th><th class=style::amount>{t("newsletter-sent")}</th><th class=style::amount>{t("newsletter-failed")}</th></tr>
+            {campaigns.into_iter().map(|stats| view! {
+                <tr>
+                    <td>{move || parse_berlin(&stats.campaign.created_at).map(|time| locale.get().date_time(time)).unwrap_or_default()}</td>
+                    <td>{stats.campaign.subject}</td>
+                    <td class=style::amount>{format!("{} / {}", stats.sent, stats.campaign.recipients)}</td>
+                    <td class=style::amount>{stats.failed}</td>
+                </tr>
+            }).collect_view()}
+        </table>
+    }
+}
+
+/// Starts the double opt-in. Answers the same whether or not the address is
+/// already subscribed, so the form can't be used to probe for subscribers.
+#[server(Subscribe, "/api")]
+pub async fn subscribe(subscribe_request: SubscribeRequest) -> Result<(), ServerFnError> {
+    if subscribe_request.validate().is_err() {
+        return Err(ServerFnError::Args(request_text("error-invalid-email")));
+    }
+    let email = normalize_email(&subscribe_request.email);
+    let subscriber = match database::get_subscriber_by_email(email.clone()).await {
+        Some(subscriber) if subscriber.status == SubscriberStatus::Confirmed => return Ok(()),
+        // signing up again after unsubscribing needs a new confirmation
+        Some(subscriber) => Subscriber {
+            status: SubscriberStatus::Pending,
+            source: subscribe_request.source,
+            requested_at: time::now(),
+            ..subscriber
+        },
+        None => Subscriber::new(Uuid::new_v4().to_string(), email, subscribe_request.source, time::now()),
+    };
+    let subscriber = Subscriber {
+        user: current_user().await.map(|u| u.uuid).or(subscriber.user),
+        locale: request_locale(),
+        ..subscriber
+    };
+    let saved = match database::get_subscriber(subscriber.uuid.clone()).await {
+        Some(_) => database::update_subscriber(subscriber).await,
+        None => database::add_subscriber(subscriber).await,
+    };
+    let Some(subscriber) = saved else {
+        return Err(ServerFnError::Args(ErrorMessage::create(ResponseError::MailFailure)));
+    };
+
+    let locale = subscriber.locale;
+    let mail = Mail::new(
+        subscriber.email.clone(),
+        locale.text("mail-newsletter-confirm-subject").to_string(),
+        locale.format("mail-newsletter-confirm-body", &[("link", &link("confirm", &subscriber.uuid)?)]),
+    );
+    mail::send(mail).await.map_err(|e| ServerFnError::Args(ErrorMessage::create(e)))
+}
+
+#[server(ConfirmSubscription, "/api")]
+pub async fn confirm_subscription(subscriber: String, token: String) -> Result<(), ServerFnError> {
+    let subscriber = checked_subscriber("confirm", subscriber, token).await?;
+    if subscriber.status == SubscriberStatus::Confirmed {
+        return Ok(());
+    }
+    let subscriber = Subscriber {
+        status: SubscriberStatus::Confirmed,
+        consent_at: Some(time::now()),
+        unsubscribed_at: None,
+        ..subscriber
+    };
+    database::update_subscriber(subscriber)
+        .await
+        .map(|_| ())
+        .ok_or(ServerFnError::Args(request_text("error-confirm-failed")))
+}
+
+#[server(Unsubscribe, "/api")]
+pub async fn unsubscribe(subscriber: String, token: String) -> Result<(), ServerFnError> {
+    let subscriber = checked_subscriber("unsubscribe", subscriber, token).await?;
+    if subscriber.status == SubscriberStatus::Unsubscribed {
+        return Ok(());
+    }
+    let subscriber = Subscriber {
+        status: SubscriberStatus::Unsubscribed,
+        unsubscribed_at: Some(time::now()),
+        ..subscriber
+    };
+    database::update_subscriber(subscriber)
+        .await
+        .map(|_| ())
+        .ok_or(ServerFnError::Args(request_text("error-unsubscribe-failed")))
+}
+
+#[server(NewsletterOverview, "/api")]
+pub async fn newsletter_overview() -> Result<(SubscriberCounts, Vec<CampaignStats>), ServerFnError> {
+    require_role(Role::Admin).await?;
+    let counts = database::get_subscriber_counts().await.unwrap_or_default();
+    let campaigns = database::get_campaign_stats().await.unwrap_or_default();
+    Ok((counts, campaigns))
+}
+
+/// Queues the campaign for every confirmed subscriber, each with their own
+/// unsubscribe link.
+#[server(SendCampaign, "/api")]
+pub async fn send_campaign(campaign: Campaign) -> Result<Campaign, ServerFnError> {
+    let user = require_role(Role::Admin).await?;
+    if campaign.validate().is_err() {
+        return Err(ServerFnError::Args(request_text("error-campaign-invalid")));
+    }
+    let subscribers = database::get_confirmed_subscribers().await.unwrap_or_default();
+    let campaign = Campaign {
+        uuid: Uuid::new_v4().to_string(),
+        created_by: user.uuid,
+        created_at: time::now(),
+        recipients: subscribers.len() as u32,
+        ..campaign
+    };
+    let Some(campaign) = database::add_campaign(campaign).await else {
+        return Err(ServerFnError::Args(ErrorMessage::create(ResponseError::MailFailure)));
+    };
+
+    let mails = subscribers
+        .into_iter()
+        .map(|subscriber| Ok(Mail::new(
+            subscriber.email,
+            campaign.subject.clone(),
+            format!(
+                "{}\n\n-- \n{}",
+                campaign.body,
+                subscriber.locale.format("mail-newsletter-footer", &[("link", &link("unsubscribe", &subscriber.uuid)?)]),
+            ),
+        )))
+        .collect::<Result<Vec<_>, ServerFnError>>()?;
+    mail::queue::enqueue_bulk(mails, Some(campaign.uuid.clone()))
+        .await
+        .map_err(|e| ServerFnError::Args(ErrorMessage::create(e)))?;
+    Ok(campaign)
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use uuid::Uuid;
+        use validator::Validate;
+
+        use crate::app::auth::{current_user, require_role};
+        use crate::app::database;
+        use crate::app::errors::{ErrorMessage, ResponseError, ResponseErrorTrait};
+        use crate::app::i18n::{request_locale, request_text};
+        use crate::app::mail::{self, site_url, Mail};
+        use crate::app::model::newsletter::{normalize_email, Subscriber, SubscriberStatus};
+        use crate::app::model::user::Role;
+        use crate::app::time;
+        use crate::app::signing;
+
+        /// Key for the links in newsletter mails.
+        const SECRET: &str = "NEWSLETTER_SECRET";
+
+        fn link(purpose: &str, subscriber: &str) -> Result<String, ServerFnError> {
+            let secret = signing::secret(SECRET)
+                .map_err(|_| ServerFnError::Args(ErrorMessage::create(ResponseError::MailFailure)))?;
+            let token = signing::sign(&secret, &format!("newsletter:{purpose}:{subscriber}"));
+            Ok(format!("{}/newsletter/{}/{}/{}", site_url(), purpose, subscriber, token))
+        }
+
+        async fn checked_subscriber(purpose: &str, subscriber: String, token: String) -> Result<Subscriber, ServerFnError> {
+            let message = format!("newsletter:{purpose}:{subscriber}");
+            let valid = signing::secret(SECRET).is_ok_and(|secret| signing::verify_full(&secret, &message, &token));
+            if !valid {
+                return Err(ServerFnError::Args(request_text("error-invalid-link")));
+            }
+            database::get_subscriber(subscriber)
+                .await
+                .ok_or(ServerFnError::Args(request_text("error-invalid-link")))
+        }
+    }
+}
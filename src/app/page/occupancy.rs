@@ -0,0 +1,353 @@
+use leptos::{prelude::*, task::spawn_local};
+use std::time::Duration;
+
+use crate::app::i18n::{t, use_locale};
+use crate::app::model::venue::{Area, CrowdLevel, OccupancyLevel, WARNING_PERCENT};
+use crate::app::push::{use_push, PushMessage};
+use crate::app::seo::PageMeta;
+
+stylance::import_style!(style, "../../style/occupancy.module.scss");
+stylance::import_style!(door_style, "../../style/door.module.scss");
+stylance::import_style!(cart_style, "../../style/cart.module.scss");
+
+/// Guests only need a rough idea, so the home page asks now and then.
+const CROWD_REFRESH: Duration = Duration::from_secs(60);
+
+/// Counts of all areas, kept current by the server's pushes.
+fn live_areas() -> Signal<Option<Vec<Area>>> {
+    let loaded = Resource::new(|| (), |_| occupancy_areas());
+    let (pushed, set_pushed) = signal(None);
+    use_push().on(move |message| {
+        if let PushMessage::Occupancy(areas) = message {
+            set_pushed(Some(areas));
+        }
+    });
+
+    Signal::derive(move || pushed.get().or_else(|| loaded.get().and_then(Result::ok)))
+}
+
+fn level_class(area: &Area) -> &'static str {
+    match area.level() {
+        OccupancyLevel::Normal => style::normal,
+        OccupancyLevel::Warning => style::warning,
+        OccupancyLevel::Full => style::full,
+    }
+}
+
+/// Clickers for door staff, one per area.
+#[leptos::component]
+pub fn OccupancyPage() -> impl IntoView {
+    let areas = live_areas();
+    let push = use_push();
+    let (error_message, set_error_message) = signal(String::new());
+    let locale = use_locale();
+
+    view! {
+        <PageMeta title=locale.get_untracked().text("occupancy-title") noindex=true/>
+        <div class=format!("container {}", door_style::door)>
+            <div class="h2">{t("occupancy-title")}</div>
+            <span class=move || if push.connected() { door_style::online } else { door_style::offline }>
+                {move || locale.get().text(if push.connected() { "push-live" } else { "push-reconnecting" })}
+            </span>
+            <span class=door_style::error_label>{error_message}</span>
+            <Transition fallback=move || view! { <p>{t("loading")}</p> }>
+                {move || areas.get().map(|areas| if areas.is_empty() {
+                    view! { <span>{t("occupancy-empty")}</span> }.into_any()
+                } else {
+                    areas.into_iter()
+                        .map(|area| view! { <AreaClicker area set_error_message/> })
+                        .collect_view()
+                        .into_any()
+                })}
+            </Transition>
+        </div>
+    }
+}
+
+#[component]
+fn AreaClicker(area: Area, set_error_message: WriteSignal<String>) -> impl IntoView {
+    let locale = use_locale();
+    let click = move |uuid: String, delta: i32| {
+        spawn_local(async move {
+            // the new count arrives as a push
+            match count_occupancy(uuid, delta).await {
+                Ok(_) => set_error_message(String::new()),
+                Err(e) => set_error_message(e.to_string()),
+            }
+        });
+    };
+    let on_reset = {
+        let uuid = area.uuid.clone();
+        move |_| {
+            let confirmed = window()
+                .confirm_with_message(locale.get_untracked().text("occupancy-reset-confirm"))
+                .unwrap_or(false);
+            if !confirmed {
+                return;
+            }
+            let uuid = uuid.clone();
+            spawn_local(async move {
+                if let Err(e) = reset_occupancy(uuid).await {
+                    set_error_message(e.to_string());
+                }
+            });
+        }
+    };
+    let full = area.level() == OccupancyLevel::Full;
+    let notice = match area.level() {
+        OccupancyLevel::Full => Some("occupancy-full"),
+        OccupancyLevel::Warning => Some("occupancy-warning"),
+        OccupancyLevel::Normal => None,
+    };
+    let (out_uuid, in_uuid) = (area.uuid.clone(), area.uuid.clone());
+    let (capacity, percent) = (area.capacity.to_string(), area.percent().to_string());
+
+    view! {
+        <div class=format!("{} {}", style::area, level_class(&area))>
+            <div class=style::area_head>
+                <span class="h3">{area.name.clone()}</span>
+                <button class=door_style::button on:click=on_reset title=t("occupancy-reset")>
+                    <i class="bi bi-arrow-counterclockwise"></i>
+                </button>
+            </div>
+            <div class=style::clicker>
+                <button class=style::click on:click=move |_| click(out_uuid.clone(), -1)>"−"</button>
+                <div class=door_style::count>
+                    <span class=door_style::count_value>{area.inside}</span>
+                    <span>{move || locale.get().format("occupancy-of", &[("capacity", &capacity), ("percent", &percent)])}</span>
+                </div>
+                <button class=style::click disabled=full on:click=move |_| click(in_uuid.clone(), 1)>"+"</button>
+            </div>
+            <Gauge area=area.clone()/>
+            {notice.map(|notice| view! { <span class=style::notice>{t(notice)}</span> })}
+        </div>
+    }
+}
+
+#[component]
+fn Gauge(area: Area) -> impl IntoView {
+    view! {
+        <div class=style::gauge>
+            <div class=style::gauge_fill style=format!("width: {}%", area.percent().min(100))></div>
+            <div class=style::gauge_mark style=format!("left: {WARNING_PERCENT}%")></div>
+        </div>
+    }
+}
+
+/// All areas at a glance, for the door page.
+#[component]
+pub fn OccupancyOverview() -> impl IntoView {
+    let areas = live_areas();
+
+    view! {
+        <Transition fallback=|| ()>
+            {move || areas.get().filter(|areas| !areas.is_empty()).map(|areas| view! {
+                <a class=style::overview href="/occupancy">
+                    {areas.into_iter().map(|area| view! {
+                        <span class=format!("{} {}", style::overview_area, level_class(&area))>
+                            {format!("{} {}/{}", area.name, area.inside, area.capacity)}
+                        </span>
+                    }).collect_view()}
+                </a>
+            })}
+        </Transition>
+    }
+}
+
+/// "It's busy / come now" for guests, shown while the venue is open.
+#[component]
+pub fn CrowdIndicator() -> impl IntoView {
+    let crowd = Resource::new(|| (), |_| crowd_level());
+
+    Effect::new(move |_| {
+        if let Ok(handle) = set_interval_with_handle(move || crowd.refetch(), CROWD_REFRESH) {
+            on_cleanup(move || handle.clear());
+        }
+    });
+
+    view! {
+        <Transition fallback=|| ()>
+            {move || crowd.get().and_then(Result::ok).flatten().map(|level| {
+                let (class, key) = match level {
+                    CrowdLevel::Quiet => (style::quiet, "crowd-quiet"),
+                    CrowdLevel::Busy => (style::busy, "crowd-busy"),
+                    CrowdLevel::Full => (style::crowded, "crowd-full"),
+                };
+                view! {
+                    <div class=format!("{} {}", style::crowd, class)>
+                        <i class="bi bi-people-fill"></i>
+                        <span>{t(key)}</span>
+                    </div>
+                }
+            })}
+        </Transition>
+    }
+}
+
+#[leptos::component]
+pub fn AreasAdminPage() -> impl IntoView {
+    let version = RwSignal::new(0u32);
+    let areas = Resource::new(move || version.get(), |_| occupancy_areas());
+    let (error_message, set_error_message) = signal(String::new());
+    let locale = use_locale();
+
+    view! {
+        <PageMeta title=locale.get_untracked().text("areas-title") noindex=true/>
+        <div class=format!("container {}", cart_style::cart)>
+            <div class="h2">{t("areas-title")}</div>
+            <span>{move || locale.get().format("areas-hint", &[("percent", &WARNING_PERCENT.to_string())])}</span>
+            <span class=cart_style::error>{error_message}</span>
+            <Transition fallback=move || view! { <p>{t("loading")}</p> }>
+                {move || areas.get().map(|areas| match areas {
+                    Ok(areas) => view! {
+                        <table class=cart_style::table>
+                            <tr>
+                                <th>{t("name")}</th>
+                                <th>{t("areas-capacity")}</th>
+                                <th>{t("areas-position")}</th>
+                                <th></th>
+                            </tr>
+                            {areas.into_iter()
+                                .map(|area| view! { <AreaRow area version set_error_message/> })
+                                .collect_view()}
+                            <AreaRow area=Area::new(String::new(), String::new(), 100) version set_error_message/>
+                        </table>
+                    }.into_any(),
+                    Err(e) => view! { <span class=cart_style::error>{e.to_string()}</span> }.into_any(),
+                })}
+            </Transition>
+        </div>
+    }
+}
+
+#[component]
+fn AreaRow(area: Area, version: RwSignal<u32>, set_error_message: WriteSignal<String>) -> impl IntoView {
+    let is_new = area.uuid.is_empty();
+    let editing = RwSignal::new(area);
+
+    let on_save = move |_| {
+        let area = editing.get_untracked();
+        spawn_local(async move {
+            match save_area(area).await {
+                Ok(_) => {
+                    set_error_message(String::new());
+                    version.update(|v| *v += 1);
+                }
+                Err(e) => set_error_message(e.to_string()),
+            }
+        });
+    };
+    let on_delete = move |_| {
+        let uuid = editing.with_untracked(|a| a.uuid.clone());
+        spawn_local(async move {
+            match remove_area(uuid).await {
+                Ok(_) => version.update(|v| *v += 1),
+                Err(e) => set_error_message(e.to_string()),
+            }
+        });
+    };
+
+    view! {
+        <tr>
+            <td>
+                <input type="text" placeholder=t("areas-new") class=cart_style::input
+                    prop:value=move || editing.with(|a| a.name.clone())
+                    on:input=move |e| editing.update(|a| a.name = event_target_value(&e))
+                />
+            </td>
+            <td>
+                <input type="number" min="1" class=cart_style::input
+                    prop:value=move || editing.with(|a| a.capacity.to_string())
+                    on:input=move |e| editing.update(|a| a.capacity = event_target_value(&e).parse().unwrap_or(0))
+                />
+            </td>
+            <td>
+                <input type="number" min="0" class=cart_style::input
+                    prop:value=move || editing.with(|a| a.position.to_string())
+                    on:input=move |e| editing.update(|a| a.position = event_target_value(&e).parse().unwrap_or(0))
+                />
+            </td>
+            <td class=cart_style::amount>
+                <button class=cart_style::button on:click=on_save>
+                    {t(if is_new { "admin-create" } else { "admin-save" })}
+                </button>
+                {(!is_new).then(|| view! {
+                    <button class=cart_style::button on:click=on_delete>{t("admin-delete")}</button>
+                })}
+            </td>
+        </tr>
+    }
+}
+
+#[server(OccupancyAreas, "/api")]
+pub async fn occupancy_areas() -> Result<Vec<Area>, ServerFnError> {
+    require_role(Role::Staff).await?;
+    Ok(database::get_areas().await.unwrap_or_default())
+}
+
+/// A door click, +1 for in and -1 for out. In fails once the area is full.
+#[server(CountOccupancy, "/api")]
+pub async fn count_occupancy(area: String, delta: i32) -> Result<Area, ServerFnError> {
+    let user = require_role(Role::Staff).await?;
+    occupancy::count(area, delta, user.uuid)
+        .await
+        .map_err(|e| ServerFnError::Args(ErrorMessage::create(e)))
+}
+
+#[server(ResetOccupancy, "/api")]
+pub async fn reset_occupancy(area: String) -> Result<Area, ServerFnError> {
+    let user = require_role(Role::Staff).await?;
+    occupancy::reset(area, user.uuid)
+        .await
+        .map_err(|e| ServerFnError::Args(ErrorMessage::create(e)))
+}
+
+/// How full the venue is, `None` while it's closed.
+#[server(CrowdLevelNow, "/api")]
+pub async fn crowd_level() -> Result<Option<CrowdLevel>, ServerFnError> {
+    Ok(CrowdLevel::of(&database::get_areas().await.unwrap_or_default()))
+}
+
+/// Creates the area or changes its name, capacity or position.
+#[server(SaveArea, "/api")]
+pub async fn save_area(area: Area) -> Result<Area, ServerFnError> {
+    require_role(Role::Admin).await?;
+    let area = Area {
+        uuid: match area.uuid.as_str() {
+            "" => Uuid::new_v4().to_string(),
+            uuid => uuid.to_string(),
+        },
+        name: area.name.trim().to_string(),
+        ..area
+    };
+    if area.validate().is_err() {
+        return Err(ServerFnError::Args(request_text("error-area-invalid")));
+    }
+    let saved = database::save_area(area)
+        .await
+        .ok_or_else(|| ServerFnError::Args(ErrorMessage::create(ResponseError::AreaNotFound)))?;
+    occupancy::publish().await;
+    Ok(saved)
+}
+
+#[server(RemoveArea, "/api")]
+pub async fn remove_area(uuid: String) -> Result<(), ServerFnError> {
+    require_role(Role::Admin).await?;
+    database::delete_area(uuid).await;
+    occupancy::publish().await;
+    Ok(())
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use uuid::Uuid;
+        use validator::Validate;
+
+        use crate::app::auth::require_role;
+        use crate::app::database;
+        use crate::app::errors::{ErrorMessage, ResponseError, ResponseErrorTrait};
+        use crate::app::i18n::request_text;
+        use crate::app::model::user::Role;
+        use crate::app::occupancy;
+    }
+}
@@ -0,0 +1,267 @@
+use leptos::{prelude::*, task::spawn_local};
+use leptos::ev;
+use leptos::logging::log;
+use leptos_router::hooks::use_params_map;
+use std::time::Duration;
+
+use crate::app::i18n::{t, use_locale, Locale};
+use crate::app::model::door::{ConflictKind, DoorMode, DoorSnapshot, ScanConflict};
+use crate::app::model::ticket::{DoorCounts, ScanResult};
+use crate::app::offline;
+use crate::app::page::door::{door_preload, door_sync, CountsBar, ScanPanel};
+use crate::app::scanner::Scanner;
+use crate::app::seo::PageMeta;
+
+stylance::import_style!(style, "../../style/door.module.scss");
+
+const SYNC_RETRY: Duration = Duration::from_secs(30);
+
+/// Signals shared by the offline door view and its sync routines.
+#[derive(Clone, Copy)]
+struct OfflineDoor {
+    slug: RwSignal<String>,
+    snapshot: RwSignal<Option<DoorSnapshot>>,
+    online: RwSignal<bool>,
+    syncing: RwSignal<bool>,
+    pending: RwSignal<usize>,
+    counts: RwSignal<DoorCounts>,
+    conflicts: RwSignal<Vec<ScanConflict>>,
+    status: RwSignal<String>,
+    locale: RwSignal<Locale>,
+}
+
+impl OfflineDoor {
+    fn new() -> OfflineDoor {
+        OfflineDoor {
+            slug: RwSignal::new(String::new()),
+            snapshot: RwSignal::new(None),
+            online: RwSignal::new(true),
+            syncing: RwSignal::new(false),
+            pending: RwSignal::new(0),
+            counts: RwSignal::new(DoorCounts::default()),
+            conflicts: RwSignal::new(Vec::new()),
+            status: RwSignal::new(String::new()),
+            locale: use_locale(),
+        }
+    }
+
+    fn event(&self) -> Option<String> {
+        self.snapshot.with_untracked(|s| s.as_ref().map(|s| s.event.uuid.clone()))
+    }
+
+    /// Restores the stored list and, when online, syncs and fetches a fresh one.
+    async fn load(self) {
+        let slug = self.slug.get_untracked();
+        match offline::load_snapshot(&slug).await {
+            Ok(snapshot) => self.snapshot.set(snapshot),
+            Err(e) => log!("Error {:?}", e),
+        }
+        if self.online.get_untracked() && self.sync().await {
+            self.preload().await;
+        }
+        self.refresh().await;
+    }
+
+    async fn preload(self) {
+        let slug = self.slug.get_untracked();
+        match door_preload(slug.clone()).await {
+            Ok(snapshot) => {
+                let tickets = snapshot.tickets.len();
+                let mut stored = snapshot.clone();
+                stored.tickets = Vec::new();
+                match offline::save_snapshot(&slug, snapshot).await {
+                    Ok(_) => {
+                        self.snapshot.set(Some(stored));
+                        self.status.set(self.locale.get_untracked().format("offline-loaded", &[("count", &tickets.to_string())]));
+                    }
+                    Err(e) => self.status.set(e),
+                }
+            }
+            Err(e) => {
+                log!("Error {:?}", e);
+                self.status.set(self.locale.get_untracked().text("offline-load-failed").to_string());
+            }
+        }
+    }
+
+    /// Pushes pending scans. Synced scans are only dropped locally once the
+    /// server confirmed them; resending is harmless as the server ignores
+    /// scans it already has. Returns whether nothing is left to push, as a
+    /// fresh list would otherwise undo the check-ins made offline.
+    async fn sync(self) -> bool {
+        let Some(event) = self.event() else { return true };
+        if self.syncing.get_untracked() {
+            return false;
+        }
+        self.syncing.set(true);
+
+        let synced = match offline::pending_scans(&event).await {
+            Ok(scans) if !scans.is_empty() => match door_sync(event, scans.clone()).await {
+                Ok(report) => {
+                    let _ = offline::forget_scans(&scans).await;
+                    self.status.set(self.locale.get_untracked().format("offline-synced", &[
+                        ("applied", &report.applied.to_string()),
+                        ("conflicts", &report.conflicts.len().to_string()),
+                    ]));
+                    self.conflicts.update(|conflicts| conflicts.extend(report.conflicts));
+                    true
+                }
+                Err(e) => {
+                    log!("Error {:?}", e);
+                    self.status.set(self.locale.get_untracked().text("offline-sync-failed").to_string());
+                    false
+                }
+            },
+            Ok(_) => true,
+            Err(e) => {
+                self.status.set(e);
+                false
+            }
+        };
+
+        self.syncing.set(false);
+        self.refresh().await;
+        synced
+    }
+
+    async fn refresh(self) {
+        let Some(event) = self.event() else { return };
+        if let Ok(scans) = offline::pending_scans(&event).await {
+            self.pending.set(scans.len());
+        }
+        if let Ok(counts) = offline::local_counts(&event).await {
+            self.counts.set(counts);
+        }
+    }
+}
+
+#[leptos::component]
+pub fn OfflineDoorPage() -> impl IntoView {
+    let params = use_params_map();
+    let door = OfflineDoor::new();
+
+    let (mode, set_mode) = signal(DoorMode::In);
+    let (last_scan, set_last_scan) = signal::<Option<ScanResult>>(None);
+
+    Effect::new(move |_| {
+        let slug = params.read().get("event").unwrap_or_default();
+        door.slug.set(slug);
+        door.online.set(window().navigator().on_line());
+        spawn_local(door.load());
+    });
+
+    Effect::new(move |_| {
+        let online = window_event_listener(ev::online, move |_| {
+            door.online.set(true);
+            spawn_local(async move {
+                door.sync().await;
+            });
+        });
+        let offline = window_event_listener(ev::offline, move |_| door.online.set(false));
+        let retry = set_interval_with_handle(
+            move || {
+                if door.online.get_untracked() && door.pending.get_untracked() > 0 {
+                    spawn_local(async move {
+                        door.sync().await;
+                    });
+                }
+            },
+            SYNC_RETRY,
+        ).ok();
+        on_cleanup(move || {
+            online.remove();
+            offline.remove();
+            if let Some(retry) = retry {
+                retry.clear();
+            }
+        });
+    });
+
+    let on_scan = Callback::new(move |code: String| {
+        let Some(snapshot) = door.snapshot.get_untracked() else {
+            door.status.set(door.locale.get_untracked().text("offline-no-list").to_string());
+            return;
+        };
+        let mode = mode.get_untracked();
+        spawn_local(async move {
+            match offline::scan(&snapshot, code, mode, door.locale.get_untracked()).await {
+                Ok(result) => set_last_scan(Some(result)),
+                Err(e) => door.status.set(e),
+            }
+            door.refresh().await;
+            if door.online.get_untracked() {
+                door.sync().await;
+            }
+        });
+    });
+
+    let on_reload = move |_| {
+        spawn_local(async move {
+            if door.sync().await {
+                door.preload().await;
+            }
+            door.refresh().await;
+        });
+    };
+
+    view! {
+        <PageMeta title=door.locale.get_untracked().text("offline-title") noindex=true/>
+        <div class=format!("container {}", style::door)>
+            <div class="h2">
+                {move || door.snapshot.with(|s| s.as_ref().map(|s| s.event.title.clone()).unwrap_or_default())}
+            </div>
+            <div class=style::name_row>
+                <span class=move || if door.online.get() { style::online } else { style::offline }>
+                    {move || door.locale.get().text(if door.online.get() { "offline-online" } else { "offline-offline" })}
+                </span>
+                <span>{move || door.locale.get().format("offline-pending", &[("count", &door.pending.get().to_string())])}</span>
+                <button class=style::button on:click=on_reload disabled=move || !door.online.get()>
+                    <i class="bi bi-arrow-repeat"></i>
+                </button>
+            </div>
+            {move || view! { <CountsBar counts=door.counts.get()/> }}
+            <div class=style::modes>
+                <button
+                    class=move || if mode() == DoorMode::In { style::mode_active } else { style::mode }
+                    on:click=move |_| set_mode(DoorMode::In)
+                >{t("door-mode-in")}</button>
+                <button
+                    class=move || if mode() == DoorMode::Out { style::mode_active } else { style::mode }
+                    on:click=move |_| set_mode(DoorMode::Out)
+                >{t("door-mode-out")}</button>
+            </div>
+            <Scanner on_scan/>
+            <span>{move || door.status.get()}</span>
+            {move || last_scan().map(|result| view! { <ScanPanel result/> })}
+            <div class=style::names>
+                <For
+                    each=move || door.conflicts.get()
+                    key=|conflict| conflict.scan.clone()
+                    children=move |conflict| view! { <ConflictRow conflict/> }
+                />
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn ConflictRow(conflict: ScanConflict) -> impl IntoView {
+    let locale = use_locale().get_untracked();
+    let reason = match conflict.kind {
+        ConflictKind::DuplicateEntry => locale.format("conflict-duplicate", &[
+            ("device", &conflict.winner_device.unwrap_or_default()),
+            ("time", &conflict.winner_at.unwrap_or_default()),
+        ]),
+        ConflictKind::Refunded => locale.text("conflict-refunded").to_string(),
+        ConflictKind::Cancelled => locale.text("conflict-cancelled").to_string(),
+        ConflictKind::Unknown => locale.text("conflict-unknown").to_string(),
+    };
+
+    view! {
+        <div class=format!("{} {}", style::name_row, style::conflict)>
+            <span class=style::panel_code>{conflict.code}</span>
+            <span>{conflict.holder_name.unwrap_or_default()}</span>
+            <span>{format!("{} ({})", reason, conflict.device)}</span>
+        </div>
+    }
+}
@@ -0,0 +1,161 @@
+use leptos::prelude::*;
+use leptos_router::hooks::use_params_map;
+
+use crate::app::i18n::{t, use_locale};
+use crate::app::ical::parse_berlin;
+use crate::app::model::Order;
+use crate::app::model::shipping::country_name;
+use crate::app::model::order::OrderStatus;
+use crate::app::push::{use_push, PushMessage};
+use crate::app::seo::PageMeta;
+
+stylance::import_style!(style, "../../style/cart.module.scss");
+
+/// Catalogue key of the status.
+pub fn status_label(status: OrderStatus) -> &'static str {
+    match status {
+        OrderStatus::Pending => "order-pending",
+        OrderStatus::Paid => "order-paid",
+        OrderStatus::Fulfilled => "order-fulfilled",
+        OrderStatus::Refunded => "order-refunded",
+        OrderStatus::Cancelled => "order-cancelled",
+    }
+}
+
+#[leptos::component]
+pub fn OrderPage() -> impl IntoView {
+    let params = use_params_map();
+    let uuid = move || params.read().get("order").unwrap_or_default();
+    let order = Resource::new(uuid, order_details);
+    // e.g. paid while the buyer waits here after paying
+    use_push().on(move |message| {
+        if let PushMessage::OrderUpdated { order: updated, .. } = message {
+            if updated == uuid() {
+                order.refetch();
+            }
+        }
+    });
+
+    view! {
+        <PageMeta title=use_locale().get_untracked().text("order-title") noindex=true/>
+        <div class=format!("container {}", style::cart)>
+            <Suspense fallback=move || view! { <p>{t("loading")}</p> }>
+                {move || order.get().map(|order| match order {
+                    Ok(order) => view! { <OrderSummary order/> }.into_any(),
+                    Err(e) => view! { <span class=style::error>{e.to_string()}</span> }.into_any(),
+                })}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+pub fn OrderSummary(order: Order) -> impl IntoView {
+    let locale = use_locale();
+    let created_at = parse_berlin(&order.created_at);
+
+    view! {
+        <div class=style::row>
+            <div class="h2">{t("order-title")}</div>
+            <span class=style::status>{t(status_label(order.status))}</span>
+        </div>
+        <span>{move || created_at.map(|time| locale.get().date_time(time)).unwrap_or_default()}</span>
+        <table class=style::table>
+            <tr>
+                <th>{t("column-item")}</th><th>{t("column-quantity")}</th>
+                <th class=style::amount>{t("column-price")}</th><th class=style::amount>{t("column-sum")}</th>
+            </tr>
+            {order.items.into_iter().map(|item| view! {
+                <tr>
+                    <td>{item.name}</td>
+                    <td>{item.quantity}</td>
+                    <td class=style::amount>{move || locale.get().price(item.unit_price_cents)}</td>
+                    <td class=style::amount>{move || locale.get().price(item.total_cents)}</td>
+                </tr>
+            }).collect_view()}
+            {order.vat.into_iter().map(|vat| view! {
+                <tr>
+                    <td>{move || locale.get().format("order-vat", &[("rate", &vat.rate.to_string())])}</td><td></td><td></td>
+                    <td class=style::amount>{move || locale.get().price(vat.vat_cents)}</td>
+                </tr>
+            }).collect_view()}
+            <tr class=style::total>
+                <td>{t("total")}</td><td></td><td></td>
+                <td class=style::amount>{move || locale.get().price(order.total_cents)}</td>
+            </tr>
+        </table>
+        {order.delivery.map(|delivery| match delivery.address {
+            Some(address) => {
+                let address = format!(
+                    "{}, {} {}, {}",
+                    address.line1,
+                    address.zipcode,
+                    address.city,
+                    country_name(&address.country),
+                );
+                view! {
+                    <span>{move || locale.get().format("order-shipping-to", &[("address", &address)])}</span>
+                }.into_any()
+            }
+            None => view! { <span>{t("pickup")}</span> }.into_any(),
+        })}
+    }
+}
+
+/// Orders of the logged in user, newest first.
+#[component]
+pub fn OrderHistory() -> impl IntoView {
+    let orders = Resource::new(|| (), |_| my_orders());
+    let locale = use_locale();
+
+    view! {
+        <div class="h2">{t("order-history")}</div>
+        <Transition fallback=move || ()>
+            {move || orders.get().map(|orders| view! {
+                <table class=style::table>
+                    {orders.unwrap_or_default().into_iter().map(|order| {
+                        let created_at = parse_berlin(&order.created_at);
+                        view! {
+                            <tr>
+                                <td>
+                                    <a href=format!("/orders/{}", order.uuid)>
+                                        {move || created_at.map(|time| locale.get().date(time.date())).unwrap_or_default()}
+                                    </a>
+                                </td>
+                                <td>{order.items.iter().map(|i| format!("{}× {}", i.quantity, i.name)).collect::<Vec<_>>().join(", ")}</td>
+                                <td class=style::status>{t(status_label(order.status))}</td>
+                                <td class=style::amount>{move || locale.get().price(order.total_cents)}</td>
+                            </tr>
+                        }
+                    }).collect_view()}
+                </table>
+            })}
+        </Transition>
+    }
+}
+
+#[server(MyOrders, "/api")]
+pub async fn my_orders() -> Result<Vec<Order>, ServerFnError> {
+    let Some(user) = current_user().await else {
+        return Err(ServerFnError::Args(request_text("error-not-logged-in")));
+    };
+    Ok(database::get_orders_for_user(user.uuid).await.unwrap_or_default())
+}
+
+/// The order id is only known to the buyer, who gets it from checkout.
+#[server(OrderDetails, "/api")]
+pub async fn order_details(order: String) -> Result<Order, ServerFnError> {
+    match database::get_order(order).await {
+        Some(order) => Ok(order),
+        None => Err(ServerFnError::Args(ErrorMessage::create(ResponseError::OrderNotFound))),
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use crate::app::auth::current_user;
+        use crate::app::database;
+        use crate::app::errors::{ErrorMessage, ResponseError, ResponseErrorTrait};
+        use crate::app::i18n::request_text;
+    }
+}
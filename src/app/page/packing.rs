@@ -0,0 +1,197 @@
+use leptos::{prelude::*, task::spawn_local};
+use leptos::logging::log;
+
+use crate::app::i18n::{t, use_locale};
+use crate::app::ical::parse_berlin;
+use crate::app::model::{Address, Order};
+use crate::app::model::cart::ItemKind;
+use crate::app::model::shipping::{country_name, DeliveryMethod};
+use crate::app::seo::PageMeta;
+
+stylance::import_style!(style, "../../style/cart.module.scss");
+
+/// One line per address line, for labels and the export.
+fn address_text(address: &Address) -> String {
+    [
+        Some(address.line1.clone()),
+        address.line2.clone(),
+        Some(format!("{} {} {}", address.zipcode, address.city, address.state).trim().to_string()),
+        Some(country_name(&address.country).to_string()),
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|l| !l.is_empty())
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+#[leptos::component]
+pub fn PackingListPage() -> impl IntoView {
+    let version = RwSignal::new(0u32);
+    let orders = Resource::new(move || version.get(), |_| orders_to_pack());
+    let (download, set_download) = signal::<Option<String>>(None);
+
+    let on_export = move |_| {
+        spawn_local(async move {
+            match export_packing_list().await {
+                Ok(text) => {
+                    let encoded: String = js_sys::encode_uri_component(&text).into();
+                    set_download(Some(format!("data:text/csv;charset=utf-8,{encoded}")));
+                }
+                Err(e) => log!("Error {:?}", e),
+            }
+        });
+    };
+
+    view! {
+        <PageMeta title=use_locale().get_untracked().text("packing-title") noindex=true/>
+        <div class=format!("container {}", style::cart)>
+            <div class=style::row>
+                <div class="h2">{t("packing-title")}</div>
+                <div class=style::actions>
+                    <button class=style::button on:click=on_export>{t("packing-export")}</button>
+                    {move || download().map(|href| view! {
+                        <a href=href download="packliste.csv">"packliste.csv"</a>
+                    })}
+                </div>
+            </div>
+            <Transition fallback=move || view! { <p>{t("loading")}</p> }>
+                {move || orders.get().map(|orders| match orders {
+                    Ok(orders) if orders.is_empty() => view! { <span>{t("packing-empty")}</span> }.into_any(),
+                    Ok(orders) => orders.into_iter()
+                        .map(|order| view! { <PackingSlip order version/> })
+                        .collect_view()
+                        .into_any(),
+                    Err(e) => view! { <span class=style::error>{e.to_string()}</span> }.into_any(),
+                })}
+            </Transition>
+        </div>
+    }
+}
+
+#[component]
+fn PackingSlip(order: Order, version: RwSignal<u32>) -> impl IntoView {
+    let (error_message, set_error_message) = signal(String::new());
+    let delivery = order.delivery.clone();
+    let shipping = delivery.as_ref().is_some_and(|d| d.method == DeliveryMethod::Shipping);
+    let created_at = parse_berlin(&order.created_at);
+    let locale = use_locale();
+
+    let uuid = order.uuid.clone();
+    let on_done = move |_| {
+        let uuid = uuid.clone();
+        spawn_local(async move {
+            match mark_handed_over(uuid).await {
+                Ok(_) => version.update(|v| *v += 1),
+                Err(e) => set_error_message(e.to_string()),
+            }
+        });
+    };
+
+    view! {
+        <div class=style::row>
+            <span class=style::total>
+                <a href=format!("/orders/{}", order.uuid)>{order.uuid.get(..8).unwrap_or_default().to_string()}</a>
+                " · "{order.buyer_name.clone()}" · "{move || created_at.map(|time| locale.get().date(time.date())).unwrap_or_default()}
+            </span>
+            <button class=style::button on:click=on_done>
+                {t(if shipping { "packing-shipped" } else { "packing-picked-up" })}
+            </button>
+        </div>
+        <span class=style::status>{t(if shipping { "packing-shipping" } else { "packing-pickup" })}</span>
+        {delivery.and_then(|d| d.address).map(|address| view! {
+            <pre>{format!("{}\n{}", order.buyer_name, address_text(&address))}</pre>
+        })}
+        <table class=style::table>
+            <tr><th>"SKU"</th><th>{t("column-item")}</th><th>{t("column-quantity")}</th></tr>
+            {order.items.into_iter().filter(|i| i.kind == ItemKind::Merch).map(|item| view! {
+                <tr>
+                    <td>{item.reference}</td>
+                    <td>{item.name}</td>
+                    <td>{item.quantity}</td>
+                </tr>
+            }).collect_view()}
+        </table>
+        <span class=style::error>{error_message}</span>
+    }
+}
+
+#[server(OrdersToPack, "/api")]
+pub async fn orders_to_pack() -> Result<Vec<Order>, ServerFnError> {
+    require_role(Role::Staff).await?;
+    Ok(database::get_orders_to_pack().await.unwrap_or_default())
+}
+
+#[server(ExportPackingList, "/api")]
+pub async fn export_packing_list() -> Result<String, ServerFnError> {
+    require_role(Role::Staff).await?;
+    let mut records = vec![["order", "created_at", "name", "email", "delivery", "address", "sku", "item", "quantity"]
+        .iter()
+        .map(|h| h.to_string())
+        .collect::<Vec<_>>()];
+    for order in database::get_orders_to_pack().await.unwrap_or_default() {
+        let (method, address) = match &order.delivery {
+            Some(delivery) => (
+                format!("{:?}", delivery.method),
+                delivery.address.as_ref().map(address_text).unwrap_or_default(),
+            ),
+            None => (String::new(), String::new()),
+        };
+        for item in order.items.iter().filter(|i| i.kind == ItemKind::Merch) {
+            records.push(vec![
+                order.uuid.clone(),
+                order.created_at.clone(),
+                order.buyer_name.clone(),
+                order.email.clone(),
+                method.clone(),
+                address.clone(),
+                item.reference.clone(),
+                item.name.clone(),
+                item.quantity.to_string(),
+            ]);
+        }
+    }
+    Ok(csv::write(&records))
+}
+
+/// Paid -> Fulfilled once the parcel is sent or the buyer picked it up, which
+/// is when the order is invoiced.
+#[server(MarkHandedOver, "/api")]
+pub async fn mark_handed_over(order: String) -> Result<(), ServerFnError> {
+    require_role(Role::Staff).await?;
+    let order = match database::transition_order(order, OrderStatus::Fulfilled, time::now()).await {
+        Ok(Some(order)) => order,
+        Ok(None) => return Err(ServerFnError::Args(ErrorMessage::create(ResponseError::OrderTransitionFailure))),
+        Err(e) => return Err(ServerFnError::Args(ErrorMessage::create(e))),
+    };
+    payment::notify_buyer(&order);
+    if let Err(e) = invoice::issue_for_order(&order).await {
+        println!("error in issuing invoice for order {}: {:?}", order.uuid, e);
+    }
+    if order.delivery.as_ref().is_some_and(|d| d.method == DeliveryMethod::Shipping) {
+        let mail = Mail::new(
+            order.email.clone(),
+            order.locale.text("mail-shipped-subject").to_string(),
+            order.locale.format("mail-shipped-body", &[("name", &order.buyer_name), ("order", &order.uuid)]),
+        );
+        if let Err(e) = mail::send(mail).await {
+            println!("error in mailing shipment of order {}: {:?}", order.uuid, e);
+        }
+    }
+    Ok(())
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use crate::app::auth::require_role;
+        use crate::app::csv;
+        use crate::app::database;
+        use crate::app::errors::{ErrorMessage, ResponseError, ResponseErrorTrait};
+        use crate::app::invoice;
+        use crate::app::mail::{self, Mail};
+        use crate::app::model::order::OrderStatus;
+        use crate::app::model::user::Role;
+        use crate::app::payment;
+        use crate::app::time;
+    }
+}
@@ -0,0 +1,302 @@
+use leptos::{prelude::*, task::spawn_local};
+
+use crate::app::i18n::{t, use_locale};
+use crate::app::model::Product;
+use crate::app::model::product::{ProductDetails, ProductVariant};
+use crate::app::seo::PageMeta;
+
+stylance::import_style!(style, "../../style/cart.module.scss");
+stylance::import_style!(shop_style, "../../style/shop.module.scss");
+
+#[leptos::component]
+pub fn ProductsAdminPage() -> impl IntoView {
+    let version = RwSignal::new(0u32);
+    let products = Resource::new(move || version.get(), |_| all_products());
+
+    view! {
+        <PageMeta title=use_locale().get_untracked().text("products-title") noindex=true/>
+        <div class=format!("container {}", style::cart)>
+            <div class="h2">{t("products-title")}</div>
+            <ProductForm version/>
+            <Transition fallback=move || view! { <p>{t("loading")}</p> }>
+                {move || products.get().map(|products| match products {
+                    Ok(products) => products.into_iter()
+                        .map(|details| view! { <ProductStock details version/> })
+                        .collect_view()
+                        .into_any(),
+                    Err(e) => view! { <span class=style::error>{e.to_string()}</span> }.into_any(),
+                })}
+            </Transition>
+        </div>
+    }
+}
+
+#[component]
+fn ProductForm(version: RwSignal<u32>) -> impl IntoView {
+    let (name, set_name) = signal(String::new());
+    let (slug, set_slug) = signal(String::new());
+    let (description, set_description) = signal(String::new());
+    let (images, set_images) = signal(String::new());
+    let (error_message, set_error_message) = signal(String::new());
+
+    let on_submit = move |_| {
+        let mut product = Product::new(String::new(), slug.get_untracked(), name.get_untracked());
+        product.description = description.get_untracked();
+        product.images = images
+            .get_untracked()
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+        spawn_local(async move {
+            match add_product(product).await {
+                Ok(_) => {
+                    set_name(String::new());
+                    set_slug(String::new());
+                    set_description(String::new());
+                    set_images(String::new());
+                    set_error_message(String::new());
+                    version.update(|v| *v += 1);
+                }
+                Err(e) => set_error_message(e.to_string()),
+            }
+        });
+    };
+
+    view! {
+        <div class=style::row>
+            <input type="text" placeholder=t("name") class=style::input
+                prop:value=name
+                on:input=move |e| set_name(event_target_value(&e))
+            />
+            <input type="text" placeholder="Slug" class=style::input
+                prop:value=slug
+                on:input=move |e| set_slug(event_target_value(&e))
+            />
+        </div>
+        <div class=style::row>
+            <input type="text" placeholder=t("products-description") class=style::input
+                prop:value=description
+                on:input=move |e| set_description(event_target_value(&e))
+            />
+            <input type="text" placeholder=t("products-images") class=style::input
+                prop:value=images
+                on:input=move |e| set_images(event_target_value(&e))
+            />
+            <button class=style::button on:click=on_submit>{t("admin-create")}</button>
+        </div>
+        <span class=style::error>{error_message}</span>
+    }
+}
+
+#[component]
+fn ProductStock(details: ProductDetails, version: RwSignal<u32>) -> impl IntoView {
+    let ProductDetails { product, variants } = details;
+    let (error_message, set_error_message) = signal(String::new());
+    let locale = use_locale();
+
+    let on_set_stock = Callback::new(move |(sku, stock): (String, u32)| {
+        spawn_local(async move {
+            match set_stock(sku, stock).await {
+                Ok(_) => version.update(|v| *v += 1),
+                Err(e) => set_error_message(e.to_string()),
+            }
+        });
+    });
+
+    let uuid = product.uuid.clone();
+    let active = product.active;
+    let on_toggle = move |_| {
+        let uuid = uuid.clone();
+        spawn_local(async move {
+            match set_product_active(uuid, !active).await {
+                Ok(_) => version.update(|v| *v += 1),
+                Err(e) => set_error_message(e.to_string()),
+            }
+        });
+    };
+
+    view! {
+        <div class=style::row>
+            <a class=shop_style::name href=format!("/shop/{}", product.slug)>{product.name}</a>
+            <button class=style::button on:click=on_toggle>
+                {t(if active { "products-hide" } else { "products-show" })}
+            </button>
+        </div>
+        <table class=style::table>
+            <tr><th>"SKU"</th><th>{t("products-variant")}</th><th class=style::amount>{t("column-price")}</th><th class=style::amount>{t("products-stock")}</th></tr>
+            {variants.into_iter().map(|variant| {
+                let (stock, set_stock_input) = signal(variant.stock.to_string());
+                let sku = variant.sku.clone();
+                view! {
+                    <tr>
+                        <td>{variant.sku.clone()}</td>
+                        <td>{variant.label()}</td>
+                        <td class=style::amount>{move || locale.get().price(variant.price_cents)}</td>
+                        <td class=style::amount>
+                            <input type="number" class=style::input_small
+                                prop:value=stock
+                                on:input=move |e| set_stock_input(event_target_value(&e))
+                                on:change=move |_| {
+                                    if let Ok(stock) = stock.get_untracked().parse() {
+                                        on_set_stock.run((sku.clone(), stock));
+                                    }
+                                }
+                            />
+                            {variant.is_low().then(|| view! { <span class=shop_style::low>" "{t("products-low")}</span> })}
+                        </td>
+                    </tr>
+                }
+            }).collect_view()}
+        </table>
+        <VariantForm product=product.uuid version/>
+        <span class=style::error>{error_message}</span>
+    }
+}
+
+#[component]
+fn VariantForm(product: String, version: RwSignal<u32>) -> impl IntoView {
+    let locale = use_locale();
+    let (sku, set_sku) = signal(String::new());
+    let (size, set_size) = signal(String::new());
+    let (colour, set_colour) = signal(String::new());
+    let (price, set_price) = signal(String::new());
+    let (stock, set_stock_input) = signal(String::new());
+    let (weight, set_weight) = signal(String::new());
+    let (error_message, set_error_message) = signal(String::new());
+
+    let on_submit = move |_| {
+        let Ok(euros) = price.get_untracked().trim().replace(',', ".").parse::<f64>() else {
+            set_error_message(locale.get_untracked().text("products-price-invalid").to_string());
+            return;
+        };
+        let optional = |s: String| (!s.trim().is_empty()).then(|| s.trim().to_string());
+        let mut variant = ProductVariant::new(
+            sku.get_untracked().trim().to_string(),
+            product.clone(),
+            (euros * 100.0).round() as i64,
+            stock.get_untracked().trim().parse().unwrap_or(0),
+        );
+        variant.size = optional(size.get_untracked());
+        variant.colour = optional(colour.get_untracked());
+        variant.weight_grams = weight.get_untracked().trim().parse().unwrap_or(0);
+        spawn_local(async move {
+            match add_product_variant(variant).await {
+                Ok(_) => {
+                    set_sku(String::new());
+                    set_error_message(String::new());
+                    version.update(|v| *v += 1);
+                }
+                Err(e) => set_error_message(e.to_string()),
+            }
+        });
+    };
+
+    view! {
+        <div class=style::row>
+            <input type="text" placeholder="SKU" class=style::input
+                prop:value=sku
+                on:input=move |e| set_sku(event_target_value(&e))
+            />
+            <input type="text" placeholder=t("products-size") class=style::input_small
+                prop:value=size
+                on:input=move |e| set_size(event_target_value(&e))
+            />
+            <input type="text" placeholder=t("products-colour") class=style::input
+                prop:value=colour
+                on:input=move |e| set_colour(event_target_value(&e))
+            />
+            <input type="text" placeholder=t("column-price") class=style::input_small
+                prop:value=price
+                on:input=move |e| set_price(event_target_value(&e))
+            />
+            <input type="number" placeholder=t("products-stock") class=style::input_small
+                prop:value=stock
+                on:input=move |e| set_stock_input(event_target_value(&e))
+            />
+            <input type="number" placeholder=t("products-grams") class=style::input_small
+                prop:value=weight
+                on:input=move |e| set_weight(event_target_value(&e))
+            />
+            <button class=style::button on:click=on_submit>{t("products-variant")}</button>
+        </div>
+        <span class=style::error>{error_message}</span>
+    }
+}
+
+#[server(AllProducts, "/api")]
+pub async fn all_products() -> Result<Vec<ProductDetails>, ServerFnError> {
+    require_role(Role::Admin).await?;
+    let products = database::get_products().await.unwrap_or_default();
+    let variants = database::get_variants(Vec::new()).await.unwrap_or_default();
+    Ok(with_variants(products, variants))
+}
+
+#[server(AddProduct, "/api")]
+pub async fn add_product(product: Product) -> Result<Product, ServerFnError> {
+    require_role(Role::Admin).await?;
+    let product = Product {
+        uuid: Uuid::new_v4().to_string(),
+        slug: product.slug.trim().to_lowercase(),
+        ..product
+    };
+    if product.validate().is_err() {
+        return Err(ServerFnError::Args(request_text("error-product-invalid")));
+    }
+    if database::get_product_by_slug(product.slug.clone()).await.is_some() {
+        return Err(ServerFnError::Args(request_text("error-slug-taken")));
+    }
+    database::add_product(product)
+        .await
+        .ok_or(ServerFnError::Args(ErrorMessage::create(ResponseError::ProductNotFound)))
+}
+
+#[server(SetProductActive, "/api")]
+pub async fn set_product_active(product: String, active: bool) -> Result<Product, ServerFnError> {
+    require_role(Role::Admin).await?;
+    let Some(product) = database::get_product(product).await else {
+        return Err(ServerFnError::Args(ErrorMessage::create(ResponseError::ProductNotFound)));
+    };
+    database::update_product(Product { active, ..product })
+        .await
+        .ok_or(ServerFnError::Args(ErrorMessage::create(ResponseError::ProductNotFound)))
+}
+
+#[server(AddProductVariant, "/api")]
+pub async fn add_product_variant(variant: ProductVariant) -> Result<ProductVariant, ServerFnError> {
+    require_role(Role::Admin).await?;
+    if variant.validate().is_err() || variant.price_cents <= 0 {
+        return Err(ServerFnError::Args(request_text("error-variant-invalid")));
+    }
+    if database::get_product(variant.product.clone()).await.is_none() {
+        return Err(ServerFnError::Args(ErrorMessage::create(ResponseError::ProductNotFound)));
+    }
+    if database::get_variant(variant.sku.clone()).await.is_some() {
+        return Err(ServerFnError::Args(request_text("error-sku-taken")));
+    }
+    database::add_variant(variant)
+        .await
+        .ok_or(ServerFnError::Args(ErrorMessage::create(ResponseError::ProductNotFound)))
+}
+
+#[server(SetStock, "/api")]
+pub async fn set_stock(sku: String, stock: u32) -> Result<(), ServerFnError> {
+    require_role(Role::Admin).await?;
+    database::set_variant_stock(sku, stock)
+        .await
+        .map_err(|e| ServerFnError::Args(ErrorMessage::create(e)))
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use uuid::Uuid;
+        use validator::Validate;
+
+        use crate::app::auth::require_role;
+        use crate::app::database;
+        use crate::app::errors::{ErrorMessage, ResponseError, ResponseErrorTrait};
+        use crate::app::i18n::request_text;
+        use crate::app::model::user::Role;
+        use crate::app::page::shop::with_variants;
+    }
+}
@@ -0,0 +1,185 @@
+use leptos::{prelude::*, task::spawn_local};
+use leptos_router::hooks::use_params_map;
+use serde::{Deserialize, Serialize};
+
+use crate::app::i18n::{t, use_locale};
+use crate::app::ical::parse_berlin;
+use crate::app::model::{Event, Refund, Ticket};
+use crate::app::model::refund::{CancellationReport, RefundReason, RefundStatus};
+use crate::app::model::ticket::TicketStatus;
+use crate::app::page::door::status_key;
+use crate::app::seo::PageMeta;
+
+stylance::import_style!(style, "../../style/cart.module.scss");
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct RefundOverview {
+    pub event: Event,
+    /// Tickets sold through the shop.
+    pub tickets: Vec<Ticket>,
+    pub refunds: Vec<Refund>,
+}
+
+#[leptos::component]
+pub fn EventRefundsPage() -> impl IntoView {
+    let params = use_params_map();
+    let version = RwSignal::new(0u32);
+    let overview = Resource::new(
+        move || (params.read().get("event").unwrap_or_default(), version.get()),
+        |(slug, _)| refund_overview(slug),
+    );
+
+    view! {
+        <PageMeta title=use_locale().get_untracked().text("refunds-title") noindex=true/>
+        <div class=format!("container {}", style::cart)>
+            <Transition fallback=move || view! { <p>{t("loading")}</p> }>
+                {move || overview.get().map(|overview| match overview {
+                    Ok(overview) => view! { <RefundManager overview version/> }.into_any(),
+                    Err(e) => view! { <span class=style::error>{e.to_string()}</span> }.into_any(),
+                })}
+            </Transition>
+        </div>
+    }
+}
+
+#[component]
+fn RefundManager(overview: RefundOverview, version: RwSignal<u32>) -> impl IntoView {
+    let RefundOverview { event, tickets, refunds } = overview;
+    let (error_message, set_error_message) = signal(String::new());
+    let (report, set_report) = signal::<Option<CancellationReport>>(None);
+    let (confirm, set_confirm) = signal(false);
+    let locale = use_locale();
+
+    let uuid = event.uuid.clone();
+    let on_cancel_event = move |_| {
+        if !confirm.get_untracked() {
+            set_confirm(true);
+            return;
+        }
+        let uuid = uuid.clone();
+        spawn_local(async move {
+            match cancel_event(uuid).await {
+                Ok(result) => {
+                    set_report(Some(result));
+                    version.update(|v| *v += 1);
+                }
+                Err(e) => set_error_message(e.to_string()),
+            }
+        });
+    };
+
+    let on_refund = Callback::new(move |ticket: String| {
+        spawn_local(async move {
+            match refund_single_ticket(ticket).await {
+                Ok(_) => version.update(|v| *v += 1),
+                Err(e) => set_error_message(e.to_string()),
+            }
+        });
+    });
+
+    view! {
+        <div class=style::row>
+            <div class="h2">{event.title}" – "{t("refunds-title")}</div>
+            {if event.cancelled {
+                view! { <span class=style::status>{t("cancelled")}</span> }.into_any()
+            } else {
+                view! {
+                    <button class=style::button on:click=on_cancel_event>
+                        {move || locale.get().text(if confirm() { "refunds-confirm-cancel" } else { "refunds-cancel-event" })}
+                    </button>
+                }.into_any()
+            }}
+        </div>
+        <span class=style::error>{error_message}</span>
+        {move || report().map(|report| view! {
+            <span>{locale.get_untracked().format("refunds-report", &[
+                ("orders", &report.orders.to_string()),
+                ("amount", &locale.get_untracked().price(report.refunded_cents)),
+                ("mailed", &report.mailed.to_string()),
+            ])}</span>
+            {report.failed.into_iter().map(|order| view! {
+                <div class=style::error>{locale.get_untracked().format("refunds-failed", &[("order", &order)])}</div>
+            }).collect_view()}
+        })}
+        <table class=style::table>
+            <tr><th>{t("refunds-code")}</th><th>{t("refunds-holder")}</th><th>{t("refunds-status")}</th><th></th></tr>
+            {tickets.into_iter().map(|ticket| {
+                let uuid = ticket.uuid.clone();
+                view! {
+                    <tr>
+                        <td>{ticket.code}</td>
+                        <td>{ticket.holder_name}</td>
+                        <td>{t(status_key(ticket.status))}</td>
+                        <td>
+                            {(ticket.status == TicketStatus::Valid).then(|| view! {
+                                <button class=style::button on:click=move |_| on_refund.run(uuid.clone())>{t("refunds-refund")}</button>
+                            })}
+                        </td>
+                    </tr>
+                }
+            }).collect_view()}
+        </table>
+        <table class=style::table>
+            <tr>
+                <th>{t("column-date")}</th><th>{t("refunds-order")}</th><th>{t("refunds-reason")}</th>
+                <th>{t("refunds-status")}</th><th class=style::amount>{t("column-amount")}</th>
+            </tr>
+            {refunds.into_iter().map(|refund| view! {
+                <tr>
+                    <td>{move || parse_berlin(&refund.created_at).map(|time| locale.get().date_time(time)).unwrap_or_default()}</td>
+                    <td><a href=format!("/orders/{}", refund.order)>{refund.order.get(..8).unwrap_or_default().to_string()}</a></td>
+                    <td>{t(match refund.reason {
+                        RefundReason::EventCancelled => "refunds-reason-cancelled",
+                        RefundReason::Requested => "refunds-reason-requested",
+                    })}</td>
+                    <td class=style::status>{t(match refund.status {
+                        RefundStatus::Succeeded => "refunds-succeeded",
+                        RefundStatus::Failed => "refunds-status-failed",
+                    })}</td>
+                    <td class=style::amount>{move || locale.get().price(refund.amount_cents)}</td>
+                </tr>
+            }).collect_view()}
+        </table>
+    }
+}
+
+#[server(RefundOverviewFor, "/api")]
+pub async fn refund_overview(slug: String) -> Result<RefundOverview, ServerFnError> {
+    require_role(Role::Admin).await?;
+    let Some(event) = database::get_event_by_slug(slug).await else {
+        return Err(ServerFnError::Args(ErrorMessage::create(ResponseError::EventNotFound)));
+    };
+    let mut tickets = database::get_tickets_for_event(event.uuid.clone()).await.unwrap_or_default();
+    tickets.retain(|t| t.order.is_some());
+    let refunds = database::get_refunds_for_event(event.uuid.clone()).await.unwrap_or_default();
+    Ok(RefundOverview { event, tickets, refunds })
+}
+
+#[server(CancelEvent, "/api")]
+pub async fn cancel_event(event: String) -> Result<CancellationReport, ServerFnError> {
+    let user = require_role(Role::Admin).await?;
+    let Some(event) = database::get_events(vec![event]).await.and_then(|e| e.into_iter().next()) else {
+        return Err(ServerFnError::Args(ErrorMessage::create(ResponseError::EventNotFound)));
+    };
+    refund::cancel_event(event, user.uuid)
+        .await
+        .map_err(|e| ServerFnError::Args(ErrorMessage::create(e)))
+}
+
+#[server(RefundSingleTicket, "/api")]
+pub async fn refund_single_ticket(ticket: String) -> Result<Refund, ServerFnError> {
+    let user = require_role(Role::Admin).await?;
+    refund::refund_ticket(ticket, user.uuid)
+        .await
+        .map_err(|e| ServerFnError::Args(ErrorMessage::create(e)))
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use crate::app::auth::require_role;
+        use crate::app::database;
+        use crate::app::errors::{ErrorMessage, ResponseError, ResponseErrorTrait};
+        use crate::app::model::user::Role;
+        use crate::app::refund;
+    }
+}
@@ -0,0 +1,172 @@
+use leptos::prelude::*;
+use leptos_router::hooks::use_params_map;
+
+use crate::app::i18n::{t, use_locale};
+use crate::app::images::ResponsiveImage;
+use crate::app::model::cart::{CartItem, ItemKind};
+use crate::app::model::product::ProductDetails;
+use crate::app::page::cart::use_cart;
+use crate::app::seo::PageMeta;
+
+stylance::import_style!(style, "../../style/shop.module.scss");
+stylance::import_style!(cart_style, "../../style/cart.module.scss");
+
+#[leptos::component]
+pub fn ShopPage() -> impl IntoView {
+    let products = Resource::new(|| (), |_| shop_products());
+    let locale = use_locale().get_untracked();
+
+    view! {
+        <PageMeta title=locale.text("shop-title") description=locale.text("shop-description")/>
+        <div class=format!("container {}", style::shop)>
+            <div class="h2">{t("shop-title")}</div>
+            <Suspense fallback=move || view! { <p>{t("loading")}</p> }>
+                {move || products.get().map(|products| match products {
+                    Ok(products) => view! {
+                        <div class=style::grid>
+                            {products.into_iter().map(|details| view! { <ProductCard details/> }).collect_view()}
+                        </div>
+                    }.into_any(),
+                    Err(e) => view! { <span class=cart_style::error>{e.to_string()}</span> }.into_any(),
+                })}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+fn ProductCard(details: ProductDetails) -> impl IntoView {
+    let locale = use_locale();
+    let price = details.lowest_price_cents();
+    let in_stock = details.in_stock();
+    let ProductDetails { product, .. } = details;
+
+    view! {
+        <a class=style::card href=format!("/shop/{}", product.slug)>
+            {product.images.first().map(|image| view! { <ResponsiveImage class=style::image src=image.clone() alt=product.name.clone() sizes="320px"/> })}
+            <span class=style::name>{product.name}</span>
+            {if in_stock {
+                view! {
+                    <span class=style::price>
+                        {move || {
+                            let locale = locale.get();
+                            price.map(|cents| locale.format("shop-from", &[("price", &locale.price(cents))])).unwrap_or_default()
+                        }}
+                    </span>
+                }.into_any()
+            } else {
+                view! { <span class=style::sold_out>{t("sold-out")}</span> }.into_any()
+            }}
+        </a>
+    }
+}
+
+#[leptos::component]
+pub fn ProductPage() -> impl IntoView {
+    let params = use_params_map();
+    let slug = move || params.read().get("slug").unwrap_or_default();
+    let details = Resource::new(slug, product_details);
+
+    view! {
+        <div class=format!("container {}", style::shop)>
+            <Suspense fallback=move || view! { <p>{t("loading")}</p> }>
+                {move || details.get().map(|details| match details {
+                    Ok(details) => view! { <ProductView details/> }.into_any(),
+                    Err(e) => view! { <span class=cart_style::error>{e.to_string()}</span> }.into_any(),
+                })}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+fn ProductView(details: ProductDetails) -> impl IntoView {
+    let cart = use_cart();
+    let locale = use_locale();
+    let rows = details.variants.iter().map(|variant| {
+        let sold_out = variant.stock == 0;
+        let price_cents = variant.price_cents;
+        let item = CartItem::new(
+            ItemKind::Merch,
+            variant.sku.clone(),
+            details.item_name(variant),
+            variant.price_cents,
+        );
+        view! {
+            <tr>
+                <td>{variant.label()}</td>
+                <td class=cart_style::amount>{move || locale.get().price(price_cents)}</td>
+                <td class=cart_style::amount>
+                    <button class=cart_style::button disabled=sold_out
+                        on:click=move |_| cart.update(|c| c.add(item.clone()))
+                    >
+                        {t(if sold_out { "sold-out" } else { "add-to-cart" })}
+                    </button>
+                </td>
+            </tr>
+        }
+    }).collect_view();
+    let ProductDetails { product, .. } = details;
+
+    view! {
+        <PageMeta
+            title=product.name.clone()
+            path=format!("/shop/{}", product.slug)
+            description=product.description.clone()
+            image=product.images.first().cloned().unwrap_or_default()
+            og_type="product"
+        />
+        <div class="h2">{product.name}</div>
+        <div class=style::gallery>
+            {product.images.into_iter().map(|image| view! { <ResponsiveImage src=image sizes="(max-width: 480px) 100vw, 320pt"/> }).collect_view()}
+        </div>
+        <p>{product.description}</p>
+        <table class=cart_style::table>{rows}</table>
+        <a class=cart_style::button href="/cart">{t("go-to-cart")}</a>
+    }
+}
+
+#[server(ShopProducts, "/api")]
+pub async fn shop_products() -> Result<Vec<ProductDetails>, ServerFnError> {
+    let products: Vec<Product> = database::get_products()
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|p| p.active)
+        .collect();
+    if products.is_empty() {
+        return Ok(Vec::new());
+    }
+    let uuids = products.iter().map(|p| p.uuid.clone()).collect();
+    let variants = database::get_variants(uuids).await.unwrap_or_default();
+    Ok(with_variants(products, variants))
+}
+
+#[server(ProductDetailsFor, "/api")]
+pub async fn product_details(slug: String) -> Result<ProductDetails, ServerFnError> {
+    let Some(product) = database::get_product_by_slug(slug).await.filter(|p| p.active) else {
+        return Err(ServerFnError::Args(ErrorMessage::create(ResponseError::ProductNotFound)));
+    };
+    let variants = database::get_variants(vec![product.uuid.clone()]).await.unwrap_or_default();
+    Ok(ProductDetails { product, variants })
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use crate::app::database;
+        use crate::app::errors::{ErrorMessage, ResponseError, ResponseErrorTrait};
+        use crate::app::model::Product;
+        use crate::app::model::product::ProductVariant;
+
+        /// Groups variants under their products, keeping the product order.
+        pub fn with_variants(products: Vec<Product>, variants: Vec<ProductVariant>) -> Vec<ProductDetails> {
+            products
+                .into_iter()
+                .map(|product| {
+                    let variants = variants.iter().filter(|v| v.product == product.uuid).cloned().collect();
+                    ProductDetails { product, variants }
+                })
+                .collect()
+        }
+    }
+}
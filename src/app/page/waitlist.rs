@@ -0,0 +1,355 @@
+use leptos::{prelude::*, task::spawn_local};
+use leptos_router::hooks::use_params_map;
+
+use crate::app::i18n::{t, use_locale};
+use crate::app::ical::{input_value, parse_berlin};
+use crate::app::model::{Event, User};
+use crate::app::model::waitlist::{OfferDetails, WaitlistEntry, WaitlistReport, WaitlistAction, WaitlistState, WaitlistStatus, MAX_PLACES};
+use crate::app::seo::PageMeta;
+
+stylance::import_style!(style, "../../style/cart.module.scss");
+
+/// Joining and leaving the waitlist, shown once all tickets of an event
+/// are gone.
+#[component]
+pub fn WaitlistPanel(event: String) -> impl IntoView {
+    let user = use_context::<(ReadSignal<Option<User>>, WriteSignal<Option<User>>)>().map(|u| u.0);
+    let logged_in = move || user.is_some_and(|u| u.with(Option::is_some));
+    let version = RwSignal::new(0u32);
+    let state = Resource::new(
+        {
+            let event = event.clone();
+            move || (event.clone(), user.and_then(|u| u.with(|u| u.as_ref().map(|u| u.uuid.clone()))), version.get())
+        },
+        |(event, _, _)| waitlist_state(event),
+    );
+    let (quantity, set_quantity) = signal(1u32);
+    let (error_message, set_error_message) = signal(String::new());
+    let locale = use_locale();
+
+    let on_join = {
+        let event = event.clone();
+        move |_| {
+            let event = event.clone();
+            spawn_local(async move {
+                match join_waitlist(event, quantity.get_untracked()).await {
+                    Ok(_) => {
+                        set_error_message(String::new());
+                        version.update(|v| *v += 1);
+                    }
+                    Err(e) => set_error_message(e.to_string()),
+                }
+            });
+        }
+    };
+    let on_leave = move |_| {
+        let event = event.clone();
+        spawn_local(async move {
+            match leave_waitlist(event).await {
+                Ok(_) => {
+                    set_error_message(String::new());
+                    version.update(|v| *v += 1);
+                }
+                Err(e) => set_error_message(e.to_string()),
+            }
+        });
+    };
+
+    view! {
+        <div class="h3">{t("waitlist-title")}</div>
+        <Show when=logged_in fallback=|| view! { <span>{t("waitlist-login")}</span> }>
+            <Transition fallback=move || ()>
+                {move || state.get().and_then(Result::ok).flatten().map(|state| match state.entry {
+                    Some(entry) if entry.status == WaitlistStatus::Offered => view! {
+                        <span>{t("waitlist-offered")}</span>
+                        <a class=style::button href=state.claim.unwrap_or_default()>{t("offer-claim")}</a>
+                    }.into_any(),
+                    Some(_) => view! {
+                        <span>{move || locale.get().format("waitlist-position", &[("ahead", &state.ahead.to_string())])}</span>
+                        <button class=style::button on:click=on_leave.clone()>{t("waitlist-leave")}</button>
+                    }.into_any(),
+                    None => view! {
+                        <span>{t("waitlist-intro")}</span>
+                        <div class=style::row>
+                            <select class=style::input on:change=move |e| set_quantity(event_target_value(&e).parse().unwrap_or(1))>
+                                {(1..=MAX_PLACES).map(|n| view! {
+                                    <option value=n.to_string() selected=move || quantity.get() == n>
+                                        {move || locale.get().format("waitlist-places", &[("count", &n.to_string())])}
+                                    </option>
+                                }).collect_view()}
+                            </select>
+                            <button class=style::button on:click=on_join.clone()>{t("waitlist-join")}</button>
+                        </div>
+                    }.into_any(),
+                })}
+            </Transition>
+        </Show>
+        <span class=style::error>{error_message}</span>
+    }
+}
+
+/// Where the claim link of an offer leads.
+#[leptos::component]
+pub fn WaitlistOfferPage() -> impl IntoView {
+    let params = use_params_map();
+    let entry = move || params.read().get("entry").unwrap_or_default();
+    let token = move || params.read().get("token").unwrap_or_default();
+    let details = Resource::new(move || (entry(), token()), |(entry, token)| offer_details(entry, token));
+    let user = use_context::<(ReadSignal<Option<User>>, WriteSignal<Option<User>>)>().map(|u| u.0);
+    let logged_in = move || user.is_some_and(|u| u.with(Option::is_some));
+    let (pending, set_pending) = signal(false);
+    let (error_message, set_error_message) = signal(String::new());
+    let locale = use_locale();
+
+    let on_claim = move |_| {
+        set_pending(true);
+        spawn_local(async move {
+            match claim_offer(entry(), token()).await {
+                Ok(redirect_url) => {
+                    let _ = window().location().set_href(&redirect_url);
+                }
+                Err(e) => {
+                    set_error_message(e.to_string());
+                    set_pending(false);
+                }
+            }
+        });
+    };
+
+    view! {
+        <PageMeta title=locale.get_untracked().text("offer-title") noindex=true/>
+        <div class=format!("container {}", style::cart)>
+            <div class="h2">{t("offer-title")}</div>
+            <Suspense fallback=move || view! { <p>{t("loading")}</p> }>
+                {move || details.get().map(|details| match details {
+                    Ok(OfferDetails { entry: WaitlistEntry { offer: Some(offer), .. }, event_title, ticket_name, price_cents }) => {
+                        let expires = parse_berlin(&offer.expires_at);
+                        view! {
+                            <span>{move || locale.get().format("offer-details", &[
+                                ("quantity", &offer.quantity.to_string()),
+                                ("ticket", &ticket_name),
+                                ("event", &event_title),
+                                ("price", &locale.get().price(price_cents * offer.quantity as i64)),
+                            ])}</span>
+                            <span>{move || locale.get().format("offer-expires", &[
+                                ("time", &expires.map(|t| locale.get().date_time(t)).unwrap_or_default()),
+                            ])}</span>
+                            <Show when=logged_in fallback=|| view! { <span>{t("offer-login")}</span> }>
+                                <button class=style::button disabled=pending on:click=on_claim>{t("offer-claim")}</button>
+                            </Show>
+                        }.into_any()
+                    }
+                    _ => view! { <span>{t("offer-gone")}</span> }.into_any(),
+                })}
+            </Suspense>
+            <span class=style::error>{error_message}</span>
+        </div>
+    }
+}
+
+fn status_key(status: WaitlistStatus) -> &'static str {
+    match status {
+        WaitlistStatus::Waiting => "waitlists-waiting",
+        WaitlistStatus::Offered => "waitlists-offered",
+        WaitlistStatus::Claimed => "waitlists-claimed",
+        WaitlistStatus::Expired => "waitlists-expired",
+        WaitlistStatus::Left => "waitlists-left",
+    }
+}
+
+fn action_key(action: WaitlistAction) -> &'static str {
+    match action {
+        WaitlistAction::Joined => "waitlists-joined",
+        WaitlistAction::Offered => "waitlists-offered",
+        WaitlistAction::Claimed => "waitlists-claimed",
+        WaitlistAction::Expired => "waitlists-expired",
+        WaitlistAction::Left => "waitlists-left",
+        WaitlistAction::Released => "waitlists-released",
+    }
+}
+
+#[leptos::component]
+pub fn WaitlistAdminPage() -> impl IntoView {
+    let events = Resource::new(|| (), |_| waitlist_events());
+    let (event, set_event) = signal(String::new());
+    let report = Resource::new(move || event.get(), event_waitlist);
+
+    view! {
+        <PageMeta title=use_locale().get_untracked().text("waitlists-title") noindex=true/>
+        <div class=format!("container {}", style::cart)>
+            <div class="h2">{t("waitlists-title")}</div>
+            <Transition fallback=move || ()>
+                <select class=style::input on:change=move |e| set_event(event_target_value(&e))>
+                    <option value="">{t("admin-choose-event")}</option>
+                    {move || events.get().and_then(Result::ok).unwrap_or_default().into_iter().map(|event: Event| view! {
+                        <option value=event.uuid>
+                            {format!("{} · {}", input_value(&Some(event.starts_at)).replace('T', " "), event.title)}
+                        </option>
+                    }).collect_view()}
+                </select>
+            </Transition>
+            <Show when=move || !event.get().is_empty()>
+                <Transition fallback=move || view! { <p>{t("loading")}</p> }>
+                    {move || report.get().map(|report| match report {
+                        Ok(report) => view! {
+                            <table class=style::table>
+                                <tr>
+                                    <th>"#"</th><th>{t("email")}</th><th>{t("waitlists-places")}</th><th>{t("waitlists-status")}</th><th>{t("waitlists-offer-until")}</th>
+                                </tr>
+                                {report.entries.into_iter().map(|entry| view! {
+                                    <tr>
+                                        <td>{entry.number}</td>
+                                        <td>{entry.email}</td>
+                                        <td>{entry.quantity}</td>
+                                        <td>{t(status_key(entry.status))}</td>
+                                        <td>{entry.offer.map(|o| input_value(&Some(o.expires_at)).replace('T', " ")).unwrap_or_default()}</td>
+                                    </tr>
+                                }).collect_view()}
+                            </table>
+                            <div class="h3">{t("waitlists-log")}</div>
+                            <table class=style::table>
+                                {report.audit.into_iter().map(|audit| view! {
+                                    <tr>
+                                        <td>{input_value(&Some(audit.created_at)).replace('T', " ")}</td>
+                                        <td>{t(action_key(audit.action))}</td>
+                                        <td>{audit.entry.unwrap_or_default()}</td>
+                                        <td>{audit.quantity}</td>
+                                    </tr>
+                                }).collect_view()}
+                            </table>
+                        }.into_any(),
+                        Err(e) => view! { <span class=style::error>{e.to_string()}</span> }.into_any(),
+                    })}
+                </Transition>
+            </Show>
+        </div>
+    }
+}
+
+/// None when logged out.
+#[server(WaitlistStateFor, "/api")]
+pub async fn waitlist_state(event: String) -> Result<Option<WaitlistState>, ServerFnError> {
+    let Some(user) = current_user().await else {
+        return Ok(None);
+    };
+    Ok(Some(state(event, &user).await))
+}
+
+#[server(JoinWaitlist, "/api")]
+pub async fn join_waitlist(event: String, quantity: u32) -> Result<WaitlistState, ServerFnError> {
+    let Some(user) = current_user().await else {
+        return Err(ServerFnError::Args(request_text("error-not-logged-in")));
+    };
+    if !(1..=MAX_PLACES).contains(&quantity) {
+        return Err(ServerFnError::Args(request_locale().format("error-waitlist-places", &[("max", &MAX_PLACES.to_string())])));
+    }
+    let Some(found) = database::get_events(vec![event.clone()]).await.unwrap_or_default().into_iter().next() else {
+        return Err(ServerFnError::Args(ErrorMessage::create(ResponseError::EventNotFound)));
+    };
+    let ticket_types = database::get_ticket_types(event.clone()).await.unwrap_or_default();
+    // a waitlist only makes sense while there is nothing to buy
+    if found.cancelled || ticket_types.iter().any(|t| t.available() > 0) {
+        return Err(ServerFnError::Args(ErrorMessage::create(ResponseError::WaitlistFailure)));
+    }
+    waitlist::join(&found, &user, quantity)
+        .await
+        .map_err(|e| ServerFnError::Args(ErrorMessage::create(e)))?;
+    Ok(state(event, &user).await)
+}
+
+#[server(LeaveWaitlist, "/api")]
+pub async fn leave_waitlist(event: String) -> Result<(), ServerFnError> {
+    let Some(user) = current_user().await else {
+        return Err(ServerFnError::Args(request_text("error-not-logged-in")));
+    };
+    let Some(entry) = database::get_active_waitlist_entry(event, user.uuid).await else {
+        return Ok(());
+    };
+    waitlist::leave(entry.uuid).await.map_err(|e| ServerFnError::Args(ErrorMessage::create(e)))
+}
+
+#[server(OfferDetailsFor, "/api")]
+pub async fn offer_details(entry: String, token: String) -> Result<OfferDetails, ServerFnError> {
+    let entry = valid_offer(entry, token).await?;
+    let ticket_type = entry.offer.as_ref().map(|o| o.ticket_type.clone()).unwrap_or_default();
+    let Some(ticket_type) = database::get_ticket_type(ticket_type).await else {
+        return Err(ServerFnError::Args(ErrorMessage::create(ResponseError::TicketNotFound)));
+    };
+    let Some(event) = database::get_events(vec![entry.event.clone()]).await.unwrap_or_default().into_iter().next() else {
+        return Err(ServerFnError::Args(ErrorMessage::create(ResponseError::EventNotFound)));
+    };
+    Ok(OfferDetails {
+        entry,
+        event_title: event.title,
+        ticket_name: ticket_type.name,
+        price_cents: ticket_type.price_cents,
+    })
+}
+
+/// Orders the offered places and returns where to pay for them.
+#[server(ClaimOffer, "/api")]
+pub async fn claim_offer(entry: String, token: String) -> Result<String, ServerFnError> {
+    let entry = valid_offer(entry, token).await?;
+    let Some(user) = current_user().await else {
+        return Err(ServerFnError::Args(request_text("error-not-logged-in")));
+    };
+    let order = waitlist::claim(entry, &user).await.map_err(|e| ServerFnError::Args(ErrorMessage::create(e)))?;
+    payment::start(&order).await.map_err(|e| ServerFnError::Args(ErrorMessage::create(e)))
+}
+
+#[server(WaitlistEvents, "/api")]
+pub async fn waitlist_events() -> Result<Vec<Event>, ServerFnError> {
+    require_role(Role::Admin).await?;
+    Ok(database::get_events_since(String::new()).await.unwrap_or_default())
+}
+
+#[server(EventWaitlist, "/api")]
+pub async fn event_waitlist(event: String) -> Result<WaitlistReport, ServerFnError> {
+    require_role(Role::Admin).await?;
+    Ok(WaitlistReport {
+        entries: database::get_waitlist(event.clone()).await.unwrap_or_default(),
+        audit: database::get_waitlist_audit(event).await.unwrap_or_default(),
+    })
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use chrono::Utc;
+
+        use crate::app::auth::{current_user, require_role};
+        use crate::app::database;
+        use crate::app::errors::{ErrorMessage, ResponseError, ResponseErrorTrait};
+        use crate::app::i18n::{request_locale, request_text};
+        use crate::app::model::user::Role;
+        use crate::app::payment;
+        use crate::app::waitlist;
+
+        async fn state(event: String, user: &User) -> WaitlistState {
+            let Some(entry) = database::get_active_waitlist_entry(event.clone(), user.uuid.clone()).await else {
+                return WaitlistState { entry: None, ahead: 0, claim: None };
+            };
+            let ahead = database::get_waitlist(event)
+                .await
+                .unwrap_or_default()
+                .iter()
+                .filter(|e| e.status == WaitlistStatus::Waiting && e.number < entry.number)
+                .count() as u32;
+            // the owner may follow the link without the mail
+            let claim = match entry.status {
+                WaitlistStatus::Offered => waitlist::claim_path(&entry),
+                _ => None,
+            };
+            WaitlistState { entry: Some(entry), ahead, claim }
+        }
+
+        /// An entry behind a valid claim link whose offer still stands.
+        async fn valid_offer(entry: String, token: String) -> Result<WaitlistEntry, ServerFnError> {
+            let entry = waitlist::checked_entry(entry, token).await.map_err(|e| ServerFnError::Args(ErrorMessage::create(e)))?;
+            let open = entry.offer.as_ref().is_some_and(|o| o.is_open_at(Utc::now()));
+            if entry.status != WaitlistStatus::Offered || !open {
+                return Err(ServerFnError::Args(ErrorMessage::create(ResponseError::OfferInvalid)));
+            }
+            Ok(entry)
+        }
+    }
+}
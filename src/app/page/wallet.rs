@@ -0,0 +1,176 @@
+use leptos::{prelude::*, task::spawn_local};
+use qrcode::QrCode;
+use qrcode::render::svg;
+use serde::{Deserialize, Serialize};
+
+use crate::app::i18n::{t, use_locale};
+use crate::app::ical::parse_berlin;
+use crate::app::model::{Event, Ticket};
+use crate::app::model::ticket::TicketStatus;
+
+stylance::import_style!(style, "../../style/wallet.module.scss");
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct WalletTicket {
+    pub ticket: Ticket,
+    pub event: Event,
+    pub upcoming: bool,
+}
+
+/// The user's tickets, upcoming events first.
+#[component]
+pub fn TicketWallet() -> impl IntoView {
+    let version = RwSignal::new(0u32);
+    let tickets = Resource::new(move || version.get(), |_| my_tickets());
+
+    view! {
+        <div class="h2">{t("wallet-title")}</div>
+        <Transition fallback=move || ()>
+            {move || tickets.get().map(|tickets| {
+                let (upcoming, past): (Vec<_>, Vec<_>) = tickets.unwrap_or_default()
+                    .into_iter()
+                    .partition(|t| t.upcoming);
+                view! {
+                    <div class=style::wallet>
+                        {upcoming.into_iter().map(|ticket| view! { <TicketCard ticket version/> }).collect_view()}
+                    </div>
+                    {(!past.is_empty()).then(|| view! { <h3>{t("wallet-past")}</h3> })}
+                    <div class=style::wallet>
+                        {past.into_iter().map(|ticket| view! { <TicketCard ticket version/> }).collect_view()}
+                    </div>
+                }
+            })}
+        </Transition>
+    }
+}
+
+fn qr_svg(code: &str) -> String {
+    match QrCode::new(code.as_bytes()) {
+        Ok(qr) => qr
+            .render::<svg::Color>()
+            .min_dimensions(160, 160)
+            .dark_color(svg::Color("#000000"))
+            .light_color(svg::Color("#ffffff"))
+            .build(),
+        Err(_) => String::new(),
+    }
+}
+
+#[component]
+fn TicketCard(ticket: WalletTicket, version: RwSignal<u32>) -> impl IntoView {
+    let WalletTicket { ticket, event, upcoming } = ticket;
+    let transferable = upcoming && ticket.status == TicketStatus::Valid;
+    let (email, set_email) = signal(String::new());
+    let (show_transfer, set_show_transfer) = signal(false);
+    let (error_message, set_error_message) = signal(String::new());
+    let locale = use_locale();
+    let starts_at = parse_berlin(&event.starts_at);
+
+    let uuid = ticket.uuid.clone();
+    let on_transfer = move |_| {
+        let uuid = uuid.clone();
+        spawn_local(async move {
+            match transfer_ticket(uuid, email.get_untracked()).await {
+                Ok(_) => version.update(|v| *v += 1),
+                Err(e) => set_error_message(e.to_string()),
+            }
+        });
+    };
+
+    view! {
+        <div class=style::card>
+            <div class=style::title>{event.title}</div>
+            <span>{move || starts_at.map(|time| locale.get().date_time(time)).unwrap_or_default()}</span>
+            <span>{ticket.holder_name}</span>
+            {match ticket.status {
+                TicketStatus::Valid if upcoming => view! {
+                    <div class=style::qr inner_html=qr_svg(&ticket.code)></div>
+                }.into_any(),
+                TicketStatus::Valid => view! { <span class=style::state>{t("wallet-expired")}</span> }.into_any(),
+                TicketStatus::CheckedIn | TicketStatus::Out => view! { <span class=style::state>{t("wallet-used")}</span> }.into_any(),
+                TicketStatus::Refunded => view! { <span class=style::state>{t("wallet-refunded")}</span> }.into_any(),
+                TicketStatus::Cancelled => view! { <span class=style::state>{t("wallet-cancelled")}</span> }.into_any(),
+            }}
+            <span class=style::code>{ticket.code}</span>
+            {transferable.then(|| view! {
+                <button class=style::button on:click=move |_| set_show_transfer(!show_transfer())>{t("wallet-transfer")}</button>
+                <Show when=show_transfer>
+                    <input type="email" placeholder=t("email") class=style::input
+                        prop:value=email
+                        on:input=move |e| set_email(event_target_value(&e))
+                    />
+                    <button class=style::button on:click=on_transfer.clone()>{t("wallet-send")}</button>
+                    <span class=style::error>{error_message}</span>
+                </Show>
+            })}
+        </div>
+    }
+}
+
+#[server(MyTickets, "/api")]
+pub async fn my_tickets() -> Result<Vec<WalletTicket>, ServerFnError> {
+    let Some(user) = current_user().await else {
+        return Err(ServerFnError::Args(request_text("error-not-logged-in")));
+    };
+    let tickets = database::get_wallet_tickets(user.uuid).await.unwrap_or_default();
+    let mut uuids: Vec<String> = tickets.iter().map(|t| t.event.clone()).collect();
+    uuids.sort();
+    uuids.dedup();
+    let events = database::get_events(uuids).await.unwrap_or_default();
+
+    let now = Local::now();
+    let mut wallet: Vec<WalletTicket> = tickets
+        .into_iter()
+        .filter_map(|ticket| {
+            let event = events.iter().find(|e| e.uuid == ticket.event)?.clone();
+            let upcoming = DateTime::parse_from_rfc3339(&event.starts_at)
+                .map(|starts_at| starts_at + Duration::hours(12) > now)
+                .unwrap_or(true);
+            Some(WalletTicket { ticket, event, upcoming })
+        })
+        .collect();
+    wallet.sort_by_key(|w| DateTime::parse_from_rfc3339(&w.event.starts_at).ok());
+    Ok(wallet)
+}
+
+/// Passes an unused ticket on. The old code stops working right away.
+#[server(TransferTicket, "/api")]
+pub async fn transfer_ticket(ticket: String, email: String) -> Result<Ticket, ServerFnError> {
+    let email = email.trim().to_lowercase();
+    if !ValidateEmail::validate_email(&email) {
+        return Err(ServerFnError::Args(request_text("error-invalid-email")));
+    }
+    let Some(user) = current_user().await else {
+        return Err(ServerFnError::Args(request_text("error-not-logged-in")));
+    };
+    let ticket = match database::get_ticket(ticket).await {
+        Some(ticket) if ticket.owner.as_ref() == Some(&user.uuid) => ticket,
+        _ => return Err(ServerFnError::Args(ErrorMessage::create(ResponseError::TicketNotFound))),
+    };
+
+    // without an account the ticket goes to the address alone
+    let owner = database::get_user_by_mail(email.clone()).await.map(|u| u.uuid);
+    let code = database::take_door_code(ticket.event.clone()).await.unwrap_or_else(Ticket::generate_code);
+    let replacement = ticket.transfer(
+        Uuid::new_v4().to_string(),
+        code,
+        email,
+        owner,
+    );
+    database::transfer_ticket(ticket.uuid, replacement)
+        .await
+        .map_err(|e| ServerFnError::Args(ErrorMessage::create(e)))
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use chrono::{DateTime, Duration, Local};
+        use uuid::Uuid;
+        use validator::ValidateEmail;
+
+        use crate::app::auth::current_user;
+        use crate::app::database;
+        use crate::app::errors::{ErrorMessage, ResponseError, ResponseErrorTrait};
+        use crate::app::i18n::request_text;
+    }
+}
@@ -0,0 +1,184 @@
+//! Provider that never leaves the site: its payment page just has buttons to
+//! succeed or fail, which send the same signed webhook a real provider would.
+//! Only for development, it is off unless `PAYMENT_PROVIDER` is `fake`.
+
+use leptos::{prelude::*, task::spawn_local};
+use leptos::logging::log;
+use leptos_router::hooks::{use_navigate, use_params_map};
+
+use super::PaymentEventKind;
+use crate::app::i18n::{t, use_locale};
+use crate::app::seo::PageMeta;
+
+stylance::import_style!(style, "../../style/cart.module.scss");
+
+pub const FAKE_PROVIDER: &str = "fake";
+
+#[component]
+pub fn FakePaymentPage() -> impl IntoView {
+    let params = use_params_map();
+    let order = move || params.read().get("order").unwrap_or_default();
+    let payment = move || params.read().get("payment").unwrap_or_default();
+    let locale = use_locale();
+    let (error, set_error) = signal(String::new());
+
+    let complete = move |kind: PaymentEventKind| {
+        let order = order();
+        let payment = payment();
+        spawn_local(async move {
+            match fake_payment_complete(order.clone(), payment, kind).await {
+                Ok(_) => use_navigate()(&format!("/orders/{order}"), Default::default()),
+                Err(e) => {
+                    log!("Error {:?}", e);
+                    set_error(locale.get_untracked().text("fake-payment-failed").to_string());
+                }
+            }
+        });
+    };
+
+    view! {
+        <PageMeta title=use_locale().get_untracked().text("fake-payment-title") noindex=true/>
+        <div class=format!("container {}", style::cart)>
+            <div class="h2">{t("fake-payment-title")}</div>
+            <span>{move || locale.get().format("fake-payment-id", &[("payment", &payment())])}</span>
+            <div class=style::actions>
+                <button class=style::button on:click=move |_| complete(PaymentEventKind::Succeeded)>{t("fake-payment-pay")}</button>
+                <button class=style::button on:click=move |_| complete(PaymentEventKind::Failed)>{t("fake-payment-cancel")}</button>
+            </div>
+            <span class=style::error>{error}</span>
+        </div>
+    }
+}
+
+#[server(FakePaymentComplete, "/api")]
+pub async fn fake_payment_complete(order: String, payment: String, kind: PaymentEventKind) -> Result<(), ServerFnError> {
+    let Ok(Provider::Fake(provider)) = super::provider() else {
+        return Err(ServerFnError::Args(ErrorMessage::create(ResponseError::PaymentFailure)));
+    };
+    let event = PaymentEvent {
+        id: Uuid::new_v4().to_string(),
+        payment_id: payment,
+        order,
+        kind,
+    };
+    let body = serde_json::to_string(&event)?;
+    let signature = provider.sign(&body);
+    match super::handle_webhook(&provider, &signature, body.as_bytes()).await {
+        Ok(_) => Ok(()),
+        Err(e) => Err(ServerFnError::Args(ErrorMessage::create(e))),
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use leptos::serde_json;
+        use once_cell::sync::Lazy;
+        use uuid::Uuid;
+
+        use super::{PaymentEvent, PaymentProvider, PaymentSession, Provider};
+        use crate::app::errors::{ErrorMessage, ResponseError, ResponseErrorTrait};
+        use crate::app::model::Order;
+        use crate::app::model::order::PaymentRef;
+        use crate::app::signing;
+
+        /// Used when `PAYMENT_WEBHOOK_SECRET` is not set. Good enough for the
+        /// fake provider, whose webhooks come from this very process.
+        static PROCESS_SECRET: Lazy<String> = Lazy::new(signing::generate_key);
+
+        pub struct FakeProvider {
+            secret: String,
+        }
+
+        impl FakeProvider {
+            pub fn from_env() -> FakeProvider {
+                FakeProvider {
+                    secret: std::env::var("PAYMENT_WEBHOOK_SECRET").unwrap_or_else(|_| PROCESS_SECRET.clone()),
+                }
+            }
+
+            pub fn sign(&self, body: &str) -> String {
+                signing::sign(&self.secret, body)
+            }
+        }
+
+        impl PaymentProvider for FakeProvider {
+            fn name(&self) -> &'static str {
+                FAKE_PROVIDER
+            }
+
+            async fn create_payment(&self, order: &Order) -> Result<PaymentSession, ResponseError> {
+                let payment_id = format!("fake_{}", Uuid::new_v4().simple());
+                Ok(PaymentSession {
+                    provider: FAKE_PROVIDER.to_string(),
+                    redirect_url: format!("/payment/fake/{}/{}", order.uuid, payment_id),
+                    payment_id,
+                })
+            }
+
+            async fn refund(&self, payment: &PaymentRef, amount_cents: i64) -> Result<String, ResponseError> {
+                if payment.provider != FAKE_PROVIDER || amount_cents <= 0 {
+                    return Err(ResponseError::RefundFailure);
+                }
+                Ok(format!("fake_refund_{}", Uuid::new_v4().simple()))
+            }
+
+            fn verify_webhook(&self, signature: &str, body: &[u8]) -> Result<PaymentEvent, ResponseError> {
+                let body = std::str::from_utf8(body).map_err(|_| ResponseError::PaymentFailure)?;
+                if !signing::verify_full(&self.secret, body, signature) {
+                    return Err(ResponseError::PaymentFailure);
+                }
+                serde_json::from_str(body).map_err(|_| ResponseError::PaymentFailure)
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+
+    fn provider() -> FakeProvider {
+        FakeProvider { secret: String::from("test-secret") }
+    }
+
+    fn payment(provider: &str) -> PaymentRef {
+        PaymentRef { provider: provider.to_string(), payment_id: String::from("fake_1") }
+    }
+
+    fn event_body() -> String {
+        let event = PaymentEvent {
+            id: String::from("e1"),
+            payment_id: String::from("fake_1"),
+            order: String::from("o1"),
+            kind: PaymentEventKind::Succeeded,
+        };
+        serde_json::to_string(&event).unwrap()
+    }
+
+    #[tokio::test]
+    async fn refunds_positive_amounts_of_own_payments() {
+        assert!(provider().refund(&payment(FAKE_PROVIDER), 967).await.is_ok());
+        assert!(matches!(provider().refund(&payment(FAKE_PROVIDER), 0).await, Err(ResponseError::RefundFailure)));
+        assert!(matches!(provider().refund(&payment(FAKE_PROVIDER), -100).await, Err(ResponseError::RefundFailure)));
+        assert!(matches!(provider().refund(&payment("stripe"), 967).await, Err(ResponseError::RefundFailure)));
+    }
+
+    #[test]
+    fn accepts_signed_webhooks_only() {
+        let provider = provider();
+        let body = event_body();
+        let signature = provider.sign(&body);
+        let event = provider.verify_webhook(&signature, body.as_bytes()).unwrap();
+        assert_eq!(event.order, "o1");
+        assert_eq!(event.kind, PaymentEventKind::Succeeded);
+
+        let tampered = body.replace("Succeeded", "Failed");
+        assert!(matches!(provider.verify_webhook(&signature, tampered.as_bytes()), Err(ResponseError::PaymentFailure)));
+        assert!(matches!(
+            provider.verify_webhook(&signature[..signature.len() / 2], body.as_bytes()),
+            Err(ResponseError::PaymentFailure)
+        ));
+        let other = FakeProvider { secret: String::from("other-secret") };
+        assert!(matches!(other.verify_webhook(&signature, body.as_bytes()), Err(ResponseError::PaymentFailure)));
+    }
+}
@@ -0,0 +1,360 @@
+//! Payment providers and the order state machine they drive. Checkout asks
+//! the configured provider for a payment session and sends the buyer to its
+//! redirect URL; the provider later reports the outcome through a signed
+//! webhook, which is what actually moves the order on.
+
+use serde::{Deserialize, Serialize};
+
+pub mod fake;
+
+/// Where to send the buyer to pay for an order.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct PaymentSession {
+    pub provider: String,
+    pub payment_id: String,
+    pub redirect_url: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+pub enum PaymentEventKind {
+    Succeeded,
+    Failed,
+}
+
+/// A verified webhook callback. `id` is unique per delivery attempt of the
+/// provider, so replays can be recognised.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct PaymentEvent {
+    pub id: String,
+    pub payment_id: String,
+    pub order: String,
+    pub kind: PaymentEventKind,
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use std::collections::BTreeMap;
+        use std::future::Future;
+        use uuid::Uuid;
+
+        use crate::app::database;
+        use crate::app::invoice;
+        use crate::app::errors::ResponseError;
+        use crate::app::mail::{self, Mail};
+        use crate::app::stock;
+        use crate::app::time::{now, stamp};
+        use crate::app::waitlist;
+        use crate::app::model::{Order, Ticket};
+        use crate::app::model::cart::ItemKind;
+        use crate::app::model::discount::{GiftVoucher, Redemption, RedemptionKind};
+        use crate::app::model::order::{OrderStatus, PaymentRef};
+        use crate::app::model::ticket::TicketKind;
+        use crate::app::push::{self, PushMessage, Topic};
+
+        pub use fake::FakeProvider;
+        use fake::FAKE_PROVIDER;
+
+        pub trait PaymentProvider {
+            /// Name used in the webhook route and stored on the order.
+            fn name(&self) -> &'static str;
+
+            /// Starts a payment over `order.amount_due()`.
+            fn create_payment(&self, order: &Order)
+                -> impl Future<Output = Result<PaymentSession, ResponseError>> + Send;
+
+            /// Pays `amount_cents` of a payment back and returns the provider's refund id.
+            fn refund(&self, payment: &PaymentRef, amount_cents: i64)
+                -> impl Future<Output = Result<String, ResponseError>> + Send;
+
+            /// Checks the signature of a webhook body and decodes it.
+            fn verify_webhook(&self, signature: &str, body: &[u8]) -> Result<PaymentEvent, ResponseError>;
+        }
+
+        /// The providers `PAYMENT_PROVIDER` can choose from. A new provider
+        /// is a module with a `PaymentProvider` and a variant here.
+        pub enum Provider {
+            Fake(FakeProvider),
+        }
+
+        impl PaymentProvider for Provider {
+            fn name(&self) -> &'static str {
+                match self {
+                    Provider::Fake(provider) => provider.name(),
+                }
+            }
+
+            async fn create_payment(&self, order: &Order) -> Result<PaymentSession, ResponseError> {
+                match self {
+                    Provider::Fake(provider) => provider.create_payment(order).await,
+                }
+            }
+
+            async fn refund(&self, payment: &PaymentRef, amount_cents: i64) -> Result<String, ResponseError> {
+                match self {
+                    Provider::Fake(provider) => provider.refund(payment, amount_cents).await,
+                }
+            }
+
+            fn verify_webhook(&self, signature: &str, body: &[u8]) -> Result<PaymentEvent, ResponseError> {
+                match self {
+                    Provider::Fake(provider) => provider.verify_webhook(signature, body),
+                }
+            }
+        }
+
+        /// The provider configured via `PAYMENT_PROVIDER`. There is none by
+        /// default; the fake one has to be asked for explicitly, as it lets
+        /// anyone pay with a click.
+        pub fn provider() -> Result<Provider, ResponseError> {
+            match std::env::var("PAYMENT_PROVIDER").as_deref() {
+                Ok(FAKE_PROVIDER) => Ok(Provider::Fake(FakeProvider::from_env())),
+                _ => Err(ResponseError::PaymentFailure),
+            }
+        }
+
+        /// Asks the provider for a payment of what is left to pay and returns
+        /// where to send the buyer. Orders covered by a voucher are paid right
+        /// away; the order is cancelled if the payment can't be started.
+        pub async fn start(order: &Order) -> Result<String, ResponseError> {
+            if order.amount_due() == 0 {
+                mark_paid(order.uuid.clone()).await?;
+                return Ok(format!("/orders/{}", order.uuid));
+            }
+
+            let session = match provider() {
+                Ok(provider) => provider.create_payment(order).await,
+                Err(e) => Err(e),
+            };
+            let session = match session {
+                Ok(session) => session,
+                Err(e) => {
+                    let _ = cancel_pending(order.uuid.clone()).await;
+                    return Err(e);
+                }
+            };
+            let payment = PaymentRef {
+                provider: session.provider,
+                payment_id: session.payment_id,
+            };
+            if let Err(e) = database::set_order_payment(order.uuid.clone(), payment).await {
+                let _ = cancel_pending(order.uuid.clone()).await;
+                return Err(e);
+            }
+            Ok(session.redirect_url)
+        }
+
+        /// Processes a webhook delivery. The event is claimed by its id before
+        /// anything is done, so of two deliveries of the same event only one
+        /// handles it; the other, like any later retry, is acknowledged without
+        /// doing anything. A claim is given up again if handling fails, so the
+        /// provider's retry picks up where the failed attempt stopped.
+        pub async fn handle_webhook<P: PaymentProvider>(provider: &P, signature: &str, body: &[u8])
+            -> Result<(), ResponseError> {
+
+            let event = provider.verify_webhook(signature, body)?;
+            let order = database::get_order(event.order.clone()).await.ok_or(ResponseError::OrderNotFound)?;
+            let expected = PaymentRef {
+                provider: provider.name().to_string(),
+                payment_id: event.payment_id.clone(),
+            };
+            if order.payment.as_ref() != Some(&expected) {
+                return Err(ResponseError::PaymentFailure);
+            }
+
+            let payload = String::from_utf8_lossy(body).to_string();
+            if !database::add_payment_event(provider.name().to_string(), event.id.clone(), payload, now()).await {
+                // claimed before, unless the database failed us
+                if database::has_payment_event(provider.name().to_string(), event.id).await {
+                    return Ok(());
+                }
+                return Err(ResponseError::PaymentFailure);
+            }
+
+            let handled = match event.kind {
+                PaymentEventKind::Succeeded => mark_paid(order.uuid).await,
+                PaymentEventKind::Failed => cancel_pending(order.uuid).await,
+            };
+            if handled.is_err() {
+                database::remove_payment_event(provider.name().to_string(), event.id).await;
+            }
+            handled
+        }
+
+        /// Pending -> Paid, then issues the tickets and vouchers. Orders without
+        /// merch have nothing left to ship, so they are invoiced and fulfilled
+        /// right away; the others once handed over. For an
+        /// order that is paid already, issues what an earlier attempt left out.
+        pub async fn mark_paid(order: String) -> Result<(), ResponseError> {
+            let order = match database::transition_order(order.clone(), OrderStatus::Paid, now()).await? {
+                Some(order) => {
+                    record_redemptions(&order).await;
+                    order
+                }
+                None => match database::get_order(order).await {
+                    Some(order) if order.status == OrderStatus::Paid => order,
+                    _ => return Ok(()),
+                },
+            };
+            issue_tickets(&order).await?;
+            issue_vouchers(&order).await?;
+            if order.has_merch() {
+                notify_buyer(&order);
+                return Ok(());
+            }
+            if let Err(e) = invoice::issue_for_order(&order).await {
+                println!("error in issuing invoice for order {}: {:?}", order.uuid, e);
+            }
+            if let Some(fulfilled) = database::transition_order(order.uuid, OrderStatus::Fulfilled, now()).await? {
+                notify_buyer(&fulfilled);
+            }
+            Ok(())
+        }
+
+        /// Pending -> Cancelled, handing the reserved tickets and stock, the
+        /// discount use and the voucher balance back.
+        pub async fn cancel_pending(order: String) -> Result<(), ResponseError> {
+            let Some(order) = database::transition_order(order, OrderStatus::Cancelled, now()).await? else {
+                return Ok(());
+            };
+            release_reserved(&order).await;
+            notify_buyer(&order);
+            Ok(())
+        }
+
+        /// How long an order may wait for its payment, configured via
+        /// `ORDER_HOLD_MINUTES`. Providers give up on sessions well before.
+        fn hold_minutes() -> i64 {
+            std::env::var("ORDER_HOLD_MINUTES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|m| *m > 0)
+                .unwrap_or(60)
+        }
+
+        /// Cancels orders whose payment never came, so what they hold goes
+        /// back on sale. Run by `jobs`.
+        pub async fn cancel_abandoned() -> Result<(), ResponseError> {
+            let before = stamp(chrono::Utc::now() - chrono::Duration::minutes(hold_minutes()));
+            let mut result = Ok(());
+            for order in database::get_pending_orders_before(before).await.unwrap_or_default() {
+                if let Err(e) = cancel_pending(order.uuid.clone()).await {
+                    println!("error in cancelling abandoned order {}: {:?}", order.uuid, e);
+                    result = Err(e);
+                }
+            }
+            result
+        }
+
+        /// Updates the buyer's open order pages; orders placed without an
+        /// account are left to the mails.
+        pub fn notify_buyer(order: &Order) {
+            if let Some(user) = order.user.clone() {
+                push::publish(Topic::User(user), PushMessage::OrderUpdated {
+                    order: order.uuid.clone(),
+                    status: order.status,
+                });
+            }
+        }
+
+        pub async fn release_reserved(order: &Order) {
+            for item in order.items.iter().filter(|i| i.kind == ItemKind::Ticket) {
+                waitlist::release(item.reference.clone(), item.quantity).await;
+            }
+            for item in order.items.iter().filter(|i| i.kind == ItemKind::Merch) {
+                stock::put_back(item.reference.clone(), item.quantity).await;
+            }
+            if let Some(code) = order.discount_code.clone() {
+                database::release_discount(code).await;
+            }
+            if let Some(code) = order.voucher_code.clone() {
+                database::credit_voucher(code, order.voucher_cents).await;
+            }
+        }
+
+        async fn record_redemptions(order: &Order) {
+            let discount = order.items.iter().map(|i| i.discount_cents).sum();
+            let redemptions = [
+                (order.discount_code.clone(), RedemptionKind::Discount, discount),
+                (order.voucher_code.clone(), RedemptionKind::Voucher, order.voucher_cents),
+            ];
+            for (code, kind, amount_cents) in redemptions {
+                let Some(code) = code else {
+                    continue;
+                };
+                let redemption = Redemption::new(Uuid::new_v4().to_string(), code, kind, order.uuid.clone(), amount_cents, now());
+                if database::add_redemption(redemption).await.is_none() {
+                    println!("error in recording redemption for order {}", order.uuid);
+                }
+            }
+        }
+
+        /// Creates the gift vouchers bought with an order that do not exist yet
+        /// and mails the codes of all of them.
+        async fn issue_vouchers(order: &Order) -> Result<(), ResponseError> {
+            let mut vouchers = database::get_vouchers_for_order(order.uuid.clone()).await
+                .ok_or(ResponseError::OrderTransitionFailure)?;
+            let mut wanted: BTreeMap<i64, u32> = BTreeMap::new();
+            for item in order.items.iter().filter(|i| i.kind == ItemKind::Voucher) {
+                *wanted.entry(item.unit_price_cents).or_default() += item.quantity;
+            }
+            let mut issued = false;
+            for (cents, quantity) in wanted {
+                let existing = vouchers.iter().filter(|v| v.initial_cents == cents).count() as u32;
+                for _ in existing..quantity {
+                    let voucher = GiftVoucher::new(cents, Some(order.uuid.clone()), now());
+                    vouchers.push(database::add_voucher(voucher).await.ok_or(ResponseError::OrderTransitionFailure)?);
+                    issued = true;
+                }
+            }
+            if !issued {
+                return Ok(());
+            }
+            let locale = order.locale;
+            let codes = vouchers
+                .iter()
+                .map(|voucher| format!("{}  {}", voucher.code, locale.price(voucher.initial_cents)))
+                .collect::<Vec<_>>();
+            let mail = Mail::new(
+                order.email.clone(),
+                locale.text("mail-vouchers-subject").to_string(),
+                locale.format("mail-vouchers-body", &[("codes", &codes.join("\n"))]),
+            );
+            if let Err(e) = mail::send(mail).await {
+                println!("error in mailing vouchers of order {}: {:?}", order.uuid, e);
+            }
+            Ok(())
+        }
+
+        /// Creates the tickets of an order that do not exist yet. Passed on
+        /// tickets count through their replacement.
+        async fn issue_tickets(order: &Order) -> Result<(), ResponseError> {
+            let tickets = database::get_tickets_for_order(order.uuid.clone()).await
+                .ok_or(ResponseError::OrderTransitionFailure)?;
+            for item in order.items.iter().filter(|i| i.kind == ItemKind::Ticket) {
+                let ticket_type = database::get_ticket_type(item.reference.clone()).await
+                    .ok_or(ResponseError::TicketNotFound)?;
+                let existing = tickets
+                    .iter()
+                    .filter(|t| t.ticket_type.as_ref() == Some(&item.reference) && t.transferred_to.is_none())
+                    .count() as u32;
+                for _ in existing..item.quantity {
+                    let code = database::take_door_code(ticket_type.event.clone()).await
+                        .unwrap_or_else(Ticket::generate_code);
+                    let mut ticket = Ticket::new(
+                        Uuid::new_v4().to_string(),
+                        ticket_type.event.clone(),
+                        code,
+                        order.email.clone(),
+                        TicketKind::Ticket,
+                    );
+                    ticket.owner = order.user.clone();
+                    ticket.holder_email = Some(order.email.clone());
+                    ticket.order = Some(order.uuid.clone());
+                    ticket.ticket_type = Some(ticket_type.uuid.clone());
+                    database::add_ticket(ticket).await.ok_or(ResponseError::OrderTransitionFailure)?;
+                }
+            }
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,157 @@
+//! Minimal PDF writer: A4 pages with Helvetica text and lines, which is all an
+//! invoice needs. Text is encoded as WinAnsi, so umlauts and € work with the
+//! standard fonts and nothing has to be embedded.
+
+use std::fmt::Write;
+
+pub const PAGE_WIDTH: f32 = 595.0;
+pub const PAGE_HEIGHT: f32 = 842.0;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Font {
+    Regular,
+    Bold,
+}
+
+impl Font {
+    fn resource(self) -> &'static str {
+        match self {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Pdf {
+    pages: Vec<Vec<u8>>,
+}
+
+impl Pdf {
+    pub fn new() -> Pdf {
+        Pdf { pages: vec![Vec::new()] }
+    }
+
+    pub fn add_page(&mut self) {
+        self.pages.push(Vec::new());
+    }
+
+    /// Writes `text` with its baseline `y` points below the top of the page.
+    pub fn text(&mut self, x: f32, y: f32, size: f32, font: Font, text: &str) {
+        let page = self.page();
+        page.extend_from_slice(
+            format!("BT /{} {} Tf {:.2} {:.2} Td (", font.resource(), size, x, PAGE_HEIGHT - y).as_bytes(),
+        );
+        for byte in encode(text) {
+            if matches!(byte, b'(' | b')' | b'\\') {
+                page.push(b'\\');
+            }
+            page.push(byte);
+        }
+        page.extend_from_slice(b") Tj ET\n");
+    }
+
+    /// Like `text`, but ending at `x`. Widths are estimated, which is exact
+    /// for the digits and separators amounts are made of.
+    pub fn text_right(&mut self, x: f32, y: f32, size: f32, font: Font, text: &str) {
+        let width: f32 = text.chars().map(char_width).sum::<f32>() * size / 1000.0;
+        self.text(x - width, y, size, font, text);
+    }
+
+    pub fn line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32) {
+        let line = format!(
+            "0.5 w {:.2} {:.2} m {:.2} {:.2} l S\n",
+            x1,
+            PAGE_HEIGHT - y1,
+            x2,
+            PAGE_HEIGHT - y2
+        );
+        self.page().extend_from_slice(line.as_bytes());
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        // 1: catalog, 2: page tree, 3/4: fonts, then a page and its content per page
+        let mut objects: Vec<Vec<u8>> = Vec::new();
+        let kids = (0..self.pages.len())
+            .fold(String::new(), |mut kids, i| {
+                let _ = write!(kids, "{} 0 R ", 5 + 2 * i);
+                kids
+            });
+        objects.push(b"<< /Type /Catalog /Pages 2 0 R >>".to_vec());
+        objects.push(format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.trim_end(), self.pages.len()).into_bytes());
+        objects.push(b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>".to_vec());
+        objects.push(b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>".to_vec());
+        for (i, content) in self.pages.into_iter().enumerate() {
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+                 /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                PAGE_WIDTH,
+                PAGE_HEIGHT,
+                6 + 2 * i
+            ).into_bytes());
+            let mut stream = format!("<< /Length {} >>\nstream\n", content.len()).into_bytes();
+            stream.extend_from_slice(&content);
+            stream.extend_from_slice(b"\nendstream");
+            objects.push(stream);
+        }
+
+        let mut out = b"%PDF-1.4\n".to_vec();
+        let mut offsets = Vec::new();
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+            out.extend_from_slice(object);
+            out.extend_from_slice(b"\nendobj\n");
+        }
+        let xref = out.len();
+        let mut table = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            let _ = writeln!(table, "{:010} 00000 n ", offset);
+        }
+        let _ = writeln!(
+            table,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF",
+            objects.len() + 1,
+            xref
+        );
+        out.extend_from_slice(table.as_bytes());
+        out
+    }
+
+    fn page(&mut self) -> &mut Vec<u8> {
+        if self.pages.is_empty() {
+            self.pages.push(Vec::new());
+        }
+        self.pages.last_mut().expect("there is always a page")
+    }
+}
+
+/// WinAnsi is Latin-1 plus a few extras in 0x80..0x9F; anything else becomes `?`.
+fn encode(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c {
+            '€' => 0x80,
+            '–' => 0x96,
+            '—' => 0x97,
+            '„' => 0x84,
+            '“' => 0x93,
+            '”' => 0x94,
+            ' '..='~' => c as u8,
+            '\u{a0}'..='\u{ff}' => c as u32 as u8,
+            _ => b'?',
+        })
+        .collect()
+}
+
+/// Helvetica advance widths in thousandths of the font size.
+fn char_width(c: char) -> f32 {
+    match c {
+        '0'..='9' | '€' => 556.0,
+        ',' | '.' | ' ' | '/' | 'I' => 278.0,
+        '-' => 333.0,
+        '%' => 889.0,
+        'i' | 'l' => 222.0,
+        'm' | 'M' | 'W' => 833.0,
+        _ => 556.0,
+    }
+}
@@ -0,0 +1,241 @@
+//! Live updates from the server to open pages. The server publishes typed
+//! messages to topics; every page keeps one server-sent event stream that
+//! carries the messages of the topics its user may see, and reconnects with
+//! backoff when it drops.
+
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::app::model::order::OrderStatus;
+use crate::app::model::user::Role;
+use crate::app::model::venue::Area;
+use crate::app::model::User;
+
+/// The stream, served in `main.rs`.
+pub const PATH: &str = "/api/push";
+
+/// Who gets a message.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub enum Topic {
+    Everyone,
+    /// Users with this role or a higher one.
+    Role(Role),
+    /// One user by `User.uuid`, on all their devices.
+    User(String),
+}
+
+impl Topic {
+    pub fn reaches(&self, user: Option<&User>) -> bool {
+        match self {
+            Topic::Everyone => true,
+            Topic::Role(role) => user.is_some_and(|user| user.role >= *role),
+            Topic::User(uuid) => user.is_some_and(|user| user.uuid == *uuid),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub enum PushMessage {
+    /// The last ticket of a type is gone.
+    SoldOut { event: String, ticket_type: String },
+    /// Counts of all areas, see `occupancy`.
+    Occupancy(Vec<Area>),
+    /// One of the user's orders moved on, e.g. the payment came through.
+    OrderUpdated { order: String, status: OrderStatus },
+    /// Something staff or admins should know about right away, as a
+    /// catalogue key and its placeholders, shown in the reader's language.
+    Notification { key: String, args: Vec<(String, String)>, link: Option<String> },
+}
+
+/// Handle on the page's stream, see `provide_push`.
+#[derive(Debug, Clone, Copy)]
+pub struct Push {
+    latest: RwSignal<Option<PushMessage>>,
+    connected: RwSignal<bool>,
+}
+
+impl Push {
+    /// Calls `handler` with every message arriving while the calling
+    /// component lives.
+    pub fn on(self, handler: impl Fn(PushMessage) + 'static) {
+        Effect::new(move |subscribed: Option<()>| {
+            let latest = self.latest.get();
+            // the first run only subscribes, the message then is old news
+            if subscribed.is_some() {
+                if let Some(message) = latest {
+                    handler(message);
+                }
+            }
+        });
+    }
+
+    pub fn connected(&self) -> bool {
+        self.connected.get()
+    }
+
+    /// Opens a new stream, so the topics follow a login or logout.
+    pub fn reconnect(self) {
+        #[cfg(feature = "hydrate")]
+        client::connect(self);
+    }
+}
+
+/// Opens the stream once per page load; called by `App`.
+pub fn provide_push() {
+    let push = Push {
+        latest: RwSignal::new(None),
+        connected: RwSignal::new(false),
+    };
+    provide_context(push);
+
+    #[cfg(feature = "hydrate")]
+    Effect::new(move |_| client::connect(push));
+}
+
+pub fn use_push() -> Push {
+    expect_context::<Push>()
+}
+
+#[cfg(feature = "hydrate")]
+mod client {
+    use leptos::prelude::*;
+    use leptos::serde_json;
+    use leptos::web_sys::{Event, EventSource, MessageEvent};
+    use std::cell::{Cell, RefCell};
+    use std::time::Duration;
+    use wasm_bindgen::closure::Closure;
+    use wasm_bindgen::JsCast;
+
+    use super::{Push, PushMessage, PATH};
+
+    const MIN_BACKOFF_MS: u64 = 1000;
+    const MAX_BACKOFF_MS: u64 = 30_000;
+
+    /// The closures have to live as long as the stream.
+    struct Connection {
+        source: EventSource,
+        _on_open: Closure<dyn FnMut(Event)>,
+        _on_message: Closure<dyn FnMut(MessageEvent)>,
+        _on_error: Closure<dyn FnMut(Event)>,
+    }
+
+    thread_local! {
+        static CONNECTION: RefCell<Option<Connection>> = const { RefCell::new(None) };
+        /// Counts `connect` calls, so retries scheduled for an older stream
+        /// are dropped.
+        static GENERATION: Cell<u32> = const { Cell::new(0) };
+        static FAILURES: Cell<u32> = const { Cell::new(0) };
+    }
+
+    /// Doubles with every failure in a row, with jitter so a restarted
+    /// server isn't hit by all pages at once.
+    fn backoff(failures: u32) -> Duration {
+        let ceiling = (MIN_BACKOFF_MS << failures.min(5)).min(MAX_BACKOFF_MS);
+        let jittered = ceiling / 2 + (js_sys::Math::random() * (ceiling / 2) as f64) as u64;
+        Duration::from_millis(jittered)
+    }
+
+    pub fn connect(push: Push) {
+        let generation = GENERATION.with(|g| {
+            g.set(g.get().wrapping_add(1));
+            g.get()
+        });
+        if let Some(old) = CONNECTION.take() {
+            old.source.close();
+        }
+        let Ok(source) = EventSource::new(PATH) else {
+            return;
+        };
+
+        let on_open = Closure::<dyn FnMut(Event)>::new(move |_: Event| {
+            FAILURES.set(0);
+            push.connected.set(true);
+        });
+        let on_message = Closure::<dyn FnMut(MessageEvent)>::new(move |e: MessageEvent| {
+            let message = e.data().as_string().and_then(|data| serde_json::from_str::<PushMessage>(&data).ok());
+            if let Some(message) = message {
+                push.latest.set(Some(message));
+            }
+        });
+        let on_error = Closure::<dyn FnMut(Event)>::new({
+            let source = source.clone();
+            move |_: Event| {
+                // the browser would retry at its own pace, this backs off
+                source.close();
+                push.connected.set(false);
+                let failures = FAILURES.get();
+                FAILURES.set(failures + 1);
+                set_timeout(
+                    move || {
+                        if GENERATION.get() == generation {
+                            connect(push);
+                        }
+                    },
+                    backoff(failures),
+                );
+            }
+        });
+        source.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+        source.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        source.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+
+        CONNECTION.set(Some(Connection {
+            source,
+            _on_open: on_open,
+            _on_message: on_message,
+            _on_error: on_error,
+        }));
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use actix_web::web::Bytes;
+        use leptos::serde_json;
+        use once_cell::sync::Lazy;
+        use std::time::Duration;
+        use tokio::sync::broadcast;
+
+        /// Slow listeners skip messages instead of holding up the others.
+        const CHANNEL_CAPACITY: usize = 64;
+        /// Proxies close streams that stay silent for long.
+        const KEEP_ALIVE: Duration = Duration::from_secs(25);
+
+        #[derive(Debug, Clone)]
+        struct Envelope {
+            topic: Topic,
+            message: PushMessage,
+        }
+
+        static CHANNEL: Lazy<broadcast::Sender<Envelope>> = Lazy::new(|| broadcast::channel(CHANNEL_CAPACITY).0);
+
+        /// Sends a message to all open pages the topic reaches.
+        pub fn publish(topic: Topic, message: PushMessage) {
+            // fails only while nobody listens
+            let _ = CHANNEL.send(Envelope { topic, message });
+        }
+
+        /// The stream of a page, as server-sent events. `user` is who was
+        /// logged in when it was opened.
+        pub fn events(user: Option<User>) -> impl futures_util::Stream<Item = Result<Bytes, actix_web::Error>> {
+            use futures_util::stream;
+            use tokio::sync::broadcast::error::RecvError;
+
+            let receiver = CHANNEL.subscribe();
+            stream::unfold((receiver, user), |(mut receiver, user)| async move {
+                loop {
+                    let chunk = match tokio::time::timeout(KEEP_ALIVE, receiver.recv()).await {
+                        Ok(Ok(envelope)) if envelope.topic.reaches(user.as_ref()) => {
+                            let data = serde_json::to_string(&envelope.message).unwrap_or_default();
+                            Bytes::from(format!("data: {data}\n\n"))
+                        }
+                        Ok(Ok(_)) | Ok(Err(RecvError::Lagged(_))) => continue,
+                        Ok(Err(RecvError::Closed)) => return None,
+                        Err(_) => Bytes::from_static(b": keep-alive\n\n"),
+                    };
+                    return Some((Ok(chunk), (receiver, user)));
+                }
+            })
+        }
+    }
+}
@@ -0,0 +1,225 @@
+//! Paying money back, for single tickets or for everything sold for an event
+//! that was called off. Tickets are invalidated first, so a failed payout never
+//! leaves a refunded ticket usable at the door.
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use uuid::Uuid;
+
+        use crate::app::database;
+        use crate::app::errors::ResponseError;
+        use crate::app::feed;
+        use crate::app::i18n::Locale;
+        use crate::app::ical::parse_berlin;
+        use crate::app::invoice;
+        use crate::app::mail::{self, Mail};
+        use crate::app::model::{Event, Order, Refund};
+        use crate::app::model::cart::ItemKind;
+        use crate::app::model::order::OrderStatus;
+        use crate::app::model::refund::{CancellationReport, RefundReason, RefundStatus};
+        use crate::app::payment::{self, PaymentProvider};
+        use crate::app::time;
+        use crate::app::waitlist;
+
+        /// Attempts before giving up on an order that keeps changing underneath.
+        const UPDATE_ATTEMPTS: u32 = 3;
+
+        /// Marks lines of an order as refunded. `lines` picks `(reference,
+        /// quantity)` pairs from the current order and is asked again if another
+        /// refund got in between. Returns the updated order and the amount.
+        async fn refund_lines<F>(order: String, lines: F) -> Result<(Order, i64), ResponseError>
+            where F: Fn(&Order) -> Vec<(String, u32)> {
+
+            for _ in 0..UPDATE_ATTEMPTS {
+                let mut current = database::get_order(order.clone()).await.ok_or(ResponseError::OrderNotFound)?;
+                let previous = current.refunded_cents;
+                let mut amount = 0;
+                for (reference, quantity) in lines(&current) {
+                    amount += current.refund_line(&reference, quantity).ok_or(ResponseError::RefundFailure)?;
+                }
+                if amount == 0 {
+                    return Ok((current, 0));
+                }
+                current.updated_at = time::now();
+                if database::update_order_refunds(current.clone(), previous).await {
+                    return Ok((current, amount));
+                }
+            }
+            Err(ResponseError::RefundFailure)
+        }
+
+        /// Tells the buyer where the money of a refund goes.
+        fn payout(refund: &Refund, locale: Locale) -> String {
+            let provider_cents = refund.amount_cents - refund.voucher_cents;
+            match (provider_cents, refund.voucher_cents) {
+                (cents, 0) => locale.format("mail-payout-provider", &[("amount", &locale.price(cents))]),
+                (0, voucher) => locale.format("mail-payout-voucher", &[("amount", &locale.price(voucher))]),
+                (cents, voucher) => locale.format(
+                    "mail-payout-both",
+                    &[("amount", &locale.price(cents)), ("voucher", &locale.price(voucher))],
+                ),
+            }
+        }
+
+        /// Sends the money back and records the refund. What the buyer paid
+        /// through the provider goes back there first, the rest is credited to
+        /// the voucher that paid for the order.
+        async fn pay_back(
+            order: &Order,
+            event: String,
+            tickets: Vec<String>,
+            amount_cents: i64,
+            reason: RefundReason,
+            by: String,
+        ) -> Refund {
+            let mut refund = Refund::new(
+                Uuid::new_v4().to_string(),
+                event,
+                order.uuid.clone(),
+                tickets,
+                amount_cents,
+                reason,
+                by,
+                time::now(),
+            );
+            // `order` already includes this refund
+            let refunded_before = order.refunded_cents - amount_cents;
+            let provider_cents = amount_cents.min((order.amount_due() - refunded_before).max(0));
+            refund.voucher_cents = amount_cents - provider_cents;
+
+            let paid_back = match (order.payment.as_ref(), provider_cents) {
+                (_, 0) => Ok(None),
+                (Some(payment), _) => match payment::provider() {
+                    Ok(provider) => provider.refund(payment, provider_cents).await.map(Some),
+                    Err(e) => Err(e),
+                },
+                (None, _) => Err(ResponseError::RefundFailure),
+            };
+            match paid_back {
+                Ok(provider_refund) => {
+                    refund.status = RefundStatus::Succeeded;
+                    refund.provider_refund = provider_refund;
+                }
+                Err(e) => println!("error in refunding order {}: {:?}", order.uuid, e),
+            }
+            if let (Some(code), true) = (order.voucher_code.clone(), refund.voucher_cents > 0) {
+                database::credit_voucher(code, refund.voucher_cents).await;
+            }
+            if database::add_refund(refund.clone()).await.is_none() {
+                println!("error in recording refund {:?}", refund);
+            }
+            refund
+        }
+
+        /// Closes a fully refunded order and corrects its invoice.
+        async fn settle(order: &Order) {
+            if order.fully_refunded() {
+                match database::transition_order(order.uuid.clone(), OrderStatus::Refunded, time::now()).await {
+                    Ok(Some(refunded)) => payment::notify_buyer(&refunded),
+                    Ok(None) => {}
+                    Err(e) => println!("error in closing refunded order {}: {:?}", order.uuid, e),
+                }
+            }
+            if let Err(e) = invoice::reissue(order).await {
+                println!("error in correcting invoice of order {}: {:?}", order.uuid, e);
+            }
+        }
+
+        /// Refunds one unused ticket of a paid order.
+        pub async fn refund_ticket(ticket: String, by: String) -> Result<Refund, ResponseError> {
+            let ticket = database::get_ticket(ticket).await.ok_or(ResponseError::TicketNotFound)?;
+            let (Some(order), Some(ticket_type)) = (ticket.order.clone(), ticket.ticket_type.clone()) else {
+                return Err(ResponseError::RefundFailure);
+            };
+            if !database::refund_ticket(ticket.uuid.clone()).await {
+                return Err(ResponseError::RefundFailure);
+            }
+
+            let (order, amount) = refund_lines(order, |_| vec![(ticket_type.clone(), 1)]).await?;
+            let refund = pay_back(&order, ticket.event.clone(), vec![ticket.uuid], amount, RefundReason::Requested, by).await;
+            // the place goes to the waitlist or back on sale
+            waitlist::release(ticket_type, 1).await;
+            settle(&order).await;
+
+            let locale = order.locale;
+            let mail = Mail::new(
+                order.email.clone(),
+                locale.text("mail-refund-subject").to_string(),
+                locale.format("mail-refund-body", &[("order", &order.uuid), ("payout", &payout(&refund, locale))]),
+            );
+            if let Err(e) = mail::send(mail).await {
+                println!("error in mailing refund of order {}: {:?}", order.uuid, e);
+            }
+            Ok(refund)
+        }
+
+        /// Calls an event off: invalidates all its tickets, refunds every order
+        /// that bought some and tells the buyers. Running it again only picks
+        /// up what was not refunded yet.
+        pub async fn cancel_event(event: Event, by: String) -> Result<CancellationReport, ResponseError> {
+            database::set_event_cancelled(event.uuid.clone()).await?;
+            database::invalidate_event_tickets(event.uuid.clone()).await?;
+            if feed::publish_event(&Event { cancelled: true, ..event.clone() }).await.is_none() {
+                println!("error in updating feed entry of event {}", event.uuid);
+            }
+
+            let ticket_types: Vec<String> = database::get_ticket_types(event.uuid.clone())
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .map(|t| t.uuid)
+                .collect();
+            let orders = database::get_paid_orders_containing(ticket_types.clone()).await.unwrap_or_default();
+
+            let mut report = CancellationReport::default();
+            for order in orders {
+                let lines = |order: &Order| order.items
+                    .iter()
+                    .filter(|i| i.kind == ItemKind::Ticket && ticket_types.contains(&i.reference))
+                    .filter(|i| i.refundable_quantity() > 0)
+                    .map(|i| (i.reference.clone(), i.refundable_quantity()))
+                    .collect::<Vec<_>>();
+                let (order, amount) = match refund_lines(order.uuid.clone(), lines).await {
+                    Ok((_, 0)) => continue,
+                    Ok(refunded) => refunded,
+                    Err(e) => {
+                        println!("error in refunding order {}: {:?}", order.uuid, e);
+                        report.failed.push(order.uuid);
+                        continue;
+                    }
+                };
+                let tickets = database::get_tickets_for_order(order.uuid.clone())
+                    .await
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|t| t.event == event.uuid)
+                    .map(|t| t.uuid)
+                    .collect();
+
+                let refund = pay_back(&order, event.uuid.clone(), tickets, amount, RefundReason::EventCancelled, by.clone()).await;
+                match refund.status {
+                    RefundStatus::Succeeded => report.refunded_cents += amount,
+                    RefundStatus::Failed => report.failed.push(order.uuid.clone()),
+                }
+                report.orders += 1;
+                settle(&order).await;
+
+                let locale = order.locale;
+                let date = parse_berlin(&event.starts_at).map(|t| locale.date(t.date())).unwrap_or_default();
+                let mail = Mail::new(
+                    order.email.clone(),
+                    locale.format("mail-cancelled-subject", &[("event", &event.title)]),
+                    locale.format(
+                        "mail-cancelled-body",
+                        &[("event", &event.title), ("date", &date), ("payout", &payout(&refund, locale))],
+                    ),
+                );
+                match mail::send(mail).await {
+                    Ok(_) => report.mailed += 1,
+                    Err(e) => println!("error in mailing cancellation of order {}: {:?}", order.uuid, e),
+                }
+            }
+            Ok(report)
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use leptos::{prelude::*, task::spawn_local};
+use leptos::ev;
+use leptos::html::Video;
+
+use crate::app::i18n::{t, use_locale};
+
+stylance::import_style!(style, "../style/scanner.module.scss");
+
+/// Reads ticket codes either from the camera (where the browser ships the
+/// `BarcodeDetector` API) or from manual entry, and hands each one to `on_scan`.
+#[component]
+pub fn Scanner(on_scan: Callback<String>) -> impl IntoView {
+    let (code, set_code) = signal(String::new());
+    let (camera_on, set_camera_on) = signal(false);
+    let (camera_error, set_camera_error) = signal(String::new());
+    let video_ref = NodeRef::<Video>::new();
+    let locale = use_locale();
+
+    let submit = move || {
+        let value = code.get_untracked().trim().to_owned();
+        if !value.is_empty() {
+            on_scan.run(value);
+            set_code(String::new());
+        }
+    };
+
+    let on_enter = move |e: ev::KeyboardEvent| {
+        if e.key() == "Enter" {
+            submit();
+        }
+    };
+
+    let on_camera_pressed = move |_| {
+        if camera_on.get_untracked() {
+            set_camera_on(false);
+            return;
+        }
+        set_camera_error(String::new());
+        set_camera_on(true);
+        spawn_local(async move {
+            #[cfg(feature = "hydrate")]
+            if let Err(e) = camera::run(video_ref, camera_on, on_scan).await {
+                let _ = set_camera_error.try_set(locale.get_untracked().text(e).to_string());
+            }
+            let _ = set_camera_on.try_set(false);
+        });
+    };
+
+    on_cleanup(move || {
+        let _ = set_camera_on.try_set(false);
+    });
+
+    view! {
+        <div class=style::scanner>
+            <video node_ref=video_ref
+                class=move || if camera_on() { style::video } else { style::hidden }
+                muted=true
+                playsinline=true
+            ></video>
+            <span class=style::error_label>{camera_error}</span>
+            <div class=style::row>
+                <input type="text" placeholder=t("scanner-code")
+                    autocomplete="off"
+                    prop:value=code
+                    on:input=move |e| set_code(event_target_value(&e))
+                    on:keydown=on_enter
+                    class=style::input
+                />
+                <button class=style::button on:click=move |_| submit()>{t("scanner-ok")}</button>
+                <button class=style::button on:click=on_camera_pressed>
+                    <i class=move || if camera_on() { "bi bi-camera-video-off" } else { "bi bi-camera-video" }></i>
+                </button>
+            </div>
+        </div>
+    }
+}
+
+#[cfg(feature = "hydrate")]
+mod camera {
+    use leptos::prelude::*;
+    use leptos::html::Video;
+    use wasm_bindgen::{prelude::*, JsCast};
+    use leptos::web_sys::{HtmlVideoElement, MediaStream, MediaStreamConstraints, MediaStreamTrack};
+    use wasm_bindgen_futures::JsFuture;
+
+    /// Codes read more than once within this window are only reported once.
+    const REPEAT_MS: f64 = 3000.0;
+    const POLL_MS: i32 = 250;
+
+    #[wasm_bindgen]
+    extern "C" {
+        type BarcodeDetector;
+
+        #[wasm_bindgen(constructor, catch)]
+        fn new(options: &JsValue) -> Result<BarcodeDetector, JsValue>;
+
+        #[wasm_bindgen(method)]
+        fn detect(this: &BarcodeDetector, source: &HtmlVideoElement) -> js_sys::Promise;
+    }
+
+    /// Scans until the camera is switched off. Errors are catalogue keys.
+    pub async fn run(
+        video_ref: NodeRef<Video>,
+        camera_on: ReadSignal<bool>,
+        on_scan: Callback<String>,
+    ) -> Result<(), &'static str> {
+        if !js_sys::Reflect::has(&window(), &JsValue::from_str("BarcodeDetector")).unwrap_or(false) {
+            return Err("scanner-unsupported");
+        }
+        let video = video_ref.get_untracked().ok_or("scanner-no-camera")?;
+
+        let options = js_sys::Object::new();
+        let formats = js_sys::Array::of2(&"qr_code".into(), &"code_128".into());
+        let _ = js_sys::Reflect::set(&options, &"formats".into(), &formats);
+        let detector = BarcodeDetector::new(&options)
+            .map_err(|_| "scanner-unsupported")?;
+
+        let stream = open(&video).await.map_err(|_| "scanner-no-camera")?;
+
+        let mut last = (String::new(), 0.0);
+        while camera_on.try_get_untracked().unwrap_or(false) {
+            if let Some(found) = detect(&detector, &video).await {
+                let now = js_sys::Date::now();
+                if found != last.0 || now - last.1 > REPEAT_MS {
+                    on_scan.run(found.clone());
+                    last = (found, now);
+                }
+            }
+            sleep(POLL_MS).await;
+        }
+
+        for track in stream.get_tracks().iter() {
+            if let Ok(track) = track.dyn_into::<MediaStreamTrack>() {
+                track.stop();
+            }
+        }
+        video.set_src_object(None);
+        Ok(())
+    }
+
+    async fn open(video: &HtmlVideoElement) -> Result<MediaStream, JsValue> {
+        let devices = window().navigator().media_devices()?;
+        let facing = js_sys::Object::new();
+        js_sys::Reflect::set(&facing, &"facingMode".into(), &"environment".into())?;
+        let constraints = MediaStreamConstraints::new();
+        constraints.set_video(&facing);
+
+        let stream: MediaStream = JsFuture::from(devices.get_user_media_with_constraints(&constraints)?)
+            .await?
+            .dyn_into()?;
+        video.set_src_object(Some(&stream));
+        JsFuture::from(video.play()?).await?;
+        Ok(stream)
+    }
+
+    async fn detect(detector: &BarcodeDetector, video: &HtmlVideoElement) -> Option<String> {
+        let found = JsFuture::from(detector.detect(video)).await.ok()?;
+        let first = js_sys::Array::from(&found).get(0);
+        if first.is_undefined() {
+            return None;
+        }
+        js_sys::Reflect::get(&first, &"rawValue".into()).ok()?.as_string()
+    }
+
+    async fn sleep(ms: i32) {
+        let promise = js_sys::Promise::new(&mut |resolve, _| {
+            let _ = window().set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, ms);
+        });
+        let _ = JsFuture::from(promise).await;
+    }
+}
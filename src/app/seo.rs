@@ -0,0 +1,194 @@
+//! Per-page head tags for search engines and link previews, plus schema.org
+//! data for events.
+
+use leptos::prelude::*;
+use leptos_meta::{Link, Meta, Title};
+use leptos_router::hooks::use_location;
+
+use crate::app::i18n::use_locale;
+
+pub const SITE_NAME: &str = "STAMPFFABRIK";
+
+/// Shown in link previews of pages without an image of their own.
+const DEFAULT_IMAGE: &str = "/assets/stampffabrik_1024.png";
+
+/// Base URL for canonical links. The server knows it from `SITE_URL`; after
+/// hydration the page's own origin is used.
+pub fn site_url() -> String {
+    cfg_if::cfg_if! {
+        if #[cfg(feature = "ssr")] {
+            crate::app::mail::site_url()
+        } else {
+            window().location().origin().unwrap_or_default()
+        }
+    }
+}
+
+/// `path` on the site as an absolute URL; URLs are passed through.
+pub fn absolute_url(path: &str) -> String {
+    if path.starts_with("http://") || path.starts_with("https://") {
+        path.to_string()
+    } else {
+        format!("{}/{}", site_url(), path.trim_start_matches('/'))
+    }
+}
+
+/// Title, description, canonical URL and OpenGraph/Twitter card tags of a
+/// page. The canonical URL is the current path unless `path` is given; pages
+/// that should not show up in search results pass `noindex`.
+#[component]
+pub fn PageMeta(
+    /// Empty for the home page, which only carries the site name.
+    #[prop(into)] title: String,
+    #[prop(into, optional)] path: Option<String>,
+    #[prop(into, optional)] description: Option<String>,
+    /// Path or URL; empty for the site logo.
+    #[prop(into, optional)] image: Option<String>,
+    /// OpenGraph type, `website` if not given.
+    #[prop(optional)] og_type: Option<&'static str>,
+    #[prop(optional)] noindex: bool,
+) -> impl IntoView {
+    let locale = use_locale().get_untracked();
+    let title = if title.is_empty() {
+        SITE_NAME.to_string()
+    } else {
+        format!("{title} · {SITE_NAME}")
+    };
+    let description = description
+        .filter(|d| !d.trim().is_empty())
+        .unwrap_or_else(|| locale.text("site-description").to_string());
+    let url = absolute_url(&path.unwrap_or_else(|| use_location().pathname.get_untracked()));
+    let image = absolute_url(image.as_deref().filter(|i| !i.is_empty()).unwrap_or(DEFAULT_IMAGE));
+
+    view! {
+        <Title text=title.clone()/>
+        <Meta name="description" content=description.clone()/>
+        <Link rel="canonical" href=url.clone()/>
+        {noindex.then(|| view! { <Meta name="robots" content="noindex"/> })}
+        <Meta property="og:site_name" content=SITE_NAME/>
+        <Meta property="og:type" content=og_type.unwrap_or("website")/>
+        <Meta property="og:title" content=title.clone()/>
+        <Meta property="og:description" content=description.clone()/>
+        <Meta property="og:url" content=url/>
+        <Meta property="og:image" content=image.clone()/>
+        <Meta property="og:locale" content=locale.territory()/>
+        <Meta name="twitter:card" content="summary_large_image"/>
+        <Meta name="twitter:title" content=title/>
+        <Meta name="twitter:description" content=description/>
+        <Meta name="twitter:image" content=image/>
+    }
+}
+
+/// Structured data for search engines. `json` must come from one of the
+/// builders below, which escape it for a script element.
+#[component]
+pub fn JsonLd(json: String) -> impl IntoView {
+    view! { <script type="application/ld+json" inner_html=json></script> }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use leptos::serde_json::{self, json, Value};
+
+        use crate::app::invoice::seller_from_env;
+        use crate::app::model::Event;
+        use crate::app::model::artist::LineupEntry;
+        use crate::app::model::event::TicketType;
+        use crate::app::model::shipping::HOME_COUNTRY;
+
+        /// Serialises JSON-LD so it can't end the script element early.
+        fn to_json_ld(value: &Value) -> String {
+            serde_json::to_string(value)
+                .unwrap_or_default()
+                .replace('<', "\\u003c")
+                .replace('>', "\\u003e")
+                .replace('&', "\\u0026")
+        }
+
+        fn price(cents: i64) -> String {
+            format!("{}.{:02}", cents / 100, cents % 100)
+        }
+
+        /// schema.org `MusicEvent` for an event page. The venue address is
+        /// the one printed on invoices. Without a lineup the event itself
+        /// stands in as performer, which search engines require.
+        pub fn music_event(event: &Event, ticket_types: &[TicketType], lineup: &[LineupEntry]) -> String {
+            let url = absolute_url(&format!("/events/{}", event.slug));
+            let venue = seller_from_env();
+            let country = match venue.address.country.as_str() {
+                "" => HOME_COUNTRY,
+                country => country,
+            };
+            let offers: Vec<Value> = ticket_types
+                .iter()
+                .map(|ticket_type| json!({
+                    "@type": "Offer",
+                    "name": ticket_type.name,
+                    "price": price(ticket_type.price_cents),
+                    "priceCurrency": "EUR",
+                    "availability": if ticket_type.available() > 0 {
+                        "https://schema.org/InStock"
+                    } else {
+                        "https://schema.org/SoldOut"
+                    },
+                    "url": url,
+                }))
+                .collect();
+            let performers: Vec<Value> = match lineup {
+                [] => vec![json!({
+                    "@type": "PerformingGroup",
+                    "name": event.title,
+                })],
+                lineup => lineup
+                    .iter()
+                    .map(|entry| json!({
+                        "@type": "MusicGroup",
+                        "name": entry.artist.name,
+                        "url": absolute_url(&format!("/artists/{}", entry.artist.slug)),
+                        "sameAs": entry.artist.links,
+                    }))
+                    .collect(),
+            };
+
+            let mut value = json!({
+                "@context": "https://schema.org",
+                "@type": "MusicEvent",
+                "name": event.title,
+                "url": url,
+                "startDate": event.starts_at,
+                "doorTime": event.doors_at,
+                "eventStatus": if event.cancelled {
+                    "https://schema.org/EventCancelled"
+                } else {
+                    "https://schema.org/EventScheduled"
+                },
+                "eventAttendanceMode": "https://schema.org/OfflineEventAttendanceMode",
+                "location": {
+                    "@type": "Place",
+                    "name": venue.name,
+                    "address": {
+                        "@type": "PostalAddress",
+                        "streetAddress": venue.address.line1,
+                        "postalCode": venue.address.zipcode,
+                        "addressLocality": venue.address.city,
+                        "addressCountry": country,
+                    },
+                },
+                "performer": performers,
+                "organizer": {
+                    "@type": "Organization",
+                    "name": venue.name,
+                    "url": site_url(),
+                },
+                "offers": offers,
+            });
+            if let Some(ends_at) = &event.ends_at {
+                value["endDate"] = json!(ends_at);
+            }
+            if let Some(poster) = &event.poster {
+                value["image"] = json!([absolute_url(poster)]);
+            }
+            to_json_ld(&value)
+        }
+    }
+}
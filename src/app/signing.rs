@@ -0,0 +1,108 @@
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+type HmacSha256 = Hmac<Sha256>;
+
+const KEY_BYTES: usize = 32;
+/// Hex length of an untruncated signature.
+const SIGNATURE_LENGTH: usize = 64;
+
+/// Key for signed links from `var`, falling back to `JWT_KEY`, so links keep
+/// working across restarts either way.
+pub fn secret(var: &str) -> Result<String, std::env::VarError> {
+    std::env::var(var).or_else(|_| std::env::var("JWT_KEY"))
+}
+
+/// Random secret, hex encoded.
+pub fn generate_key() -> String {
+    let mut key = [0u8; KEY_BYTES];
+    rand::thread_rng().fill_bytes(&mut key);
+    to_hex(&key)
+}
+
+/// SHA-256 of `message`, hex encoded.
+pub fn digest(message: &str) -> String {
+    to_hex(&Sha256::digest(message.as_bytes()))
+}
+
+/// HMAC-SHA256 of `message`, hex encoded.
+pub fn sign(key: &str, message: &str) -> String {
+    let mut mac = HmacSha256::new_from_slice(key.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(message.as_bytes());
+    to_hex(&mac.finalize().into_bytes())
+}
+
+/// Checks a (possibly truncated) hex signature in constant time.
+pub fn verify(key: &str, message: &str, signature: &str) -> bool {
+    let signature = match from_hex(signature) {
+        Some(bytes) if !bytes.is_empty() => bytes,
+        _ => return false,
+    };
+    let mut mac = HmacSha256::new_from_slice(key.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(message.as_bytes());
+    mac.verify_truncated_left(&signature).is_ok()
+}
+
+/// Like `verify`, but only accepts the untruncated signature. For tokens in
+/// links, which are never shortened.
+pub fn verify_full(key: &str, message: &str, signature: &str) -> bool {
+    signature.len() == SIGNATURE_LENGTH && verify(key, message, signature)
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str = "door-key";
+
+    #[test]
+    fn hex_round_trip() {
+        assert_eq!(from_hex(&to_hex(&[0x00, 0x7f, 0xff])), Some(vec![0x00, 0x7f, 0xff]));
+        assert_eq!(from_hex("abc"), None);
+        assert_eq!(from_hex("zz"), None);
+    }
+
+    #[test]
+    fn truncated_signatures_verify() {
+        let signature = sign(KEY, "ABC123");
+        assert_eq!(signature.len(), SIGNATURE_LENGTH);
+        assert!(verify(KEY, "ABC123", &signature));
+        assert!(verify(KEY, "ABC123", &signature[..16]));
+        assert!(verify(KEY, "ABC123", &signature[..2]));
+    }
+
+    #[test]
+    fn bad_signatures_do_not_verify() {
+        let signature = sign(KEY, "ABC123");
+        assert!(!verify(KEY, "ABC124", &signature));
+        assert!(!verify("other-key", "ABC123", &signature));
+        // only prefixes count, and whole bytes
+        assert!(!verify(KEY, "ABC123", &signature[2..18]));
+        assert!(!verify(KEY, "ABC123", &signature[..15]));
+        assert!(!verify(KEY, "ABC123", ""));
+        assert!(!verify(KEY, "ABC123", &format!("{signature}00")));
+    }
+
+    #[test]
+    fn links_need_the_whole_signature() {
+        let signature = sign(KEY, "confirm:1234");
+        assert!(verify_full(KEY, "confirm:1234", &signature));
+        assert!(!verify_full(KEY, "confirm:1234", &signature[..32]));
+        assert!(!verify_full(KEY, "unsubscribe:1234", &signature));
+    }
+}
@@ -0,0 +1,144 @@
+//! `sitemap.xml` and `robots.txt`, both generated when requested.
+
+/// Never crawled, see `robots`.
+pub const DISALLOWED: [&str; 3] = ["/admin", "/account", "/api"];
+
+/// Pages that only make sense for one visitor or for staff. They stay
+/// crawlable but are left out of the sitemap.
+const PRIVATE: [&str; 8] = ["/cart", "/orders", "/door", "/guestlist", "/occupancy", "/payment", "/newsletter", "/waitlist"];
+
+/// Static routes of the app, taken from the route list at startup.
+#[derive(Debug, Clone, Default)]
+pub struct StaticRoutes(pub Vec<String>);
+
+impl StaticRoutes {
+    /// Keeps the routes without parameters that belong in the sitemap;
+    /// pages behind parameters are added from the database instead.
+    pub fn new(paths: impl IntoIterator<Item = String>) -> StaticRoutes {
+        let mut paths: Vec<String> = paths
+            .into_iter()
+            .filter(|path| !path.contains(['{', '}', '*', ':']))
+            .filter(|path| listed(path))
+            .collect();
+        paths.sort();
+        paths.dedup();
+        StaticRoutes(paths)
+    }
+}
+
+fn listed(path: &str) -> bool {
+    DISALLOWED
+        .iter()
+        .chain(PRIVATE.iter())
+        .all(|prefix| path != *prefix && !path.starts_with(&format!("{prefix}/")))
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SitemapUrl {
+    /// Path on the site.
+    pub path: String,
+    /// RFC 3339, which is a valid W3C datetime.
+    pub lastmod: Option<String>,
+}
+
+impl SitemapUrl {
+    pub fn new(path: String, lastmod: Option<String>) -> SitemapUrl {
+        SitemapUrl { path, lastmod }
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+pub fn render(urls: &[SitemapUrl], site_url: &str) -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+    );
+    for url in urls {
+        xml.push_str("<url><loc>");
+        xml.push_str(&escape(&format!("{site_url}/{}", url.path.trim_start_matches('/'))));
+        xml.push_str("</loc>");
+        if let Some(lastmod) = &url.lastmod {
+            xml.push_str(&format!("<lastmod>{}</lastmod>", escape(lastmod)));
+        }
+        xml.push_str("</url>\n");
+    }
+    xml + "</urlset>\n"
+}
+
+/// `ROBOTS_INDEX=false` keeps all crawlers out, e.g. on staging;
+/// `ROBOTS_DISALLOW` adds comma separated paths to the default exclusions.
+pub fn robots(site_url: &str, index: bool, extra: &[String]) -> String {
+    let mut text = String::from("User-agent: *\n");
+    if index {
+        for path in DISALLOWED.iter().map(|p| p.to_string()).chain(extra.iter().cloned()) {
+            text.push_str(&format!("Disallow: {path}\n"));
+        }
+    } else {
+        text.push_str("Disallow: /\n");
+    }
+    text + &format!("\nSitemap: {site_url}/sitemap.xml\n")
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use crate::app::database;
+        use crate::app::time;
+
+        /// The static routes plus every public event, product, artist and post.
+        pub async fn urls(routes: &StaticRoutes) -> Vec<SitemapUrl> {
+            let mut urls: Vec<SitemapUrl> = routes.0.iter().map(|path| SitemapUrl::new(path.clone(), None)).collect();
+
+            let now = time::now();
+            let feed_items = database::get_feed_items(now.clone(), u32::MAX).await.unwrap_or_default();
+            let events = database::get_events_since(String::new()).await.unwrap_or_default();
+            urls.extend(events.into_iter().map(|event| {
+                let lastmod = feed_items
+                    .iter()
+                    .find(|item| item.reference.as_deref() == Some(event.uuid.as_str()))
+                    .map(|item| item.updated_at.clone());
+                SitemapUrl::new(format!("/events/{}", event.slug), lastmod)
+            }));
+
+            let products = database::get_products().await.unwrap_or_default();
+            urls.extend(
+                products
+                    .into_iter()
+                    .filter(|product| product.active)
+                    .map(|product| SitemapUrl::new(format!("/shop/{}", product.slug), None)),
+            );
+
+            let artists = database::get_artists().await.unwrap_or_default();
+            urls.extend(
+                artists
+                    .into_iter()
+                    .map(|artist| SitemapUrl::new(format!("/artists/{}", artist.slug), Some(artist.updated_at))),
+            );
+
+            let posts = database::get_published_posts(now).await.unwrap_or_default();
+            urls.extend(
+                posts
+                    .into_iter()
+                    .map(|post| SitemapUrl::new(format!("/news/{}", post.slug), Some(post.updated_at))),
+            );
+            urls
+        }
+
+        pub fn robots_from_env(site_url: &str) -> String {
+            let index = std::env::var("ROBOTS_INDEX").map(|v| v != "false").unwrap_or(true);
+            let extra: Vec<String> = std::env::var("ROBOTS_DISALLOW")
+                .unwrap_or_default()
+                .split(',')
+                .map(|path| path.trim().to_string())
+                .filter(|path| path.starts_with('/'))
+                .collect();
+            robots(site_url, index, &extra)
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! Merch stock. Checkout takes stock when the order is placed, cancelled
+//! orders put it back, and admins get a mail when a variant runs low.
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use crate::app::database;
+        use crate::app::errors::ResponseError;
+        use crate::app::mail::{self, Mail};
+        use crate::app::model::product::ProductVariant;
+        use crate::app::model::user::Role;
+        use crate::app::push::{self, PushMessage, Topic};
+
+        /// Takes `quantity` of a variant and alerts the admins if that made
+        /// it drop to its threshold.
+        pub async fn take(sku: String, quantity: u32) -> Result<ProductVariant, ResponseError> {
+            let variant = database::take_stock(sku, quantity).await?;
+            // only the checkout crossing the threshold sends the alert
+            if variant.is_low() && variant.stock + quantity > variant.low_stock_threshold {
+                alert_low_stock(&variant).await;
+            }
+            Ok(variant)
+        }
+
+        pub async fn put_back(sku: String, quantity: u32) {
+            database::return_stock(sku, quantity).await;
+        }
+
+        async fn alert_low_stock(variant: &ProductVariant) {
+            let product = database::get_product(variant.product.clone())
+                .await
+                .map(|p| p.name)
+                .unwrap_or_default();
+            let admins = database::get_all_users()
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|u| u.role == Role::Admin);
+            for admin in admins {
+                let locale = admin.locale;
+                let mail = Mail::new(
+                    admin.email,
+                    locale.format("mail-low-stock-subject", &[("product", &product), ("sku", &variant.sku)]),
+                    locale.format("mail-low-stock-body", &[
+                        ("product", &product),
+                        ("variant", &variant.label()),
+                        ("sku", &variant.sku),
+                        ("count", &variant.stock.to_string()),
+                    ]),
+                );
+                if let Err(e) = mail::send(mail).await {
+                    println!("error in mailing low stock of {}: {:?}", variant.sku, e);
+                }
+            }
+            push::publish(Topic::Role(Role::Admin), PushMessage::Notification {
+                key: String::from("notice-low-stock"),
+                args: vec![
+                    (String::from("product"), format!("{} {}", product, variant.label())),
+                    (String::from("count"), variant.stock.to_string()),
+                ],
+                link: Some(String::from("/admin/products")),
+            });
+        }
+    }
+}
@@ -0,0 +1,16 @@
+//! Timestamps as they are stored with records: RFC 3339 in UTC with
+//! milliseconds, e.g. `2026-10-19T08:05:35.123Z`, the shape of
+//! `Date.toISOString()` on door devices. One offset and one length keep them
+//! ordering as strings whatever the clocks in Berlin do; records from before
+//! carry the server's offset, so queries still compare them as `<datetime>`.
+//! They are shown in Berlin time, see `ical::parse_berlin`.
+
+use chrono::{DateTime, SecondsFormat, Utc};
+
+pub fn stamp(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+pub fn now() -> String {
+    stamp(Utc::now())
+}
@@ -0,0 +1,240 @@
+//! Waitlists for sold out events. Places freed by cancelled orders and
+//! refunds go to the first in line instead of back on sale; they are held
+//! for a while and offered by mail with a claim link. Offers that run out
+//! pass on to the next in line, checked by a recurring job. Every step goes
+//! to the audit log.
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use chrono::{DateTime, Duration, Utc};
+        use uuid::Uuid;
+
+        use crate::app::database;
+        use crate::app::errors::ResponseError;
+        use crate::app::i18n::request_locale;
+        use crate::app::ical::parse_berlin;
+        use crate::app::mail::{self, site_url, Mail};
+        use crate::app::model::{Event, Order, User};
+        use crate::app::model::cart::ItemKind;
+        use crate::app::model::event::TicketType;
+        use crate::app::model::order::{LineItem, OrderStatus};
+        use crate::app::model::waitlist::{WaitlistAction, WaitlistAudit, WaitlistEntry, WaitlistOffer, WaitlistStatus};
+        use crate::app::time::{now, stamp};
+        use crate::app::push::{self, PushMessage, Topic};
+        use crate::app::signing;
+
+        /// How long places are held for someone, configured via
+        /// `WAITLIST_OFFER_HOURS`. Offers never last past doors.
+        fn offer_hours() -> i64 {
+            std::env::var("WAITLIST_OFFER_HOURS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|h| *h > 0)
+                .unwrap_or(12)
+        }
+
+        /// Key for the claim links.
+        const SECRET: &str = "WAITLIST_SECRET";
+
+        /// Bound to the offer, so the link of an earlier offer stops working.
+        fn message(entry: &str, offer: &WaitlistOffer) -> String {
+            format!("waitlist:{entry}:{}", offer.offered_at)
+        }
+
+        pub fn claim_path(entry: &WaitlistEntry) -> Option<String> {
+            let offer = entry.offer.as_ref()?;
+            let token = signing::sign(&signing::secret(SECRET).ok()?, &message(&entry.uuid, offer));
+            Some(format!("/waitlist/{}/{}", entry.uuid, token))
+        }
+
+        /// The entry behind a claim link.
+        pub async fn checked_entry(entry: String, token: String) -> Result<WaitlistEntry, ResponseError> {
+            let entry = database::get_waitlist_entry(entry).await.ok_or(ResponseError::OfferInvalid)?;
+            let Some(offer) = entry.offer.as_ref() else {
+                return Err(ResponseError::OfferInvalid);
+            };
+            let signed = message(&entry.uuid, offer);
+            if !signing::secret(SECRET).is_ok_and(|secret| signing::verify_full(&secret, &signed, &token)) {
+                return Err(ResponseError::OfferInvalid);
+            }
+            Ok(entry)
+        }
+
+        async fn audit(event: &str, entry: Option<&WaitlistEntry>, action: WaitlistAction, ticket_type: Option<String>, quantity: u32) {
+            database::add_waitlist_audit(WaitlistAudit {
+                uuid: Uuid::new_v4().to_string(),
+                event: event.to_string(),
+                entry: entry.map(|e| e.uuid.clone()),
+                action,
+                ticket_type,
+                quantity,
+                created_at: now(),
+            })
+            .await;
+        }
+
+        async fn get_event(uuid: String) -> Option<Event> {
+            database::get_events(vec![uuid]).await.unwrap_or_default().into_iter().next()
+        }
+
+        /// Puts the user in line for up to `quantity` places.
+        pub async fn join(event: &Event, user: &User, quantity: u32) -> Result<WaitlistEntry, ResponseError> {
+            let entry = WaitlistEntry::new(
+                Uuid::new_v4().to_string(),
+                event.uuid.clone(),
+                user.uuid.clone(),
+                user.email.clone(),
+                quantity,
+                now(),
+            );
+            let entry = database::add_waitlist_entry(entry).await?;
+            audit(&entry.event, Some(&entry), WaitlistAction::Joined, None, entry.quantity).await;
+            Ok(entry)
+        }
+
+        /// Takes the entry out of line; places held for it pass on.
+        pub async fn leave(entry: String) -> Result<(), ResponseError> {
+            let before = database::leave_waitlist(entry).await.ok_or(ResponseError::WaitlistFailure)?;
+            audit(&before.event, Some(&before), WaitlistAction::Left, None, 0).await;
+            if let (WaitlistStatus::Offered, Some(offer)) = (before.status, before.offer) {
+                pass_on_held(&before.event, offer.ticket_type, offer.quantity).await;
+            }
+            Ok(())
+        }
+
+        /// Hands freed places to the waitlist of their event, and only what
+        /// nobody there wants back on sale.
+        pub async fn release(ticket_type: String, quantity: u32) {
+            let left = match database::get_ticket_type(ticket_type.clone()).await {
+                Some(ticket_type) => offer(&ticket_type, quantity).await,
+                None => quantity,
+            };
+            if left > 0 {
+                database::release_tickets(ticket_type, left).await;
+            }
+        }
+
+        /// Places of an offer that wasn't taken, on to the next in line.
+        async fn pass_on_held(event: &str, ticket_type: String, quantity: u32) {
+            let left = match database::get_ticket_type(ticket_type.clone()).await {
+                Some(ticket_type) => offer(&ticket_type, quantity).await,
+                None => quantity,
+            };
+            if left > 0 {
+                database::release_tickets(ticket_type.clone(), left).await;
+                audit(event, None, WaitlistAction::Released, Some(ticket_type), left).await;
+            }
+        }
+
+        /// Offers `places` to those in line, first come first served; the
+        /// first may get fewer than they asked for. Returns the places left.
+        async fn offer(ticket_type: &TicketType, mut places: u32) -> u32 {
+            let Some(event) = get_event(ticket_type.event.clone()).await else {
+                return places;
+            };
+            let Some(doors) = DateTime::parse_from_rfc3339(&event.doors_at).ok().map(|doors| doors.with_timezone(&Utc)) else {
+                return places;
+            };
+            let current = Utc::now();
+            let expires = (current + Duration::hours(offer_hours())).min(doors);
+            if event.cancelled || expires <= current {
+                return places;
+            }
+
+            let mut skipped = None;
+            while places > 0 {
+                let Some(next) = database::get_next_waiting(event.uuid.clone()).await else {
+                    break;
+                };
+                if skipped.as_ref() == Some(&next.uuid) {
+                    break;
+                }
+                let quantity = next.quantity.min(places);
+                let offer = WaitlistOffer {
+                    ticket_type: ticket_type.uuid.clone(),
+                    quantity,
+                    offered_at: now(),
+                    expires_at: stamp(expires),
+                    order: None,
+                };
+                // taken or offered elsewhere since; if the same entry comes
+                // up again, it's the update itself that fails
+                let Some(offered) = database::offer_waitlist_entry(next.uuid.clone(), offer).await else {
+                    skipped = Some(next.uuid);
+                    continue;
+                };
+                places -= quantity;
+                audit(&event.uuid, Some(&offered), WaitlistAction::Offered, Some(ticket_type.uuid.clone()), quantity).await;
+                announce(&event, ticket_type, &offered).await;
+            }
+            places
+        }
+
+        async fn announce(event: &Event, ticket_type: &TicketType, entry: &WaitlistEntry) {
+            let (Some(path), Some(offer)) = (claim_path(entry), entry.offer.as_ref()) else {
+                return;
+            };
+            let locale = database::get_user_by_id(entry.user.clone()).await.map(|u| u.locale).unwrap_or_default();
+            let expires = parse_berlin(&offer.expires_at).map(|t| locale.date_time(t)).unwrap_or_default();
+            let mail = Mail::new(
+                entry.email.clone(),
+                locale.format("mail-waitlist-subject", &[("event", &event.title)]),
+                locale.format("mail-waitlist-body", &[
+                    ("event", &event.title),
+                    ("quantity", &offer.quantity.to_string()),
+                    ("ticket_type", &ticket_type.name),
+                    ("expires", &expires),
+                    ("link", &format!("{}{}", site_url(), path)),
+                ]),
+            );
+            if let Err(e) = mail::send(mail).await {
+                println!("error in mailing waitlist offer {}: {:?}", entry.uuid, e);
+            }
+            push::publish(Topic::User(entry.user.clone()), PushMessage::Notification {
+                key: String::from("notice-waitlist-offer"),
+                args: vec![(String::from("event"), event.title.clone())],
+                link: Some(path),
+            });
+        }
+
+        /// Turns an offer into a pending order for the held places.
+        pub async fn claim(entry: WaitlistEntry, user: &User) -> Result<Order, ResponseError> {
+            let offer = entry.offer.clone().ok_or(ResponseError::OfferInvalid)?;
+            if entry.user != user.uuid || entry.status != WaitlistStatus::Offered || !offer.is_open_at(Utc::now()) {
+                return Err(ResponseError::OfferInvalid);
+            }
+            let ticket_type = database::get_ticket_type(offer.ticket_type.clone()).await.ok_or(ResponseError::TicketNotFound)?;
+            let item = LineItem::new(
+                ItemKind::Ticket,
+                ticket_type.uuid.clone(),
+                ticket_type.name.clone(),
+                offer.quantity,
+                ticket_type.price_cents,
+                ticket_type.vat_rate,
+            );
+            let mut order = Order::new(Uuid::new_v4().to_string(), Some(user.uuid.clone()), entry.email.clone(), vec![item], now());
+            order.buyer_name = format!("{} {}", user.name, user.last_name).trim().to_string();
+            order.locale = request_locale();
+            let order = database::add_order(order).await.ok_or(ResponseError::OrderCreationFailure)?;
+
+            if database::claim_waitlist_offer(entry.uuid.clone(), order.uuid.clone(), now()).await.is_none() {
+                // ran out meanwhile and the places moved on, so there is nothing to give back
+                let _ = database::transition_order(order.uuid, OrderStatus::Cancelled, now()).await;
+                return Err(ResponseError::OfferInvalid);
+            }
+            audit(&entry.event, Some(&entry), WaitlistAction::Claimed, Some(ticket_type.uuid), offer.quantity).await;
+            Ok(order)
+        }
+
+        /// Passes on offers that ran out. Run by `jobs`.
+        pub async fn expire_offers() {
+            for entry in database::expire_waitlist_offers(now()).await.unwrap_or_default() {
+                let Some(offer) = entry.offer.clone() else {
+                    continue;
+                };
+                audit(&entry.event, Some(&entry), WaitlistAction::Expired, Some(offer.ticket_type.clone()), offer.quantity).await;
+                pass_on_held(&entry.event, offer.ticket_type, offer.quantity).await;
+            }
+        }
+    }
+}
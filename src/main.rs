@@ -15,6 +15,11 @@ async fn main() -> std::io::Result<()> {
     let addr = conf.leptos_options.site_addr;
     
     dotenv().ok();
+
+    // delivers queued mail in the background, throttled
+    actix_web::rt::spawn(database::background(mail::queue::run()));
+    // expiring offers and orders, cleanups and other scheduled work
+    jobs::start();
     
     HttpServer::new(move || {
         let routes = generate_route_list(App);
@@ -27,21 +32,48 @@ async fn main() -> std::io::Result<()> {
             // serve JS/WASM/CSS from `pkg`
             .service(Files::new("/pkg", format!("{site_root}/pkg")))
             // serve other assets from the `assets` directory
-            .service(Files::new("/assets", &site_root))
+            .service(
+                web::scope("/assets")
+                    .wrap(middleware::DefaultHeaders::new().add((http::header::CACHE_CONTROL, ASSET_CACHE_CONTROL)))
+                    .service(Files::new("", &site_root)),
+            )
+            // resized copies of images, see `images::ResponsiveImage`
+            .service(image_rendition)
+            .service(media_file)
+            .service(media_upload)
+            .service(push_events)
+            .app_data(
+                actix_multipart::form::MultipartFormConfig::default()
+                    .total_limit(MEDIA_UPLOAD_LIMIT)
+                    .memory_limit(MEDIA_UPLOAD_LIMIT),
+            )
             // serve the favicon from /favicon.ico
             .service(favicon)
+            .service(sitemap_xml)
+            .service(robots_txt)
+            .service(payment_webhook)
+            .service(events_feed)
+            .service(event_ics)
+            .service(atom_feed)
+            .service(rss_feed)
+            .app_data(web::Data::new(sitemap::StaticRoutes::new(
+                routes.iter().map(|route| route.path().to_string()),
+            )))
             .leptos_routes(routes, {
                 let leptos_options = leptos_options.clone();
                 move || {
                     view! {
                         <!DOCTYPE html>
-                        <html lang="en">
+                        <html>
                             <head>
                                 <meta charset="utf-8"/>
                                 <meta name="viewport" content="width=device-width, initial-scale=1"/>
                                 <AutoReload options=leptos_options.clone() />
                                 <HydrationScripts options=leptos_options.clone()/>
                                 <MetaTags/>
+                                <link rel="alternate" type="application/atom+xml" title="Stampffabrik" href="/feed.atom"/>
+                                <link rel="alternate" type="application/rss+xml" title="Stampffabrik" href="/feed.rss"/>
+                                <link rel="alternate" type="text/calendar" title="Stampffabrik" href="/events.ics"/>
                             </head>
                             <body>
                                 <App/>
@@ -70,6 +102,261 @@ async fn favicon(
     ))?)
 }
 
+/// Public pages for search engines: the static routes plus published
+/// events, products and posts.
+#[cfg(feature = "ssr")]
+#[actix_web::get("/sitemap.xml")]
+async fn sitemap_xml(
+    routes: actix_web::web::Data<stampffabrik::app::sitemap::StaticRoutes>,
+) -> actix_web::HttpResponse {
+    use stampffabrik::app::{mail, sitemap};
+
+    let urls = sitemap::urls(&routes).await;
+    actix_web::HttpResponse::Ok()
+        .content_type("application/xml; charset=utf-8")
+        .insert_header(("Cache-Control", "public, max-age=3600"))
+        .body(sitemap::render(&urls, &mail::site_url()))
+}
+
+/// Keeps crawlers out of `/admin`, `/account` and `/api`; see
+/// `sitemap::robots` for the settings.
+#[cfg(feature = "ssr")]
+#[actix_web::get("/robots.txt")]
+async fn robots_txt() -> actix_web::HttpResponse {
+    use stampffabrik::app::{mail, sitemap};
+
+    actix_web::HttpResponse::Ok()
+        .content_type("text/plain; charset=utf-8")
+        .insert_header(("Cache-Control", "public, max-age=86400"))
+        .body(sitemap::robots_from_env(&mail::site_url()))
+}
+
+/// Assets and image copies keep their address when they change, so caches
+/// check back daily; the ETag makes that cheap.
+#[cfg(feature = "ssr")]
+const ASSET_CACHE_CONTROL: &str = "public, max-age=86400";
+
+/// Copy of a local image in one of `images::WIDTHS`, e.g.
+/// `/img/480/assets/event_prisma.png.webp`.
+#[cfg(feature = "ssr")]
+#[actix_web::get("/img/{width}/{path:.*}")]
+async fn image_rendition(
+    params: actix_web::web::Path<(u32, String)>,
+) -> actix_web::Result<actix_web::CustomizeResponder<actix_files::NamedFile>> {
+    use actix_web::Responder;
+    use stampffabrik::app::images::{self, ImageFormat};
+
+    let (width, path) = params.into_inner();
+    let (source, format) = path
+        .rsplit_once('.')
+        .and_then(|(source, extension)| Some((source.to_string(), ImageFormat::from_extension(extension)?)))
+        .ok_or_else(|| actix_web::error::ErrorNotFound("unknown image format"))?;
+    let Some(file) = images::rendition(source, width, format).await else {
+        return Err(actix_web::error::ErrorNotFound("image not found"));
+    };
+    Ok(actix_files::NamedFile::open(file)?
+        .customize()
+        .insert_header((actix_web::http::header::CONTENT_TYPE, format.mime_type()))
+        .insert_header((actix_web::http::header::CACHE_CONTROL, ASSET_CACHE_CONTROL)))
+}
+
+/// Files are stored under their content hash, so their address never
+/// points to anything else.
+#[cfg(feature = "ssr")]
+const MEDIA_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+/// Several files per upload, each up to `media::MAX_UPLOAD_BYTES`.
+#[cfg(feature = "ssr")]
+const MEDIA_UPLOAD_LIMIT: usize = 8 * stampffabrik::app::media::MAX_UPLOAD_BYTES;
+
+/// Uploaded media, from whichever storage backend is configured.
+#[cfg(feature = "ssr")]
+#[actix_web::get("/media/{key}")]
+async fn media_file(key: actix_web::web::Path<String>) -> actix_web::HttpResponse {
+    use actix_web::HttpResponse;
+    use stampffabrik::app::media::{self, MediaType, StorageBackend};
+
+    let key = key.into_inner();
+    let Some(media_type) = key.rsplit_once('.').and_then(|(_, extension)| MediaType::from_extension(extension)) else {
+        return HttpResponse::NotFound().finish();
+    };
+    match media::storage().get(&key).await {
+        Ok(bytes) => HttpResponse::Ok()
+            .content_type(media_type.mime_type())
+            .insert_header((actix_web::http::header::CACHE_CONTROL, MEDIA_CACHE_CONTROL))
+            .body(bytes),
+        Err(_) => HttpResponse::NotFound().finish(),
+    }
+}
+
+#[cfg(feature = "ssr")]
+#[derive(actix_multipart::form::MultipartForm)]
+struct MediaUpload {
+    // each file is checked against `media::MAX_UPLOAD_BYTES` again
+    #[multipart(limit = "20MiB")]
+    files: Vec<actix_multipart::form::bytes::Bytes>,
+}
+
+/// Uploads to the media library, admins only. Answers with the stored
+/// files, which may be existing ones for content uploaded before. The body
+/// is only read once the role is checked.
+#[cfg(feature = "ssr")]
+#[actix_web::post("/api/media")]
+async fn media_upload(request: actix_web::HttpRequest, payload: actix_web::web::Payload) -> actix_web::HttpResponse {
+    use actix_multipart::form::MultipartForm;
+    use actix_web::{FromRequest, HttpResponse};
+    use stampffabrik::app::auth::user_from_request;
+    use stampffabrik::app::errors::{ErrorMessage, ResponseErrorTrait};
+    use stampffabrik::app::media;
+    use stampffabrik::app::model::user::Role;
+
+    match user_from_request(&request).await {
+        Some(user) if user.role >= Role::Admin => {}
+        Some(_) => return HttpResponse::Forbidden().finish(),
+        None => return HttpResponse::Unauthorized().finish(),
+    }
+
+    let form = match MultipartForm::<MediaUpload>::from_request(&request, &mut payload.into_inner()).await {
+        Ok(form) => form,
+        Err(e) => return e.error_response(),
+    };
+    let mut stored = Vec::new();
+    for file in form.into_inner().files {
+        let name = file.file_name.unwrap_or_default();
+        match media::upload(name.clone(), file.data.to_vec()).await {
+            Ok(media_file) => stored.push(media_file),
+            Err(e) => return HttpResponse::BadRequest().body(format!("{name}: {}", ErrorMessage::create(e))),
+        }
+    }
+    HttpResponse::Ok().json(stored)
+}
+
+/// Live updates for open pages, as server-sent events. The topics follow
+/// whoever is logged in when the stream is opened; see `push`.
+#[cfg(feature = "ssr")]
+#[actix_web::get("/api/push")]
+async fn push_events(request: actix_web::HttpRequest) -> actix_web::HttpResponse {
+    use stampffabrik::app::auth::user_from_request;
+    use stampffabrik::app::push;
+
+    let user = user_from_request(&request).await;
+    actix_web::HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((actix_web::http::header::CACHE_CONTROL, "no-cache"))
+        .streaming(push::events(user))
+}
+
+/// Callbacks from payment providers. Answered with 200 once the event is
+/// handled or was handled before, so the provider stops retrying.
+#[cfg(feature = "ssr")]
+#[actix_web::post("/api/payment/webhook/{provider}")]
+async fn payment_webhook(
+    provider: actix_web::web::Path<String>,
+    request: actix_web::HttpRequest,
+    body: actix_web::web::Bytes,
+) -> actix_web::HttpResponse {
+    use actix_web::HttpResponse;
+    use stampffabrik::app::payment::{self, PaymentProvider};
+
+    let configured = match payment::provider() {
+        Ok(configured) if provider.into_inner() == configured.name() => configured,
+        _ => return HttpResponse::NotFound().finish(),
+    };
+    let signature = request
+        .headers()
+        .get("X-Signature")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+
+    match payment::handle_webhook(&configured, signature, &body).await {
+        Ok(_) => HttpResponse::Ok().finish(),
+        Err(e) => {
+            println!("error in payment webhook: {:?}", e);
+            HttpResponse::BadRequest().finish()
+        }
+    }
+}
+
+/// Past events stay in the feed this long, so subscribers keep their history.
+#[cfg(feature = "ssr")]
+const FEED_HISTORY_DAYS: i64 = 90;
+
+/// Public iCalendar feed of all events, for subscribing in calendar apps.
+#[cfg(feature = "ssr")]
+#[actix_web::get("/events.ics")]
+async fn events_feed() -> actix_web::HttpResponse {
+    use stampffabrik::app::{database, ical, mail, time};
+
+    let now = chrono::Utc::now();
+    let from = time::stamp(now - chrono::Duration::days(FEED_HISTORY_DAYS));
+    let events = database::get_events_since(from).await.unwrap_or_default();
+    actix_web::HttpResponse::Ok()
+        .content_type("text/calendar; charset=utf-8")
+        .body(ical::calendar(&events, &mail::site_url(), now))
+}
+
+/// A single event as a download, for "add to calendar".
+#[cfg(feature = "ssr")]
+#[actix_web::get("/events/{slug}.ics")]
+async fn event_ics(slug: actix_web::web::Path<String>) -> actix_web::HttpResponse {
+    use actix_web::HttpResponse;
+    use stampffabrik::app::{database, ical, mail};
+
+    let Some(event) = database::get_event_by_slug(slug.into_inner()).await else {
+        return HttpResponse::NotFound().finish();
+    };
+    HttpResponse::Ok()
+        .content_type("text/calendar; charset=utf-8")
+        .insert_header(("Content-Disposition", format!("attachment; filename=\"{}.ics\"", event.slug)))
+        .body(ical::calendar(&[event], &mail::site_url(), chrono::Utc::now()))
+}
+
+/// Feed readers poll often; they may cache for a while and revalidate with
+/// the ETag or the date of the latest change.
+#[cfg(feature = "ssr")]
+async fn feed_response(
+    request: actix_web::HttpRequest,
+    content_type: &str,
+    render: fn(&[stampffabrik::app::model::feed::FeedItem], &str, chrono::DateTime<chrono::Utc>) -> String,
+) -> actix_web::HttpResponse {
+    use actix_web::HttpResponse;
+    use stampffabrik::app::{database, feed, mail, time};
+
+    let items = database::get_feed_items(time::now(), feed::FEED_LENGTH).await.unwrap_or_default();
+    let updated = feed::last_updated(&items).unwrap_or_else(chrono::Utc::now);
+    let etag = format!("W/\"{}-{}\"", items.len(), updated.timestamp());
+    let last_modified = feed::http_date(updated);
+
+    let header = |name: &str| request.headers().get(name).and_then(|value| value.to_str().ok());
+    let unchanged = match header("If-None-Match") {
+        Some(tags) => tags.split(',').any(|tag| tag.trim() == etag),
+        None => header("If-Modified-Since") == Some(last_modified.as_str()),
+    };
+    let mut response = if unchanged { HttpResponse::NotModified() } else { HttpResponse::Ok() };
+    response
+        .insert_header(("Cache-Control", "public, max-age=900"))
+        .insert_header(("ETag", etag))
+        .insert_header(("Last-Modified", last_modified));
+    if unchanged {
+        return response.finish();
+    }
+    response
+        .content_type(content_type)
+        .body(render(&items, &mail::site_url(), updated))
+}
+
+#[cfg(feature = "ssr")]
+#[actix_web::get("/feed.atom")]
+async fn atom_feed(request: actix_web::HttpRequest) -> actix_web::HttpResponse {
+    feed_response(request, "application/atom+xml; charset=utf-8", stampffabrik::app::feed::atom).await
+}
+
+#[cfg(feature = "ssr")]
+#[actix_web::get("/feed.rss")]
+async fn rss_feed(request: actix_web::HttpRequest) -> actix_web::HttpResponse {
+    feed_response(request, "application/rss+xml; charset=utf-8", stampffabrik::app::feed::rss).await
+}
+
 #[cfg(not(any(feature = "ssr", feature = "csr")))]
 pub fn main() {
     // no client-side main function